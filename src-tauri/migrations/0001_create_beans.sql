@@ -0,0 +1,8 @@
+-- 咖啡豆：前端的完整 JSON 保存在 data，常用于排序、筛选的字段另存一列
+CREATE TABLE IF NOT EXISTS beans (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    roaster TEXT,
+    data TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,23 @@
+-- 冲煮笔记：前端的完整 JSON 保存在 data，筛选、排序用到的字段另存一列
+CREATE TABLE IF NOT EXISTS notes (
+    id TEXT PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    method TEXT,
+    bean_id TEXT,
+    dose REAL,
+    yield REAL,
+    brew_time REAL,
+    rating REAL,
+    data TEXT NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS notes_timestamp ON notes (timestamp DESC);
+CREATE INDEX IF NOT EXISTS notes_bean_id ON notes (bean_id);
+
+-- 风味标签，一条笔记可以有多个
+CREATE TABLE IF NOT EXISTS note_tags (
+    note_id TEXT NOT NULL REFERENCES notes (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (note_id, tag)
+);
+CREATE INDEX IF NOT EXISTS note_tags_tag ON note_tags (tag);
@@ -0,0 +1,12 @@
+-- 咖啡豆和冲煮笔记的全文索引
+-- title、body 是分词后的文字（中日韩文字逐字分开），raw_title、raw_body 保存原文用于展示和高亮
+-- 索引内容由应用写入，打开数据库时发现条数对不上会重建
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5 (
+    kind UNINDEXED,
+    item_id UNINDEXED,
+    title,
+    body,
+    raw_title UNINDEXED,
+    raw_body UNINDEXED,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
@@ -0,0 +1,9 @@
+-- 回收站：删除的咖啡豆、冲煮笔记先移到这里，可以恢复，超过保留期后自动清除
+CREATE TABLE IF NOT EXISTS trash (
+    kind TEXT NOT NULL,
+    id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    deleted_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, id)
+);
+CREATE INDEX IF NOT EXISTS trash_deleted_at ON trash (deleted_at);
@@ -0,0 +1,12 @@
+-- 修改记录：咖啡豆、冲煮笔记的每次修改都追加一条，保存修改前后的完整数据（NULL 表示不存在），用于撤销、重做
+-- state：applied 已生效 / undone 已撤销（可重做） / discarded 撤销后又有新的修改，不能再重做
+CREATE TABLE IF NOT EXISTS journal (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    before TEXT,
+    after TEXT,
+    changed_at INTEGER NOT NULL,
+    state TEXT NOT NULL DEFAULT 'applied'
+);
+CREATE INDEX IF NOT EXISTS journal_entity ON journal (entity_id);
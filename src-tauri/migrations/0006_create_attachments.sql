@@ -0,0 +1,17 @@
+-- 附件（咖啡豆、笔记的图片）：按内容的 BLAKE3 哈希保存一份，文件位于 attachments/{hash}.{ext}
+CREATE TABLE IF NOT EXISTS attachments (
+    hash TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    mime TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+-- 引用附件的记录；回收站中的记录仍保留引用，清除后才释放
+CREATE TABLE IF NOT EXISTS attachment_refs (
+    hash TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    PRIMARY KEY (hash, kind, entity_id)
+);
+CREATE INDEX IF NOT EXISTS attachment_refs_entity ON attachment_refs (kind, entity_id);
@@ -0,0 +1,17 @@
+-- 同步冲突：同一条记录在两台设备上修改了相同的字段时保存两边的版本，等待用户选择
+-- base 为上次同步时的版本（找不到时为 NULL），fields 为冲突字段的 JSON 数组
+-- local_updated_at / remote_updated_at：两边版本在各自设备上的修改时间（毫秒），未知时为 NULL
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    base TEXT,
+    local TEXT NOT NULL,
+    remote TEXT NOT NULL,
+    fields TEXT NOT NULL,
+    local_updated_at INTEGER,
+    remote_updated_at INTEGER,
+    detected_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sync_conflicts_entity ON sync_conflicts (kind, entity_id);
@@ -0,0 +1,19 @@
+-- 烘焙批次：从 Artisan 的 .alog 烘焙记录导入，可以关联到一款咖啡豆（bean_id）
+-- 时间以秒为单位，重量以克为单位；source 为导入的文件名
+CREATE TABLE IF NOT EXISTS roast_batches (
+    id TEXT PRIMARY KEY,
+    bean_id TEXT,
+    title TEXT NOT NULL,
+    roast_date TEXT,
+    green_weight REAL,
+    roasted_weight REAL,
+    weight_loss REAL,
+    first_crack_time REAL,
+    drop_time REAL,
+    development_time REAL,
+    development_ratio REAL,
+    roast_level TEXT,
+    source TEXT NOT NULL,
+    imported_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS roast_batches_bean ON roast_batches (bean_id);
@@ -0,0 +1,383 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::json_file;
+
+// 应用锁：启用后，返回数据的命令需要先通过生物识别或口令验证
+const APP_LOCK_FILE: &str = "app-lock.json";
+const DEFAULT_AUTO_LOCK_MINUTES: u32 = 5;
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// 连续输错口令：前几次不限制，之后每次输错等待时间翻倍，最长一小时
+const FREE_ATTEMPTS: u32 = 5;
+const BASE_RETRY_DELAY_SECS: i64 = 30;
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppLockConfig {
+    enabled: bool,
+    passcode_hash: Option<String>,
+    auto_lock_minutes: u32, // 0 表示不自动锁定
+    // 输错次数和下次可以尝试的时间，保存下来避免重启应用绕过等待
+    #[serde(default)]
+    failed_attempts: u32,
+    #[serde(default)]
+    retry_after_ms: Option<i64>,
+}
+
+impl Default for AppLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            passcode_hash: None,
+            auto_lock_minutes: DEFAULT_AUTO_LOCK_MINUTES,
+            failed_attempts: 0,
+            retry_after_ms: None,
+        }
+    }
+}
+
+fn retry_delay(failed_attempts: u32) -> Option<chrono::Duration> {
+    let over = failed_attempts.checked_sub(FREE_ATTEMPTS)?;
+    let secs = (BASE_RETRY_DELAY_SECS << over.min(8)).min(MAX_RETRY_DELAY_SECS);
+    Some(chrono::Duration::seconds(secs))
+}
+
+pub struct AppLockState {
+    config: AppLockConfig,
+    locked: bool,
+    last_activity: Instant,
+}
+
+impl AppLockState {
+    // 启用了应用锁时，启动后处于锁定状态
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let config: AppLockConfig = config_path(app)
+            .ok()
+            .and_then(|path| json_file::load(&path).ok())
+            .unwrap_or_default();
+        Self::new(config)
+    }
+
+    fn new(config: AppLockConfig) -> Self {
+        Self {
+            locked: config.enabled,
+            config,
+            last_activity: Instant::now(),
+        }
+    }
+
+    // 检查是否锁定（超过自动锁定时间未活动时转为锁定）
+    fn is_locked(&mut self) -> bool {
+        self.is_locked_at(Instant::now())
+    }
+
+    fn is_locked_at(&mut self, now: Instant) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        let timeout = Duration::from_secs(self.config.auto_lock_minutes as u64 * 60);
+        if !self.locked && self.config.auto_lock_minutes > 0 && now.saturating_duration_since(self.last_activity) >= timeout {
+            self.locked = true;
+        }
+        self.locked
+    }
+
+    // 未锁定时刷新活动时间
+    fn touch(&mut self, now: Instant) -> Result<(), String> {
+        if self.is_locked_at(now) {
+            return Err("应用已锁定".to_string());
+        }
+        self.last_activity = now;
+        Ok(())
+    }
+
+    fn unlock(&mut self) {
+        self.locked = false;
+        self.last_activity = Instant::now();
+        self.config.failed_attempts = 0;
+        self.config.retry_after_ms = None;
+    }
+
+    // 用口令解锁；还在等待时间内时不验证口令
+    fn unlock_with_passcode(&mut self, passcode: &str, now: DateTime<Utc>) -> Result<(), String> {
+        let now_ms = now.timestamp_millis();
+        if let Some(retry_after_ms) = self.config.retry_after_ms.filter(|at| *at > now_ms) {
+            let secs = (retry_after_ms - now_ms + 999) / 1000;
+            return Err(format!("口令错误次数过多，请 {} 秒后再试", secs));
+        }
+        let verified = self
+            .config
+            .passcode_hash
+            .as_deref()
+            .is_some_and(|hash| verify_passcode(passcode, hash));
+        if verified {
+            self.unlock();
+            return Ok(());
+        }
+        self.config.failed_attempts += 1;
+        self.config.retry_after_ms = retry_delay(self.config.failed_attempts).map(|delay| (now + delay).timestamp_millis());
+        Err("口令错误".to_string())
+    }
+
+    fn status(&mut self) -> AppLockStatus {
+        AppLockStatus {
+            enabled: self.config.enabled,
+            locked: self.is_locked(),
+            has_passcode: self.config.passcode_hash.is_some(),
+            biometrics_available: biometrics_available(),
+            auto_lock_minutes: self.config.auto_lock_minutes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub has_passcode: bool,
+    pub biometrics_available: bool,
+    pub auto_lock_minutes: u32,
+}
+
+fn config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(APP_LOCK_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn with_state<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut AppLockState) -> T) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<AppLockState>>>()
+        .ok_or("应用锁未初始化")?;
+    let mut lock = state.lock().map_err(|e| e.to_string())?;
+    Ok(f(&mut lock))
+}
+
+fn emit_status(app: &tauri::AppHandle) {
+    if let Ok(status) = with_state(app, |s| s.status()) {
+        let _ = app.emit("app-lock-changed", status);
+    }
+}
+
+// 返回数据的命令在开头调用：锁定时返回错误，未锁定时刷新活动时间
+pub fn ensure_unlocked(app: &tauri::AppHandle) -> Result<(), String> {
+    with_state(app, |s| s.touch(Instant::now()))?
+}
+
+// 不是用户操作触发的请求（如局域网同步的来访连接）用它检查，不刷新活动时间；读不到状态时按锁定处理
+pub fn is_locked(app: &tauri::AppHandle) -> bool {
+    with_state(app, |s| s.is_locked()).unwrap_or(true)
+}
+
+// 后台检查自动锁定，锁定时通知前端显示锁屏
+pub fn spawn_auto_lock_watcher(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut was_locked = with_state(&app, |s| s.is_locked()).unwrap_or(false);
+        loop {
+            thread::sleep(AUTO_LOCK_CHECK_INTERVAL);
+            let locked = with_state(&app, |s| s.is_locked()).unwrap_or(false);
+            if locked && !was_locked {
+                emit_status(&app);
+            }
+            was_locked = locked;
+        }
+    });
+}
+
+fn hash_passcode(passcode: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passcode.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn verify_passcode(passcode: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(passcode.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "windows")]
+fn biometrics_available() -> bool {
+    use windows::Security::Credentials::UI::{UserConsentVerifier, UserConsentVerifierAvailability};
+    UserConsentVerifier::CheckAvailabilityAsync()
+        .and_then(|op| op.get())
+        .map(|availability| availability == UserConsentVerifierAvailability::Available)
+        .unwrap_or(false)
+}
+
+// macOS Touch ID 需要 LocalAuthentication 绑定，暂未接入；其它平台使用口令解锁
+#[cfg(not(target_os = "windows"))]
+fn biometrics_available() -> bool {
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn verify_biometrics(reason: &str) -> Result<bool, String> {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{UserConsentVerificationResult, UserConsentVerifier};
+    UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason))
+        .and_then(|op| op.get())
+        .map(|result| result == UserConsentVerificationResult::Verified)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn verify_biometrics(_reason: &str) -> Result<bool, String> {
+    Err("当前平台不支持生物识别，请使用口令解锁".to_string())
+}
+
+#[tauri::command]
+pub fn get_app_lock_status(app: tauri::AppHandle) -> Result<AppLockStatus, String> {
+    with_state(&app, |s| s.status())
+}
+
+// 启用/关闭应用锁或修改口令、自动锁定时间（已启用时需要先解锁）
+#[tauri::command]
+pub fn configure_app_lock(
+    app: tauri::AppHandle,
+    enabled: bool,
+    passcode: Option<String>,
+    auto_lock_minutes: Option<u32>,
+) -> Result<AppLockStatus, String> {
+    ensure_unlocked(&app)?;
+
+    let passcode_hash = passcode
+        .filter(|p| !p.is_empty())
+        .map(|p| hash_passcode(&p))
+        .transpose()?;
+
+    let mut config = with_state(&app, |s| s.config.clone())?;
+    if let Some(hash) = passcode_hash {
+        config.passcode_hash = Some(hash);
+    }
+    if let Some(minutes) = auto_lock_minutes {
+        config.auto_lock_minutes = minutes;
+    }
+    config.enabled = enabled;
+
+    if config.enabled && config.passcode_hash.is_none() && !biometrics_available() {
+        return Err("启用应用锁需要先设置口令".to_string());
+    }
+
+    json_file::save(&config_path(&app)?, &config).map_err(|e| e.to_string())?;
+    with_state(&app, |s| {
+        s.config = config;
+        s.unlock();
+    })?;
+    emit_status(&app);
+    get_app_lock_status(app)
+}
+
+// 前端在用户操作时调用，推迟自动锁定
+#[tauri::command]
+pub fn touch_app_activity(app: tauri::AppHandle) -> Result<(), String> {
+    ensure_unlocked(&app)
+}
+
+#[tauri::command]
+pub fn lock_app(app: tauri::AppHandle) -> Result<(), String> {
+    with_state(&app, |s| s.locked = s.config.enabled)?;
+    emit_status(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unlock_app(app: tauri::AppHandle, passcode: String) -> Result<(), String> {
+    let (result, config) = with_state(&app, |s| {
+        (s.unlock_with_passcode(&passcode, Utc::now()), s.config.clone())
+    })?;
+    json_file::save(&config_path(&app)?, &config).map_err(|e| e.to_string())?;
+    result?;
+    emit_status(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unlock_app_with_biometrics(app: tauri::AppHandle) -> Result<(), String> {
+    if !verify_biometrics("解锁 Brew Guide")? {
+        return Err("验证未通过".to_string());
+    }
+    let config = with_state(&app, |s| {
+        s.unlock();
+        s.config.clone()
+    })?;
+    json_file::save(&config_path(&app)?, &config).map_err(|e| e.to_string())?;
+    emit_status(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked_state(passcode: &str) -> AppLockState {
+        AppLockState::new(AppLockConfig {
+            enabled: true,
+            passcode_hash: Some(hash_passcode(passcode).unwrap()),
+            ..AppLockConfig::default()
+        })
+    }
+
+    #[test]
+    fn auto_locks_after_inactivity() {
+        let mut state = locked_state("2580");
+        let start = Instant::now();
+        assert!(state.touch(start).is_err());
+        state.unlock_with_passcode("2580", Utc::now()).unwrap();
+        let start = state.last_activity;
+        assert!(state.touch(start + Duration::from_secs(4 * 60)).is_ok());
+        // 活动刷新了计时，从上次活动起算
+        assert!(!state.is_locked_at(start + Duration::from_secs(8 * 60)));
+        assert!(state.touch(start + Duration::from_secs(9 * 60)).is_err());
+
+        // 0 分钟表示不自动锁定；关闭应用锁后不再拦截
+        state.config.auto_lock_minutes = 0;
+        state.locked = false;
+        assert!(state.touch(start + Duration::from_secs(24 * 3600)).is_ok());
+        state.config.enabled = false;
+        state.locked = true;
+        assert!(state.touch(start).is_ok());
+    }
+
+    #[test]
+    fn changed_passcode_replaces_the_old_one() {
+        let mut state = locked_state("2580");
+        state.config.passcode_hash = Some(hash_passcode("1379").unwrap());
+        let now = Utc::now();
+        assert!(state.unlock_with_passcode("2580", now).is_err());
+        assert!(state.unlock_with_passcode("1379", now).is_ok());
+        assert_eq!(state.config.failed_attempts, 0);
+    }
+
+    #[test]
+    fn repeated_failures_back_off() {
+        assert_eq!(retry_delay(FREE_ATTEMPTS - 1), None);
+        assert_eq!(retry_delay(FREE_ATTEMPTS), Some(chrono::Duration::seconds(30)));
+        assert_eq!(retry_delay(FREE_ATTEMPTS + 1), Some(chrono::Duration::seconds(60)));
+        assert_eq!(retry_delay(u32::MAX), Some(chrono::Duration::seconds(MAX_RETRY_DELAY_SECS)));
+
+        let mut state = locked_state("2580");
+        let now = Utc::now();
+        for _ in 0..FREE_ATTEMPTS {
+            assert_eq!(state.unlock_with_passcode("0000", now).unwrap_err(), "口令错误");
+        }
+        // 等待期间连正确的口令也不接受
+        assert!(state.unlock_with_passcode("2580", now).unwrap_err().contains("30 秒"));
+        let later = now + chrono::Duration::seconds(31);
+        assert!(state.unlock_with_passcode("2580", later).is_ok());
+        assert_eq!(state.config.retry_after_ms, None);
+    }
+}
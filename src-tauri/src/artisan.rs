@@ -0,0 +1,414 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+use serde_json::{json, Map, Number, Value};
+use std::path::Path;
+
+use crate::journal;
+use crate::store::{atomically, number_field, record_data, text_field, with_store, write_record, RecordKind};
+
+// 导入 Artisan 的 .alog 烘焙记录（Python 字典的 repr 文本），保存为烘焙批次并可关联到一款咖啡豆
+// 关联的咖啡豆没有烘焙日期、烘焙度时用这次烘焙的数据补上
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+const BATCH_COLUMNS: &str = "id, bean_id, title, roast_date, green_weight, roasted_weight, weight_loss, \
+first_crack_time, drop_time, development_time, development_ratio, roast_level, source, imported_at";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoastBatch {
+    pub id: String,
+    pub bean_id: Option<String>,
+    pub title: String,
+    pub roast_date: Option<String>,
+    pub green_weight: Option<f64>,
+    pub roasted_weight: Option<f64>,
+    pub weight_loss: Option<f64>,
+    pub first_crack_time: Option<f64>,
+    pub drop_time: Option<f64>,
+    pub development_time: Option<f64>,
+    pub development_ratio: Option<f64>,
+    pub roast_level: Option<String>,
+    pub source: String,
+    pub imported_at: i64,
+}
+
+// Python 字面量（dict、list、tuple、字符串、数字、True/False/None）转换为 JSON
+struct PythonLiteral<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl PythonLiteral<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(format!("位置 {} 应为 {}，实际为 {}", i, expected, c)),
+            None => Err(format!("文件意外结束，应为 {}", expected)),
+        }
+    }
+
+    // 读取逗号分隔的元素直到 close，允许末尾多一个逗号
+    fn items(&mut self, close: char, mut item: impl FnMut(&mut Self) -> Result<(), String>) -> Result<(), String> {
+        loop {
+            self.skip_whitespace();
+            if self.chars.next_if(|(_, c)| *c == close).is_some() {
+                return Ok(());
+            }
+            item(self)?;
+            self.skip_whitespace();
+            if self.chars.next_if(|(_, c)| *c == ',').is_none() {
+                return self.expect(close);
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        let (index, c) = *self.chars.peek().ok_or("文件意外结束")?;
+        match c {
+            '{' => {
+                self.chars.next();
+                let mut map = Map::new();
+                self.items('}', |parser| {
+                    // Artisan 的字典键都是字符串，偶尔是数字
+                    let key = match parser.value()? {
+                        Value::String(key) => key,
+                        other => other.to_string(),
+                    };
+                    parser.expect(':')?;
+                    map.insert(key, parser.value()?);
+                    Ok(())
+                })?;
+                Ok(Value::Object(map))
+            }
+            '[' | '(' => {
+                self.chars.next();
+                let mut items = Vec::new();
+                self.items(if c == '[' { ']' } else { ')' }, |parser| {
+                    items.push(parser.value()?);
+                    Ok(())
+                })?;
+                Ok(Value::Array(items))
+            }
+            '\'' | '"' => self.string(),
+            'u' | 'b' | 'r' if matches!(self.peek_second(), Some('\'' | '"')) => {
+                self.chars.next();
+                self.string()
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_alphanumeric() || matches!(*c, '.' | '-' | '+' | '_')) {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "True" => Ok(Value::Bool(true)),
+                    "False" => Ok(Value::Bool(false)),
+                    // 没有测量值时 Artisan 写入 nan
+                    "None" | "nan" | "inf" | "-inf" => Ok(Value::Null),
+                    _ => word
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(Number::from_f64)
+                        .map(Value::Number)
+                        .ok_or_else(|| format!("位置 {} 无法识别的内容：{}", index, word)),
+                }
+            }
+        }
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        let mut ahead = self.chars.clone();
+        ahead.next();
+        ahead.next().map(|(_, c)| c)
+    }
+
+    fn string(&mut self) -> Result<Value, String> {
+        let (_, quote) = self.chars.next().ok_or("文件意外结束")?;
+        let mut text = String::new();
+        loop {
+            let (_, c) = self.chars.next().ok_or("字符串没有结束")?;
+            if c == quote {
+                return Ok(Value::String(text));
+            }
+            if c != '\\' {
+                text.push(c);
+                continue;
+            }
+            let (_, escaped) = self.chars.next().ok_or("字符串没有结束")?;
+            match escaped {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                'r' => text.push('\r'),
+                'x' | 'u' | 'U' => {
+                    let digits = match escaped {
+                        'x' => 2,
+                        'u' => 4,
+                        _ => 8,
+                    };
+                    let hex: String = (0..digits).filter_map(|_| self.chars.next().map(|(_, c)| c)).collect();
+                    let code = u32::from_str_radix(&hex, 16).map_err(|_| format!("无效的转义：\\{}{}", escaped, hex))?;
+                    text.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                }
+                other => text.push(other),
+            }
+        }
+    }
+}
+
+fn parse_alog(text: &str) -> Result<Map<String, Value>, String> {
+    let mut parser = PythonLiteral {
+        chars: text.char_indices().peekable(),
+    };
+    match parser.value().map_err(|e| format!("不是有效的 Artisan 烘焙记录：{}", e))? {
+        Value::Object(log) => Ok(log),
+        _ => Err("不是有效的 Artisan 烘焙记录".to_string()),
+    }
+}
+
+fn grams(amount: f64, unit: &str) -> f64 {
+    match unit.to_lowercase().as_str() {
+        "kg" => amount * 1000.0,
+        "lb" => amount * 453.592_37,
+        "oz" => amount * 28.349_52,
+        _ => amount,
+    }
+}
+
+fn round1(n: f64) -> f64 {
+    (n * 10.0).round() / 10.0
+}
+
+fn roast_date(log: &Value) -> Option<NaiveDate> {
+    if let Some(date) = text_field(log, "roastisodate").and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+        return Some(date);
+    }
+    if let Some(epoch) = number_field(log, "roastepoch") {
+        return chrono::DateTime::from_timestamp(epoch as i64, 0).map(|at| at.with_timezone(&chrono::Local).date_naive());
+    }
+    // 旧版本只有 Qt 格式的日期，如 “Sat Oct 12 2026”
+    text_field(log, "roastdate").and_then(|d| NaiveDate::parse_from_str(d.trim(), "%a %b %d %Y").ok())
+}
+
+// 有 Agtron 色值时按色值判断，否则按失重率估计
+fn roast_level(ground_color: Option<f64>, weight_loss: Option<f64>) -> Option<&'static str> {
+    if let Some(color) = ground_color.filter(|c| *c > 0.0) {
+        return Some(match color {
+            c if c >= 80.0 => "极浅烘焙",
+            c if c >= 70.0 => "浅度烘焙",
+            c if c >= 60.0 => "中浅烘焙",
+            c if c >= 50.0 => "中度烘焙",
+            c if c >= 40.0 => "中深烘焙",
+            _ => "深度烘焙",
+        });
+    }
+    Some(match weight_loss.filter(|w| *w > 0.0)? {
+        w if w < 12.0 => "浅度烘焙",
+        w if w < 14.0 => "中浅烘焙",
+        w if w < 16.0 => "中度烘焙",
+        w if w < 18.0 => "中深烘焙",
+        _ => "深度烘焙",
+    })
+}
+
+fn roast_batch(log: &Map<String, Value>, bytes: &[u8], source: &str, bean_id: Option<String>) -> RoastBatch {
+    let log_value = Value::Object(log.clone());
+    let computed = log.get("computed").cloned().unwrap_or_default();
+    let (green_weight, roasted_weight) = match log.get("weight").and_then(Value::as_array).map(Vec::as_slice) {
+        Some([green, roasted, unit, ..]) => {
+            let unit = unit.as_str().unwrap_or("g");
+            let weight = |w: &Value| w.as_f64().filter(|w| *w > 0.0).map(|w| round1(grams(w, unit)));
+            (weight(green), weight(roasted))
+        }
+        _ => (None, None),
+    };
+    let weight_loss = match (green_weight, roasted_weight) {
+        (Some(green), Some(roasted)) => Some(round1((green - roasted) / green * 100.0)),
+        _ => number_field(&computed, "weight_loss").filter(|w| *w > 0.0),
+    };
+    let first_crack_time = number_field(&computed, "FCs_time").filter(|t| *t > 0.0);
+    let drop_time = number_field(&computed, "DROP_time").or_else(|| number_field(&computed, "totaltime")).filter(|t| *t > 0.0);
+    let development_time = match (first_crack_time, drop_time) {
+        (Some(crack), Some(drop)) if drop > crack => Some(round1(drop - crack)),
+        _ => None,
+    };
+    let id = text_field(&log_value, "roastUUID")
+        .map(|uuid| format!("artisan-{}", uuid))
+        .unwrap_or_else(|| format!("artisan-{}", &blake3::hash(bytes).to_hex()[..16]));
+    let title = text_field(&log_value, "title")
+        .map(str::trim)
+        .filter(|t| !t.is_empty() && *t != "Roaster Scope")
+        .or_else(|| text_field(&log_value, "beans").map(str::trim).filter(|b| !b.is_empty()))
+        .unwrap_or(source);
+    RoastBatch {
+        id,
+        bean_id,
+        title: title.lines().next().unwrap_or_default().to_string(),
+        roast_date: roast_date(&log_value).map(|d| d.format("%Y-%m-%d").to_string()),
+        green_weight,
+        roasted_weight,
+        weight_loss,
+        first_crack_time,
+        drop_time,
+        development_time,
+        development_ratio: development_time.zip(drop_time).map(|(dev, drop)| round1(dev / drop * 100.0)),
+        roast_level: roast_level(number_field(&log_value, "ground_color"), weight_loss).map(str::to_string),
+        source: source.to_string(),
+        imported_at: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+fn batch_from_row(row: &Row) -> rusqlite::Result<RoastBatch> {
+    Ok(RoastBatch {
+        id: row.get(0)?,
+        bean_id: row.get(1)?,
+        title: row.get(2)?,
+        roast_date: row.get(3)?,
+        green_weight: row.get(4)?,
+        roasted_weight: row.get(5)?,
+        weight_loss: row.get(6)?,
+        first_crack_time: row.get(7)?,
+        drop_time: row.get(8)?,
+        development_time: row.get(9)?,
+        development_ratio: row.get(10)?,
+        roast_level: row.get(11)?,
+        source: row.get(12)?,
+        imported_at: row.get(13)?,
+    })
+}
+
+// 重复导入同一份记录时更新；没有指定咖啡豆时保留原来的关联
+fn save_batch(conn: &Connection, batch: &RoastBatch) -> rusqlite::Result<RoastBatch> {
+    conn.execute(
+        &format!(
+            "INSERT INTO roast_batches ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+             ON CONFLICT(id) DO UPDATE SET bean_id = COALESCE(?2, bean_id), title = ?3, roast_date = ?4,
+             green_weight = ?5, roasted_weight = ?6, weight_loss = ?7, first_crack_time = ?8, drop_time = ?9,
+             development_time = ?10, development_ratio = ?11, roast_level = ?12, source = ?13, imported_at = ?14",
+            BATCH_COLUMNS
+        ),
+        params![
+            batch.id,
+            batch.bean_id,
+            batch.title,
+            batch.roast_date,
+            batch.green_weight,
+            batch.roasted_weight,
+            batch.weight_loss,
+            batch.first_crack_time,
+            batch.drop_time,
+            batch.development_time,
+            batch.development_ratio,
+            batch.roast_level,
+            batch.source,
+            batch.imported_at,
+        ],
+    )?;
+    conn.query_row(
+        &format!("SELECT {} FROM roast_batches WHERE id = ?1", BATCH_COLUMNS),
+        [&batch.id],
+        batch_from_row,
+    )
+}
+
+// 咖啡豆缺少烘焙日期、烘焙度时用烘焙批次的数据补上
+fn fill_bean(conn: &Connection, batch: &RoastBatch) -> rusqlite::Result<bool> {
+    let Some(bean_id) = &batch.bean_id else {
+        return Ok(false);
+    };
+    let Some(mut bean) = record_data(conn, RecordKind::Bean, bean_id)?.and_then(|data| serde_json::from_str::<Value>(&data).ok())
+    else {
+        return Ok(false);
+    };
+    let mut changed = false;
+    for (key, value) in [("roastDate", &batch.roast_date), ("roastLevel", &batch.roast_level)] {
+        if let (Some(value), None) = (value, text_field(&bean, key).filter(|v| !v.is_empty())) {
+            bean[key] = json!(value);
+            changed = true;
+        }
+    }
+    if changed {
+        journal::track(conn, RecordKind::Bean, bean_id, || write_record(conn, RecordKind::Bean, &bean))?;
+    }
+    Ok(changed)
+}
+
+fn import(conn: &Connection, batch: &RoastBatch) -> rusqlite::Result<(RoastBatch, bool)> {
+    atomically(conn, || {
+        let saved = save_batch(conn, batch)?;
+        let bean_changed = fill_bean(conn, &saved)?;
+        Ok((saved, bean_changed))
+    })
+}
+
+// 导入一份 .alog 烘焙记录，bean_id 为要关联的咖啡豆
+#[tauri::command]
+pub fn import_artisan_log(app: tauri::AppHandle, path: String, bean_id: Option<String>) -> Result<RoastBatch, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let path = Path::new(&path);
+    if std::fs::metadata(path).map_err(|e| e.to_string())?.len() > MAX_FILE_BYTES {
+        return Err("文件过大".to_string());
+    }
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let log = parse_alog(&String::from_utf8_lossy(&bytes))?;
+    let source = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let batch = roast_batch(&log, &bytes, &source, bean_id.filter(|id| !id.is_empty()));
+    let (saved, bean_changed) = with_store(&app, |store| import(&store.conn, &batch))?;
+    if bean_changed {
+        crate::store::publish_changes(&app)?;
+    }
+    crate::telemetry::record(&app, "import.artisan");
+    Ok(saved)
+}
+
+// 烘焙批次列表（按烘焙日期倒序），指定 bean_id 时只返回这款咖啡豆的
+#[tauri::command]
+pub fn list_roast_batches(app: tauri::AppHandle, bean_id: Option<String>) -> Result<Vec<RoastBatch>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, |store| {
+        let mut stmt = store.conn.prepare(&format!(
+            "SELECT {} FROM roast_batches WHERE ?1 IS NULL OR bean_id = ?1 ORDER BY roast_date DESC, imported_at DESC",
+            BATCH_COLUMNS
+        ))?;
+        let rows = stmt.query_map([&bean_id], batch_from_row)?;
+        rows.collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_an_alog_and_fills_the_linked_bean() {
+        let alog = r#"{'version': '2.10.6', 'roastUUID': 'a1b2', 'title': 'Ethiopia Guji', 'beans': 'Guji\nwashed',
+ 'roastisodate': '2026-10-12', 'weight': [0.25, 0.213, 'Kg'], 'ground_color': 0, 'flavors': (5.0, 6.5),
+ 'computed': {'FCs_time': 492.5, 'DROP_time': 615.0, 'weight_loss': 14.8, 'det': nan},
+ 'roastertype': u'Huky 500', 'notes': "it's 很甜", 'heavyFC': False, 'timeindex': [0, 12, -1]}"#;
+        let log = parse_alog(alog).unwrap();
+        assert_eq!(log["notes"], "it's 很甜");
+        assert_eq!(log["computed"]["det"], Value::Null);
+
+        let batch = roast_batch(&log, alog.as_bytes(), "guji.alog", Some("b1".to_string()));
+        assert_eq!(batch.id, "artisan-a1b2");
+        assert_eq!(batch.roast_date.as_deref(), Some("2026-10-12"));
+        assert_eq!((batch.green_weight, batch.roasted_weight, batch.weight_loss), (Some(250.0), Some(213.0), Some(14.8)));
+        assert_eq!((batch.development_time, batch.development_ratio), (Some(122.5), Some(19.9)));
+        assert_eq!(batch.roast_level.as_deref(), Some("中度烘焙"));
+
+        let conn = crate::store::test_connection();
+        crate::store::write_bean(&conn, &json!({ "id": "b1", "name": "古吉", "roastLevel": "浅度烘焙" })).unwrap();
+        let (saved, changed) = import(&conn, &batch).unwrap();
+        assert!(changed);
+        assert_eq!(saved, batch);
+        let bean: Value = serde_json::from_str(&record_data(&conn, RecordKind::Bean, "b1").unwrap().unwrap()).unwrap();
+        assert_eq!((bean["roastDate"].as_str(), bean["roastLevel"].as_str()), (Some("2026-10-12"), Some("浅度烘焙")));
+
+        // 重新导入时不指定咖啡豆，保留原来的关联
+        let (saved, changed) = import(&conn, &RoastBatch { bean_id: None, ..batch }).unwrap();
+        assert_eq!((saved.bean_id.as_deref(), changed), (Some("b1"), false));
+    }
+}
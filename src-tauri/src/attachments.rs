@@ -0,0 +1,206 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::Manager;
+
+use crate::store::{with_store, RecordKind};
+
+// 图片附件：按内容的 BLAKE3 哈希去重，同一张图片只保存一份
+// 咖啡豆、笔记的图片字段保存相对路径 attachments/{hash}.{ext}，写入记录时同步引用关系，
+// gc_attachments 删除没有任何记录（包括回收站中的）引用的文件
+pub(crate) const ATTACHMENTS_DIR: &str = "attachments";
+const MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub hash: String,
+    pub path: String, // 相对应用数据目录的路径，保存到图片字段中
+    pub file: String, // 绝对路径，前端用 convertFileSrc 显示
+    pub size: usize,
+    pub mime: &'static str,
+    pub deduplicated: bool, // 已有相同内容的附件
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcResult {
+    pub removed: usize,
+    pub freed_bytes: u64,
+}
+
+// 根据文件头判断图片格式
+fn sniff(bytes: &[u8]) -> (&'static str, &'static str) {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => ("image/jpeg", "jpg"),
+        [0x89, b'P', b'N', b'G', ..] => ("image/png", "png"),
+        [b'G', b'I', b'F', b'8', ..] => ("image/gif", "gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => ("image/webp", "webp"),
+        [_, _, _, _, b'f', b't', b'y', b'p', b'h', b'e', b'i', b'c', ..] => ("image/heic", "heic"),
+        _ => ("application/octet-stream", "bin"),
+    }
+}
+
+// 附件路径中的哈希，不是附件路径时返回 None
+fn hash_of(reference: &str) -> Option<&str> {
+    let name = reference.trim().strip_prefix(ATTACHMENTS_DIR)?.strip_prefix('/')?;
+    let hash = name.split('.').next()?;
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then_some(hash)
+}
+
+fn collect_hashes<'a>(value: &'a Value, hashes: &mut HashSet<&'a str>) {
+    match value {
+        Value::String(s) => hashes.extend(hash_of(s)),
+        Value::Array(items) => items.iter().for_each(|item| collect_hashes(item, hashes)),
+        Value::Object(map) => map.values().for_each(|item| collect_hashes(item, hashes)),
+        _ => {}
+    }
+}
+
+// 写入咖啡豆或笔记时更新它引用的附件
+pub fn sync_refs(conn: &Connection, kind: RecordKind, id: &str, value: &Value) -> rusqlite::Result<()> {
+    let mut hashes = HashSet::new();
+    collect_hashes(value, &mut hashes);
+    conn.execute(
+        "DELETE FROM attachment_refs WHERE kind = ?1 AND entity_id = ?2",
+        [kind.key(), id],
+    )?;
+    for hash in hashes {
+        conn.execute(
+            "INSERT OR IGNORE INTO attachment_refs (hash, kind, entity_id) VALUES (?1, ?2, ?3)",
+            [hash, kind.key(), id],
+        )?;
+    }
+    Ok(())
+}
+
+fn save(conn: &Connection, data_dir: &Path, bytes: &[u8]) -> Result<Attachment, String> {
+    if bytes.is_empty() {
+        return Err("附件内容为空".to_string());
+    }
+    if bytes.len() > MAX_ATTACHMENT_BYTES {
+        return Err(format!("附件过大：{} 字节", bytes.len()));
+    }
+    let hash = blake3::hash(bytes).to_hex().to_string();
+    let (mime, ext) = sniff(bytes);
+    let existing: Option<String> = conn
+        .query_row("SELECT path FROM attachments WHERE hash = ?1", [&hash], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let path = existing.clone().unwrap_or_else(|| format!("{}/{}.{}", ATTACHMENTS_DIR, hash, ext));
+    let file = data_dir.join(&path);
+    // 记录还在但文件被删除时重新写入
+    if !file.exists() {
+        std::fs::create_dir_all(data_dir.join(ATTACHMENTS_DIR)).map_err(|e| e.to_string())?;
+        std::fs::write(&file, bytes).map_err(|e| e.to_string())?;
+    }
+    if existing.is_none() {
+        conn.execute(
+            "INSERT INTO attachments (hash, path, size, mime, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![hash, path, bytes.len() as i64, mime, chrono::Utc::now().timestamp_millis()],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(Attachment {
+        hash,
+        path,
+        file: file.to_string_lossy().into_owned(),
+        size: bytes.len(),
+        mime,
+        deduplicated: existing.is_some(),
+    })
+}
+
+// 删除没有引用的附件，以及附件目录中没有记录的文件
+// 刚保存、还没写入记录的附件保留一小时，避免和正在编辑的咖啡豆冲突
+fn gc(conn: &Connection, data_dir: &Path) -> rusqlite::Result<GcResult> {
+    let grace = chrono::Utc::now().timestamp_millis() - 60 * 60 * 1000;
+    let mut stmt = conn.prepare(
+        "SELECT hash, path FROM attachments
+         WHERE created_at < ?1 AND hash NOT IN (SELECT hash FROM attachment_refs)",
+    )?;
+    let unused: Vec<(String, String)> = stmt
+        .query_map([grace], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let mut result = GcResult::default();
+    let mut remove_file = |path: &Path| {
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        match std::fs::remove_file(path) {
+            Ok(()) => {
+                result.removed += 1;
+                result.freed_bytes += size;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!("删除附件 {} 失败：{}", path.display(), e),
+        }
+    };
+    for (hash, path) in &unused {
+        remove_file(&data_dir.join(path));
+        conn.execute("DELETE FROM attachments WHERE hash = ?1", [hash])?;
+    }
+
+    let known: HashSet<String> = conn
+        .prepare("SELECT path FROM attachments")?
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    if let Ok(entries) = std::fs::read_dir(data_dir.join(ATTACHMENTS_DIR)) {
+        for entry in entries.flatten() {
+            let path = format!("{}/{}", ATTACHMENTS_DIR, entry.file_name().to_string_lossy());
+            if !known.contains(&path) {
+                remove_file(&entry.path());
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn data_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+// 保存图片，内容相同的图片只保存一份；返回的 path 写入咖啡豆或笔记的图片字段
+#[tauri::command]
+pub fn save_attachment(app: tauri::AppHandle, bytes: Vec<u8>) -> Result<Attachment, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let dir = data_dir(&app)?;
+    with_store(&app, |store| Ok(save(&store.conn, &dir, &bytes)))?
+}
+
+#[tauri::command]
+pub fn gc_attachments(app: tauri::AppHandle) -> Result<GcResult, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let dir = data_dir(&app)?;
+    let result = with_store(&app, |store| gc(&store.conn, &dir))?;
+    if result.removed > 0 {
+        log::info!("已清理 {} 个附件，释放 {} 字节", result.removed, result.freed_bytes);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn stores_identical_images_once_and_tracks_references() {
+        let conn = crate::store::test_connection();
+        let dir = std::env::temp_dir().join(format!("brew-guide-attachments-{}", std::process::id()));
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 1, 2, 3];
+        let first = save(&conn, &dir, &png).unwrap();
+        let second = save(&conn, &dir, &png).unwrap();
+        assert_eq!(first.path, second.path);
+        assert!(first.path.ends_with(".png"));
+        assert!(second.deduplicated);
+
+        let bean = json!({ "id": "b1", "name": "耶加雪菲", "image": first.path });
+        sync_refs(&conn, RecordKind::Bean, "b1", &bean).unwrap();
+        let refs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM attachment_refs WHERE hash = ?1", [&first.hash], |row| row.get(0))
+            .unwrap();
+        assert_eq!(refs, 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,224 @@
+use serde::Serialize;
+use std::sync::{mpsc, Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+use crate::recipe::{Recipe, StageKind};
+use crate::settings::AudioSettings;
+
+// 冲煮提示音：切换步骤的提示音、步骤结束前的 3-2-1 倒数和注水节拍器
+// 由后端计时任务按实际经过的时间触发，webview 卡顿时也不会错拍
+// macOS / Windows 用 rodio 在独立线程播放合成的音调；其他平台（Linux 构建需要 ALSA 开发库、移动端）
+// 只发出 brew-cue 事件，由前端播放
+const COUNTDOWN_SECONDS: u8 = 3;
+// 节拍器按流速打拍时每拍对应的注水量
+const BEAT_GRAMS: f64 = 10.0;
+// 计时跳变（跳到下一步、系统休眠）时不补放之前错过的提示音
+const MAX_CATCH_UP: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Cue {
+    StageChange,
+    Countdown(u8),
+    Tick,
+    Finished,
+}
+
+// 频率（Hz）、时长和相对提示开始的延迟（毫秒）
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tone {
+    freq: f32,
+    millis: u64,
+    delay: u64,
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const fn tone(freq: f32, millis: u64, delay: u64) -> Tone {
+    Tone { freq, millis, delay }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const STAGE_CHANGE_TONES: [Tone; 2] = [tone(880.0, 120, 0), tone(1320.0, 160, 120)];
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const COUNTDOWN_TONES: [Tone; 1] = [tone(660.0, 80, 0)];
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const TICK_TONES: [Tone; 1] = [tone(1000.0, 30, 0)];
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const FINISHED_TONES: [Tone; 3] = [tone(1320.0, 120, 0), tone(880.0, 120, 140), tone(660.0, 240, 280)];
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn tones(cue: Cue) -> &'static [Tone] {
+    match cue {
+        Cue::StageChange => &STAGE_CHANGE_TONES,
+        Cue::Countdown(_) => &COUNTDOWN_TONES,
+        Cue::Tick => &TICK_TONES,
+        Cue::Finished => &FINISHED_TONES,
+    }
+}
+
+fn beat_interval(settings: &AudioSettings) -> f64 {
+    match settings.flow_target.filter(|flow| *flow > 0.0) {
+        Some(flow) => BEAT_GRAMS / flow,
+        None => 60.0 / settings.metronome_bpm.max(1) as f64,
+    }
+}
+
+// 计时从 from 秒走到 to 秒之间（不含 from，含 to）应该播放的提示音，按时间排序
+pub fn cues_between(recipe: &Recipe, settings: &AudioSettings, from: f64, to: f64) -> Vec<Cue> {
+    let from = from.max(to - MAX_CATCH_UP);
+    let within = |time: f64| time > from && time <= to;
+    let mut cues: Vec<(f64, Cue)> = Vec::new();
+    for stage in &recipe.stages {
+        if stage.end <= stage.start {
+            continue;
+        }
+        if settings.step_chime && within(stage.start) {
+            cues.push((stage.start, Cue::StageChange));
+        }
+        if settings.countdown {
+            for n in 1..=COUNTDOWN_SECONDS {
+                let time = stage.end - n as f64;
+                if time > stage.start && within(time) {
+                    cues.push((time, Cue::Countdown(n)));
+                }
+            }
+        }
+        if settings.metronome && matches!(stage.kind, StageKind::Pour | StageKind::Bloom) {
+            let interval = beat_interval(settings);
+            let mut beat = ((from.max(stage.start) - stage.start) / interval).floor().max(0.0) + 1.0;
+            loop {
+                let time = stage.start + beat * interval;
+                if time >= stage.end || time > to {
+                    break;
+                }
+                if within(time) {
+                    cues.push((time, Cue::Tick));
+                }
+                beat += 1.0;
+            }
+        }
+    }
+    if within(recipe.total_time) {
+        cues.push((recipe.total_time, Cue::Finished));
+    }
+    cues.sort_by(|a, b| a.0.total_cmp(&b.0));
+    cues.into_iter().map(|(_, cue)| cue).collect()
+}
+
+// 播放线程的发送端，没有音频输出的平台为 None
+#[derive(Debug, Default)]
+pub struct AudioPlayer {
+    sender: Option<mpsc::Sender<(Cue, f32)>>,
+}
+
+// 打开输出设备失败（没有声卡、设备被占用）时下次播放再试
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn play_loop(receiver: mpsc::Receiver<(Cue, f32)>) {
+    use rodio::source::{SineWave, Source};
+    use std::time::Duration;
+
+    let mut output: Option<(rodio::OutputStream, rodio::OutputStreamHandle)> = None;
+    for (cue, volume) in receiver {
+        if output.is_none() {
+            match rodio::OutputStream::try_default() {
+                Ok(stream) => output = Some(stream),
+                Err(e) => {
+                    log::warn!("打开音频输出失败：{}", e);
+                    continue;
+                }
+            }
+        }
+        let Some((_, handle)) = output.as_ref() else {
+            continue;
+        };
+        for tone in tones(cue) {
+            let source = SineWave::new(tone.freq)
+                .take_duration(Duration::from_millis(tone.millis))
+                .fade_in(Duration::from_millis(5))
+                .amplify(volume)
+                .delay(Duration::from_millis(tone.delay));
+            if let Err(e) = handle.play_raw(source) {
+                log::warn!("播放提示音失败：{}", e);
+                output = None;
+                break;
+            }
+        }
+    }
+}
+
+impl AudioPlayer {
+    pub fn spawn() -> Self {
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
+        {
+            let (sender, receiver) = mpsc::channel();
+            match std::thread::Builder::new().name("audio".into()).spawn(move || play_loop(receiver)) {
+                Ok(_) => return Self { sender: Some(sender) },
+                Err(e) => log::warn!("启动音频线程失败：{}", e),
+            }
+        }
+        Self::default()
+    }
+}
+
+pub fn play(app: &tauri::AppHandle, cue: Cue, settings: &AudioSettings) {
+    let _ = app.emit("brew-cue", cue);
+    let sender = app
+        .try_state::<Arc<Mutex<AudioPlayer>>>()
+        .and_then(|player| player.lock().ok().and_then(|player| player.sender.clone()));
+    if let Some(sender) = sender {
+        let _ = sender.send((cue, settings.volume.clamp(0.0, 1.0)));
+    }
+}
+
+#[tauri::command]
+pub fn set_audio_cues(app: tauri::AppHandle, audio: AudioSettings) -> Result<(), String> {
+    if !(20..=240).contains(&audio.metronome_bpm) {
+        return Err("节拍器速度应在 20–240 BPM 之间".to_string());
+    }
+    if audio.flow_target.is_some_and(|flow| !flow.is_finite() || flow <= 0.0) {
+        return Err("注水流速应大于 0".to_string());
+    }
+    if !(0.0..=1.0).contains(&audio.volume) {
+        return Err("音量应在 0–1 之间".to_string());
+    }
+    crate::settings::update(&app, |s| s.audio = audio)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn schedules_cues_from_the_timer() {
+        let method = json!({ "params": { "water": "225g", "stages": [
+            { "pourType": "circle", "label": "焖蒸", "water": "30", "duration": 10 },
+            { "pourType": "wait", "label": "等待", "duration": 20 },
+            { "pourType": "circle", "label": "注水", "water": "195", "duration": 30 },
+        ] } });
+        let recipe = Recipe::from_method(&method).unwrap();
+        let settings = AudioSettings::default();
+
+        assert_eq!(cues_between(&recipe, &settings, -1.0, 0.05), [Cue::StageChange]);
+        assert_eq!(cues_between(&recipe, &settings, 6.9, 7.0), [Cue::Countdown(3)]);
+        assert_eq!(cues_between(&recipe, &settings, 9.9, 10.05), [Cue::StageChange]);
+        assert_eq!(cues_between(&recipe, &settings, 59.9, 60.0), [Cue::Finished]);
+        // 跳到下一步时不补放错过的倒数
+        assert_eq!(cues_between(&recipe, &settings, 12.0, 30.0), [Cue::StageChange]);
+
+        let metronome = AudioSettings {
+            metronome: true,
+            flow_target: Some(5.0),
+            countdown: false,
+            ..AudioSettings::default()
+        };
+        // 每 10g 一拍，5g/s 即每 2 秒一拍；等待步骤不打拍
+        let ticks = (0..600)
+            .flat_map(|i| cues_between(&recipe, &metronome, i as f64 * 0.1, (i + 1) as f64 * 0.1))
+            .filter(|cue| *cue == Cue::Tick)
+            .count();
+        assert_eq!(ticks, 4 + 14);
+    }
+}
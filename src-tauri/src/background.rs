@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::json_file;
+use crate::CoffeeBean;
+
+// 检查日期变化的间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// 咖啡豆缓存保存在 tray-beans.json，启动时托盘直接用它渲染，不必等前端加载完成
+const BEAN_CACHE_FILE: &str = "tray-beans.json";
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SavedBeans {
+    beans: Vec<CoffeeBean>,
+}
+
+// 前端最近一次推送的咖啡豆数据，用于在没有前端参与时重新计算赏味期
+#[derive(Default)]
+pub struct BeanCache {
+    pub beans: Vec<CoffeeBean>,
+    refreshed_on: Option<chrono::NaiveDate>,
+}
+
+fn cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(BEAN_CACHE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+impl BeanCache {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let saved: SavedBeans = cache_path(app)
+            .ok()
+            .and_then(|path| {
+                json_file::load(&path)
+                    .map_err(|e| log::warn!("读取咖啡豆缓存失败：{}", e))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self {
+            beans: saved.beans,
+            refreshed_on: None,
+        }
+    }
+
+    pub fn replace(&mut self, beans: Vec<CoffeeBean>, today: chrono::NaiveDate) {
+        self.beans = beans;
+        self.refreshed_on = Some(today);
+    }
+
+    // 扣减剩余量（与前端 formatCoffeeBeanAmount 一致，保留一位小数）
+    pub fn deduct_remaining(&mut self, bean_id: &str, grams: f64) {
+        let Some(bean) = self.beans.iter_mut().find(|b| b.id == bean_id) else {
+            return;
+        };
+        let remaining = (bean.remaining_grams().unwrap_or(0.0) - grams).max(0.0);
+        let rounded = (remaining * 10.0).round() / 10.0;
+        bean.remaining = Some(if rounded.fract() == 0.0 {
+            format!("{}", rounded as i64)
+        } else {
+            format!("{:.1}", rounded)
+        });
+    }
+}
+
+// 保存咖啡豆缓存；托盘上的操作（扣减用量、标记喝完等）会先改缓存再刷新，统一在刷新时保存
+pub fn save_cache(app: &tauri::AppHandle, beans: &[CoffeeBean]) {
+    let saved = SavedBeans { beans: beans.to_vec() };
+    if let Err(e) = cache_path(app).and_then(|path| json_file::save(&path, &saved).map_err(|e| e.to_string())) {
+        log::warn!("保存咖啡豆缓存失败：{}", e);
+    }
+}
+
+// 启动时用上次保存的咖啡豆数据渲染托盘，没有保存过时保持「加载中…」
+pub fn render_saved(app: &tauri::AppHandle) {
+    let has_beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| !cache.beans.is_empty()))
+        .unwrap_or(false);
+    if !has_beans {
+        return;
+    }
+    if let Err(e) = refresh(app) {
+        log::warn!("用保存的数据渲染托盘失败：{}", e);
+    }
+}
+
+// 用缓存的咖啡豆数据重新计算赏味期并刷新托盘、小组件快照
+pub fn refresh(app: &tauri::AppHandle) -> Result<(), String> {
+    let today = crate::today(app);
+    let beans = match app.try_state::<Arc<Mutex<BeanCache>>>() {
+        Some(state) => {
+            let mut cache = state.lock().map_err(|e| e.to_string())?;
+            cache.refreshed_on = Some(today);
+            cache.beans.clone()
+        }
+        None => return Ok(()),
+    };
+    save_cache(app, &beans);
+    crate::update_tray_with_beans(app, beans)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// 跨过零点后需要刷新（赏味期天数每天变化）
+fn needs_refresh(app: &tauri::AppHandle) -> bool {
+    let today = crate::today(app);
+    app.try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| {
+            state
+                .lock()
+                .ok()
+                .map(|cache| cache.refreshed_on.is_some_and(|day| day != today))
+        })
+        .unwrap_or(false)
+}
+
+// 桌面端：后台线程定期检查日期变化，窗口关闭到托盘时也能保持赏味期天数正确
+pub fn spawn_refresh_loop(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(REFRESH_INTERVAL);
+        if needs_refresh(&app) {
+            if let Err(e) = refresh(&app) {
+                log::warn!("后台刷新失败：{}", e);
+                crate::diagnostics::record_error(&app, "background", e);
+            }
+            if let Err(e) = crate::notifications::check_freshness_changes(&app) {
+                log::warn!("检查赏味期变化失败：{}", e);
+            }
+            if let Err(e) = crate::weekly_report::check(&app) {
+                log::warn!("生成周报失败：{}", e);
+            }
+        }
+        crate::notifications::flush_pending(&app);
+    });
+}
+
+// 移动端：由 BGTaskScheduler（iOS）/ WorkManager（Android）的后台任务调用
+#[tauri::command]
+pub fn run_background_refresh(app: tauri::AppHandle) -> Result<(), String> {
+    refresh(&app)?;
+    crate::notifications::check_freshness_changes(&app)?;
+    crate::weekly_report::check(&app)?;
+    crate::notifications::flush_pending(&app);
+    Ok(())
+}
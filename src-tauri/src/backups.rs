@@ -0,0 +1,336 @@
+use chrono::{Datelike, IsoWeek, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+
+use crate::attachments::ATTACHMENTS_DIR;
+use crate::store::with_store;
+
+// 自动备份：每天一次，以及批量删除、合并、恢复备份等操作前，把数据库和图片附件打包为
+// backups/snapshots/snapshot-{时间}-{原因}.zip；每天保留最新的一份（最近 7 天），每周保留最新的一份（最近 4 周）
+const SNAPSHOT_DIR: &str = "backups/snapshots";
+const SNAPSHOT_PREFIX: &str = "snapshot-";
+const TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+const DATABASE_ENTRY: &str = "brew-guide.db";
+const MANIFEST_ENTRY: &str = "manifest.json";
+
+const KEEP_DAILY: usize = 7;
+const KEEP_WEEKLY: usize = 4;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub id: String,
+    pub reason: String, // daily / manual / before-merge / before-bulk-update / before-bulk-delete / before-restore
+    pub created_at: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    created_at: String,
+    reason: String,
+    app_version: String,
+}
+
+fn snapshot_dir(app: &tauri::AppHandle) -> Result<(PathBuf, PathBuf), String> {
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let dir = data_dir.join(SNAPSHOT_DIR);
+    Ok((data_dir, dir))
+}
+
+// 从 id（文件名去掉 .zip）中解析创建时间和原因
+fn parse_id(id: &str) -> Option<(NaiveDateTime, &str)> {
+    let rest = id.strip_prefix(SNAPSHOT_PREFIX)?;
+    let time = rest.get(..15)?;
+    let reason = rest.get(15..)?.strip_prefix('-')?;
+    let created = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+    let valid = !reason.is_empty() && reason.chars().all(|c| c.is_ascii_lowercase() || c == '-');
+    valid.then_some((created, reason))
+}
+
+fn format_time(time: NaiveDateTime) -> String {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_else(|| time.to_string())
+}
+
+// 由 id 得到备份信息，id 格式不对时返回 None（远程备份也按同样的格式命名）
+pub fn describe(id: &str, size: u64) -> Option<BackupInfo> {
+    let (created, reason) = parse_id(id)?;
+    Some(BackupInfo {
+        id: id.to_string(),
+        reason: reason.to_string(),
+        created_at: format_time(created),
+        size,
+    })
+}
+
+pub fn new_id(reason: &str) -> String {
+    format!("{}{}-{}", SNAPSHOT_PREFIX, Local::now().format(TIME_FORMAT), reason)
+}
+
+// 全部备份，最新的在前
+fn list(dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            describe(name.strip_suffix(".zip")?, entry.metadata().ok()?.len())
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    backups
+}
+
+// 要保留的备份的下标：times 按时间从新到旧排列，每天、每周都保留最新的一份
+fn to_keep(times: &[NaiveDateTime]) -> HashSet<usize> {
+    let mut days: Vec<NaiveDate> = Vec::new();
+    let mut weeks: Vec<IsoWeek> = Vec::new();
+    let mut keep = HashSet::new();
+    for (i, time) in times.iter().enumerate() {
+        let day = time.date();
+        if !days.contains(&day) && days.len() < KEEP_DAILY {
+            days.push(day);
+            keep.insert(i);
+        }
+        let week = time.iso_week();
+        if !weeks.contains(&week) && weeks.len() < KEEP_WEEKLY {
+            weeks.push(week);
+            keep.insert(i);
+        }
+    }
+    keep
+}
+
+// 按保留规则应删除的备份
+pub fn expired(ids: &[String]) -> Vec<String> {
+    let mut backups: Vec<(NaiveDateTime, &String)> = ids
+        .iter()
+        .filter_map(|id| parse_id(id).map(|(created, _)| (created, id)))
+        .collect();
+    backups.sort_by(|a, b| b.cmp(a));
+    let times: Vec<NaiveDateTime> = backups.iter().map(|(created, _)| *created).collect();
+    let keep = to_keep(&times);
+    backups
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !keep.contains(i))
+        .map(|(_, (_, id))| id.clone())
+        .collect()
+}
+
+fn rotate(dir: &Path) {
+    let ids: Vec<String> = list(dir).into_iter().map(|info| info.id).collect();
+    for id in expired(&ids) {
+        match std::fs::remove_file(dir.join(format!("{}.zip", id))) {
+            Ok(()) => log::info!("已删除旧备份 {}", id),
+            Err(e) => log::warn!("删除旧备份 {} 失败：{}", id, e),
+        }
+    }
+}
+
+// 先写入临时文件，完成后再改名，避免列出写了一半的备份
+fn write_snapshot(path: &Path, database: &Path, data_dir: &Path, manifest: &Manifest) -> Result<(), String> {
+    let partial = path.with_extension("zip.partial");
+    let file = File::create(&partial).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    let manifest = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_ENTRY, options).map_err(|e| e.to_string())?;
+    zip.write_all(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(DATABASE_ENTRY, options).map_err(|e| e.to_string())?;
+    std::io::copy(&mut File::open(database).map_err(|e| e.to_string())?, &mut zip).map_err(|e| e.to_string())?;
+    if let Ok(entries) = std::fs::read_dir(data_dir.join(ATTACHMENTS_DIR)) {
+        for entry in entries.flatten().filter(|entry| entry.path().is_file()) {
+            let name = format!("{}/{}", ATTACHMENTS_DIR, entry.file_name().to_string_lossy());
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            std::io::copy(&mut File::open(entry.path()).map_err(|e| e.to_string())?, &mut zip)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, path).map_err(|e| e.to_string())
+}
+
+// 把当前数据库和附件打包到 path；只在导出数据库时持有数据库锁
+pub fn package(app: &tauri::AppHandle, path: &Path, reason: &str) -> Result<(), String> {
+    let (data_dir, _) = snapshot_dir(app)?;
+    let manifest = Manifest {
+        created_at: Local::now().to_rfc3339(),
+        reason: reason.to_string(),
+        app_version: app.package_info().version.to_string(),
+    };
+    let database = with_store(app, |store| Ok(crate::store::backup(&store.conn, &data_dir, "snapshot")))??;
+    let result = write_snapshot(path, &database, &data_dir, &manifest);
+    let _ = std::fs::remove_file(&database);
+    result
+}
+
+// 备份目录中的临时文件路径，用于导出、导入加密备份
+pub fn work_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    let (_, dir) = snapshot_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(name))
+}
+
+// 创建一份备份并清理旧备份
+pub fn snapshot(app: &tauri::AppHandle, reason: &str) -> Result<BackupInfo, String> {
+    let (_, dir) = snapshot_dir(app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let id = new_id(reason);
+    package(app, &dir.join(format!("{}.zip", id)), reason)?;
+    rotate(&dir);
+    crate::history::record_backup(app, reason);
+    list(&dir)
+        .into_iter()
+        .find(|info| info.id == id)
+        .ok_or_else(|| format!("找不到刚创建的备份：{}", id))
+}
+
+// 危险操作前的备份失败时只记录日志，不阻止操作
+pub fn snapshot_before(app: &tauri::AppHandle, operation: &str) {
+    let reason = format!("before-{}", operation);
+    if let Err(e) = snapshot(app, &reason) {
+        log::warn!("创建备份 {} 失败：{}", reason, e);
+    }
+}
+
+pub fn created_today(id: &str) -> bool {
+    parse_id(id).is_some_and(|(created, _)| created.date() == Local::now().date_naive())
+}
+
+// 今天还没有备份时创建每日备份
+fn ensure_daily(app: &tauri::AppHandle) -> Result<(), String> {
+    let (_, dir) = snapshot_dir(app)?;
+    if list(&dir).iter().any(|info| created_today(&info.id)) {
+        return Ok(());
+    }
+    let info = snapshot(app, "daily")?;
+    log::info!("已创建每日备份 {}", info.id);
+    Ok(())
+}
+
+// 桌面端：后台线程每小时检查一次是否需要每日备份
+pub fn spawn_backup_loop(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        if let Err(e) = ensure_daily(&app) {
+            log::warn!("创建每日备份失败：{}", e);
+        }
+        thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+// 解压备份：数据库写入临时文件，附件只补上当前没有的文件（附件按内容命名，同名即相同）
+fn extract(path: &Path, data_dir: &Path, database: &Path) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut has_database = false;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = if name == Path::new(DATABASE_ENTRY) {
+            has_database = true;
+            database.to_path_buf()
+        } else if name.parent() == Some(Path::new(ATTACHMENTS_DIR)) && entry.is_file() {
+            let target = data_dir.join(&name);
+            if target.exists() {
+                continue;
+            }
+            std::fs::create_dir_all(data_dir.join(ATTACHMENTS_DIR)).map_err(|e| e.to_string())?;
+            target
+        } else {
+            continue;
+        };
+        let mut out = File::create(&target).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+    }
+    if has_database {
+        Ok(())
+    } else {
+        Err("备份中没有数据库".to_string())
+    }
+}
+
+#[tauri::command]
+pub fn create_backup(app: tauri::AppHandle) -> Result<BackupInfo, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let info = snapshot(&app, "manual")?;
+    crate::telemetry::record(&app, "backup.create");
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn list_backups(app: tauri::AppHandle) -> Result<Vec<BackupInfo>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let (_, dir) = snapshot_dir(&app)?;
+    Ok(list(&dir))
+}
+
+// 从备份文件恢复：补上缺少的附件，备份当前数据后再替换数据库
+// 先解压再备份：备份时会清理旧备份，可能删除正要恢复的文件
+pub fn restore_archive(app: &tauri::AppHandle, path: &Path) -> Result<(), String> {
+    let (data_dir, _) = snapshot_dir(app)?;
+    let database = path.with_extension("restore.db");
+    let result = extract(path, &data_dir, &database)
+        .and_then(|()| {
+            snapshot(app, "before-restore")
+                .map(|_| ())
+                .map_err(|e| format!("恢复前备份当前数据失败：{}", e))
+        })
+        .and_then(|()| crate::store::replace_database(app, &database));
+    let _ = std::fs::remove_file(&database);
+    result?;
+    crate::store::publish_changes(app)
+}
+
+#[tauri::command]
+pub fn restore_backup(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if parse_id(&id).is_none() {
+        return Err(format!("无效的备份：{}", id));
+    }
+    let (_, dir) = snapshot_dir(&app)?;
+    let path = dir.join(format!("{}.zip", id));
+    if !path.exists() {
+        return Err(format!("找不到备份：{}", id));
+    }
+    restore_archive(&app, &path)?;
+    crate::telemetry::record(&app, "backup.restore");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_newest_snapshot_per_day_and_week() {
+        let time = |s: &str| NaiveDateTime::parse_from_str(s, TIME_FORMAT).unwrap();
+        // 从新到旧：同一天的两份只留较新的；超过 7 天的按周保留
+        let mut times = vec![time("20261016-180000"), time("20261016-090000")];
+        times.extend((1..=12).map(|day| time(&format!("202610{:02}-090000", 16 - day))));
+        times.extend([time("20260901-090000"), time("20260801-090000")]);
+        let mut keep: Vec<usize> = to_keep(&times).into_iter().collect();
+        keep.sort();
+        // 10-16 至 10-10 共 7 天（已覆盖第 42、41 周）；再按周保留第 40 周的 10-04、第 36 周的 09-01，08-01 超过 4 周
+        assert_eq!(keep, vec![0, 2, 3, 4, 5, 6, 7, 13, 14]);
+        assert_eq!(parse_id("snapshot-20261016-180000-before-merge").unwrap().1, "before-merge");
+    }
+}
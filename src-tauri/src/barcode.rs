@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::json_file;
+use crate::store::{number_field, text_field};
+
+// 扫描咖啡豆包装上的条码后查询 Open Food Facts，返回候选的商品名称、品牌和重量用于预填新咖啡豆
+// 结果缓存在 app_cache_dir/barcodes.json；网络不可用时使用过期的缓存
+const PRODUCT_API: &str = "https://world.openfoodfacts.org/api/v2/product";
+const PRODUCT_FIELDS: &str = "product_name,product_name_zh,product_name_en,generic_name,brands,quantity,product_quantity,product_quantity_unit";
+const NAME_FIELDS: [&str; 4] = ["product_name_zh", "product_name", "product_name_en", "generic_name"];
+const CACHE_FILE: &str = "barcodes.json";
+const CACHE_DAYS: i64 = 30;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductCandidates {
+    pub names: Vec<String>,
+    pub brands: Vec<String>,
+    pub weight_grams: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedLookup {
+    // None 表示 Open Food Facts 中没有这个条码
+    product: Option<ProductCandidates>,
+    fetched_at: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BarcodeCache {
+    entries: HashMap<String, CachedLookup>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LookupSource {
+    Network,
+    Cache,
+    // 网络请求失败，使用过期的缓存
+    OfflineCache,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BarcodeLookup {
+    pub ean: String,
+    pub found: bool,
+    pub candidates: ProductCandidates,
+    // 用第一个候选预填的新咖啡豆（没有 ID）
+    pub bean: Option<Value>,
+    pub source: LookupSource,
+    pub fetched_at: i64,
+}
+
+// EAN-8 / UPC-A / EAN-13 / GTIN-14，校验最后一位
+fn normalize_ean(ean: &str) -> Result<String, String> {
+    let digits: String = ean.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    if !matches!(digits.len(), 8 | 12 | 13 | 14) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("无效的条码：{}", ean));
+    }
+    let values: Vec<u32> = digits.chars().filter_map(|c| c.to_digit(10)).collect();
+    let (check, body) = values.split_last().ok_or("无效的条码")?;
+    let sum: u32 = body.iter().rev().enumerate().map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d }).sum();
+    if (10 - sum % 10) % 10 != *check {
+        return Err(format!("条码校验位不正确：{}", ean));
+    }
+    Ok(digits)
+}
+
+fn grams_from_quantity(product: &Value) -> Option<f64> {
+    let unit = text_field(product, "product_quantity_unit").unwrap_or("g").to_lowercase();
+    let amount = number_field(product, "product_quantity")
+        .or_else(|| text_field(product, "product_quantity").and_then(|q| q.trim().parse().ok()));
+    let grams = match (amount, unit.as_str()) {
+        (Some(amount), "g") => Some(amount),
+        (Some(amount), "kg") => Some(amount * 1000.0),
+        // 只有“250 g”这样的文字时自己解析
+        _ => {
+            let quantity = text_field(product, "quantity")?.to_lowercase();
+            let number: String = quantity.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+            let amount: f64 = number.parse().ok()?;
+            let unit = quantity[number.len()..].trim();
+            if unit.starts_with("kg") {
+                Some(amount * 1000.0)
+            } else if unit.starts_with('g') || unit.starts_with('克') {
+                Some(amount)
+            } else {
+                None
+            }
+        }
+    };
+    grams.filter(|g| *g > 0.0)
+}
+
+fn parse_product(response: &Value) -> Option<ProductCandidates> {
+    if number_field(response, "status") != Some(1.0) {
+        return None;
+    }
+    let product = response.get("product")?;
+    let mut names: Vec<String> = Vec::new();
+    for field in NAME_FIELDS {
+        if let Some(name) = text_field(product, field).map(str::trim).filter(|n| !n.is_empty()) {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    let brands = text_field(product, "brands")
+        .map(|brands| brands.split(',').map(str::trim).filter(|b| !b.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+    Some(ProductCandidates {
+        names,
+        brands,
+        weight_grams: grams_from_quantity(product),
+    })
+}
+
+fn bean_draft(candidates: &ProductCandidates) -> Option<Value> {
+    let mut bean = Map::new();
+    bean.insert("name".into(), json!(candidates.names.first()?));
+    if let Some(brand) = candidates.brands.first() {
+        bean.insert("roaster".into(), json!(brand));
+    }
+    if let Some(grams) = candidates.weight_grams {
+        bean.insert("capacity".into(), json!(grams.round().to_string()));
+        bean.insert("remaining".into(), json!(grams.round().to_string()));
+    }
+    Some(Value::Object(bean))
+}
+
+fn cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_cache_dir().map_err(|e| e.to_string())?.join(CACHE_FILE))
+}
+
+async fn fetch_product(ean: &str) -> Result<Option<ProductCandidates>, String> {
+    let response = reqwest::Client::builder()
+        .user_agent(concat!("BrewGuide/", env!("CARGO_PKG_VERSION")))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .get(format!("{}/{}.json", PRODUCT_API, ean))
+        .query(&[("fields", PRODUCT_FIELDS)])
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    // 没有这个商品时返回 404 和 status 0
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body: Value = response
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(parse_product(&body))
+}
+
+fn lookup_result(ean: String, cached: CachedLookup, source: LookupSource) -> BarcodeLookup {
+    let candidates = cached.product.clone().unwrap_or_default();
+    BarcodeLookup {
+        ean,
+        found: cached.product.is_some(),
+        bean: bean_draft(&candidates),
+        candidates,
+        source,
+        fetched_at: cached.fetched_at,
+    }
+}
+
+#[tauri::command]
+pub async fn lookup_barcode(app: tauri::AppHandle, ean: String) -> Result<BarcodeLookup, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let ean = normalize_ean(&ean)?;
+    let path = cache_path(&app)?;
+    let mut cache: BarcodeCache = json_file::load(&path).unwrap_or_default();
+    let now = chrono::Utc::now().timestamp_millis();
+    let cached = cache.entries.get(&ean).cloned();
+    if let Some(cached) = cached.clone() {
+        if now - cached.fetched_at < CACHE_DAYS * 24 * 60 * 60 * 1000 {
+            return Ok(lookup_result(ean, cached, LookupSource::Cache));
+        }
+    }
+
+    crate::telemetry::record(&app, "barcode.lookup");
+    match fetch_product(&ean).await {
+        Ok(product) => {
+            let entry = CachedLookup { product, fetched_at: now };
+            cache.entries.insert(ean.clone(), entry.clone());
+            if let Err(e) = json_file::save(&path, &cache) {
+                log::warn!("保存条码缓存失败：{}", e);
+            }
+            Ok(lookup_result(ean, entry, LookupSource::Network))
+        }
+        Err(e) => match cached {
+            Some(cached) => Ok(lookup_result(ean, cached, LookupSource::OfflineCache)),
+            None => Err(format!("查询条码失败：{}", e)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_open_food_facts_products() {
+        assert_eq!(normalize_ean("6 901234 567892").unwrap(), "6901234567892");
+        assert!(normalize_ean("6901234567891").is_err());
+        assert_eq!(normalize_ean("96385074").unwrap(), "96385074");
+
+        let response = json!({
+            "status": 1,
+            "product": {
+                "product_name": "Yirgacheffe Whole Bean",
+                "product_name_en": "yirgacheffe whole bean",
+                "generic_name": "Café en grains",
+                "brands": "Tim Wendelboe, TW",
+                "quantity": "0.25 kg",
+            },
+        });
+        let candidates = parse_product(&response).unwrap();
+        assert_eq!(candidates.names, vec!["Yirgacheffe Whole Bean", "Café en grains"]);
+        assert_eq!(candidates.brands, vec!["Tim Wendelboe", "TW"]);
+        assert_eq!(candidates.weight_grams, Some(250.0));
+        assert_eq!(
+            bean_draft(&candidates).unwrap(),
+            json!({ "name": "Yirgacheffe Whole Bean", "roaster": "Tim Wendelboe", "capacity": "250", "remaining": "250" })
+        );
+        assert!(parse_product(&json!({ "status": 0, "status_verbose": "product not found" })).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use pinyin::ToPinyin;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::background::BeanCache;
+use crate::navigation::{navigate_to, NavigationTarget};
+
+// 托盘「搜索咖啡豆…」打开的搜索窗口，在缓存的咖啡豆中模糊匹配
+// 中文名称同时按拼音首字母和全拼匹配，如 "yjxf"、"yejia" 都能找到「耶加雪菲」
+const LABEL: &str = "bean-search";
+
+const MAX_RESULTS: usize = 20;
+
+// 拼音匹配比直接匹配文字略低
+const INITIALS_PENALTY: i32 = 5;
+const FULL_PINYIN_PENALTY: i32 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeanSearchResult {
+    pub id: String,
+    pub name: String,
+    pub roaster: Option<String>,
+    pub state: &'static str,
+}
+
+// 模糊匹配：查询中的字符按顺序出现在文本中即算匹配（忽略大小写和空白）
+// 连续命中、在词首命中加分，文本越长分数越低；不匹配时返回 None
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for (i, c) in text.iter().enumerate() {
+        if matched == query.len() {
+            break;
+        }
+        if *c != query[matched] {
+            continue;
+        }
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(i);
+        matched += 1;
+    }
+    (matched == query.len()).then(|| score * 10 - text.len() as i32)
+}
+
+// 文字的拼音首字母（"yjxf"）和以空格分隔音节的全拼（"ye jia xue fei"），非汉字原样保留
+// 不含汉字时返回 None；多音字只取常用读音
+fn pinyin_forms(text: &str) -> Option<(String, String)> {
+    let mut initials = String::new();
+    let mut full = String::new();
+    let mut has_hanzi = false;
+    for c in text.chars() {
+        match c.to_pinyin() {
+            Some(pinyin) => {
+                has_hanzi = true;
+                initials.push_str(pinyin.first_letter());
+                full.push(' ');
+                full.push_str(pinyin.plain());
+                full.push(' ');
+            }
+            None => {
+                initials.push(c);
+                full.push(c);
+            }
+        }
+    }
+    has_hanzi.then_some((initials, full))
+}
+
+// 文字本身、拼音首字母、全拼中得分最高的一个
+fn text_score(query: &str, text: &str) -> Option<i32> {
+    let direct = fuzzy_score(query, text);
+    let pinyin = pinyin_forms(text).into_iter().flat_map(|(initials, full)| {
+        let initials = fuzzy_score(query, &initials).map(|score| score - INITIALS_PENALTY);
+        let full = fuzzy_score(query, &full).map(|score| score - FULL_PINYIN_PENALTY);
+        initials.into_iter().chain(full)
+    });
+    direct.into_iter().chain(pinyin).max()
+}
+
+// 名称优先，其次匹配烘焙商和产地
+fn fields_score(query: &str, name: &str, secondary: [Option<&str>; 2]) -> Option<i32> {
+    let secondary = secondary
+        .into_iter()
+        .flatten()
+        .filter_map(|text| text_score(query, text))
+        .map(|score| score - 20);
+    text_score(query, name).into_iter().chain(secondary).max()
+}
+
+fn bean_score(query: &str, bean: &crate::CoffeeBean) -> Option<i32> {
+    fields_score(query, &bean.name, [bean.roaster.as_deref(), bean.origin.as_deref()])
+}
+
+// 语音等只给出名称的入口：取匹配度最高的咖啡豆，优先还有剩余的
+pub fn best_match<'a>(query: &str, beans: &'a [crate::CoffeeBean]) -> Option<&'a crate::CoffeeBean> {
+    beans
+        .iter()
+        .filter(|bean| !bean.id.is_empty())
+        .filter_map(|bean| {
+            let in_stock = bean.remaining_grams().map_or(true, |grams| grams > 0.0);
+            bean_score(query, bean).map(|score| ((in_stock, score), bean))
+        })
+        .max_by_key(|(key, _)| *key)
+        .map(|(_, bean)| bean)
+}
+
+#[cfg(desktop)]
+pub fn open(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let title = crate::current_locale(app).tr("搜索咖啡豆", "Search Beans");
+    crate::open_tray_window(app, LABEL, title, 360.0, 420.0)
+}
+
+#[tauri::command]
+pub fn search_beans(app: tauri::AppHandle, query: String) -> Result<Vec<BeanSearchResult>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let today = crate::today(&app);
+    let beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.beans.clone()))
+        .unwrap_or_default();
+    let mut matches: Vec<(i32, &crate::CoffeeBean)> = beans
+        .iter()
+        .filter(|bean| !bean.id.is_empty())
+        .filter_map(|bean| bean_score(&query, bean).map(|score| (score, bean)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    Ok(matches
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, bean)| BeanSearchResult {
+            id: bean.id.clone(),
+            name: bean.name.clone(),
+            roaster: bean.roaster.clone(),
+            state: crate::widget::state_key(&crate::calculate_freshness(bean, today).freshness_state),
+        })
+        .collect())
+}
+
+// 应用内搜索：在本地数据库的全部咖啡豆中模糊匹配（含拼音），按匹配程度排序，返回完整的咖啡豆数据
+#[tauri::command]
+pub fn fuzzy_search_beans(app: tauri::AppHandle, query: String, limit: Option<usize>) -> Result<Vec<Value>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let beans = crate::store::with_store(&app, crate::store::Store::beans)?;
+    let mut matches: Vec<(i32, Value)> = beans
+        .into_iter()
+        .filter_map(|bean| {
+            let field = |key: &str| crate::store::text_field(&bean, key);
+            let score = fields_score(&query, field("name")?, [field("roaster"), field("origin")])?;
+            Some((score, bean))
+        })
+        .collect();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    Ok(matches
+        .into_iter()
+        .take(limit.unwrap_or(MAX_RESULTS))
+        .map(|(_, bean)| bean)
+        .collect())
+}
+
+// 选中搜索结果：关闭搜索窗口并跳转到咖啡豆详情
+#[tauri::command]
+pub fn open_searched_bean(app: tauri::AppHandle, bean_id: String) -> Result<(), String> {
+    crate::telemetry::record(&app, "tray.search");
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    navigate_to(&app, NavigationTarget::Bean { bean_id }).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_characters_in_order() {
+        assert!(fuzzy_score("yjf", "Yirgacheffe Jimma Fruity").is_some());
+        assert!(fuzzy_score("耶加", "埃塞俄比亚 耶加雪菲").is_some());
+        assert!(fuzzy_score("kenya", "Yirgacheffe Jimma Fruity").is_none());
+    }
+
+    #[test]
+    fn prefers_consecutive_and_shorter_matches() {
+        let consecutive = fuzzy_score("geisha", "Geisha Village").unwrap();
+        let scattered = fuzzy_score("geisha", "Green Estate Finca Sidama Hills Arabica").unwrap();
+        assert!(consecutive > scattered);
+        assert!(fuzzy_score("kenya", "Kenya AA").unwrap() > fuzzy_score("kenya", "Kenya AA Nyeri Peaberry").unwrap());
+    }
+
+    #[test]
+    fn matches_pinyin_initials_and_full_pinyin() {
+        assert!(text_score("yjxf", "耶加雪菲").is_some());
+        assert!(text_score("yejia", "埃塞俄比亚 耶加雪菲").is_some());
+        assert!(text_score("kny", "肯尼亚 AA").is_some());
+        assert!(text_score("mtn", "耶加雪菲").is_none());
+        assert!(text_score("耶加", "耶加雪菲").unwrap() > text_score("yj", "耶加雪菲").unwrap());
+    }
+}
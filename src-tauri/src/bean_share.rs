@@ -0,0 +1,209 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use tauri::Manager;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::store::{record_data, text_field, with_store, RecordKind};
+
+// 分享咖啡豆：只保留公开的字段，JSON 压缩后用 base64url 编码放进 brewguide://share?bean=... 链接，
+// 同时生成链接的二维码 PNG；对方扫码或粘贴链接后用 decode_bean_link 还原
+const LINK_PREFIX: &str = "brewguide://share?bean=";
+const SHARE_DIR: &str = "share";
+// 剩余量、价格、备注、评分、图片等属于个人记录，不分享
+const SHARED_FIELDS: [&str; 12] = [
+    "name",
+    "roaster",
+    "brand",
+    "roastLevel",
+    "roastDate",
+    "flavor",
+    "startDay",
+    "endDay",
+    "beanType",
+    "beanState",
+    "capacity",
+    "blendComponents",
+];
+const MAX_DECODED_BYTES: u64 = 64 * 1024;
+const QR_MODULE_PIXELS: usize = 8;
+const QR_QUIET_MODULES: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareCopy {
+    #[default]
+    Link,
+    Image,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeanShareLink {
+    pub url: String,
+    pub qr_path: String,
+}
+
+fn public_fields(bean: &Value) -> Value {
+    let mut shared = Map::new();
+    for key in SHARED_FIELDS {
+        match bean.get(key) {
+            None | Some(Value::Null) => {}
+            Some(Value::String(text)) if text.trim().is_empty() => {}
+            Some(value) => {
+                shared.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+    Value::Object(shared)
+}
+
+fn encode_link(bean: &Value) -> Result<String, String> {
+    let json = serde_json::to_vec(&public_fields(bean)).map_err(|e| e.to_string())?;
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(&json).map_err(|e| e.to_string())?;
+    let compressed = encoder.finish().map_err(|e| e.to_string())?;
+    Ok(format!(
+        "{}{}",
+        LINK_PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed)
+    ))
+}
+
+fn decode_link(url: &str) -> Result<Value, String> {
+    let encoded = url.trim().strip_prefix(LINK_PREFIX).ok_or("不是咖啡豆分享链接")?;
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded.trim_end_matches('='))
+        .map_err(|_| "分享链接已损坏")?;
+    // 限制解压后的大小，避免恶意构造的链接占满内存
+    let mut json = Vec::new();
+    flate2::read::DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECODED_BYTES)
+        .read_to_end(&mut json)
+        .map_err(|_| "分享链接已损坏")?;
+    let bean: Value = serde_json::from_slice(&json).map_err(|_| "分享链接已损坏")?;
+    if text_field(&bean, "name").is_none() {
+        return Err("分享链接中没有咖啡豆名称".to_string());
+    }
+    // 只接受公开字段，对方不能借分享链接写入其它数据
+    Ok(public_fields(&bean))
+}
+
+// 二维码渲染为灰度图：返回每个像素的亮度和边长
+fn qr_pixels(payload: &str) -> Result<(Vec<u8>, usize), String> {
+    let code = qrcode::QrCode::new(payload.as_bytes()).map_err(|_| "咖啡豆信息过长，无法生成二维码")?;
+    let width = code.width();
+    let colors = code.to_colors();
+    let side = (width + QR_QUIET_MODULES * 2) * QR_MODULE_PIXELS;
+    let mut pixels = vec![255u8; side * side];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let left = (index % width + QR_QUIET_MODULES) * QR_MODULE_PIXELS;
+        let top = (index / width + QR_QUIET_MODULES) * QR_MODULE_PIXELS;
+        for y in top..top + QR_MODULE_PIXELS {
+            pixels[y * side + left..y * side + left + QR_MODULE_PIXELS].fill(0);
+        }
+    }
+    Ok((pixels, side))
+}
+
+fn encode_png(pixels: &[u8], side: usize) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, side as u32, side as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(pixels).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn output_path(app: &tauri::AppHandle, path: Option<String>, name: &str, id: &str) -> Result<PathBuf, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| e.to_string())?
+            .join(SHARE_DIR)
+            .join(format!("{}.png", crate::markdown_export::file_stem(name, id))),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+// 生成分享链接和二维码图片（默认保存到缓存目录），copy 决定复制链接还是二维码图片到剪贴板
+#[tauri::command]
+pub fn share_bean(
+    app: tauri::AppHandle,
+    bean_id: String,
+    path: Option<String>,
+    copy: Option<ShareCopy>,
+) -> Result<BeanShareLink, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let bean: Value = with_store(&app, |store| record_data(&store.conn, RecordKind::Bean, &bean_id))?
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .ok_or_else(|| format!("找不到咖啡豆：{}", bean_id))?;
+    let url = encode_link(&bean)?;
+    let (pixels, side) = qr_pixels(&url)?;
+    let path = output_path(&app, path, text_field(&bean, "name").unwrap_or_default(), &bean_id)?;
+    std::fs::write(&path, encode_png(&pixels, side)?).map_err(|e| e.to_string())?;
+
+    match copy.unwrap_or_default() {
+        ShareCopy::Link => app.clipboard().write_text(url.as_str()).map_err(|e| e.to_string())?,
+        ShareCopy::Image => {
+            let rgba: Vec<u8> = pixels.iter().flat_map(|&p| [p, p, p, 255]).collect();
+            let image = tauri::image::Image::new_owned(rgba, side as u32, side as u32);
+            app.clipboard().write_image(&image).map_err(|e| e.to_string())?;
+        }
+        ShareCopy::None => {}
+    }
+    crate::telemetry::record(&app, "share.bean");
+    Ok(BeanShareLink {
+        url,
+        qr_path: path.to_string_lossy().to_string(),
+    })
+}
+
+// 解析收到的分享链接，返回咖啡豆信息（没有 ID），由前端预填到新建咖啡豆表单
+#[tauri::command]
+pub fn decode_bean_link(url: String) -> Result<Value, String> {
+    decode_link(&url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_public_fields_through_a_link() {
+        let bean = json!({
+            "id": "b1", "name": "埃塞俄比亚 古吉 罕贝拉", "roaster": "Tim", "roastDate": "2026-10-01",
+            "flavor": ["桃子", "茉莉花"], "remaining": "120", "price": "98", "notes": "", "image": "data:image/png;base64,AAAA",
+        });
+        let url = encode_link(&bean).unwrap();
+        assert!(url.starts_with(LINK_PREFIX));
+        assert_eq!(
+            decode_link(&url).unwrap(),
+            json!({ "name": "埃塞俄比亚 古吉 罕贝拉", "roaster": "Tim", "roastDate": "2026-10-01", "flavor": ["桃子", "茉莉花"] })
+        );
+        assert!(decode_link("brewguide://share?bean=not-deflate").is_err());
+        assert!(decode_link("https://example.com").is_err());
+
+        let (pixels, side) = qr_pixels(&url).unwrap();
+        assert_eq!(pixels.len(), side * side);
+        assert_eq!(pixels[0], 255);
+        // 左上角定位图案从静区之后开始
+        let corner = QR_QUIET_MODULES * QR_MODULE_PIXELS;
+        assert_eq!(pixels[corner * side + corner], 0);
+        assert!(encode_png(&pixels, side).unwrap().starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,516 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read};
+
+use crate::journal;
+use crate::store::{atomically, number_field, record_data, text_field, with_store, write_record, RecordKind};
+
+// 从 Beanconqueror 迁移：读取它导出的 Beanconqueror.json 或 zip（大数据量时咖啡豆、冲煮记录拆分在
+// Beanconqueror_Beans_1.json 等文件中），咖啡豆和冲煮记录写入数据库（ID 为 bc- 加原来的 uuid，重复导入时整体替换），
+// 冲煮方式和磨豆机由前端加入自定义器具和磨豆机列表；没有对应字段的数据在报告中列出
+const ID_PREFIX: &str = "bc-";
+const MAX_RATING: f64 = 5.0;
+
+// 已经转换的字段，其余有值的字段计入 unmapped
+const BEAN_FIELDS: &[&str] = &[
+    "config", "name", "roaster", "roastingDate", "note", "roast", "roast_custom", "aromatics", "weight", "finished",
+    "cost", "bean_roasting_type", "rating", "bean_information", "beanMix",
+];
+const BREW_FIELDS: &[&str] = &[
+    "config", "bean", "method_of_preparation", "mill", "grind_size", "grind_weight", "brew_quantity",
+    "brew_quantity_type", "brew_temperature", "brew_time", "rating", "note",
+];
+const PREPARATION_FIELDS: &[&str] = &["config", "name", "note", "type", "finished"];
+const MILL_FIELDS: &[&str] = &["config", "name", "note", "finished"];
+// 不需要导入的部分（设置中只使用评分的满分）
+const IGNORED_SECTIONS: &[&str] = &["SETTINGS", "VERSION"];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeanconquerorReport {
+    pub dry_run: bool,
+    pub beans_created: usize,
+    pub beans_updated: usize,
+    pub notes_created: usize,
+    pub notes_updated: usize,
+    pub skipped: usize,                     // 缺少名称或 uuid 的记录
+    pub equipment: Vec<Value>,              // 自定义器具（内置器具以外的冲煮方式）
+    pub grinders: Vec<Value>,               // 磨豆机，currentGrindSize 为最近一次使用的刻度
+    pub unmapped: BTreeMap<String, usize>, // 没有对应字段的数据："BREWS.tds" -> 有值的记录数，整个部分未导入时为 "GREEN_BEANS"
+}
+
+#[derive(Debug, Default)]
+struct Converted {
+    beans: Vec<Value>,
+    notes: Vec<Value>,
+    equipment: Vec<Value>,
+    grinders: Vec<Value>,
+    unmapped: BTreeMap<String, usize>,
+    skipped: usize,
+}
+
+// 拆分文件名中的部分名称，如 Beanconqueror_Brews_2.json -> BREWS
+fn chunk_section(name: &str) -> Option<String> {
+    let stem = name.rsplit('/').next()?.strip_suffix(".json")?;
+    let section = stem.strip_prefix("Beanconqueror_")?.split('_').next()?.to_uppercase();
+    Some(match section.as_str() {
+        "PREPARATIONS" => "PREPARATION".to_string(),
+        "MILLS" => "MILL".to_string(),
+        _ => section,
+    })
+}
+
+fn read_zip(bytes: &[u8]) -> Result<Map<String, Value>, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    let mut export = Map::new();
+    let mut chunks: Vec<(String, Value)> = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        let name = entry.name().to_string();
+        if !name.ends_with(".json") {
+            continue;
+        }
+        let mut text = String::new();
+        entry.read_to_string(&mut text).map_err(|e| e.to_string())?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| format!("{}：{}", name, e))?;
+        if name.rsplit('/').next() == Some("Beanconqueror.json") {
+            if let Value::Object(map) = value {
+                export.extend(map);
+            }
+        } else if let Some(section) = chunk_section(&name) {
+            chunks.push((section, value));
+        }
+    }
+    if export.is_empty() {
+        return Err("压缩包中没有 Beanconqueror.json".to_string());
+    }
+    chunks.sort_by(|a, b| a.0.cmp(&b.0));
+    for (section, value) in chunks {
+        let Value::Array(items) = value else {
+            continue;
+        };
+        let list = export.entry(section).or_insert_with(|| Value::Array(Vec::new()));
+        if let Some(list) = list.as_array_mut() {
+            list.extend(items);
+        }
+    }
+    Ok(export)
+}
+
+fn read_export(bytes: &[u8]) -> Result<Map<String, Value>, String> {
+    if bytes.starts_with(b"PK") {
+        return read_zip(bytes);
+    }
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err("不是 Beanconqueror 导出的数据".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn section<'a>(export: &'a Map<String, Value>, name: &str) -> &'a [Value] {
+    export.get(name).and_then(Value::as_array).map_or(&[], Vec::as_slice)
+}
+
+fn uuid(item: &Value) -> Option<&str> {
+    item.pointer("/config/uuid").and_then(Value::as_str).filter(|s| !s.is_empty())
+}
+
+// config.unix_timestamp 为秒
+fn timestamp(item: &Value) -> Option<i64> {
+    item.pointer("/config/unix_timestamp").and_then(Value::as_i64).map(|s| s * 1000)
+}
+
+fn has_content(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.trim().is_empty(),
+        Value::Array(items) => items.iter().any(has_content),
+        Value::Object(fields) => fields.values().any(has_content),
+    }
+}
+
+fn count_unmapped(unmapped: &mut BTreeMap<String, usize>, section: &str, item: &Value, mapped: &[&str]) {
+    for (key, value) in item.as_object().into_iter().flatten() {
+        if !mapped.contains(&key.as_str()) && has_content(value) {
+            *unmapped.entry(format!("{}.{}", section, key)).or_insert(0) += 1;
+        }
+    }
+}
+
+fn number_text(n: f64) -> String {
+    let rounded = (n * 10.0).round() / 10.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        rounded.to_string()
+    }
+}
+
+// 换算为 5 分制，保留半星
+fn scale_rating(rating: f64, max: f64) -> Option<f64> {
+    (rating > 0.0 && max > 0.0).then(|| ((rating * MAX_RATING / max).min(MAX_RATING) * 2.0).round() / 2.0)
+}
+
+fn roast_level(bean: &Value) -> Option<String> {
+    let level = match text_field(bean, "roast")? {
+        "CINNAMON_ROAST" | "AMERICAN_ROAST" | "NEW_ENGLAND_ROAST" => "极浅烘焙",
+        "HALF_CITY_ROAST" | "MODERATE_LIGHT_ROAST" => "浅度烘焙",
+        "CITY_ROAST" | "CITY_PLUS_ROAST" => "中浅烘焙",
+        "FULL_CITY_ROAST" => "中度烘焙",
+        "FULL_CITY_PLUS_ROAST" | "ITALIAN_ROAST" | "VIEANNA_ROAST" => "中深烘焙",
+        "FRENCH_ROAST" => "深度烘焙",
+        "CUSTOM_ROAST" => return text_field(bean, "roast_custom").map(str::to_string),
+        _ => return None,
+    };
+    Some(level.to_string())
+}
+
+// 内置器具对应的冲煮方式类型
+fn builtin_equipment(preparation_type: &str) -> Option<&'static str> {
+    match preparation_type {
+        "V60" => Some("V60"),
+        "KALITA" => Some("Kalita"),
+        "ORIGAMI" => Some("Origami"),
+        "CLEVER" => Some("CleverDripper"),
+        "PORTAFILTER" | "SANREMO_YOU" | "XENIA" | "METICULOUS" | "GAGGIUINO" => Some("Espresso"),
+        _ => None,
+    }
+}
+
+fn convert_bean(bean: &Value, id: String, used: f64, max_rating: f64) -> Value {
+    let mut converted = json!({
+        "id": id,
+        "timestamp": timestamp(bean).unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+        "name": text_field(bean, "name").unwrap_or_default().trim(),
+    });
+    let mut set = |key: &str, value: Value| {
+        converted[key] = value;
+    };
+    if let Some(roaster) = text_field(bean, "roaster") {
+        set("roaster", json!(roaster));
+    }
+    if let Some(date) = text_field(bean, "roastingDate").and_then(crate::roast_date::parse_roast_date) {
+        set("roastDate", json!(date.earliest.format("%Y-%m-%d").to_string()));
+    }
+    if let Some(level) = roast_level(bean) {
+        set("roastLevel", json!(level));
+    }
+    if let Some(note) = text_field(bean, "note") {
+        set("notes", json!(note));
+    }
+    let flavor: Vec<&str> = text_field(bean, "aromatics")
+        .unwrap_or_default()
+        .split([',', '，', '、', ';'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !flavor.is_empty() {
+        set("flavor", json!(flavor));
+    }
+    if let Some(weight) = number_field(bean, "weight").filter(|w| *w > 0.0) {
+        let finished = bean.get("finished").and_then(Value::as_bool).unwrap_or(false);
+        let remaining = if finished { 0.0 } else { (weight - used).max(0.0) };
+        set("capacity", json!(number_text(weight)));
+        set("remaining", json!(number_text(remaining)));
+    }
+    if let Some(cost) = number_field(bean, "cost").filter(|c| *c > 0.0) {
+        set("price", json!(number_text(cost)));
+    }
+    let bean_type = match text_field(bean, "bean_roasting_type") {
+        Some("FILTER") => Some("filter"),
+        Some("ESPRESSO") => Some("espresso"),
+        Some("OMNI") => Some("omni"),
+        _ => None,
+    };
+    if let Some(bean_type) = bean_type {
+        set("beanType", json!(bean_type));
+    }
+    if let Some(rating) = number_field(bean, "rating").and_then(|r| scale_rating(r, max_rating)) {
+        set("overallRating", json!(rating));
+    }
+    let components: Vec<Value> = bean
+        .get("bean_information")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|info| has_content(info))
+        .map(|info| {
+            let mut component = Map::new();
+            for (from, to) in [
+                ("country", "country"),
+                ("region", "region"),
+                ("farm", "estate"),
+                ("elevation", "altitude"),
+                ("processing", "process"),
+                ("variety", "variety"),
+            ] {
+                if let Some(text) = text_field(info, from) {
+                    component.insert(to.to_string(), json!(text));
+                }
+            }
+            if let Some(percentage) = number_field(info, "percentage").filter(|p| *p > 0.0) {
+                component.insert("percentage".to_string(), json!(percentage));
+            }
+            Value::Object(component)
+        })
+        .filter(|component| component.as_object().is_some_and(|c| !c.is_empty()))
+        .collect();
+    if !components.is_empty() {
+        set("blendComponents", Value::Array(components));
+    }
+    converted
+}
+
+fn convert(export: &Map<String, Value>) -> Converted {
+    let mut converted = Converted::default();
+    let settings = section(export, "SETTINGS").first().cloned().unwrap_or_default();
+    let brew_max = number_field(&settings, "brew_rating").unwrap_or(MAX_RATING);
+    let bean_max = number_field(&settings, "bean_rating").unwrap_or(MAX_RATING);
+
+    for (name, items) in export {
+        let handled = ["BEANS", "BREWS", "PREPARATION", "MILL"].contains(&name.as_str());
+        if !handled && !IGNORED_SECTIONS.contains(&name.as_str()) && has_content(items) {
+            let count = items.as_array().map_or(1, Vec::len);
+            converted.unmapped.insert(name.clone(), count);
+        }
+    }
+
+    // 冲煮方式：内置器具直接对应，其余的作为自定义器具
+    let mut equipment: HashMap<&str, String> = HashMap::new();
+    for preparation in section(export, "PREPARATION") {
+        count_unmapped(&mut converted.unmapped, "PREPARATION", preparation, PREPARATION_FIELDS);
+        let Some(id) = uuid(preparation) else {
+            converted.skipped += 1;
+            continue;
+        };
+        let name = text_field(preparation, "name").unwrap_or("Beanconqueror").trim();
+        let preparation_type = text_field(preparation, "type").unwrap_or_default();
+        if let Some(builtin) = builtin_equipment(preparation_type) {
+            equipment.insert(id, builtin.to_string());
+            continue;
+        }
+        let equipment_id = format!("custom-custom-{}{}", ID_PREFIX, id);
+        let mut custom = json!({
+            "id": equipment_id,
+            "name": name,
+            "animationType": "custom",
+            "isCustom": true,
+            "timestamp": timestamp(preparation),
+        });
+        if let Some(note) = text_field(preparation, "note") {
+            custom["note"] = json!(note);
+        }
+        converted.equipment.push(custom);
+        equipment.insert(id, equipment_id);
+    }
+
+    let brews = section(export, "BREWS");
+    for mill in section(export, "MILL") {
+        count_unmapped(&mut converted.unmapped, "MILL", mill, MILL_FIELDS);
+        let Some(id) = uuid(mill) else {
+            converted.skipped += 1;
+            continue;
+        };
+        let mut grinder = json!({
+            "id": format!("{}{}", ID_PREFIX, id),
+            "name": text_field(mill, "name").unwrap_or("Beanconqueror").trim(),
+        });
+        let latest = brews
+            .iter()
+            .filter(|brew| text_field(brew, "mill") == Some(id) && text_field(brew, "grind_size").is_some())
+            .max_by_key(|brew| timestamp(brew).unwrap_or_default());
+        if let Some(size) = latest.and_then(|brew| text_field(brew, "grind_size")) {
+            grinder["currentGrindSize"] = json!(size);
+        }
+        converted.grinders.push(grinder);
+    }
+
+    let mut used: HashMap<&str, f64> = HashMap::new();
+    for brew in brews {
+        if let Some(bean) = text_field(brew, "bean") {
+            *used.entry(bean).or_insert(0.0) += number_field(brew, "grind_weight").unwrap_or(0.0);
+        }
+    }
+    let mut beans: HashMap<&str, Value> = HashMap::new();
+    for bean in section(export, "BEANS") {
+        count_unmapped(&mut converted.unmapped, "BEANS", bean, BEAN_FIELDS);
+        let (Some(id), Some(_)) = (uuid(bean), text_field(bean, "name")) else {
+            converted.skipped += 1;
+            continue;
+        };
+        let value = convert_bean(bean, format!("{}{}", ID_PREFIX, id), used.get(id).copied().unwrap_or(0.0), bean_max);
+        beans.insert(id, value.clone());
+        converted.beans.push(value);
+    }
+
+    for brew in brews {
+        count_unmapped(&mut converted.unmapped, "BREWS", brew, BREW_FIELDS);
+        let (Some(id), Some(time)) = (uuid(brew), timestamp(brew)) else {
+            converted.skipped += 1;
+            continue;
+        };
+        let mut params = Map::new();
+        let coffee = number_field(brew, "grind_weight").filter(|g| *g > 0.0);
+        let water = number_field(brew, "brew_quantity").filter(|g| *g > 0.0);
+        if let Some(coffee) = coffee {
+            params.insert("coffee".to_string(), json!(format!("{}g", number_text(coffee))));
+        }
+        if let Some(water) = water {
+            params.insert("water".to_string(), json!(format!("{}g", number_text(water))));
+        }
+        if let (Some(coffee), Some(water)) = (coffee, water) {
+            params.insert("ratio".to_string(), json!(format!("1:{}", number_text(water / coffee))));
+        }
+        if let Some(size) = text_field(brew, "grind_size") {
+            params.insert("grindSize".to_string(), json!(size));
+        }
+        if let Some(temp) = number_field(brew, "brew_temperature").filter(|t| *t > 0.0) {
+            params.insert("temp".to_string(), json!(format!("{}°C", number_text(temp))));
+        }
+        let bean = text_field(brew, "bean").and_then(|bean| beans.get(bean));
+        let mut note = json!({
+            "id": format!("{}{}", ID_PREFIX, id),
+            "timestamp": time,
+            "params": params,
+            "coffeeBeanInfo": {
+                "name": bean.and_then(|b| text_field(b, "name")).unwrap_or_default(),
+                "roastLevel": bean.and_then(|b| text_field(b, "roastLevel")).unwrap_or_default(),
+            },
+            "rating": number_field(brew, "rating").and_then(|r| scale_rating(r, brew_max)).unwrap_or(0.0),
+            "taste": {},
+            "notes": text_field(brew, "note").unwrap_or_default(),
+        });
+        if let Some(bean) = bean {
+            note["beanId"] = bean["id"].clone();
+            for key in ["roastDate", "roaster"] {
+                if let Some(value) = bean.get(key) {
+                    note["coffeeBeanInfo"][key] = value.clone();
+                }
+            }
+        }
+        if let Some(equipment) = text_field(brew, "method_of_preparation").and_then(|p| equipment.get(p)) {
+            note["equipment"] = json!(equipment);
+        }
+        if let Some(seconds) = number_field(brew, "brew_time").filter(|t| *t > 0.0) {
+            note["totalTime"] = json!(seconds);
+        }
+        converted.notes.push(note);
+    }
+    converted
+}
+
+fn import(conn: &Connection, converted: Converted, dry_run: bool) -> rusqlite::Result<BeanconquerorReport> {
+    let mut report = BeanconquerorReport {
+        dry_run,
+        skipped: converted.skipped,
+        ..Default::default()
+    };
+    atomically(conn, || {
+        for (kind, records) in [(RecordKind::Bean, &converted.beans), (RecordKind::Note, &converted.notes)] {
+            for value in records {
+                let id = text_field(value, "id").unwrap_or_default();
+                let exists = record_data(conn, kind, id)?.is_some();
+                match (kind, exists) {
+                    (RecordKind::Bean, true) => report.beans_updated += 1,
+                    (RecordKind::Bean, false) => report.beans_created += 1,
+                    (RecordKind::Note, true) => report.notes_updated += 1,
+                    (RecordKind::Note, false) => report.notes_created += 1,
+                }
+                if !dry_run {
+                    journal::track(conn, kind, id, || write_record(conn, kind, value))?;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    report.equipment = converted.equipment;
+    report.grinders = converted.grinders;
+    report.unmapped = converted.unmapped;
+    Ok(report)
+}
+
+// 导入 Beanconqueror 导出的 .json 或 .zip；dry_run 为 true 时只转换并返回结果，不写入数据
+#[tauri::command]
+pub fn import_beanconqueror(app: tauri::AppHandle, path: String, dry_run: bool) -> Result<BeanconquerorReport, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+    let converted = convert(&read_export(&bytes)?);
+    if !dry_run {
+        crate::backups::snapshot_before(&app, "beanconqueror-import");
+    }
+    let report = with_store(&app, |store| import(&store.conn, converted, dry_run))?;
+    if !dry_run {
+        if report.beans_created + report.beans_updated + report.notes_created + report.notes_updated > 0 {
+            crate::store::publish_changes(&app)?;
+        }
+        crate::telemetry::record(&app, "import.beanconqueror");
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn converts_beans_brews_and_equipment_from_a_zip_export() {
+        let main = json!({
+            "BEANS": [{
+                "config": { "uuid": "b1", "unix_timestamp": 1_790_000_000 },
+                "name": "Ethiopia Guji", "roaster": "Tim Wendelboe", "roastingDate": "2026-09-20T00:00:00.000Z",
+                "roast": "CITY_ROAST", "aromatics": "Peach, Jasmine", "weight": 250, "cost": 18.5,
+                "bean_information": [{ "country": "Ethiopia", "processing": "Washed", "farm": "" }],
+                "ean_article_number": "7090000000000", "decaffeinated": false,
+            }],
+            "PREPARATION": [
+                { "config": { "uuid": "p1" }, "name": "My V60", "type": "V60" },
+                { "config": { "uuid": "p2" }, "name": "Chemex 6 cup", "type": "CHEMEX" },
+            ],
+            "MILL": [{ "config": { "uuid": "m1" }, "name": "Comandante" }],
+            "SETTINGS": [{ "brew_rating": 10 }],
+            "GREEN_BEANS": [{ "name": "Kenya" }],
+        });
+        let brews = json!([{
+            "config": { "uuid": "r1", "unix_timestamp": 1_791_000_000 },
+            "bean": "b1", "method_of_preparation": "p2", "mill": "m1", "grind_size": "24",
+            "grind_weight": 30, "brew_quantity": 500, "brew_temperature": 94, "brew_time": 240,
+            "rating": 7, "tds": 1.35, "note": "sweet",
+        }]);
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, value) in [("Beanconqueror.json", &main), ("Beanconqueror_Brews_1.json", &brews)] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(value.to_string().as_bytes()).unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+
+        let converted = convert(&read_export(&bytes).unwrap());
+        let bean = &converted.beans[0];
+        assert_eq!(bean["id"], "bc-b1");
+        assert_eq!(bean["roastDate"], "2026-09-20");
+        assert_eq!(bean["roastLevel"], "中浅烘焙");
+        assert_eq!(bean["flavor"], json!(["Peach", "Jasmine"]));
+        assert_eq!((&bean["capacity"], &bean["remaining"], &bean["price"]), (&json!("250"), &json!("220"), &json!("18.5")));
+        assert_eq!(bean["blendComponents"], json!([{ "country": "Ethiopia", "process": "Washed" }]));
+
+        let note = &converted.notes[0];
+        assert_eq!(note["beanId"], "bc-b1");
+        assert_eq!(note["equipment"], "custom-custom-bc-p2");
+        assert_eq!(note["params"], json!({ "coffee": "30g", "water": "500g", "ratio": "1:16.7", "grindSize": "24", "temp": "94°C" }));
+        assert_eq!(note["rating"], 3.5);
+        assert_eq!(converted.equipment.len(), 1);
+        assert_eq!(converted.grinders, vec![json!({ "id": "bc-m1", "name": "Comandante", "currentGrindSize": "24" })]);
+        let unmapped: Vec<&str> = converted.unmapped.keys().map(String::as_str).collect();
+        assert_eq!(unmapped, vec!["BEANS.ean_article_number", "BREWS.tds", "GREEN_BEANS"]);
+
+        let conn = crate::store::test_connection();
+        let report = import(&conn, converted, false).unwrap();
+        assert_eq!((report.beans_created, report.notes_created), (1, 1));
+        assert!(record_data(&conn, RecordKind::Note, "bc-r1").unwrap().is_some());
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::i18n::Locale;
+
+// 托盘「最近冲煮」子菜单：前端推送最近的冲煮笔记，点击后跳转到笔记详情
+// 统计区的「今日冲煮」：前端推送最近八天的冲煮用量（周报统计上周），按配置时区的日期统计，跨过零点后随托盘刷新归零
+const MAX_RECENT_BREWS: usize = 5;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentBrew {
+    pub id: String,
+    pub method: Option<String>,
+    pub bean_name: Option<String>,
+    pub rating: Option<f64>,
+    pub timestamp: i64, // 毫秒
+}
+
+impl RecentBrew {
+    // 菜单文案，如 "V60 一刀流 · 耶加雪菲 · ★4.5 · 2 小时前"
+    // 隐私模式下不显示咖啡豆名称
+    pub fn label(&self, locale: Locale, now_millis: i64, private: bool) -> String {
+        let bean_name = self.bean_name.as_deref().filter(|_| !private);
+        let mut parts: Vec<String> = [self.method.as_deref(), bean_name]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        if let Some(rating) = self.rating.filter(|r| *r > 0.0) {
+            let decimals = if rating.fract() == 0.0 { 0 } else { 1 };
+            parts.push(format!("★{}", locale.format_number(rating, decimals)));
+        }
+        let minutes = (now_millis - self.timestamp).max(0) / 60_000;
+        parts.push(locale.format_time_ago(minutes));
+        parts.join(" · ")
+    }
+}
+
+// 一次冲煮的用粉量
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewDose {
+    pub timestamp: i64, // 毫秒
+    pub grams: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DailyBrewStats {
+    pub count: usize,
+    pub grams: f64,
+}
+
+#[derive(Default)]
+pub struct BrewCache {
+    recent: Vec<RecentBrew>,
+    doses: Vec<BrewDose>,
+}
+
+pub fn recent(app: &tauri::AppHandle) -> Vec<RecentBrew> {
+    app.try_state::<Arc<Mutex<BrewCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.recent.clone()))
+        .unwrap_or_default()
+}
+
+// 指定日期的冲煮杯数和总用粉量
+pub fn daily_stats(app: &tauri::AppHandle, day: NaiveDate) -> DailyBrewStats {
+    stats_between(app, day, day)
+}
+
+// 日期区间内（含首尾）的冲煮杯数和总用粉量，用于周报
+pub fn stats_between(app: &tauri::AppHandle, from: NaiveDate, to: NaiveDate) -> DailyBrewStats {
+    let doses = app
+        .try_state::<Arc<Mutex<BrewCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.doses.clone()))
+        .unwrap_or_default();
+    doses
+        .iter()
+        .filter(|dose| {
+            DateTime::<Utc>::from_timestamp_millis(dose.timestamp)
+                .is_some_and(|at| (from..=to).contains(&crate::date_of(app, at)))
+        })
+        .fold(DailyBrewStats::default(), |stats, dose| DailyBrewStats {
+            count: stats.count + 1,
+            grams: stats.grams + dose.grams.unwrap_or(0.0),
+        })
+}
+
+// 前端笔记变化时推送，按时间倒序只保留最近几条
+#[tauri::command]
+pub fn update_tray_recent_brews(app: tauri::AppHandle, mut brews: Vec<RecentBrew>) -> Result<(), String> {
+    brews.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+    brews.truncate(MAX_RECENT_BREWS);
+    let state = app
+        .try_state::<Arc<Mutex<BrewCache>>>()
+        .ok_or("冲煮记录未初始化")?;
+    state.lock().map_err(|e| e.to_string())?.recent = brews;
+    crate::background::refresh(&app)
+}
+
+#[tauri::command]
+pub fn update_tray_daily_stats(app: tauri::AppHandle, doses: Vec<BrewDose>) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<BrewCache>>>()
+        .ok_or("冲煮记录未初始化")?;
+    state.lock().map_err(|e| e.to_string())?.doses = doses;
+    crate::background::refresh(&app)
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::Emitter;
+
+use crate::journal;
+use crate::store::{atomically, record_data, with_store, RecordKind};
+
+// 批量操作：一次修改或删除多款咖啡豆（如统一设置烘焙商、调整赏味期、标记为喝完）
+// 在同一个事务中执行，任何一条失败都整体回滚；完成后只把修改交给前端一次、发送一次 store-changed 事件
+// 每条记录仍分别记入修改记录，可以逐条撤销
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StoreChangedEvent<'a> {
+    kind: RecordKind,
+    ids: &'a [String],
+}
+
+// 合并修改：值为 null 的字段删除，id 不能修改
+fn apply_patch(bean: &mut Value, patch: &Map<String, Value>) {
+    let Some(object) = bean.as_object_mut() else {
+        return;
+    };
+    for (key, value) in patch.iter().filter(|(key, _)| key.as_str() != "id") {
+        if value.is_null() {
+            object.remove(key);
+        } else {
+            object.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+fn notify(app: &tauri::AppHandle, kind: RecordKind, ids: &[String]) -> Result<(), String> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    crate::store::publish_changes(app)?;
+    let _ = app.emit("store-changed", StoreChangedEvent { kind, ids });
+    Ok(())
+}
+
+// 对多款咖啡豆应用同一份修改，返回修改后的数据；有找不到的咖啡豆时不做任何修改
+#[tauri::command]
+pub fn bulk_update_beans(app: tauri::AppHandle, ids: Vec<String>, patch: Map<String, Value>) -> Result<Vec<Value>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if patch.get("name").is_some_and(|name| !name.as_str().is_some_and(|n| !n.trim().is_empty())) {
+        return Err("咖啡豆缺少名称".to_string());
+    }
+    crate::backups::snapshot_before(&app, "bulk-update");
+    let updated = with_store(&app, |store| {
+        let conn = &store.conn;
+        // 先读出全部咖啡豆，确认都存在后再修改
+        let mut beans = Vec::new();
+        for id in &ids {
+            let Some(data) = record_data(conn, RecordKind::Bean, id)? else {
+                return Ok(Err(format!("找不到咖啡豆：{}", id)));
+            };
+            let mut bean: Value =
+                serde_json::from_str(&data).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+            apply_patch(&mut bean, &patch);
+            beans.push(bean);
+        }
+        atomically(conn, || {
+            for (id, bean) in ids.iter().zip(&beans) {
+                journal::track(conn, RecordKind::Bean, id, || crate::store::write_bean(conn, bean))?;
+            }
+            Ok(())
+        })?;
+        Ok(Ok(beans))
+    })??;
+    crate::telemetry::record(&app, "bulk.update");
+    notify(&app, RecordKind::Bean, &ids)?;
+    Ok(updated)
+}
+
+// 把多条咖啡豆或笔记移到回收站，返回实际删除的 id
+#[tauri::command]
+pub fn bulk_delete(app: tauri::AppHandle, kind: RecordKind, ids: Vec<String>) -> Result<Vec<String>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    crate::backups::snapshot_before(&app, "bulk-delete");
+    let deleted = with_store(&app, |store| {
+        let conn = &store.conn;
+        atomically(conn, || {
+            let mut deleted = Vec::new();
+            for id in &ids {
+                if journal::track(conn, kind, id, || crate::trash::move_to_trash(conn, kind, id))? {
+                    deleted.push(id.clone());
+                }
+            }
+            Ok(deleted)
+        })
+    })?;
+    crate::telemetry::record(&app, "bulk.delete");
+    notify(&app, kind, &deleted)?;
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn patches_fields_and_removes_nulls() {
+        let mut bean = json!({ "id": "b1", "name": "耶加雪菲", "roaster": "旧", "startDay": 7 });
+        let patch = json!({ "id": "b2", "roaster": "新", "startDay": null, "remaining": "0" });
+        apply_patch(&mut bean, patch.as_object().unwrap());
+        assert_eq!(bean, json!({ "id": "b1", "name": "耶加雪菲", "roaster": "新", "remaining": "0" }));
+    }
+}
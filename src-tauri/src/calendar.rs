@@ -0,0 +1,191 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::background::BeanCache;
+use crate::CoffeeBean;
+
+// 赏味期日历（iCalendar）：每款咖啡豆“进入赏味期”和“赏味期结束”各一个全天事件
+// 冷冻、在途、已喝完的咖啡豆不生成事件；订阅文件保存在 app_data_dir/calendar/brew-guide.ics，
+// 开启后咖啡豆变化时自动重新生成，系统日历订阅这个文件即可
+const FEED_DIR: &str = "calendar";
+const FEED_FILE: &str = "brew-guide.ics";
+const MAX_LINE_OCTETS: usize = 75;
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+// 超过 75 字节的行折行，续行以空格开头；不拆开 UTF-8 字符
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn is_active(bean: &CoffeeBean) -> bool {
+    !bean.is_frozen.unwrap_or(false)
+        && !bean.is_in_transit.unwrap_or(false)
+        && !bean.remaining_grams().is_some_and(|grams| grams <= 0.0)
+}
+
+fn push_event(out: &mut String, uid: &str, date: NaiveDate, summary: &str, stamp: &str) {
+    push_line(out, "BEGIN:VEVENT");
+    push_line(out, &format!("UID:{}@brew-guide", uid));
+    push_line(out, &format!("DTSTAMP:{}", stamp));
+    push_line(out, &format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+    if let Some(next) = date.succ_opt() {
+        push_line(out, &format!("DTEND;VALUE=DATE:{}", next.format("%Y%m%d")));
+    }
+    push_line(out, &format!("SUMMARY:{}", escape_text(summary)));
+    push_line(out, "TRANSP:TRANSPARENT");
+    push_line(out, "END:VEVENT");
+}
+
+fn build_ics(beans: &[CoffeeBean], generated_at: DateTime<Utc>) -> String {
+    let stamp = generated_at.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//Brew Guide//Flavor Window//ZH",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+        "X-WR-CALNAME:咖啡豆赏味期",
+    ] {
+        push_line(&mut out, line);
+    }
+    for bean in beans.iter().filter(|bean| !bean.id.is_empty() && is_active(bean)) {
+        let Some((start, end)) = bean
+            .roast_date
+            .as_deref()
+            .and_then(|date| crate::roast_date::flavor_window(date, bean.start_day, bean.end_day))
+        else {
+            continue;
+        };
+        push_event(&mut out, &format!("{}-start", bean.id), start, &format!("{} 进入赏味期", bean.name), &stamp);
+        push_event(&mut out, &format!("{}-end", bean.id), end, &format!("{} 赏味期结束", bean.name), &stamp);
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+// 先写临时文件再替换，日历应用不会读到写了一半的文件
+fn write_ics(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let partial = path.with_extension("ics.partial");
+    std::fs::write(&partial, content).map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, path).map_err(|e| e.to_string())
+}
+
+// 只有 DTSTAMP 不同时不重写，避免日历应用频繁重新加载
+fn same_events(old: &str, new: &str) -> bool {
+    let events = |text: &str| text.lines().filter(|line| !line.starts_with("DTSTAMP:")).collect::<Vec<_>>().join("\n");
+    events(old) == events(new)
+}
+
+fn feed_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(FEED_DIR).join(FEED_FILE))
+}
+
+fn cached_beans(app: &tauri::AppHandle) -> Vec<CoffeeBean> {
+    app.try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.beans.clone()))
+        .unwrap_or_default()
+}
+
+// 前端推送咖啡豆后调用（apply_beans），未开启订阅时不做任何事
+pub fn refresh_feed(app: &tauri::AppHandle, beans: &[CoffeeBean]) -> Result<(), String> {
+    if !crate::settings::get(app).calendar_feed {
+        return Ok(());
+    }
+    let path = feed_path(app)?;
+    let content = build_ics(beans, Utc::now());
+    if std::fs::read_to_string(&path).is_ok_and(|old| same_events(&old, &content)) {
+        return Ok(());
+    }
+    write_ics(&path, &content)
+}
+
+#[tauri::command]
+pub fn export_ics(app: tauri::AppHandle, path: String) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let content = build_ics(&cached_beans(&app), Utc::now());
+    write_ics(Path::new(&path), &content)?;
+    crate::telemetry::record(&app, "export.ics");
+    Ok(content.matches("BEGIN:VEVENT").count())
+}
+
+// 开启或关闭订阅文件，开启时返回文件路径（在系统日历中以文件或 file:// 链接订阅）
+#[tauri::command]
+pub fn set_calendar_feed(app: tauri::AppHandle, enabled: bool) -> Result<Option<String>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    crate::settings::update(&app, |settings| settings.calendar_feed = enabled)?;
+    let path = feed_path(&app)?;
+    if !enabled {
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(e.to_string());
+            }
+        }
+        return Ok(None);
+    }
+    refresh_feed(&app, &cached_beans(&app))?;
+    crate::telemetry::record(&app, "calendar.feed");
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_flavor_window_events() {
+        let bean = |id: &str, frozen: bool| CoffeeBean {
+            id: id.to_string(),
+            name: "埃塞俄比亚 耶加雪菲 科契尔, 水洗 G1 (Konga Cooperative, Yirgacheffe)".to_string(),
+            remaining: Some("120".to_string()),
+            capacity: None,
+            roast_date: Some("2026-10-01".to_string()),
+            start_day: Some(5),
+            end_day: None,
+            is_frozen: Some(frozen),
+            is_in_transit: None,
+            expected_arrival: None,
+            pinned: None,
+            roaster: None,
+            origin: None,
+            process: None,
+        };
+        let at = DateTime::parse_from_rfc3339("2026-10-16T08:00:00Z").unwrap().with_timezone(&Utc);
+        let ics = build_ics(&[bean("b1", false), bean("b2", true)], at);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("UID:b1-start@brew-guide\r\nDTSTAMP:20261016T080000Z\r\nDTSTART;VALUE=DATE:20261006\r\nDTEND;VALUE=DATE:20261007\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261031\r\n"));
+        assert!(ics.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains("SUMMARY:埃塞俄比亚 耶加雪菲 科契尔\\, 水洗 G1 (Konga Cooperative\\, Yirgacheffe) 进入赏味期\r\n"));
+
+        // 只有生成时间不同时不需要重写订阅文件
+        let later = build_ics(&[bean("b1", false)], at + chrono::Duration::hours(1));
+        assert!(same_events(&ics, &later));
+        assert!(!same_events(&ics, &build_ics(&[bean("b1", false), bean("b3", false)], at)));
+    }
+}
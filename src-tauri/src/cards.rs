@@ -0,0 +1,250 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::pdf::{wrap, PdfDocument, Page};
+use crate::store::{join_components, number_field, record_data, text_field, with_store, RecordKind};
+
+// 打印或分享用的 A6 卡片：咖啡豆卡片（名称、烘焙信息、赏味期、风味）和冲煮方案卡片（参数、步骤），
+// 带有在应用中打开的二维码；未指定保存路径时生成到缓存目录的 cards/ 下，返回文件路径
+const PAGE_WIDTH: f64 = 105.0;
+const PAGE_HEIGHT: f64 = 148.0;
+const MARGIN: f64 = 8.0;
+const CONTENT_WIDTH: f64 = PAGE_WIDTH - MARGIN * 2.0;
+const LABEL_WIDTH: f64 = 18.0;
+const BEAN_QR_SIZE: f64 = 28.0;
+const RECIPE_QR_SIZE: f64 = 22.0;
+const BEAN_LINK_PREFIX: &str = "brewguide://bean/";
+const METHOD_LINK_PREFIX: &str = "brewguide://method/";
+const CARDS_DIR: &str = "cards";
+
+fn draw_qr(page: &mut Page, x: f64, y: f64, size: f64, payload: &str) -> Result<(), String> {
+    let code = qrcode::QrCode::with_error_correction_level(payload.as_bytes(), qrcode::EcLevel::M)
+        .map_err(|e| e.to_string())?;
+    let modules: Vec<bool> = code.to_colors().into_iter().map(|c| c == qrcode::Color::Dark).collect();
+    page.qr_code(x, y, size, code.width(), &modules);
+    Ok(())
+}
+
+// 标签和值各占一列，值过长时折行；返回下一行的位置，超出 bottom 的行不再绘制
+fn draw_row(page: &mut Page, y: f64, label: &str, value: &str, bottom: f64) -> f64 {
+    let mut y = y;
+    page.text(MARGIN, y, 9.0, 0.45, label);
+    for line in wrap(value, 9.0, CONTENT_WIDTH - LABEL_WIDTH) {
+        if y > bottom {
+            break;
+        }
+        page.text(MARGIN + LABEL_WIDTH, y, 9.0, 0.0, &line);
+        y += 4.6;
+    }
+    y + 0.8
+}
+
+fn bean_card(bean: &Value) -> Result<PdfDocument, String> {
+    let id = text_field(bean, "id").ok_or("咖啡豆缺少 id")?;
+    let mut document = PdfDocument::new(PAGE_WIDTH, PAGE_HEIGHT);
+    let page = document.add_page();
+
+    let mut y = MARGIN + 6.0;
+    for line in wrap(text_field(bean, "name").unwrap_or_default(), 16.0, CONTENT_WIDTH).iter().take(2) {
+        page.text(MARGIN, y, 16.0, 0.0, line);
+        y += 7.0;
+    }
+    if let Some(roaster) = text_field(bean, "roaster") {
+        page.text(MARGIN, y - 1.0, 9.0, 0.4, roaster);
+        y += 4.0;
+    }
+    page.line((MARGIN, y), (PAGE_WIDTH - MARGIN, y), 0.3, 0.7);
+    y += 6.0;
+
+    let roast_date = text_field(bean, "roastDate");
+    let window = roast_date.and_then(|date| {
+        crate::roast_date::flavor_window(
+            date,
+            number_field(bean, "startDay").map(|d| d as i32),
+            number_field(bean, "endDay").map(|d| d as i32),
+        )
+    });
+    let amount = |key: &str| match bean.get(key) {
+        Some(Value::String(s)) if !s.trim().is_empty() => Some(format!("{}g", s.trim().trim_end_matches('g'))),
+        Some(Value::Number(n)) => Some(format!("{}g", n)),
+        _ => None,
+    };
+    let flavor: Vec<&str> = bean.get("flavor").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect();
+    let rows = [
+        ("产地", join_components(bean, |c| text_field(c, "origin").or_else(|| text_field(c, "country")))),
+        ("处理法", join_components(bean, |c| text_field(c, "process"))),
+        ("品种", join_components(bean, |c| text_field(c, "variety"))),
+        ("烘焙度", text_field(bean, "roastLevel").map(str::to_string)),
+        ("烘焙日期", roast_date.map(str::to_string)),
+        ("赏味期", window.map(|(start, end)| format!("{} ~ {}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d")))),
+        ("容量", amount("capacity")),
+        ("风味", (!flavor.is_empty()).then(|| flavor.join(" / "))),
+    ];
+    let bottom = PAGE_HEIGHT - MARGIN - BEAN_QR_SIZE - 4.0;
+    for (label, value) in rows {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            y = draw_row(page, y, label, &value, bottom);
+        }
+    }
+
+    let qr_top = PAGE_HEIGHT - MARGIN - BEAN_QR_SIZE;
+    draw_qr(page, PAGE_WIDTH - MARGIN - BEAN_QR_SIZE, qr_top, BEAN_QR_SIZE, &format!("{}{}", BEAN_LINK_PREFIX, id))?;
+    page.text(MARGIN, PAGE_HEIGHT - MARGIN - 6.0, 11.0, 0.0, "Brew Guide");
+    page.text(MARGIN, PAGE_HEIGHT - MARGIN - 1.0, 7.0, 0.45, "扫码在 Brew Guide 中打开");
+    Ok(document)
+}
+
+// 步骤的结束时间（秒）：新版数据为每步的 duration，旧版为累计的 time
+fn stage_times(stages: &[Value]) -> Vec<Option<f64>> {
+    let mut elapsed = 0.0;
+    stages
+        .iter()
+        .map(|stage| {
+            if let Some(time) = number_field(stage, "time") {
+                elapsed = time;
+                return Some(time);
+            }
+            let duration = number_field(stage, "duration")?;
+            elapsed += duration;
+            Some(elapsed)
+        })
+        .collect()
+}
+
+fn recipe_card(method_id: &str, method: &Value) -> Result<PdfDocument, String> {
+    let mut document = PdfDocument::new(PAGE_WIDTH, PAGE_HEIGHT);
+    let params = method.get("params").unwrap_or(&Value::Null);
+    let bottom = PAGE_HEIGHT - MARGIN;
+
+    let page = document.add_page();
+    let qr_left = PAGE_WIDTH - MARGIN - RECIPE_QR_SIZE;
+    draw_qr(page, qr_left, MARGIN, RECIPE_QR_SIZE, &format!("{}{}", METHOD_LINK_PREFIX, method_id))?;
+    let mut y = MARGIN + 6.0;
+    for line in wrap(text_field(method, "name").unwrap_or_default(), 15.0, qr_left - MARGIN - 3.0).iter().take(3) {
+        page.text(MARGIN, y, 15.0, 0.0, line);
+        y += 6.5;
+    }
+    y = y.max(MARGIN + RECIPE_QR_SIZE + 4.0);
+    page.line((MARGIN, y), (PAGE_WIDTH - MARGIN, y), 0.3, 0.7);
+    y += 6.0;
+
+    // 参数分两列
+    let values: Vec<(&str, &str)> = [("粉量", "coffee"), ("水量", "water"), ("粉水比", "ratio"), ("研磨度", "grindSize"), ("水温", "temp")]
+        .iter()
+        .filter_map(|(label, key)| text_field(params, key).map(|value| (*label, value)))
+        .collect();
+    for pair in values.chunks(2) {
+        for (column, (label, value)) in pair.iter().enumerate() {
+            let x = MARGIN + column as f64 * CONTENT_WIDTH / 2.0;
+            page.text(x, y, 8.0, 0.45, label);
+            page.text(x + 12.0, y, 9.0, 0.0, value);
+        }
+        y += 5.0;
+    }
+
+    let stages: &[Value] = params.get("stages").and_then(Value::as_array).map_or(&[], Vec::as_slice);
+    if stages.is_empty() {
+        return Ok(document);
+    }
+    y += 3.0;
+    page.text(MARGIN, y, 11.0, 0.0, "步骤");
+    y += 6.0;
+    let mut page_index = 0;
+    for (stage, end) in stages.iter().zip(stage_times(stages)) {
+        let mut title = text_field(stage, "label").unwrap_or_default().to_string();
+        if let Some(water) = text_field(stage, "water") {
+            title = format!("{} · {}g", title, water.trim_end_matches('g'));
+        }
+        let details = text_field(stage, "detail").map_or_else(Vec::new, |d| wrap(d, 8.0, CONTENT_WIDTH - 14.0));
+        let height = 5.0 + details.len() as f64 * 4.0 + 1.5;
+        if y + height > bottom {
+            document.add_page();
+            page_index += 1;
+            y = MARGIN + 6.0;
+        }
+        let page = document.page(page_index);
+        if let Some(end) = end {
+            let seconds = end.round() as i64;
+            page.text(MARGIN, y, 9.0, 0.45, &format!("{}:{:02}", seconds / 60, seconds % 60));
+        }
+        page.text(MARGIN + 14.0, y, 9.0, 0.0, &title);
+        y += 5.0;
+        for line in details {
+            page.text(MARGIN + 14.0, y - 0.5, 8.0, 0.4, &line);
+            y += 4.0;
+        }
+        y += 1.5;
+    }
+    Ok(document)
+}
+
+fn output_path(app: &tauri::AppHandle, path: Option<String>, name: &str, id: &str) -> Result<PathBuf, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| e.to_string())?
+            .join(CARDS_DIR)
+            .join(format!("{}.pdf", crate::markdown_export::file_stem(name, id))),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn generate_bean_card_pdf(app: tauri::AppHandle, bean_id: String, path: Option<String>) -> Result<String, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let bean: Value = with_store(&app, |store| record_data(&store.conn, RecordKind::Bean, &bean_id))?
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .ok_or_else(|| format!("找不到咖啡豆：{}", bean_id))?;
+    let document = bean_card(&bean)?;
+    let path = output_path(&app, path, text_field(&bean, "name").unwrap_or_default(), &bean_id)?;
+    std::fs::write(&path, document.to_bytes()).map_err(|e| e.to_string())?;
+    crate::telemetry::record(&app, "cards.bean");
+    Ok(path.to_string_lossy().to_string())
+}
+
+// 冲煮方案保存在前端，method 为方案的完整数据（name、params.stages 等）
+#[tauri::command]
+pub fn generate_recipe_pdf(app: tauri::AppHandle, method_id: String, method: Value, path: Option<String>) -> Result<String, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let document = recipe_card(&method_id, &method)?;
+    let path = output_path(&app, path, text_field(&method, "name").unwrap_or_default(), &method_id)?;
+    std::fs::write(&path, document.to_bytes()).map_err(|e| e.to_string())?;
+    crate::telemetry::record(&app, "cards.recipe");
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn lays_out_bean_and_recipe_cards() {
+        let hex = |text: &str| text.chars().map(|c| format!("{:04X}", c as u32)).collect::<String>();
+        let bean = json!({
+            "id": "b1", "name": "耶加雪菲", "roastDate": "2026-10-01", "startDay": 5, "endDay": 20,
+            "capacity": "200", "flavor": ["花香", "柑橘"],
+        });
+        let text = String::from_utf8_lossy(&bean_card(&bean).unwrap().to_bytes()).to_string();
+        assert!(text.contains(&hex("耶加雪菲")));
+        assert!(text.contains(&hex("2026-10-06 ~ 2026-10-21")));
+        assert!(text.contains(&hex("200g")));
+        assert!(text.contains(&hex("花香 / 柑橘")));
+        assert!(!text.contains(&hex("处理法")));
+
+        let stages: Vec<Value> = (0..12)
+            .map(|i| json!({ "label": format!("第{}段", i + 1), "water": "40", "duration": 30, "detail": "中心向外画圈注水，保持水流稳定" }))
+            .collect();
+        let method = json!({ "name": "一刀流", "params": { "coffee": "15g", "water": "225g", "stages": stages } });
+        let text = String::from_utf8_lossy(&recipe_card("m1", &method).unwrap().to_bytes()).to_string();
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains(&hex("6:00")));
+        assert_eq!(stage_times(&[json!({ "time": 30 }), json!({ "duration": 15 })]), vec![Some(30.0), Some(45.0)]);
+    }
+}
@@ -0,0 +1,106 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+// 设备之间的加密连接（局域网同步、设备迁移共用）：每条消息是 4 字节长度 + 内容，
+// 握手后的消息用会话密钥以 AES-256-GCM 加密，随机数由发送方和消息序号组成
+// 对方通过认证之前只接受很小的消息，避免未认证的连接让我们分配大块内存
+pub const HANDSHAKE_FRAME_BYTES: usize = 4 * 1024;
+const MAX_FRAME_BYTES: usize = 256 * 1024 * 1024;
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn unhex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+pub fn write_frame(stream: &mut TcpStream, bytes: &[u8]) -> Result<(), String> {
+    let len = u32::try_from(bytes.len()).map_err(|_| "消息过大".to_string())?;
+    stream.write_all(&len.to_be_bytes()).map_err(|e| e.to_string())?;
+    stream.write_all(bytes).map_err(|e| e.to_string())
+}
+
+pub fn read_frame(stream: &mut TcpStream, max_bytes: usize) -> Result<Vec<u8>, String> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len).map_err(|e| e.to_string())?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > max_bytes {
+        return Err(format!("消息过大：{} 字节", len));
+    }
+    let mut bytes = vec![0u8; len];
+    stream.read_exact(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+// 随机数：发送方（客户端为 1）+ 递增的消息序号，同一个会话密钥下不会重复
+fn nonce(from_client: bool, counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[0] = u8::from(from_client);
+    nonce[4..].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+pub struct Channel {
+    pub stream: TcpStream,
+    cipher: Aes256Gcm,
+    is_client: bool,
+    sent: u64,
+    received: u64,
+    max_frame: usize,
+}
+
+impl Channel {
+    pub fn new(stream: TcpStream, key: &[u8; 32], is_client: bool) -> Result<Self, String> {
+        Ok(Self {
+            stream,
+            cipher: Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?,
+            is_client,
+            sent: 0,
+            received: 0,
+            max_frame: HANDSHAKE_FRAME_BYTES,
+        })
+    }
+
+    // 确认对方身份之后（配对完成或证明持有密钥）才放开消息大小限制
+    pub fn authenticated(&mut self) {
+        self.max_frame = MAX_FRAME_BYTES;
+    }
+
+    pub fn send_bytes(&mut self, plain: &[u8]) -> Result<(), String> {
+        let nonce = nonce(self.is_client, self.sent);
+        self.sent += 1;
+        let data = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plain)
+            .map_err(|_| "加密消息失败".to_string())?;
+        write_frame(&mut self.stream, &data)
+    }
+
+    pub fn receive_bytes(&mut self) -> Result<Vec<u8>, String> {
+        let data = read_frame(&mut self.stream, self.max_frame)?;
+        let nonce = nonce(!self.is_client, self.received);
+        self.received += 1;
+        self.cipher
+            .decrypt(Nonce::from_slice(&nonce), data.as_slice())
+            .map_err(|_| "消息校验失败".to_string())
+    }
+
+    pub fn send<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        self.send_bytes(&serde_json::to_vec(value).map_err(|e| e.to_string())?)
+    }
+
+    pub fn receive<T: DeserializeOwned>(&mut self) -> Result<T, String> {
+        serde_json::from_slice(&self.receive_bytes()?).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,276 @@
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+// 从剪贴板文字（烘焙商的订单确认、商品详情等）中识别咖啡豆信息，返回预填的新咖啡豆（没有 ID）
+// 优先使用“品名：xxx”这类带标签的行，没有标签时按产国、处理法关键词和日期、重量格式猜测
+const MAX_TEXT_CHARS: usize = 10_000;
+const MAX_NAME_CHARS: usize = 60;
+
+const LABELS: [(&str, &str); 9] = [
+    ("name", r"品名|名称|商品名称|商品|产品名称|豆名|name|product"),
+    ("roaster", r"烘焙商|烘焙师|店铺|品牌|roaster|brand"),
+    ("origin", r"产地|产区|产国|国家|origin|country|region"),
+    ("process", r"处理法|处理方式|处理|process|processing"),
+    ("variety", r"品种|豆种|variety|varietal"),
+    ("roastLevel", r"烘焙度|烘焙程度|roast level|roast"),
+    ("roastDate", r"烘焙日期|烘焙时间|烘焙于|roast date|roasted on|roasted"),
+    ("weight", r"规格|净含量|重量|容量|weight|net wt|net weight|size"),
+    ("flavor", r"风味|风味描述|杯测风味|flavou?r notes|flavou?rs?|tasting notes|notes"),
+];
+
+const COUNTRIES: [(&str, &str); 26] = [
+    ("埃塞俄比亚", "埃塞俄比亚"),
+    ("ethiopia", "埃塞俄比亚"),
+    ("肯尼亚", "肯尼亚"),
+    ("kenya", "肯尼亚"),
+    ("哥伦比亚", "哥伦比亚"),
+    ("colombia", "哥伦比亚"),
+    ("巴拿马", "巴拿马"),
+    ("panama", "巴拿马"),
+    ("危地马拉", "危地马拉"),
+    ("guatemala", "危地马拉"),
+    ("哥斯达黎加", "哥斯达黎加"),
+    ("costa rica", "哥斯达黎加"),
+    ("巴西", "巴西"),
+    ("brazil", "巴西"),
+    ("卢旺达", "卢旺达"),
+    ("rwanda", "卢旺达"),
+    ("布隆迪", "布隆迪"),
+    ("burundi", "布隆迪"),
+    ("秘鲁", "秘鲁"),
+    ("peru", "秘鲁"),
+    ("洪都拉斯", "洪都拉斯"),
+    ("honduras", "洪都拉斯"),
+    ("云南", "云南"),
+    ("yunnan", "云南"),
+    ("也门", "也门"),
+    ("yemen", "也门"),
+];
+
+// 较长的关键词放前面，避免“双重厌氧”先匹配到“厌氧”
+const PROCESSES: [(&str, &str); 14] = [
+    ("双重厌氧", "双重厌氧"),
+    ("厌氧日晒", "厌氧日晒"),
+    ("厌氧水洗", "厌氧水洗"),
+    ("半水洗", "半水洗"),
+    ("湿刨", "湿刨"),
+    ("红蜜", "红蜜处理"),
+    ("黄蜜", "黄蜜处理"),
+    ("黑蜜", "黑蜜处理"),
+    ("蜜处理", "蜜处理"),
+    ("水洗", "水洗"),
+    ("日晒", "日晒"),
+    ("washed", "水洗"),
+    ("natural", "日晒"),
+    ("honey", "蜜处理"),
+];
+
+// 烘焙度标签的值先补上“烘”“roast”再匹配，例如“中浅”“Light”
+const ROAST_LEVELS: [(&str, &str); 12] = [
+    ("极浅烘", "极浅烘焙"),
+    ("中浅烘", "中浅烘焙"),
+    ("中深烘", "中深烘焙"),
+    ("浅度烘", "浅度烘焙"),
+    ("浅烘", "浅度烘焙"),
+    ("中度烘", "中度烘焙"),
+    ("中烘", "中度烘焙"),
+    ("深度烘", "深度烘焙"),
+    ("深烘", "深度烘焙"),
+    ("light roast", "浅度烘焙"),
+    ("medium roast", "中度烘焙"),
+    ("dark roast", "深度烘焙"),
+];
+
+struct Patterns {
+    label: Regex,
+    full_date: Regex,
+    month_day: Regex,
+    weight: Regex,
+    noise: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        let labels: Vec<String> = LABELS.iter().map(|(key, pattern)| format!("(?P<{}>{})", key, pattern)).collect();
+        Self {
+            label: Regex::new(&format!(r"(?i)^[\s【\[]*(?:{})[\s】\]]*[:：]\s*(?P<value>.+)$", labels.join("|"))).unwrap(),
+            full_date: Regex::new(r"(20\d{2})\s*[-/.年]\s*(\d{1,2})\s*[-/.月]\s*(\d{1,2})").unwrap(),
+            month_day: Regex::new(r"(\d{1,2})\s*月\s*(\d{1,2})\s*日").unwrap(),
+            weight: Regex::new(r"(?i)(\d+(?:\.\d+)?)\s*(kg|千克|公斤|g|克|lb|oz)(?:[^a-z]|$)").unwrap(),
+            noise: Regex::new(r"(?i)订单|快递|物流|运单|收货|数量|合计|实付|价格|[¥￥$]|order|shipping|total|qty").unwrap(),
+        }
+    }
+
+    fn label_of(&self, line: &str) -> Option<(&'static str, String)> {
+        let captures = self.label.captures(line)?;
+        let (key, _) = LABELS.iter().find(|(key, _)| captures.name(key).is_some())?;
+        let value = captures["value"].trim().to_string();
+        (!value.is_empty()).then_some((*key, value))
+    }
+
+    // 没有年份的日期取今天之前最近的一次
+    fn date(&self, text: &str, today: NaiveDate) -> Option<NaiveDate> {
+        if let Some(c) = self.full_date.captures(text) {
+            return NaiveDate::from_ymd_opt(c[1].parse().ok()?, c[2].parse().ok()?, c[3].parse().ok()?);
+        }
+        let c = self.month_day.captures(text)?;
+        let (month, day) = (c[1].parse().ok()?, c[2].parse().ok()?);
+        let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+        if date > today {
+            NaiveDate::from_ymd_opt(today.year() - 1, month, day)
+        } else {
+            Some(date)
+        }
+    }
+
+    fn grams(&self, text: &str) -> Option<f64> {
+        let c = self.weight.captures(text)?;
+        let amount: f64 = c[1].parse().ok()?;
+        let grams = match c[2].to_lowercase().as_str() {
+            "kg" | "千克" | "公斤" => amount * 1000.0,
+            "lb" => amount * 453.6,
+            "oz" => amount * 28.35,
+            _ => amount,
+        };
+        (grams > 0.0).then_some(grams.round())
+    }
+}
+
+fn find_keyword(text: &str, keywords: &[(&str, &'static str)]) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    keywords.iter().find(|(keyword, _)| lower.contains(keyword)).map(|(_, value)| *value)
+}
+
+fn is_roast_context(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.contains("烘焙") || lower.contains("roast")
+}
+
+pub(crate) fn parse_bean_text(text: &str, today: NaiveDate) -> Option<Value> {
+    let patterns = Patterns::new();
+    let text: String = text.chars().take(MAX_TEXT_CHARS).collect();
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+
+    let mut labeled: Map<String, Value> = Map::new();
+    for line in &lines {
+        if let Some((key, value)) = patterns.label_of(line) {
+            labeled.entry(key).or_insert(json!(value));
+        }
+    }
+    let label = |key: &str| labeled.get(key).and_then(Value::as_str);
+
+    let mut bean = Map::new();
+    let name = label("name").map(str::to_string).or_else(|| {
+        // 没有品名标签时，取第一行提到产国或处理法的文字，否则取第一行不像订单信息的短文字
+        let candidate = |line: &&&str| patterns.label_of(line).is_none() && !patterns.noise.is_match(line);
+        lines
+            .iter()
+            .filter(candidate)
+            .find(|line| find_keyword(line, &COUNTRIES).is_some() || find_keyword(line, &PROCESSES).is_some())
+            .or_else(|| lines.iter().filter(candidate).find(|line| line.chars().count() <= MAX_NAME_CHARS))
+            .map(|line| line.to_string())
+    });
+    if let Some(name) = name {
+        bean.insert("name".into(), json!(name.chars().take(MAX_NAME_CHARS).collect::<String>()));
+    }
+    if let Some(roaster) = label("roaster") {
+        bean.insert("roaster".into(), json!(roaster));
+    }
+
+    let roast_date = label("roastDate")
+        .and_then(|value| patterns.date(value, today))
+        .or_else(|| lines.iter().filter(|line| is_roast_context(line)).find_map(|line| patterns.date(line, today)));
+    if let Some(date) = roast_date.filter(|date| *date <= today) {
+        bean.insert("roastDate".into(), json!(date.format("%Y-%m-%d").to_string()));
+    }
+
+    let grams = label("weight").and_then(|value| patterns.grams(value)).or_else(|| patterns.grams(&text));
+    if let Some(grams) = grams {
+        bean.insert("capacity".into(), json!(grams.to_string()));
+        bean.insert("remaining".into(), json!(grams.to_string()));
+    }
+
+    let roast_level = match label("roastLevel") {
+        Some(value) => {
+            let level = value.replace("烘焙", "").replace('烘', "").to_lowercase().replace("roast", "");
+            let level = level.trim();
+            find_keyword(&format!("{}烘 {} roast", level, level), &ROAST_LEVELS)
+        }
+        None => find_keyword(&text, &ROAST_LEVELS),
+    };
+    if let Some(level) = roast_level {
+        bean.insert("roastLevel".into(), json!(level));
+    }
+
+    if let Some(flavor) = label("flavor") {
+        let notes: Vec<&str> = flavor
+            .split([',', '，', '、', '/', ';', '；', '|'])
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .collect();
+        if !notes.is_empty() {
+            bean.insert("flavor".into(), json!(notes));
+        }
+    }
+
+    let mut component = Map::new();
+    match label("origin") {
+        Some(origin) => component.insert("origin".into(), json!(origin)),
+        None => find_keyword(&text, &COUNTRIES).and_then(|country| component.insert("country".into(), json!(country))),
+    };
+    if let Some(process) = label("process").map(str::to_string).or_else(|| find_keyword(&text, &PROCESSES).map(str::to_string)) {
+        component.insert("process".into(), json!(process));
+    }
+    if let Some(variety) = label("variety") {
+        component.insert("variety".into(), json!(variety));
+    }
+    if !component.is_empty() {
+        bean.insert("blendComponents".into(), json!([component]));
+    }
+
+    // 至少要识别出名称以外的一项信息，否则很可能不是咖啡豆相关的文字
+    (bean.contains_key("name") && bean.len() > 1).then(|| {
+        bean.insert("beanState".into(), json!("roasted"));
+        Value::Object(bean)
+    })
+}
+
+#[tauri::command]
+pub fn parse_bean_from_clipboard(app: tauri::AppHandle) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let text = app.clipboard().read_text().map_err(|_| "剪贴板中没有文字")?;
+    let bean = parse_bean_text(&text, crate::today(&app)).ok_or("没有在剪贴板中识别到咖啡豆信息")?;
+    crate::telemetry::record(&app, "import.clipboard");
+    Ok(bean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_a_bean_draft_from_order_text() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let labeled = "您的订单已发货\n订单号：20261012001\n【品名】：埃塞俄比亚 古吉 罕贝拉 G1\n处理法：日晒\n\
+烘焙度：中浅烘焙\n烘焙日期：2026.10.12\n规格：227g x 1\n风味：桃子、茉莉花，蜂蜜\n实付：¥98";
+        assert_eq!(
+            parse_bean_text(labeled, today).unwrap(),
+            json!({
+                "name": "埃塞俄比亚 古吉 罕贝拉 G1", "roastDate": "2026-10-12", "capacity": "227", "remaining": "227",
+                "roastLevel": "中浅烘焙", "flavor": ["桃子", "茉莉花", "蜂蜜"],
+                "blendComponents": [{ "country": "埃塞俄比亚", "process": "日晒" }], "beanState": "roasted",
+            })
+        );
+
+        let plain = "Order #1042 confirmed\nKenya Gatomboya AA Washed\n250 g whole bean\nRoasted 10月14日";
+        let bean = parse_bean_text(plain, today).unwrap();
+        assert_eq!(bean["name"], "Kenya Gatomboya AA Washed");
+        assert_eq!(bean["roastDate"], "2026-10-14");
+        assert_eq!(bean["capacity"], "250");
+        assert_eq!(bean["blendComponents"], json!([{ "country": "肯尼亚", "process": "水洗" }]));
+
+        assert!(parse_bean_text("明天下午三点开会", today).is_none());
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+// 小幅回拨（NTP 在午夜前后校正几分钟）时保持已经显示的日期，避免天数来回跳
+// 回拨超过这个时长视为时间被更正（此前跑快了或手动改了时间），接受新的日期
+const MAX_HELD_ROLLBACK_SECS: i64 = 60 * 60;
+
+// 日期状态：可配置的时区 + 已见过的最大日期和最晚时刻
+#[derive(Debug, Default)]
+pub struct ClockState {
+    timezone: Option<Tz>,
+    last_today: Option<NaiveDate>,
+    latest: Option<DateTime<Utc>>,
+}
+
+impl ClockState {
+    pub fn set_timezone(&mut self, timezone: Option<Tz>) {
+        // 切换时区后日期可能合理地回退一天，重新开始记录
+        self.timezone = timezone;
+        self.last_today = None;
+        self.latest = None;
+    }
+
+    pub fn today(&mut self) -> NaiveDate {
+        self.today_at(Utc::now())
+    }
+
+    // 某一时刻在配置时区下的日期（不受回拨保护影响）
+    pub fn date_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        local_date(at, self.timezone)
+    }
+
+    // 某一时刻在配置时区下的钟点
+    pub fn time_of(&self, at: DateTime<Utc>) -> NaiveTime {
+        match self.timezone {
+            Some(tz) => at.with_timezone(&tz).time(),
+            None => at.with_timezone(&chrono::Local).time(),
+        }
+    }
+
+    pub fn today_at(&mut self, now: DateTime<Utc>) -> NaiveDate {
+        let today = local_date(now, self.timezone);
+        let rollback = self.latest.map_or(chrono::Duration::zero(), |latest| latest - now);
+        let corrected = rollback > chrono::Duration::seconds(MAX_HELD_ROLLBACK_SECS);
+        if let Some(last) = self.last_today.filter(|last| *last > today && !corrected) {
+            return last;
+        }
+        self.latest = Some(match self.latest {
+            Some(latest) if !corrected => latest.max(now),
+            _ => now,
+        });
+        self.last_today = Some(today);
+        today
+    }
+}
+
+// 将某一时刻换算为配置时区下的日历日期（未配置时使用系统本地时区）
+pub fn local_date(now: DateTime<Utc>, timezone: Option<Tz>) -> NaiveDate {
+    match timezone {
+        Some(tz) => now.with_timezone(&tz).date_naive(),
+        None => now.with_timezone(&chrono::Local).date_naive(),
+    }
+}
+
+// 按日历日期计算间隔天数，而不是经过了多少个 24 小时
+// DST 切换当天只有 23/25 小时，按时长计算会少算或多算一天
+pub fn calendar_days_between(from: NaiveDate, to: NaiveDate) -> i32 {
+    (to - from).num_days() as i32
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("无效的时区：{}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use proptest::prelude::*;
+
+    const ZONES: [Tz; 6] = [
+        chrono_tz::America::New_York,
+        chrono_tz::Europe::London,
+        chrono_tz::Australia::Sydney,
+        chrono_tz::Pacific::Auckland,
+        chrono_tz::Asia::Shanghai,
+        chrono_tz::America::Santiago,
+    ];
+
+    // 2000-01-01 ~ 2040-01-01 之间的任意时刻
+    fn instant() -> impl Strategy<Value = DateTime<Utc>> {
+        (946_684_800i64..2_208_988_800i64).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn day_count_never_decreases_as_time_moves_forward(
+            start in instant(),
+            step in 0i64..(3 * 24 * 3600),
+            zone in 0..ZONES.len(),
+        ) {
+            let tz = Some(ZONES[zone]);
+            let roast = NaiveDate::from_ymd_opt(1999, 12, 1).unwrap();
+            let before = calendar_days_between(roast, local_date(start, tz));
+            let after = calendar_days_between(roast, local_date(start + Duration::seconds(step), tz));
+            prop_assert!(after >= before);
+            // 前进不超过 3 天时，日历天数最多增加 3（DST 不会让一天被跳过）
+            prop_assert!(after - before <= 3);
+        }
+
+        #[test]
+        fn calendar_days_match_date_arithmetic(
+            offset in 0i64..20_000,
+            days in 0i64..4_000,
+        ) {
+            let from = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + Duration::days(offset);
+            let to = from + Duration::days(days);
+            prop_assert_eq!(calendar_days_between(from, to), days as i32);
+        }
+
+        #[test]
+        fn clock_state_is_monotonic_under_small_rollbacks(
+            start in instant(),
+            steps in proptest::collection::vec(-MAX_HELD_ROLLBACK_SECS..(3 * 24 * 3600), 1..32),
+            zone in 0..ZONES.len(),
+        ) {
+            let mut clock = ClockState::default();
+            clock.set_timezone(Some(ZONES[zone]));
+            let mut latest = start;
+            let mut last = clock.today_at(start);
+            for step in steps {
+                // 相对已见过的最晚时刻前进，或回拨不超过一小时
+                let now = latest + Duration::seconds(step);
+                latest = latest.max(now);
+                let today = clock.today_at(now);
+                prop_assert!(today >= last);
+                last = today;
+            }
+        }
+
+        #[test]
+        fn clock_state_accepts_corrections(
+            start in instant(),
+            ahead in (MAX_HELD_ROLLBACK_SECS + 1)..(400 * 24 * 3600),
+            zone in 0..ZONES.len(),
+        ) {
+            let tz = Some(ZONES[zone]);
+            let mut clock = ClockState::default();
+            clock.set_timezone(tz);
+            clock.today_at(start + Duration::seconds(ahead));
+            prop_assert_eq!(clock.today_at(start), local_date(start, tz));
+        }
+    }
+
+    #[test]
+    fn dst_transition_days_are_counted_once() {
+        let tz = chrono_tz::America::New_York;
+        let roast = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        // 2024-03-10 为夏令时开始日（23 小时），23:30 时仍是同一个日历日
+        let spring = tz.with_ymd_and_hms(2024, 3, 10, 23, 30, 0).unwrap().with_timezone(&Utc);
+        assert_eq!(calendar_days_between(roast, local_date(spring, Some(tz))), 1);
+
+        let roast = NaiveDate::from_ymd_opt(2024, 11, 2).unwrap();
+        // 2024-11-03 为夏令时结束日（25 小时），00:30 时已经是新的一天
+        let fall = tz.with_ymd_and_hms(2024, 11, 3, 0, 30, 0).unwrap().with_timezone(&Utc);
+        assert_eq!(calendar_days_between(roast, local_date(fall, Some(tz))), 1);
+    }
+
+    #[test]
+    fn leap_day_and_year_boundary() {
+        let feb28 = NaiveDate::from_ymd_opt(2024, 2, 28).unwrap();
+        let mar1 = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(calendar_days_between(feb28, mar1), 2);
+
+        let dec31 = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let jan1 = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(calendar_days_between(dec31, jan1), 1);
+    }
+
+    #[test]
+    fn small_rollback_keeps_previous_day() {
+        let tz = chrono_tz::Asia::Shanghai;
+        let mut clock = ClockState::default();
+        clock.set_timezone(Some(tz));
+        let at = |d, h, m| tz.with_ymd_and_hms(2025, 1, d, h, m, 0).unwrap().with_timezone(&Utc);
+        let jan2 = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+        assert_eq!(clock.today_at(at(2, 0, 3)), jan2);
+        // NTP 把时间校正回午夜之前
+        assert_eq!(clock.today_at(at(1, 23, 58)), jan2);
+        assert_eq!(clock.today_at(at(2, 0, 1)), jan2);
+    }
+
+    #[test]
+    fn forward_jump_then_correction_follows_the_clock() {
+        let tz = chrono_tz::Asia::Shanghai;
+        let mut clock = ClockState::default();
+        clock.set_timezone(Some(tz));
+        let at = |d, h| tz.with_ymd_and_hms(2025, 1, d, h, 0, 0).unwrap().with_timezone(&Utc);
+        assert_eq!(clock.today_at(at(1, 9)), NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        // 系统时钟跑快了两天，随后被更正
+        assert_eq!(clock.today_at(at(3, 9)), NaiveDate::from_ymd_opt(2025, 1, 3).unwrap());
+        assert_eq!(clock.today_at(at(1, 10)), NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        // 更正之后照常前进，不会停在跑快时的日期
+        assert_eq!(clock.today_at(at(2, 9)), NaiveDate::from_ymd_opt(2025, 1, 2).unwrap());
+    }
+}
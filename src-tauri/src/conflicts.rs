@@ -0,0 +1,229 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::journal;
+use crate::store::{atomically, record_data, with_store, RecordKind};
+use crate::sync::{merge_fields, parse_key};
+
+// 同步冲突：同一条记录在两台设备上都修改过时按字段合并，只有一边修改的字段直接合并，
+// 两边修改了同一字段的记录保存到 sync_conflicts 表（本地数据暂时保留本地的值），由用户选择保留哪一边
+const STRATEGIES: &[&str] = &["local", "remote", "newest"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub id: i64,
+    pub kind: RecordKind,
+    pub entity_id: String,
+    pub source: String, // sync-folder / lan-sync
+    pub fields: Vec<String>,
+    pub base: Option<Value>,
+    pub local: Value,
+    pub remote: Value,
+    pub local_updated_at: Option<i64>,
+    pub remote_updated_at: Option<i64>,
+    pub detected_at: i64,
+}
+
+const CONFLICT_COLUMNS: &str =
+    "id, kind, entity_id, source, fields, base, local, remote, local_updated_at, remote_updated_at, detected_at";
+
+fn conflict_from_row(row: &Row) -> rusqlite::Result<Option<SyncConflict>> {
+    let parse = |text: Option<String>| text.and_then(|t| serde_json::from_str::<Value>(&t).ok());
+    let Some(kind) = RecordKind::from_key(&row.get::<_, String>(1)?) else {
+        return Ok(None);
+    };
+    let (Some(local), Some(remote)) = (parse(row.get(6)?), parse(row.get(7)?)) else {
+        return Ok(None);
+    };
+    Ok(Some(SyncConflict {
+        id: row.get(0)?,
+        kind,
+        entity_id: row.get(2)?,
+        source: row.get(3)?,
+        fields: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default(),
+        base: parse(row.get(5)?),
+        local,
+        remote,
+        local_updated_at: row.get(8)?,
+        remote_updated_at: row.get(9)?,
+        detected_at: row.get(10)?,
+    }))
+}
+
+fn updated_at(conn: &Connection, kind: RecordKind, id: &str) -> rusqlite::Result<Option<i64>> {
+    conn.query_row(&format!("SELECT updated_at FROM {} WHERE id = ?1", kind.table()), [id], |row| row.get(0))
+        .optional()
+}
+
+fn write_tracked(conn: &Connection, kind: RecordKind, id: &str, value: &Value) -> rusqlite::Result<()> {
+    journal::track(conn, kind, id, || journal::apply(conn, kind, id, Some(value)))
+}
+
+// 处理两边都修改过的记录：合并只有一边修改的字段，剩下的冲突保存下来（同一记录之前未处理的冲突被替换）
+// base 为上次同步时的哈希，versions 为对方记录的修改时间；返回有冲突字段的记录数
+pub fn detect(
+    conn: &Connection,
+    source: &str,
+    base: &BTreeMap<String, String>,
+    conflicts: &[(String, Value)],
+    versions: &BTreeMap<String, i64>,
+) -> rusqlite::Result<usize> {
+    atomically(conn, || {
+        let mut count = 0;
+        for (key, remote) in conflicts {
+            let Some((kind, id)) = parse_key(key) else {
+                continue;
+            };
+            let Some(local) = record_data(conn, kind, id)?.and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            else {
+                continue;
+            };
+            let base_value = match base.get(key) {
+                Some(hash) => journal::version_with_hash(conn, kind, id, hash)?,
+                None => None,
+            };
+            let local_updated_at = updated_at(conn, kind, id)?;
+            let (merged, fields) = merge_fields(base_value.as_ref(), &local, remote);
+            if merged != local {
+                write_tracked(conn, kind, id, &merged)?;
+            }
+            if fields.is_empty() {
+                continue;
+            }
+            conn.execute(
+                "DELETE FROM sync_conflicts WHERE kind = ?1 AND entity_id = ?2",
+                [kind.key(), id],
+            )?;
+            conn.execute(
+                "INSERT INTO sync_conflicts (kind, entity_id, source, base, local, remote, fields, local_updated_at, remote_updated_at, detected_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    kind.key(),
+                    id,
+                    source,
+                    base_value.map(|v| v.to_string()),
+                    local.to_string(),
+                    remote.to_string(),
+                    serde_json::to_string(&fields).unwrap_or_default(),
+                    local_updated_at,
+                    versions.get(key),
+                    chrono::Utc::now().timestamp_millis(),
+                ],
+            )?;
+            count += 1;
+        }
+        Ok(count)
+    })
+}
+
+fn list(conn: &Connection) -> rusqlite::Result<Vec<SyncConflict>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM sync_conflicts ORDER BY detected_at DESC, id DESC",
+        CONFLICT_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], conflict_from_row)?;
+    let mut conflicts = Vec::new();
+    for conflict in rows {
+        conflicts.extend(conflict?);
+    }
+    Ok(conflicts)
+}
+
+// 按策略处理冲突字段：local 保留本地的值，remote 采用对方的值，newest 采用修改时间较新的一边
+// 其它字段保持当前数据（包括冲突之后的修改）；返回处理后的记录
+fn resolve(conn: &Connection, id: i64, strategy: &str) -> rusqlite::Result<Option<Value>> {
+    let conflict = conn
+        .query_row(
+            &format!("SELECT {} FROM sync_conflicts WHERE id = ?1", CONFLICT_COLUMNS),
+            [id],
+            conflict_from_row,
+        )
+        .optional()?
+        .flatten();
+    let Some(conflict) = conflict else {
+        return Ok(None);
+    };
+    let take_remote = match strategy {
+        "remote" => true,
+        "newest" => conflict.remote_updated_at > conflict.local_updated_at,
+        _ => false,
+    };
+    atomically(conn, || {
+        let current = record_data(conn, conflict.kind, &conflict.entity_id)?
+            .and_then(|data| serde_json::from_str::<Value>(&data).ok());
+        let mut resolved = current.clone().unwrap_or_else(|| conflict.local.clone());
+        if let (true, Some(fields)) = (take_remote, resolved.as_object_mut()) {
+            for name in &conflict.fields {
+                match conflict.remote.get(name) {
+                    Some(value) => fields.insert(name.clone(), value.clone()),
+                    None => fields.remove(name),
+                };
+            }
+        }
+        if current.as_ref() != Some(&resolved) {
+            write_tracked(conn, conflict.kind, &conflict.entity_id, &resolved)?;
+        }
+        conn.execute("DELETE FROM sync_conflicts WHERE id = ?1", [id])?;
+        Ok(Some(resolved))
+    })
+}
+
+// 未处理的同步冲突，最近的在前
+#[tauri::command]
+pub fn list_conflicts(app: tauri::AppHandle) -> Result<Vec<SyncConflict>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, |store| list(&store.conn))
+}
+
+// 处理一个冲突，strategy 为 local / remote / newest
+#[tauri::command]
+pub fn resolve_conflict(app: tauri::AppHandle, id: i64, strategy: String) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if !STRATEGIES.contains(&strategy.as_str()) {
+        return Err(format!("未知的处理方式：{}", strategy));
+    }
+    let resolved = with_store(&app, |store| resolve(&store.conn, id, &strategy))?
+        .ok_or_else(|| format!("找不到冲突：{}", id))?;
+    crate::store::publish_changes(&app)?;
+    crate::telemetry::record(&app, "sync.resolve_conflict");
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_other_fields_and_resolves_the_rest_by_strategy() {
+        let conn = crate::store::test_connection();
+        let base = json!({ "id": "b1", "name": "耶加雪菲", "origin": "埃塞俄比亚" });
+        write_tracked(&conn, RecordKind::Bean, "b1", &base).unwrap();
+        let base_hashes = BTreeMap::from([("bean:b1".to_string(), crate::sync::hash(&base))]);
+        write_tracked(&conn, RecordKind::Bean, "b1", &json!({ "id": "b1", "name": "耶加雪菲", "origin": "Ethiopia" }))
+            .unwrap();
+
+        let remote = json!({ "id": "b1", "name": "耶加雪菲 G1", "origin": "埃塞" });
+        let conflicts = [("bean:b1".to_string(), remote)];
+        assert_eq!(detect(&conn, "sync-folder", &base_hashes, &conflicts, &BTreeMap::new()).unwrap(), 1);
+
+        let listed = list(&conn).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].fields, vec!["origin".to_string()]);
+        assert_eq!(listed[0].base.as_ref(), Some(&base));
+        let current = |conn: &Connection| -> Value {
+            serde_json::from_str(&record_data(conn, RecordKind::Bean, "b1").unwrap().unwrap()).unwrap()
+        };
+        // 只有对方修改的名称已经合并，冲突的产地暂时保留本地的值
+        assert_eq!(current(&conn), json!({ "id": "b1", "name": "耶加雪菲 G1", "origin": "Ethiopia" }));
+
+        let resolved = resolve(&conn, listed[0].id, "remote").unwrap().unwrap();
+        assert_eq!(resolved, json!({ "id": "b1", "name": "耶加雪菲 G1", "origin": "埃塞" }));
+        assert_eq!(current(&conn), resolved);
+        assert!(list(&conn).unwrap().is_empty());
+        assert!(resolve(&conn, listed[0].id, "remote").unwrap().is_none());
+    }
+}
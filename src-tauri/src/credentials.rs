@@ -0,0 +1,25 @@
+// 系统钥匙串（macOS 钥匙串、Windows 凭据管理器、Linux Secret Service）中保存的口令，如 WebDAV 密码
+const SERVICE: &str = "brew-guide";
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| e.to_string())
+}
+
+pub fn save(account: &str, secret: &str) -> Result<(), String> {
+    entry(account)?.set_password(secret).map_err(|e| e.to_string())
+}
+
+pub fn load(account: &str) -> Result<Option<String>, String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn delete(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,341 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::roast_date::parse_roast_date;
+use crate::xlsx::{Cell, XlsxWriter};
+
+// 导出咖啡豆、冲煮笔记或每日消耗统计为 CSV 或 xlsx：可以选择列和日期范围，逐行查询、逐行写入文件
+// 列名就是字段路径（嵌套字段用 . 分隔），导出的 CSV 可以直接用于 CSV 导入
+const BEAN_COLUMNS: &[&str] = &[
+    "id", "name", "roaster", "origin", "process", "variety", "roastLevel", "roastDate", "capacity", "remaining", "price",
+    "flavor", "notes",
+];
+const NOTE_COLUMNS: &[&str] = &[
+    "id", "timestamp", "method", "equipment", "beanId", "coffeeBeanInfo.name", "params.coffee", "params.water",
+    "params.ratio", "params.grindSize", "params.temp", "totalTime", "rating", "tastingTags", "notes",
+];
+const CONSUMPTION_COLUMNS: &[&str] = &["date", "beanId", "bean", "brews", "grams"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportKind {
+    Beans,
+    Notes,
+    Consumption, // 每天每款咖啡豆的冲煮次数和用量
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    pub columns: Option<Vec<String>>, // 默认导出常用的列
+    pub from: Option<String>,         // YYYY-MM-DD，含首尾；咖啡豆按烘焙日期，笔记和消耗按冲煮时间
+    pub to: Option<String>,
+    pub format: Option<String>, // csv / xlsx，默认按文件扩展名
+}
+
+enum Sink {
+    Csv(csv::Writer<File>),
+    Xlsx(XlsxWriter),
+}
+
+impl Sink {
+    fn write_row(&mut self, cells: &[Cell]) -> Result<(), String> {
+        match self {
+            Sink::Csv(writer) => {
+                let fields = cells.iter().map(|cell| match cell {
+                    Cell::Text(text) => text.clone(),
+                    Cell::Number(n) => n.to_string(),
+                    Cell::Empty => String::new(),
+                });
+                writer.write_record(fields).map_err(|e| e.to_string())
+            }
+            Sink::Xlsx(writer) => writer.write_row(cells),
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            Sink::Csv(mut writer) => writer.flush().map_err(|e| e.to_string()),
+            Sink::Xlsx(writer) => writer.finish(),
+        }
+    }
+}
+
+// 导出的日期范围，换算为设置时区下的毫秒时间戳（结束为开区间）
+struct Range {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    timezone: Option<Tz>,
+}
+
+impl Range {
+    fn millis(&self, date: Option<NaiveDate>, default: i64) -> i64 {
+        let Some(midnight) = date.and_then(|d| d.and_hms_opt(0, 0, 0)) else {
+            return default;
+        };
+        let time = match self.timezone {
+            Some(tz) => tz.from_local_datetime(&midnight).earliest().map(|t| t.timestamp_millis()),
+            None => chrono::Local.from_local_datetime(&midnight).earliest().map(|t| t.timestamp_millis()),
+        };
+        time.unwrap_or(default)
+    }
+
+    fn start(&self) -> i64 {
+        self.millis(self.from, i64::MIN)
+    }
+
+    fn end(&self) -> i64 {
+        self.millis(self.to.and_then(|d| d.succ_opt()), i64::MAX)
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        !self.from.is_some_and(|from| date < from) && !self.to.is_some_and(|to| date > to)
+    }
+
+    fn date_of(&self, millis: i64) -> Option<NaiveDate> {
+        DateTime::<Utc>::from_timestamp_millis(millis).map(|at| crate::clock::local_date(at, self.timezone))
+    }
+
+    fn format_time(&self, millis: i64) -> Option<String> {
+        let at = DateTime::<Utc>::from_timestamp_millis(millis)?;
+        Some(match self.timezone {
+            Some(tz) => at.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string(),
+            None => at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
+        })
+    }
+}
+
+fn parse_date(raw: Option<&str>) -> Result<Option<NaiveDate>, String> {
+    raw.map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| format!("无效的日期：{}", s)))
+        .transpose()
+}
+
+fn cell(value: Option<&Value>) -> Cell {
+    match value {
+        None | Some(Value::Null) => Cell::Empty,
+        Some(Value::String(text)) => Cell::Text(text.clone()),
+        Some(Value::Number(n)) => n.as_f64().map_or(Cell::Empty, Cell::Number),
+        Some(Value::Bool(b)) => Cell::Text(b.to_string()),
+        Some(Value::Array(items)) => Cell::Text(
+            items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        Some(other) => Cell::Text(other.to_string()),
+    }
+}
+
+fn record_cells(record: &Value, columns: &[String], range: &Range) -> Vec<Cell> {
+    columns
+        .iter()
+        .map(|column| {
+            let value = record.pointer(&format!("/{}", column.replace('.', "/")));
+            match (column.as_str(), value.and_then(Value::as_i64)) {
+                ("timestamp", Some(millis)) => range.format_time(millis).map_or(Cell::Empty, Cell::Text),
+                _ => cell(value),
+            }
+        })
+        .collect()
+}
+
+fn write_rows(conn: &Connection, kind: ExportKind, columns: &[String], range: &Range, sink: &mut Sink) -> Result<usize, String> {
+    let sql_error = |e: rusqlite::Error| e.to_string();
+    let mut count = 0;
+    match kind {
+        ExportKind::Beans => {
+            let mut stmt = conn.prepare("SELECT data FROM beans ORDER BY name").map_err(sql_error)?;
+            let mut rows = stmt.query([]).map_err(sql_error)?;
+            while let Some(row) = rows.next().map_err(sql_error)? {
+                let Ok(bean) = serde_json::from_str::<Value>(&row.get::<_, String>(0).map_err(sql_error)?) else {
+                    continue;
+                };
+                if range.from.is_some() || range.to.is_some() {
+                    let roasted = bean.get("roastDate").and_then(Value::as_str).and_then(parse_roast_date);
+                    if !roasted.is_some_and(|date| range.contains(date.earliest)) {
+                        continue;
+                    }
+                }
+                sink.write_row(&record_cells(&bean, columns, range))?;
+                count += 1;
+            }
+        }
+        ExportKind::Notes => {
+            let mut stmt = conn
+                .prepare("SELECT data FROM notes WHERE timestamp >= ?1 AND timestamp < ?2 ORDER BY timestamp")
+                .map_err(sql_error)?;
+            let mut rows = stmt.query(params![range.start(), range.end()]).map_err(sql_error)?;
+            while let Some(row) = rows.next().map_err(sql_error)? {
+                if let Ok(note) = serde_json::from_str::<Value>(&row.get::<_, String>(0).map_err(sql_error)?) {
+                    sink.write_row(&record_cells(&note, columns, range))?;
+                    count += 1;
+                }
+            }
+        }
+        ExportKind::Consumption => {
+            for row in consumption(conn, range).map_err(sql_error)? {
+                let cells: Vec<Cell> = columns.iter().map(|column| cell(row.get(column))).collect();
+                sink.write_row(&cells)?;
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+// 按日期、咖啡豆汇总冲煮笔记的次数和粉量
+fn consumption(conn: &Connection, range: &Range) -> rusqlite::Result<Vec<Value>> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut stmt = conn.prepare("SELECT id, name FROM beans")?;
+    for row in stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+        let (id, name) = row?;
+        names.insert(id, name);
+    }
+
+    let mut totals: BTreeMap<(NaiveDate, String), (String, u32, f64)> = BTreeMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, bean_id, dose, json_extract(data, '$.coffeeBeanInfo.name') FROM notes
+         WHERE timestamp >= ?1 AND timestamp < ?2",
+    )?;
+    let rows = stmt.query_map(params![range.start(), range.end()], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (timestamp, bean_id, dose, bean_name) = row?;
+        let Some(date) = range.date_of(timestamp) else {
+            continue;
+        };
+        let bean_id = bean_id.unwrap_or_default();
+        let name = names.get(&bean_id).cloned().or(bean_name).unwrap_or_default();
+        let total = totals.entry((date, bean_id)).or_insert((name, 0, 0.0));
+        total.1 += 1;
+        total.2 += dose.unwrap_or(0.0);
+    }
+    Ok(totals
+        .into_iter()
+        .map(|((date, bean_id), (bean, brews, grams))| {
+            serde_json::json!({
+                "date": date.format("%Y-%m-%d").to_string(),
+                "beanId": bean_id,
+                "bean": bean,
+                "brews": brews,
+                "grams": grams,
+            })
+        })
+        .collect())
+}
+
+fn export(conn: &Connection, kind: ExportKind, path: &Path, options: &ExportOptions, timezone: Option<Tz>) -> Result<usize, String> {
+    let columns: Vec<String> = match &options.columns {
+        Some(columns) if !columns.is_empty() => columns.clone(),
+        _ => match kind {
+            ExportKind::Beans => BEAN_COLUMNS,
+            ExportKind::Notes => NOTE_COLUMNS,
+            ExportKind::Consumption => CONSUMPTION_COLUMNS,
+        }
+        .iter()
+        .map(|c| c.to_string())
+        .collect(),
+    };
+    let range = Range {
+        from: parse_date(options.from.as_deref())?,
+        to: parse_date(options.to.as_deref())?,
+        timezone,
+    };
+    let xlsx = match options.format.as_deref() {
+        Some("xlsx") => true,
+        Some("csv") => false,
+        Some(other) => return Err(format!("不支持的格式：{}", other)),
+        None => path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx")),
+    };
+    let sheet = match kind {
+        ExportKind::Beans => "beans",
+        ExportKind::Notes => "notes",
+        ExportKind::Consumption => "consumption",
+    };
+    let mut sink = if xlsx {
+        Sink::Xlsx(XlsxWriter::create(path, sheet)?)
+    } else {
+        Sink::Csv(csv::Writer::from_path(path).map_err(|e| e.to_string())?)
+    };
+    let header: Vec<Cell> = columns.iter().map(|c| Cell::Text(c.clone())).collect();
+    sink.write_row(&header)?;
+    let count = write_rows(conn, kind, &columns, &range, &mut sink)?;
+    sink.finish()?;
+    Ok(count)
+}
+
+// 导出到 path（先写入临时文件再改名），返回导出的行数（不含表头）
+#[tauri::command]
+pub fn export_csv(app: tauri::AppHandle, kind: ExportKind, path: String, options: Option<ExportOptions>) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let options = options.unwrap_or_default();
+    let timezone = crate::settings::get(&app)
+        .timezone
+        .and_then(|name| crate::clock::parse_timezone(&name).ok());
+    let path = PathBuf::from(path);
+    let mut partial = path.clone().into_os_string();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let result = crate::store::with_store(&app, |store| Ok(export(&store.conn, kind, &partial, &options, timezone)))?;
+    let count = match result {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+    crate::telemetry::record(&app, "export.table");
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn exports_selected_columns_and_daily_consumption() {
+        let conn = crate::store::test_connection();
+        crate::store::write_bean(&conn, &json!({ "id": "b1", "name": "耶加雪菲" })).unwrap();
+        let tz = Some(chrono_tz::Asia::Shanghai);
+        for (id, timestamp, coffee) in [("n1", 1_792_110_600_000_i64, "15g"), ("n2", 1_792_120_000_000, "16g"), ("n3", 1_792_200_000_000, "15g")] {
+            let note = json!({ "id": id, "timestamp": timestamp, "beanId": "b1", "params": { "coffee": coffee }, "tastingTags": ["花香", "柑橘"] });
+            crate::notes::restore_note(&conn, &note).unwrap();
+        }
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("brew-guide-export-{}.csv", std::process::id()));
+
+        let options = ExportOptions {
+            columns: Some(vec!["timestamp".into(), "params.coffee".into(), "tastingTags".into()]),
+            to: Some("2026-10-16".into()),
+            ..Default::default()
+        };
+        assert_eq!(export(&conn, ExportKind::Notes, &path, &options, tz).unwrap(), 2);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            text,
+            "timestamp,params.coffee,tastingTags\n2026-10-16 08:30,15g,\"花香, 柑橘\"\n2026-10-16 11:06,16g,\"花香, 柑橘\"\n"
+        );
+
+        assert_eq!(export(&conn, ExportKind::Consumption, &path, &ExportOptions::default(), tz).unwrap(), 2);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().nth(1), Some("2026-10-16,b1,耶加雪菲,2,31"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
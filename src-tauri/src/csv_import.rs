@@ -0,0 +1,410 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+
+use crate::journal;
+use crate::roast_date::{parse_roast_date, RoastDatePrecision};
+use crate::store::{atomically, record_data, text_field, with_store, write_record, RecordKind};
+
+// CSV 导入：逐行读取 CSV（不把整个文件读入内存），按用户设置的列对应关系生成咖啡豆或冲煮笔记并校验日期、数字，
+// 出错的行跳过并报告行号和原因，其余的行在一个事务中写入（已存在的 ID 整体替换）；dry_run 时只校验不写入
+const MAX_REPORTED_ERRORS: usize = 200;
+const PREVIEW_ROWS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldType {
+    Text,
+    List,       // 以逗号、顿号、分号分隔的多个值
+    Number,     // 保存为数字
+    NumberText, // 数字，按前端的习惯保存为文字（咖啡豆的容量、剩余量、价格）
+    Grams,      // 克数，按前端的习惯保存为 "15g"
+    Integer,
+    Bool,
+    Date,     // 精确到天时保存为 YYYY-MM-DD，月份、烘焙周保留原文
+    DateTime, // 保存为毫秒时间戳
+}
+
+// 可以导入的字段：记录种类、字段路径（嵌套字段用 . 分隔）、类型
+const FIELDS: &[(RecordKind, &str, FieldType)] = &[
+    (RecordKind::Bean, "id", FieldType::Text),
+    (RecordKind::Bean, "name", FieldType::Text),
+    (RecordKind::Bean, "roaster", FieldType::Text),
+    (RecordKind::Bean, "origin", FieldType::Text),
+    (RecordKind::Bean, "process", FieldType::Text),
+    (RecordKind::Bean, "variety", FieldType::Text),
+    (RecordKind::Bean, "roastLevel", FieldType::Text),
+    (RecordKind::Bean, "roastDate", FieldType::Date),
+    (RecordKind::Bean, "capacity", FieldType::NumberText),
+    (RecordKind::Bean, "remaining", FieldType::NumberText),
+    (RecordKind::Bean, "price", FieldType::NumberText),
+    (RecordKind::Bean, "flavor", FieldType::List),
+    (RecordKind::Bean, "notes", FieldType::Text),
+    (RecordKind::Bean, "startDay", FieldType::Integer),
+    (RecordKind::Bean, "endDay", FieldType::Integer),
+    (RecordKind::Bean, "isFrozen", FieldType::Bool),
+    (RecordKind::Note, "id", FieldType::Text),
+    (RecordKind::Note, "timestamp", FieldType::DateTime),
+    (RecordKind::Note, "method", FieldType::Text),
+    (RecordKind::Note, "equipment", FieldType::Text),
+    (RecordKind::Note, "beanId", FieldType::Text),
+    (RecordKind::Note, "coffeeBeanInfo.name", FieldType::Text),
+    (RecordKind::Note, "params.coffee", FieldType::Grams),
+    (RecordKind::Note, "params.water", FieldType::Grams),
+    (RecordKind::Note, "params.ratio", FieldType::Text),
+    (RecordKind::Note, "params.grindSize", FieldType::Text),
+    (RecordKind::Note, "params.temp", FieldType::Text),
+    (RecordKind::Note, "totalTime", FieldType::Number),
+    (RecordKind::Note, "rating", FieldType::Number),
+    (RecordKind::Note, "tastingTags", FieldType::List),
+    (RecordKind::Note, "notes", FieldType::Text),
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvMapping {
+    pub kind: RecordKind,
+    pub columns: BTreeMap<String, String>, // CSV 列名 -> 字段路径
+    #[serde(default)]
+    pub delimiter: Option<char>, // 默认逗号
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvRowError {
+    pub row: u64, // 文件中的行号（表头为第 1 行）
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvImportReport {
+    pub dry_run: bool,
+    pub rows: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub errors: Vec<CsvRowError>, // 最多 200 条
+    pub preview: Vec<Value>,      // 前几条生成的记录
+}
+
+struct Column {
+    index: usize,
+    header: String,
+    path: &'static str,
+    field_type: FieldType,
+}
+
+// 数字，允许带单位（"15g"、"92 ℃"）和千位分隔符
+fn parse_number(raw: &str) -> Result<f64, String> {
+    let s = raw.trim();
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+' || c == ','))
+        .unwrap_or(s.len());
+    let unit = s[end..].trim();
+    s[..end]
+        .replace(',', "")
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && !unit.contains(|c: char| c.is_ascii_digit()))
+        .ok_or_else(|| format!("不是有效的数字：{}", raw))
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+    match raw.trim().to_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" | "是" => Ok(true),
+        "false" | "no" | "n" | "0" | "否" => Ok(false),
+        _ => Err(format!("不是有效的是/否：{}", raw)),
+    }
+}
+
+// 时间：毫秒或秒时间戳、RFC 3339，或不带时区的日期时间（按设置的时区理解）
+fn parse_datetime(raw: &str, timezone: Option<Tz>) -> Result<i64, String> {
+    let s = raw.trim();
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+        let n: i64 = s.parse().map_err(|_| format!("无效的时间：{}", raw))?;
+        return Ok(if s.len() >= 12 { n } else { n * 1000 });
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.timestamp_millis());
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y/%m/%d %H:%M:%S", "%Y/%m/%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"]
+                .iter()
+                .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| format!("无效的时间：{}", raw))?;
+    let local = match timezone {
+        Some(tz) => tz.from_local_datetime(&naive).earliest().map(|t| t.timestamp_millis()),
+        None => chrono::Local.from_local_datetime(&naive).earliest().map(|t| t.timestamp_millis()),
+    };
+    local.ok_or_else(|| format!("无效的时间：{}", raw))
+}
+
+fn parse_value(raw: &str, field_type: FieldType, timezone: Option<Tz>) -> Result<Value, String> {
+    let s = raw.trim();
+    let number_text = |n: f64| if n.fract() == 0.0 { format!("{}", n as i64) } else { n.to_string() };
+    Ok(match field_type {
+        FieldType::Text => Value::String(s.to_string()),
+        FieldType::List => Value::Array(
+            s.split([',', '，', '、', ';', '；'])
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        ),
+        FieldType::Number => serde_json::Number::from_f64(parse_number(s)?).map(Value::Number).unwrap_or_default(),
+        FieldType::NumberText => Value::String(number_text(parse_number(s)?)),
+        FieldType::Grams => Value::String(format!("{}g", number_text(parse_number(s)?))),
+        FieldType::Integer => {
+            let n = parse_number(s)?;
+            if n.fract() != 0.0 {
+                return Err(format!("不是整数：{}", raw));
+            }
+            Value::from(n as i64)
+        }
+        FieldType::Bool => Value::Bool(parse_bool(s)?),
+        FieldType::Date => match parse_roast_date(s) {
+            Some(range) if range.precision == RoastDatePrecision::Day => {
+                Value::String(range.earliest.format("%Y-%m-%d").to_string())
+            }
+            Some(_) => Value::String(s.to_string()),
+            None => return Err(format!("无效的日期：{}", raw)),
+        },
+        FieldType::DateTime => Value::from(parse_datetime(s, timezone)?),
+    })
+}
+
+fn set_path(record: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            let child = record.entry(head).or_insert_with(|| Value::Object(Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(Map::new());
+            }
+            if let Some(child) = child.as_object_mut() {
+                set_path(child, rest, value);
+            }
+        }
+        None => {
+            record.insert(path.to_string(), value);
+        }
+    }
+}
+
+fn resolve_columns(kind: RecordKind, headers: &csv::StringRecord, mapping: &BTreeMap<String, String>) -> Result<Vec<Column>, String> {
+    let mut columns = Vec::new();
+    for (header, target) in mapping {
+        let &(_, path, field_type) = FIELDS
+            .iter()
+            .find(|(k, path, _)| *k == kind && path == target)
+            .ok_or_else(|| format!("不能导入的字段：{}", target))?;
+        let index = headers
+            .iter()
+            .position(|h| h.trim() == header.trim())
+            .ok_or_else(|| format!("CSV 中没有列：{}", header))?;
+        columns.push(Column {
+            index,
+            header: header.clone(),
+            path,
+            field_type,
+        });
+    }
+    let required = match kind {
+        RecordKind::Bean => "name",
+        RecordKind::Note => "timestamp",
+    };
+    if !columns.iter().any(|c| c.path == required) {
+        return Err(format!("需要为字段 {} 指定一列", required));
+    }
+    Ok(columns)
+}
+
+struct Importer<'a> {
+    kind: RecordKind,
+    columns: Vec<Column>,
+    timezone: Option<Tz>,
+    bean_ids: HashMap<String, String>, // 小写的咖啡豆名称 -> ID，用于关联笔记
+    id_prefix: String,
+    conn: &'a Connection,
+}
+
+impl Importer<'_> {
+    fn build(&self, record: &csv::StringRecord, line: u64) -> Result<Value, (Option<String>, String)> {
+        let mut fields = Map::new();
+        for column in &self.columns {
+            let raw = record.get(column.index).unwrap_or_default();
+            if raw.trim().is_empty() {
+                continue;
+            }
+            let value = parse_value(raw, column.field_type, self.timezone).map_err(|e| (Some(column.header.clone()), e))?;
+            set_path(&mut fields, column.path, value);
+        }
+        let mut value = Value::Object(fields);
+        if text_field(&value, "id").is_none() {
+            value["id"] = Value::String(format!("{}-{}", self.id_prefix, line));
+        }
+        match self.kind {
+            RecordKind::Bean => {
+                text_field(&value, "name").ok_or((None, "缺少咖啡豆名称".to_string()))?;
+            }
+            RecordKind::Note => {
+                value.get("timestamp").ok_or((None, "缺少冲煮时间".to_string()))?;
+                if text_field(&value, "beanId").is_none() {
+                    let name = value.pointer("/coffeeBeanInfo/name").and_then(Value::as_str).map(str::to_lowercase);
+                    if let Some(id) = name.and_then(|name| self.bean_ids.get(name.trim())) {
+                        value["beanId"] = Value::String(id.clone());
+                    }
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    fn run<R: Read>(&self, reader: &mut csv::Reader<R>, dry_run: bool) -> rusqlite::Result<CsvImportReport> {
+        let mut report = CsvImportReport {
+            dry_run,
+            ..Default::default()
+        };
+        let mut record = csv::StringRecord::new();
+        loop {
+            let line = reader.position().line();
+            let built = match reader.read_record(&mut record) {
+                Ok(false) => break,
+                Ok(true) => self.build(&record, line),
+                Err(e) => Err((None, e.to_string())),
+            };
+            report.rows += 1;
+            let value = match built {
+                Ok(value) => value,
+                Err((column, message)) => {
+                    report.skipped += 1;
+                    if report.errors.len() < MAX_REPORTED_ERRORS {
+                        report.errors.push(CsvRowError {
+                            row: line,
+                            column,
+                            message,
+                        });
+                    }
+                    continue;
+                }
+            };
+            let id = text_field(&value, "id").unwrap_or_default().to_string();
+            if record_data(self.conn, self.kind, &id)?.is_some() {
+                report.updated += 1;
+            } else {
+                report.created += 1;
+            }
+            if !dry_run {
+                journal::track(self.conn, self.kind, &id, || write_record(self.conn, self.kind, &value))?;
+            }
+            if report.preview.len() < PREVIEW_ROWS {
+                report.preview.push(value);
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn bean_ids(conn: &Connection) -> rusqlite::Result<HashMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT id, name FROM beans")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut ids = HashMap::new();
+    for row in rows {
+        let (id, name) = row?;
+        ids.insert(name.trim().to_lowercase(), id);
+    }
+    Ok(ids)
+}
+
+fn import<R: Read>(
+    conn: &Connection,
+    reader: &mut csv::Reader<R>,
+    mapping: &CsvMapping,
+    timezone: Option<Tz>,
+    dry_run: bool,
+) -> Result<CsvImportReport, String> {
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let importer = Importer {
+        kind: mapping.kind,
+        columns: resolve_columns(mapping.kind, &headers, &mapping.columns)?,
+        timezone,
+        bean_ids: bean_ids(conn).map_err(|e| e.to_string())?,
+        id_prefix: format!("csv-{}", chrono::Utc::now().timestamp_millis()),
+        conn,
+    };
+    atomically(conn, || importer.run(reader, dry_run)).map_err(|e| e.to_string())
+}
+
+// 按列对应关系导入 CSV；dry_run 为 true 时只校验并返回结果，不写入数据
+#[tauri::command]
+pub fn import_csv(app: tauri::AppHandle, path: String, mapping: CsvMapping, dry_run: bool) -> Result<CsvImportReport, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let delimiter = mapping.delimiter.unwrap_or(',');
+    if !delimiter.is_ascii() {
+        return Err(format!("不支持的分隔符：{}", delimiter));
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .flexible(true)
+        .from_path(&path)
+        .map_err(|e| e.to_string())?;
+    let timezone = crate::settings::get(&app)
+        .timezone
+        .and_then(|name| crate::clock::parse_timezone(&name).ok());
+    if !dry_run {
+        crate::backups::snapshot_before(&app, "csv-import");
+    }
+    let report = with_store(&app, |store| Ok(import(&store.conn, &mut reader, &mapping, timezone, dry_run)))??;
+    if !dry_run && report.created + report.updated > 0 {
+        crate::store::publish_changes(&app)?;
+    }
+    if !dry_run {
+        crate::telemetry::record(&app, "import.csv");
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_valid_rows_and_reports_the_rest() {
+        let conn = crate::store::test_connection();
+        let csv = "名称,烘焙日期,重量\n耶加雪菲,2026-10-01,200g\n,2026-10-02,100\n肯尼亚,昨天,250\n瑰夏,2026/10/03,\"1,000\"\n";
+        let mapping = CsvMapping {
+            kind: RecordKind::Bean,
+            columns: BTreeMap::from([
+                ("名称".to_string(), "name".to_string()),
+                ("烘焙日期".to_string(), "roastDate".to_string()),
+                ("重量".to_string(), "capacity".to_string()),
+            ]),
+            delimiter: None,
+        };
+        let reader = || csv::ReaderBuilder::new().flexible(true).from_reader(csv.as_bytes());
+
+        let report = import(&conn, &mut reader(), &mapping, None, true).unwrap();
+        assert_eq!((report.rows, report.created, report.skipped), (4, 2, 2));
+        let bad: Vec<(u64, Option<&str>)> = report.errors.iter().map(|e| (e.row, e.column.as_deref())).collect();
+        assert_eq!(bad, vec![(3, None), (4, Some("烘焙日期"))]);
+        assert_eq!(report.preview[1]["roastDate"], "2026-10-03");
+        assert_eq!(report.preview[1]["capacity"], "1000");
+        assert!(crate::store::all_beans(&conn).unwrap().is_empty());
+
+        let report = import(&conn, &mut reader(), &mapping, None, false).unwrap();
+        assert_eq!(report.created, 2);
+        assert_eq!(crate::store::all_beans(&conn).unwrap().len(), 2);
+
+        assert_eq!(parse_datetime("2026-10-16 08:30", Some(chrono_tz::Asia::Shanghai)), Ok(1_792_110_600_000));
+        assert!(resolve_columns(RecordKind::Note, &csv::StringRecord::from(vec!["时间"]), &BTreeMap::new()).is_err());
+    }
+}
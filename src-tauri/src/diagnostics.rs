@@ -0,0 +1,356 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+
+use crate::background::BeanCache;
+
+// 诊断包：用户同意后收集崩溃信息、最近的错误和日志、运行指标及匿名统计，打包成 zip 供附在 issue 中
+// 不包含咖啡豆名称、备注等内容，只有数量；错误、日志和崩溃报告中的咖啡豆 ID、名称和文件路径会被替换掉
+const CRASHES_DIR: &str = "crashes";
+const MAX_RECENT_ERRORS: usize = 100;
+const MAX_CRASH_REPORTS: usize = 10;
+const MAX_LOG_BYTES: u64 = 512 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorRecord {
+    pub at: String,
+    pub source: String,
+    pub message: String,
+}
+
+pub struct DiagnosticsState {
+    consent: bool,
+    started_at: Instant,
+    errors: VecDeque<ErrorRecord>,
+}
+
+impl DiagnosticsState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        Self {
+            consent: crate::settings::get(app).diagnostics_consent,
+            started_at: Instant::now(),
+            errors: VecDeque::new(),
+        }
+    }
+}
+
+// 运行指标与匿名统计
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsSummary {
+    app_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    generated_at: String,
+    uptime_secs: u64,
+    locale: String,
+    tray_visible: bool,
+    bean_count: usize,
+    bean_states: crate::widget::WidgetCounts,
+    recent_error_count: usize,
+    crash_report_count: usize,
+}
+
+fn crashes_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(CRASHES_DIR))
+        .map_err(|e| e.to_string())
+}
+
+fn with_state<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut DiagnosticsState) -> T) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<DiagnosticsState>>>()
+        .ok_or("诊断模块未初始化")?;
+    let mut lock = state.lock().map_err(|e| e.to_string())?;
+    Ok(f(&mut lock))
+}
+
+// 记录一条错误（未同意时忽略）
+pub fn record_error(app: &tauri::AppHandle, source: &str, message: impl Into<String>) {
+    let record = ErrorRecord {
+        at: chrono::Local::now().to_rfc3339(),
+        source: source.to_string(),
+        message: message.into(),
+    };
+    let _ = with_state(app, |s| {
+        if !s.consent {
+            return;
+        }
+        if s.errors.len() >= MAX_RECENT_ERRORS {
+            s.errors.pop_front();
+        }
+        s.errors.push_back(record);
+    });
+}
+
+// 崩溃时写入崩溃报告（同意后才写），再交给原来的处理
+pub fn install_panic_hook(app: tauri::AppHandle) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // 崩溃可能发生在持有锁时，这里不等待锁
+        let consent = app
+            .try_state::<Arc<Mutex<DiagnosticsState>>>()
+            .and_then(|state| state.try_lock().ok().map(|s| s.consent))
+            .unwrap_or(false);
+        if consent {
+            if let Err(e) = write_crash_report(&app, &info.to_string()) {
+                log::error!("写入崩溃报告失败：{}", e);
+            }
+        }
+        previous(info);
+    }));
+}
+
+fn write_crash_report(app: &tauri::AppHandle, message: &str) -> Result<(), String> {
+    let dir = crashes_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let now = chrono::Local::now();
+    let report = format!(
+        "time: {}\nversion: {}\nos: {} {}\n\n{}\n\n{}\n",
+        now.to_rfc3339(),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        message,
+        std::backtrace::Backtrace::force_capture(),
+    );
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    fs::write(path, report).map_err(|e| e.to_string())
+}
+
+// 按文件名倒序（新的在前），只取最近几份
+fn recent_crash_reports(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let Ok(entries) = crashes_dir(app).and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string())) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    reports.sort();
+    reports.reverse();
+    reports.truncate(MAX_CRASH_REPORTS);
+    reports
+}
+
+fn log_files(app: &tauri::AppHandle) -> Vec<PathBuf> {
+    let Ok(entries) = app.path().app_log_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect()
+}
+
+// 日志只取末尾一段
+fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len > max_bytes {
+        file.seek(SeekFrom::Start(len - max_bytes))?;
+    }
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn build_summary(app: &tauri::AppHandle, uptime_secs: u64, recent_error_count: usize, crash_report_count: usize) -> DiagnosticsSummary {
+    let today = crate::today(app);
+    let beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.beans.clone()))
+        .unwrap_or_default();
+    let infos: Vec<_> = beans.iter().map(|bean| crate::calculate_freshness(bean, today)).collect();
+    let tray_visible = app
+        .try_state::<Arc<Mutex<crate::TrayState>>>()
+        .and_then(|state| state.lock().ok().map(|s| s.visible))
+        .unwrap_or(false);
+
+    DiagnosticsSummary {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        generated_at: chrono::Local::now().to_rfc3339(),
+        uptime_secs,
+        locale: format!("{:?}", crate::current_locale(app).language),
+        tray_visible,
+        bean_count: beans.len(),
+        bean_states: crate::widget::build_snapshot(&infos, today, None).counts,
+        recent_error_count,
+        crash_report_count,
+    }
+}
+
+// 脱敏：已知的咖啡豆 ID、名称、烘焙商换成占位符，绝对路径换成 <path>
+struct Redactor {
+    terms: Vec<(String, &'static str)>,
+    path: Regex,
+}
+
+impl Redactor {
+    fn new(beans: &[crate::CoffeeBean]) -> Self {
+        let mut terms: Vec<(String, &'static str)> = beans
+            .iter()
+            .flat_map(|bean| {
+                [
+                    (Some(bean.id.clone()), "<bean-id>"),
+                    (Some(bean.name.clone()), "<bean>"),
+                    (bean.roaster.clone(), "<roaster>"),
+                ]
+            })
+            .filter_map(|(term, placeholder)| Some((term?.trim().to_string(), placeholder)))
+            .filter(|(term, _)| !term.is_empty())
+            .collect();
+        // 长的先替换，避免名称中包含另一个名称时只替换一半
+        terms.sort_by_key(|(term, _)| (std::cmp::Reverse(term.len()), term.clone()));
+        terms.dedup_by(|a, b| a.0 == b.0);
+        Self {
+            terms,
+            path: Regex::new(r#"(^|[\s"'=(\[])(?:[A-Za-z]:[\\/]|/)(?:[^\s"'<>|:*?\\/]+[\\/])+[^\s"'<>|:*?\\/)\]]*"#).unwrap(),
+        }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        let mut text = self.path.replace_all(text, "${1}<path>").into_owned();
+        for (term, placeholder) in &self.terms {
+            text = text.replace(term.as_str(), placeholder);
+        }
+        text
+    }
+}
+
+fn write_bundle(path: &Path, entries: &[(String, Vec<u8>)]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    for (name, bytes) in entries {
+        zip.start_file(name.as_str(), options).map_err(|e| e.to_string())?;
+        zip.write_all(bytes).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_diagnostics_consent(app: tauri::AppHandle) -> Result<bool, String> {
+    with_state(&app, |s| s.consent)
+}
+
+// 撤回同意时清除已收集的错误和崩溃报告
+#[tauri::command]
+pub fn set_diagnostics_consent(app: tauri::AppHandle, consent: bool) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.diagnostics_consent = consent)?;
+    with_state(&app, |s| {
+        s.consent = consent;
+        if !consent {
+            s.errors.clear();
+        }
+    })?;
+    if !consent {
+        let dir = crashes_dir(&app)?;
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+// 生成诊断包，返回 zip 文件路径
+#[tauri::command]
+pub fn create_diagnostics_bundle(app: tauri::AppHandle) -> Result<String, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let (consent, uptime_secs, errors) = with_state(&app, |s| {
+        (s.consent, s.started_at.elapsed().as_secs(), s.errors.iter().cloned().collect::<Vec<_>>())
+    })?;
+    if !consent {
+        return Err("需要先同意收集诊断信息".to_string());
+    }
+
+    let crash_reports = recent_crash_reports(&app);
+    let summary = build_summary(&app, uptime_secs, errors.len(), crash_reports.len());
+    let beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.beans.clone()))
+        .unwrap_or_default();
+    let redactor = Redactor::new(&beans);
+    let errors: Vec<ErrorRecord> = errors
+        .into_iter()
+        .map(|record| ErrorRecord {
+            message: redactor.redact(&record.message),
+            ..record
+        })
+        .collect();
+
+    let mut entries: Vec<(String, Vec<u8>)> = vec![
+        ("summary.json".to_string(), serde_json::to_vec_pretty(&summary).map_err(|e| e.to_string())?),
+        ("errors.json".to_string(), serde_json::to_vec_pretty(&errors).map_err(|e| e.to_string())?),
+    ];
+    for path in crash_reports.iter().chain(log_files(&app).iter()) {
+        let folder = if crash_reports.contains(path) { "crashes" } else { "logs" };
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        match read_tail(path, MAX_LOG_BYTES) {
+            Ok(bytes) => {
+                let text = redactor.redact(&String::from_utf8_lossy(&bytes));
+                entries.push((format!("{}/{}", folder, name), text.into_bytes()));
+            }
+            Err(e) => log::warn!("读取 {} 失败：{}", path.display(), e),
+        }
+    }
+
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("diagnostics");
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "brew-guide-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    write_bundle(&path, &entries)?;
+    crate::telemetry::record(&app, "diagnostics.bundle");
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bean(id: &str, name: &str, roaster: Option<&str>) -> crate::CoffeeBean {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": name, "roaster": roaster })).unwrap()
+    }
+
+    #[test]
+    fn redacts_beans_and_paths() {
+        let redactor = Redactor::new(&[
+            bean("bean-42", "埃塞俄比亚 花魁", Some("某某烘焙")),
+            bean("bean-7", "花魁", None),
+        ]);
+        let text = redactor.redact(
+            "加载 bean-42（埃塞俄比亚 花魁，某某烘焙）失败：打开 /home/alice/.local/share/brew-guide/beans.json 出错",
+        );
+        assert_eq!(text, "加载 <bean-id>（<bean>，<roaster>）失败：打开 <path> 出错");
+        assert_eq!(
+            redactor.redact(r#"path="C:\Users\alice\AppData\notes.db" 花魁"#),
+            r#"path="<path>" <bean>"#
+        );
+        // 相对路径和网址保留，方便定位代码
+        assert_eq!(
+            redactor.redact("at src/lib.rs:12 https://api.github.com/repos"),
+            "at src/lib.rs:12 https://api.github.com/repos"
+        );
+    }
+}
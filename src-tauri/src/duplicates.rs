@@ -0,0 +1,286 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::journal;
+use crate::roast_date::parse_roast_date;
+use crate::store::{atomically, number_field, record_data, text_field, with_store, write_record, RecordKind};
+
+// 重复咖啡豆：按规范化后的名称、烘焙商、烘焙日期计算相似度，相似的咖啡豆归为一组并给出合并建议
+// merge_beans 把剩余量、容量加到保留的咖啡豆上，笔记改为关联保留的咖啡豆，其余的移到回收站
+const NAME_WEIGHT: f64 = 0.6;
+const ROASTER_WEIGHT: f64 = 0.25;
+const ROAST_DATE_WEIGHT: f64 = 0.15;
+
+// 名称相似度和总分都达到阈值才算重复
+const MIN_NAME_SIMILARITY: f64 = 0.75;
+const MIN_SCORE: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateCandidate {
+    pub id: String,
+    pub name: String,
+    pub roaster: Option<String>,
+    pub roast_date: Option<String>,
+    pub remaining: Option<f64>,
+    pub note_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub beans: Vec<DuplicateCandidate>,
+    pub score: f64,      // 组内最相似的一对的分数（0–1）
+    pub keep_id: String, // 建议保留的咖啡豆：笔记最多的，其次剩余量最多的
+    pub merge_ids: Vec<String>,
+}
+
+// 规范化：全角转半角、忽略大小写、去掉空白和标点
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c as u32 {
+            0xFF01..=0xFF5E => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+// 字符二元组的 Dice 系数
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| {
+        let chars: Vec<char> = s.chars().collect();
+        let mut counts: HashMap<(char, char), usize> = HashMap::new();
+        for pair in chars.windows(2) {
+            *counts.entry((pair[0], pair[1])).or_default() += 1;
+        }
+        counts
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    let total: usize = a.values().sum::<usize>() + b.values().sum::<usize>();
+    if total == 0 {
+        return 0.0;
+    }
+    let shared: usize = a.iter().map(|(pair, n)| (*n).min(b.get(pair).copied().unwrap_or(0))).sum();
+    2.0 * shared as f64 / total as f64
+}
+
+struct Fingerprint {
+    name: String,
+    roaster: Option<String>,
+    roast_date: Option<chrono::NaiveDate>,
+}
+
+impl Fingerprint {
+    fn of(bean: &Value) -> Self {
+        Self {
+            name: normalize(text_field(bean, "name").unwrap_or_default()),
+            roaster: text_field(bean, "roaster").map(normalize).filter(|r| !r.is_empty()),
+            roast_date: text_field(bean, "roastDate").and_then(parse_roast_date).map(|range| range.earliest),
+        }
+    }
+
+    // 缺少烘焙商或烘焙日期时算一半
+    fn score(&self, other: &Self) -> Option<f64> {
+        let name = similarity(&self.name, &other.name);
+        if name < MIN_NAME_SIMILARITY {
+            return None;
+        }
+        let matches = |a: Option<bool>| a.map_or(0.5, |same| if same { 1.0 } else { 0.0 });
+        let roaster = matches(self.roaster.as_ref().zip(other.roaster.as_ref()).map(|(a, b)| a == b));
+        let roast_date = matches(self.roast_date.zip(other.roast_date).map(|(a, b)| a == b));
+        let score = NAME_WEIGHT * name + ROASTER_WEIGHT * roaster + ROAST_DATE_WEIGHT * roast_date;
+        (score >= MIN_SCORE).then_some(score)
+    }
+}
+
+fn find_root(parents: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[i] = root;
+    root
+}
+
+// 两两比较后用并查集归组
+fn group(beans: &[Value]) -> Vec<(Vec<usize>, f64)> {
+    let fingerprints: Vec<Fingerprint> = beans.iter().map(Fingerprint::of).collect();
+    let mut parents: Vec<usize> = (0..beans.len()).collect();
+    let mut best: Vec<f64> = vec![0.0; beans.len()];
+    for i in 0..beans.len() {
+        for j in i + 1..beans.len() {
+            if let Some(score) = fingerprints[i].score(&fingerprints[j]) {
+                let (a, b) = (find_root(&mut parents, i), find_root(&mut parents, j));
+                parents[b] = a;
+                best[i] = best[i].max(score);
+                best[j] = best[j].max(score);
+            }
+        }
+    }
+    let mut groups: BTreeMap<usize, (Vec<usize>, f64)> = BTreeMap::new();
+    for (i, score) in best.iter().enumerate() {
+        let root = find_root(&mut parents, i);
+        let entry = groups.entry(root).or_default();
+        entry.0.push(i);
+        entry.1 = entry.1.max(*score);
+    }
+    groups.into_values().filter(|(members, _)| members.len() > 1).collect()
+}
+
+fn note_counts(conn: &Connection) -> rusqlite::Result<HashMap<String, usize>> {
+    let mut stmt = conn.prepare("SELECT bean_id, COUNT(*) FROM notes WHERE bean_id IS NOT NULL GROUP BY bean_id")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+    let mut counts = HashMap::new();
+    for row in rows {
+        let (bean_id, count) = row?;
+        counts.insert(bean_id, count.max(0) as usize);
+    }
+    Ok(counts)
+}
+
+fn find(conn: &Connection) -> rusqlite::Result<Vec<DuplicateGroup>> {
+    let beans: Vec<Value> = crate::store::all_beans(conn)?
+        .into_iter()
+        .filter(|bean| text_field(bean, "id").is_some())
+        .collect();
+    let counts = note_counts(conn)?;
+    let mut groups: Vec<DuplicateGroup> = group(&beans)
+        .into_iter()
+        .map(|(members, score)| {
+            let mut candidates: Vec<DuplicateCandidate> = members
+                .into_iter()
+                .map(|i| {
+                    let bean = &beans[i];
+                    let id = text_field(bean, "id").unwrap_or_default().to_string();
+                    DuplicateCandidate {
+                        note_count: counts.get(&id).copied().unwrap_or(0),
+                        id,
+                        name: text_field(bean, "name").unwrap_or_default().to_string(),
+                        roaster: text_field(bean, "roaster").map(str::to_string),
+                        roast_date: text_field(bean, "roastDate").map(str::to_string),
+                        remaining: number_field(bean, "remaining"),
+                    }
+                })
+                .collect();
+            candidates.sort_by(|a, b| {
+                b.note_count
+                    .cmp(&a.note_count)
+                    .then_with(|| b.remaining.unwrap_or(0.0).total_cmp(&a.remaining.unwrap_or(0.0)))
+            });
+            DuplicateGroup {
+                keep_id: candidates[0].id.clone(),
+                merge_ids: candidates[1..].iter().map(|c| c.id.clone()).collect(),
+                beans: candidates,
+                score,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(groups)
+}
+
+// 数量保持前端原来的类型（字符串或数字）
+fn amount_value(original: Option<&Value>, amount: f64) -> Value {
+    let text = if amount.fract() == 0.0 {
+        format!("{:.0}", amount)
+    } else {
+        format!("{:.1}", amount)
+    };
+    match original {
+        Some(Value::Number(_)) => serde_json::Number::from_f64(amount).map_or(Value::String(text), Value::Number),
+        _ => Value::String(text),
+    }
+}
+
+fn merge(conn: &Connection, keep_id: &str, merge_ids: &[String]) -> rusqlite::Result<Result<Value, String>> {
+    let parse = |data: String| serde_json::from_str::<Value>(&data).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()));
+    let Some(mut keep) = record_data(conn, RecordKind::Bean, keep_id)?.map(parse).transpose()? else {
+        return Ok(Err(format!("找不到咖啡豆：{}", keep_id)));
+    };
+    let mut merged = Vec::new();
+    for id in merge_ids.iter().filter(|id| id.as_str() != keep_id) {
+        let Some(bean) = record_data(conn, RecordKind::Bean, id)?.map(parse).transpose()? else {
+            return Ok(Err(format!("找不到咖啡豆：{}", id)));
+        };
+        merged.push((id, bean));
+    }
+
+    for key in ["remaining", "capacity"] {
+        let total: Option<f64> = std::iter::once(&keep)
+            .chain(merged.iter().map(|(_, bean)| bean))
+            .filter_map(|bean| number_field(bean, key))
+            .reduce(|a, b| a + b);
+        if let (Some(total), Some(object)) = (total, keep.as_object_mut()) {
+            let value = amount_value(object.get(key), total);
+            object.insert(key.to_string(), value);
+        }
+    }
+
+    atomically(conn, || {
+        journal::track(conn, RecordKind::Bean, keep_id, || crate::store::write_bean(conn, &keep))?;
+        for (id, _) in &merged {
+            let mut stmt = conn.prepare("SELECT id, data FROM notes WHERE bean_id = ?1")?;
+            let notes: Vec<(String, String)> = stmt
+                .query_map([id.as_str()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?;
+            for (note_id, data) in notes {
+                let mut note = parse(data)?;
+                note["beanId"] = Value::String(keep_id.to_string());
+                journal::track(conn, RecordKind::Note, &note_id, || write_record(conn, RecordKind::Note, &note))?;
+            }
+            journal::track(conn, RecordKind::Bean, id, || crate::trash::move_to_trash(conn, RecordKind::Bean, id))?;
+        }
+        Ok(())
+    })?;
+    Ok(Ok(keep))
+}
+
+#[tauri::command]
+pub fn find_duplicate_beans(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, |store| find(&store.conn))
+}
+
+// 合并咖啡豆，返回合并后保留的咖啡豆
+#[tauri::command]
+pub fn merge_beans(app: tauri::AppHandle, keep_id: String, merge_ids: Vec<String>) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    crate::backups::snapshot_before(&app, "merge");
+    let bean = with_store(&app, |store| merge(&store.conn, &keep_id, &merge_ids))??;
+    crate::telemetry::record(&app, "bean.merge");
+    crate::store::publish_changes(&app)?;
+    Ok(bean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn groups_similar_beans_and_merges_them() {
+        let conn = crate::store::test_connection();
+        let beans = [
+            json!({ "id": "b1", "name": "耶加雪菲 科契尔", "roaster": "某烘焙", "roastDate": "2026-10-01", "remaining": "100" }),
+            json!({ "id": "b2", "name": "耶加雪菲  科契尔！", "roaster": "某烘焙", "roastDate": "2026-10-01", "remaining": 50 }),
+            json!({ "id": "b3", "name": "肯尼亚 AA", "roaster": "某烘焙", "roastDate": "2026-10-01" }),
+        ];
+        for bean in &beans {
+            crate::store::write_bean(&conn, bean).unwrap();
+        }
+        let groups = find(&conn).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].beans.len(), 2);
+
+        let merged = merge(&conn, "b1", &["b2".to_string()]).unwrap().unwrap();
+        assert_eq!(merged["remaining"], json!("150"));
+        assert!(record_data(&conn, RecordKind::Bean, "b2").unwrap().is_none());
+    }
+}
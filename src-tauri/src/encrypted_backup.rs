@@ -0,0 +1,103 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::Argon2;
+use std::path::PathBuf;
+
+// 加密备份：把全部数据（数据库和图片附件）打包后用口令加密为单个文件，可以放在共享的网盘目录中
+// 文件格式：标识 + 盐（16 字节）+ 随机数（12 字节）+ 密文；口令经 Argon2id 派生密钥，用 AES-256-GCM 加密和校验
+const MAGIC: &[u8] = b"BREW-GUIDE-ENCRYPTED-BACKUP-1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const MIN_PASSWORD_CHARS: usize = 8;
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Aes256Gcm, String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())
+}
+
+fn encrypt(plain: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = derive_key(password, &salt)?
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plain, aad: MAGIC })
+        .map_err(|_| "加密备份失败".to_string())?;
+    let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+fn decrypt(data: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    let rest = data.strip_prefix(MAGIC).ok_or("不是加密备份文件")?;
+    if rest.len() < SALT_LEN + NONCE_LEN {
+        return Err("加密备份文件已损坏".to_string());
+    }
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    derive_key(password, salt)?
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: MAGIC })
+        .map_err(|_| "口令错误或文件已损坏".to_string())
+}
+
+// 导出加密备份到 path（先写入临时文件再改名，避免网盘同步写了一半的文件）
+#[tauri::command]
+pub fn export_backup_encrypted(app: tauri::AppHandle, path: String, password: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if password.chars().count() < MIN_PASSWORD_CHARS {
+        return Err(format!("口令至少需要 {} 个字符", MIN_PASSWORD_CHARS));
+    }
+    let archive = crate::backups::work_path(&app, "export.zip")?;
+    let packaged = crate::backups::package(&app, &archive, "export")
+        .and_then(|()| std::fs::read(&archive).map_err(|e| e.to_string()));
+    let _ = std::fs::remove_file(&archive);
+    let data = encrypt(&packaged?, &password)?;
+
+    let path = PathBuf::from(path);
+    let mut partial = path.clone().into_os_string();
+    partial.push(".partial");
+    std::fs::write(&partial, data).map_err(|e| e.to_string())?;
+    std::fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+    crate::telemetry::record(&app, "backup.export_encrypted");
+    Ok(())
+}
+
+// 导入加密备份：解密后按恢复备份处理（会先备份当前数据）
+#[tauri::command]
+pub fn import_backup_encrypted(app: tauri::AppHandle, path: String, password: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let data = std::fs::read(&path).map_err(|e| e.to_string())?;
+    let plain = decrypt(&data, &password)?;
+    let archive = crate::backups::work_path(&app, "import.zip")?;
+    let result = std::fs::write(&archive, plain)
+        .map_err(|e| e.to_string())
+        .and_then(|()| crate::backups::restore_archive(&app, &archive));
+    let _ = std::fs::remove_file(&archive);
+    result?;
+    crate::telemetry::record(&app, "backup.import_encrypted");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypts_only_with_the_right_password() {
+        let data = encrypt(b"brew guide backup", "correct horse").unwrap();
+        assert!(data.starts_with(MAGIC));
+        assert_eq!(decrypt(&data, "correct horse").unwrap(), b"brew guide backup");
+        assert!(decrypt(&data, "wrong password").is_err());
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&tampered, "correct horse").is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::background::BeanCache;
+use crate::CoffeeBean;
+
+mod builtin;
+#[cfg(feature = "community-extensions")]
+mod community;
+
+// 扩展接口：秤协议、导入器、导出器在启动时注册到 ExtensionRegistry
+// 社区贡献的模块放在 extensions/community 下，通过 community-extensions 特性编译，不需要改动核心模块
+
+// 蓝牙秤的广播信息
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScaleDevice {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub service_uuids: Vec<String>,
+}
+
+pub trait ScaleProtocol: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    // 根据广播名或服务 UUID 判断是否为该协议的秤
+    fn matches(&self, device: &ScaleDevice) -> bool;
+    // 解析一帧重量通知，返回克数；不是重量数据时返回 None
+    fn parse_weight(&self, frame: &[u8]) -> Option<f64>;
+}
+
+pub trait Importer: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn file_extensions(&self) -> &'static [&'static str];
+    fn import(&self, bytes: &[u8]) -> Result<Vec<CoffeeBean>, String>;
+}
+
+pub trait Exporter: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn file_extension(&self) -> &'static str;
+    fn export(&self, beans: &[CoffeeBean]) -> Result<Vec<u8>, String>;
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub file_extensions: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionCatalog {
+    pub scales: Vec<ExtensionInfo>,
+    pub importers: Vec<ExtensionInfo>,
+    pub exporters: Vec<ExtensionInfo>,
+}
+
+// 启动时注册完成后只读，所以不需要加锁
+#[derive(Default)]
+pub struct ExtensionRegistry {
+    scales: Vec<Box<dyn ScaleProtocol>>,
+    importers: Vec<Box<dyn Importer>>,
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl ExtensionRegistry {
+    // 内置模块 + 已启用的社区模块
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::default();
+        builtin::register(&mut registry);
+        #[cfg(feature = "community-extensions")]
+        community::register(&mut registry);
+        registry
+    }
+
+    // ID 重复时保留先注册的，避免社区模块覆盖内置实现
+    pub fn register_scale(&mut self, scale: Box<dyn ScaleProtocol>) {
+        if self.scales.iter().any(|s| s.id() == scale.id()) {
+            log::warn!("秤协议 {} 重复注册，已忽略", scale.id());
+            return;
+        }
+        self.scales.push(scale);
+    }
+
+    pub fn register_importer(&mut self, importer: Box<dyn Importer>) {
+        if self.importers.iter().any(|i| i.id() == importer.id()) {
+            log::warn!("导入器 {} 重复注册，已忽略", importer.id());
+            return;
+        }
+        self.importers.push(importer);
+    }
+
+    pub fn register_exporter(&mut self, exporter: Box<dyn Exporter>) {
+        if self.exporters.iter().any(|e| e.id() == exporter.id()) {
+            log::warn!("导出器 {} 重复注册，已忽略", exporter.id());
+            return;
+        }
+        self.exporters.push(exporter);
+    }
+
+    pub fn scale(&self, id: &str) -> Option<&dyn ScaleProtocol> {
+        self.scales.iter().find(|s| s.id() == id).map(|s| s.as_ref())
+    }
+
+    pub fn importer(&self, id: &str) -> Option<&dyn Importer> {
+        self.importers.iter().find(|i| i.id() == id).map(|i| i.as_ref())
+    }
+
+    pub fn exporter(&self, id: &str) -> Option<&dyn Exporter> {
+        self.exporters.iter().find(|e| e.id() == id).map(|e| e.as_ref())
+    }
+
+    fn catalog(&self) -> ExtensionCatalog {
+        ExtensionCatalog {
+            scales: self
+                .scales
+                .iter()
+                .map(|s| ExtensionInfo { id: s.id(), name: s.name(), file_extensions: Vec::new() })
+                .collect(),
+            importers: self
+                .importers
+                .iter()
+                .map(|i| ExtensionInfo { id: i.id(), name: i.name(), file_extensions: i.file_extensions().to_vec() })
+                .collect(),
+            exporters: self
+                .exporters
+                .iter()
+                .map(|e| ExtensionInfo { id: e.id(), name: e.name(), file_extensions: vec![e.file_extension()] })
+                .collect(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn list_extensions(registry: tauri::State<'_, ExtensionRegistry>) -> ExtensionCatalog {
+    registry.catalog()
+}
+
+// 扫描到蓝牙设备后调用，返回匹配的秤协议 ID
+#[tauri::command]
+pub fn match_scale_protocol(registry: tauri::State<'_, ExtensionRegistry>, device: ScaleDevice) -> Option<String> {
+    let scale = registry.scales.iter().find(|s| s.matches(&device))?;
+    log::info!("{} 使用秤协议 {}", device.name.as_deref().unwrap_or("未命名设备"), scale.id());
+    Some(scale.id().to_string())
+}
+
+#[tauri::command]
+pub fn parse_scale_weight(
+    registry: tauri::State<'_, ExtensionRegistry>,
+    protocol_id: String,
+    frame: Vec<u8>,
+) -> Result<Option<f64>, String> {
+    let scale = registry.scale(&protocol_id).ok_or("未知的秤协议")?;
+    Ok(scale.parse_weight(&frame))
+}
+
+// 用指定导入器读取文件，返回解析出的咖啡豆（由前端确认后写入）
+#[tauri::command]
+pub fn import_with_extension(
+    app: tauri::AppHandle,
+    registry: tauri::State<'_, ExtensionRegistry>,
+    importer_id: String,
+    path: String,
+) -> Result<Vec<CoffeeBean>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let importer = registry.importer(&importer_id).ok_or("未知的导入器")?;
+    let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+    crate::telemetry::record(&app, &format!("import.{}", importer.id()));
+    importer.import(&bytes)
+}
+
+// 用指定导出器导出当前库存
+#[tauri::command]
+pub fn export_with_extension(
+    app: tauri::AppHandle,
+    registry: tauri::State<'_, ExtensionRegistry>,
+    exporter_id: String,
+    path: String,
+) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let exporter = registry.exporter(&exporter_id).ok_or("未知的导出器")?;
+    let beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .ok_or("咖啡豆数据未初始化")?
+        .lock()
+        .map_err(|e| e.to_string())?
+        .beans
+        .clone();
+    let bytes = exporter.export(&beans)?;
+    crate::telemetry::record(&app, &format!("export.{}", exporter.id()));
+    fs::write(&path, bytes).map_err(|e| e.to_string())
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Exporter, ExtensionRegistry, Importer, ScaleDevice, ScaleProtocol};
+use crate::CoffeeBean;
+
+pub fn register(registry: &mut ExtensionRegistry) {
+    registry.register_scale(Box::new(BluetoothWeightScale));
+    registry.register_importer(Box::new(BrewGuideJson));
+    registry.register_exporter(Box::new(BrewGuideJson));
+}
+
+// 蓝牙标准体重秤服务（Weight Scale Service 0x181D）
+// 重量测量特征值：第 1 字节为标志位（bit0 为 1 时是英制），后两字节为小端重量
+struct BluetoothWeightScale;
+
+const WEIGHT_SCALE_SERVICE: &str = "181d";
+
+impl ScaleProtocol for BluetoothWeightScale {
+    fn id(&self) -> &'static str {
+        "bluetooth-weight-scale"
+    }
+
+    fn name(&self) -> &'static str {
+        "Bluetooth Weight Scale"
+    }
+
+    fn matches(&self, device: &ScaleDevice) -> bool {
+        // 完整 UUID 形如 0000181d-0000-1000-8000-00805f9b34fb
+        device.service_uuids.iter().any(|uuid| {
+            let uuid = uuid.to_lowercase();
+            uuid == WEIGHT_SCALE_SERVICE || uuid.starts_with(&format!("0000{}-", WEIGHT_SCALE_SERVICE))
+        })
+    }
+
+    fn parse_weight(&self, frame: &[u8]) -> Option<f64> {
+        let [flags, low, high, ..] = *frame else {
+            return None;
+        };
+        let raw = u16::from_le_bytes([low, high]) as f64;
+        if flags & 0x01 == 0 {
+            // 国际单位：分辨率 0.005 kg
+            Some(raw * 5.0)
+        } else {
+            // 英制：分辨率 0.01 lb
+            Some(raw * 0.01 * 453.592_37)
+        }
+    }
+}
+
+// Brew Guide 自己的 JSON 格式：咖啡豆数组，或前端完整备份中的 coffeeBeans 字段
+struct BrewGuideJson;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BrewGuideJsonInput {
+    Beans(Vec<CoffeeBean>),
+    #[serde(rename_all = "camelCase")]
+    Backup { coffee_beans: Vec<CoffeeBean> },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BrewGuideJsonOutput<'a> {
+    exported_at: String,
+    coffee_beans: &'a [CoffeeBean],
+}
+
+impl Importer for BrewGuideJson {
+    fn id(&self) -> &'static str {
+        "brew-guide-json"
+    }
+
+    fn name(&self) -> &'static str {
+        "Brew Guide JSON"
+    }
+
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn import(&self, bytes: &[u8]) -> Result<Vec<CoffeeBean>, String> {
+        match serde_json::from_slice(bytes).map_err(|e| e.to_string())? {
+            BrewGuideJsonInput::Beans(beans) => Ok(beans),
+            BrewGuideJsonInput::Backup { coffee_beans } => Ok(coffee_beans),
+        }
+    }
+}
+
+impl Exporter for BrewGuideJson {
+    fn id(&self) -> &'static str {
+        "brew-guide-json"
+    }
+
+    fn name(&self) -> &'static str {
+        "Brew Guide JSON"
+    }
+
+    fn file_extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn export(&self, beans: &[CoffeeBean]) -> Result<Vec<u8>, String> {
+        let output = BrewGuideJsonOutput {
+            exported_at: chrono::Local::now().to_rfc3339(),
+            coffee_beans: beans,
+        };
+        serde_json::to_vec_pretty(&output).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,6 @@
+use super::ExtensionRegistry;
+
+// 社区贡献的秤协议、导入器、导出器在这里注册（需要启用 community-extensions 特性）
+// 每个模块放在 extensions/community/ 下的单独文件中，实现对应的 trait 后在 register 里加一行，例如：
+//     registry.register_scale(Box::new(my_scale::MyScale));
+pub fn register(_registry: &mut ExtensionRegistry) {}
@@ -0,0 +1,241 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+use crate::journal;
+use crate::store::{atomically, record_data, text_field, with_store, write_record, RecordKind};
+
+// 双击或“打开方式”打开 .brewguide 文件（前端导出的完整数据格式）
+// 只包含咖啡豆和笔记、且不会覆盖已有记录时直接导入；否则交给前端确认
+const EXTENSION: &str = "brewguide";
+const MAX_FILE_BYTES: u64 = 200 * 1024 * 1024;
+const RECORD_KEYS: [(&str, RecordKind); 2] = [("coffeeBeans", RecordKind::Bean), ("brewingNotes", RecordKind::Note)];
+
+#[derive(Default)]
+pub struct FileImportState {
+    pending: Vec<ImportRequest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRequest {
+    pub id: String,
+    pub path: String,
+    pub export_date: Option<String>,
+    pub app_version: Option<String>,
+    pub beans: usize,
+    pub notes: usize,
+    // 会覆盖本地同 ID 记录的数量
+    pub existing: usize,
+    // 设置、器具等只能由前端导入的部分
+    pub other_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedFile {
+    pub path: String,
+    pub beans: usize,
+    pub notes: usize,
+}
+
+struct BrewGuideFile {
+    export_date: Option<String>,
+    app_version: Option<String>,
+    records: Vec<(RecordKind, Value)>,
+    other: Map<String, Value>,
+}
+
+impl BrewGuideFile {
+    fn count(&self, kind: RecordKind) -> usize {
+        self.records.iter().filter(|(k, _)| *k == kind).count()
+    }
+}
+
+fn parse(bytes: &[u8]) -> Result<BrewGuideFile, String> {
+    let root: Value = serde_json::from_slice(bytes).map_err(|e| format!("不是有效的 Brew Guide 数据文件：{}", e))?;
+    let mut other = match root.get("data") {
+        Some(Value::Object(data)) => data.clone(),
+        _ => return Err("不是有效的 Brew Guide 数据文件：缺少 data".to_string()),
+    };
+    let mut records = Vec::new();
+    for (key, kind) in RECORD_KEYS {
+        if let Some(Value::Array(items)) = other.remove(key) {
+            // 没有 ID 的记录无法合并，跳过
+            records.extend(items.into_iter().filter(|item| text_field(item, "id").is_some()).map(|item| (kind, item)));
+        }
+    }
+    Ok(BrewGuideFile {
+        export_date: text_field(&root, "exportDate").map(str::to_string),
+        app_version: text_field(&root, "appVersion").map(str::to_string),
+        records,
+        other,
+    })
+}
+
+fn read_file(path: &Path) -> Result<BrewGuideFile, String> {
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_FILE_BYTES {
+        return Err("文件过大".to_string());
+    }
+    parse(&std::fs::read(path).map_err(|e| e.to_string())?)
+}
+
+fn count_existing(conn: &rusqlite::Connection, file: &BrewGuideFile) -> rusqlite::Result<usize> {
+    let mut existing = 0;
+    for (kind, value) in &file.records {
+        if let Some(id) = text_field(value, "id") {
+            if record_data(conn, *kind, id)?.is_some() {
+                existing += 1;
+            }
+        }
+    }
+    Ok(existing)
+}
+
+fn write_records(conn: &rusqlite::Connection, file: &BrewGuideFile) -> rusqlite::Result<()> {
+    atomically(conn, || {
+        for (kind, value) in &file.records {
+            if let Some(id) = text_field(value, "id") {
+                journal::track(conn, *kind, id, || write_record(conn, *kind, value))?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn import_records(app: &tauri::AppHandle, file: &BrewGuideFile) -> Result<(), String> {
+    if file.records.is_empty() {
+        return Ok(());
+    }
+    crate::backups::snapshot_before(app, "file-import");
+    with_store(app, |store| write_records(&store.conn, file))?;
+    crate::store::publish_changes(app)
+}
+
+fn open_file(app: &tauri::AppHandle, path: &Path) -> Result<(), String> {
+    let file = read_file(path)?;
+    let existing = with_store(app, |store| count_existing(&store.conn, &file))?;
+    let path_text = path.to_string_lossy().into_owned();
+    crate::show_main_window(app);
+
+    if existing == 0 && file.other.is_empty() {
+        import_records(app, &file)?;
+        crate::telemetry::record(app, "import.file");
+        let _ = app.emit(
+            "file-imported",
+            ImportedFile {
+                path: path_text,
+                beans: file.count(RecordKind::Bean),
+                notes: file.count(RecordKind::Note),
+            },
+        );
+        return Ok(());
+    }
+
+    let request = ImportRequest {
+        id: format!("import-{}", chrono::Local::now().timestamp_millis()),
+        path: path_text,
+        export_date: file.export_date.clone(),
+        app_version: file.app_version.clone(),
+        beans: file.count(RecordKind::Bean),
+        notes: file.count(RecordKind::Note),
+        existing,
+        other_keys: file.other.keys().cloned().collect(),
+    };
+    let state = app.state::<Arc<Mutex<FileImportState>>>();
+    state.lock().map_err(|e| e.to_string())?.pending.push(request.clone());
+    // 前端还没启动时收不到事件，启动后通过 take_file_import_requests 拉取
+    let _ = app.emit("import-request", &request);
+    Ok(())
+}
+
+pub fn open_files(app: &tauri::AppHandle, paths: Vec<PathBuf>) {
+    for path in paths {
+        if !path.extension().is_some_and(|e| e.eq_ignore_ascii_case(EXTENSION)) {
+            continue;
+        }
+        if let Err(e) = open_file(app, &path) {
+            log::warn!("打开 {} 失败：{}", path.display(), e);
+            let _ = app.emit("file-import-failed", serde_json::json!({ "path": path, "error": e }));
+        }
+    }
+}
+
+// Windows / Linux 通过命令行参数传入文件路径
+pub fn paths_from_args(args: impl Iterator<Item = std::ffi::OsString>) -> Vec<PathBuf> {
+    args.skip(1).map(PathBuf::from).filter(|path| path.is_file()).collect()
+}
+
+// macOS / iOS 通过 RunEvent::Opened 传入文件 URL
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn open_urls(app: &tauri::AppHandle, urls: &[tauri::Url]) {
+    open_files(app, urls.iter().filter_map(|url| url.to_file_path().ok()).collect());
+}
+
+fn take_request(app: &tauri::AppHandle, id: &str) -> Result<ImportRequest, String> {
+    let state = app.state::<Arc<Mutex<FileImportState>>>();
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let index = state
+        .pending
+        .iter()
+        .position(|request| request.id == id)
+        .ok_or_else(|| format!("导入请求已失效：{}", id))?;
+    Ok(state.pending.remove(index))
+}
+
+#[tauri::command]
+pub fn take_file_import_requests(app: tauri::AppHandle) -> Result<Vec<ImportRequest>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let state = app.state::<Arc<Mutex<FileImportState>>>();
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    Ok(std::mem::take(&mut state.pending))
+}
+
+// 确认导入：咖啡豆和笔记写入数据库，返回其余数据（设置、器具、方案等）由前端继续导入
+#[tauri::command]
+pub fn confirm_file_import(app: tauri::AppHandle, id: String) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let request = take_request(&app, &id)?;
+    let file = read_file(Path::new(&request.path))?;
+    import_records(&app, &file)?;
+    crate::telemetry::record(&app, "import.file");
+    Ok(Value::Object(file.other))
+}
+
+#[tauri::command]
+pub fn dismiss_file_import(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    take_request(&app, &id)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn splits_records_from_the_rest_of_an_export() {
+        let export = json!({
+            "exportDate": "2026-10-16T08:00:00+08:00",
+            "appVersion": "1.5.0",
+            "data": {
+                "coffeeBeans": [{ "id": "b1", "name": "耶加雪菲" }, { "name": "没有 ID" }],
+                "brewingNotes": [{ "id": "n1", "beanId": "b1", "timestamp": 1_790_000_000_000_i64 }],
+                "customEquipments": [],
+            },
+        });
+        let file = parse(export.to_string().as_bytes()).unwrap();
+        assert_eq!(file.count(RecordKind::Bean), 1);
+        assert_eq!(file.count(RecordKind::Note), 1);
+        assert_eq!(file.other.keys().collect::<Vec<_>>(), vec!["customEquipments"]);
+        assert!(parse(br#"{"coffeeBeans": []}"#).is_err());
+
+        let conn = crate::store::test_connection();
+        assert_eq!(count_existing(&conn, &file).unwrap(), 0);
+        write_records(&conn, &file).unwrap();
+        assert_eq!(count_existing(&conn, &file).unwrap(), 2);
+    }
+}
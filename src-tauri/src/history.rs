@@ -0,0 +1,243 @@
+use git2::{Commit, Oid, Repository, Signature, Tree};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::Path;
+use tauri::Manager;
+
+use crate::store::{text_field, with_store, RecordKind};
+use crate::sync::{parse_key, record_key, Records};
+
+// 数据历史：开启后每次备份时把全部咖啡豆和笔记导出为 JSON（beans/、notes/ 下每条记录一个文件），
+// 提交到数据目录下的 git 仓库 history.git，数据没有变化时不提交；可以查看历史，把数据恢复到任意一次提交
+// 仓库是普通的 bare 仓库，可以用 git log -p 查看每次的变化
+const REPO_DIR: &str = "history.git";
+const BRANCH: &str = "refs/heads/main";
+const FILE_MODE: i32 = 0o100644;
+const DIR_MODE: i32 = 0o040000;
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryCommit {
+    pub id: String,
+    pub message: String,
+    pub committed_at: String,
+    pub beans: usize,
+    pub notes: usize,
+}
+
+fn open(dir: &Path) -> Result<Repository, git2::Error> {
+    if dir.exists() {
+        Repository::open_bare(dir)
+    } else {
+        Repository::init_bare(dir)
+    }
+}
+
+fn repo_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(REPO_DIR))
+        .map_err(|e| e.to_string())
+}
+
+// 文件名直接用 ID，含有其它字符时用 ID 的哈希；恢复时以文件内容中的 id 为准
+fn file_name(id: &str) -> String {
+    let safe = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if safe {
+        format!("{}.json", id)
+    } else {
+        format!("{}.json", &blake3::hash(id.as_bytes()).to_hex()[..16])
+    }
+}
+
+fn write_tree(repo: &Repository, records: &Records) -> Result<Oid, git2::Error> {
+    let mut root = repo.treebuilder(None)?;
+    for kind in [RecordKind::Bean, RecordKind::Note] {
+        let mut dir = repo.treebuilder(None)?;
+        for (key, value) in records {
+            let Some((record_kind, id)) = parse_key(key) else {
+                continue;
+            };
+            if record_kind == kind {
+                let text = serde_json::to_string_pretty(value).unwrap_or_default();
+                dir.insert(file_name(id), repo.blob(text.as_bytes())?, FILE_MODE)?;
+            }
+        }
+        root.insert(kind.table(), dir.write()?, DIR_MODE)?;
+    }
+    root.write()
+}
+
+fn kind_tree<'a>(repo: &'a Repository, tree: &Tree, kind: RecordKind) -> Result<Option<Tree<'a>>, git2::Error> {
+    match tree.get_name(kind.table()) {
+        Some(entry) => entry.to_object(repo)?.peel_to_tree().map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_records(repo: &Repository, tree: &Tree) -> Result<Records, git2::Error> {
+    let mut records = Records::new();
+    for kind in [RecordKind::Bean, RecordKind::Note] {
+        let Some(dir) = kind_tree(repo, tree, kind)? else {
+            continue;
+        };
+        for entry in dir.iter() {
+            let blob = entry.to_object(repo)?.peel_to_blob()?;
+            let Ok(value) = serde_json::from_slice::<Value>(blob.content()) else {
+                continue;
+            };
+            if let Some(id) = text_field(&value, "id") {
+                records.insert(record_key(kind, id), value.clone());
+            }
+        }
+    }
+    Ok(records)
+}
+
+fn head(repo: &Repository) -> Option<Commit<'_>> {
+    repo.find_reference(BRANCH).ok()?.peel_to_commit().ok()
+}
+
+// 提交一次数据，和上一次提交相同时返回 None
+fn commit(repo: &Repository, records: &Records, message: &str) -> Result<Option<Oid>, git2::Error> {
+    let tree_id = write_tree(repo, records)?;
+    let parent = head(repo);
+    if parent.as_ref().is_some_and(|parent| parent.tree_id() == tree_id) {
+        return Ok(None);
+    }
+    let tree = repo.find_tree(tree_id)?;
+    let signature = Signature::now("Brew Guide", "brew-guide@localhost")?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let id = repo.commit(Some(BRANCH), &signature, &signature, message, &tree, &parents)?;
+    repo.set_head(BRANCH)?;
+    Ok(Some(id))
+}
+
+fn count(repo: &Repository, tree: &Tree, kind: RecordKind) -> usize {
+    kind_tree(repo, tree, kind).ok().flatten().map_or(0, |dir| dir.len())
+}
+
+fn list(repo: &Repository, limit: usize) -> Result<Vec<HistoryCommit>, git2::Error> {
+    let Some(head) = head(repo) else {
+        return Ok(Vec::new());
+    };
+    let mut walk = repo.revwalk()?;
+    walk.push(head.id())?;
+    let mut commits = Vec::new();
+    for id in walk.take(limit) {
+        let commit = repo.find_commit(id?)?;
+        let tree = commit.tree()?;
+        let committed_at = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|time| time.with_timezone(&chrono::Local).to_rfc3339())
+            .unwrap_or_default();
+        commits.push(HistoryCommit {
+            id: commit.id().to_string(),
+            message: commit.message().unwrap_or_default().trim().to_string(),
+            committed_at,
+            beans: count(repo, &tree, RecordKind::Bean),
+            notes: count(repo, &tree, RecordKind::Note),
+        });
+    }
+    Ok(commits)
+}
+
+fn commit_current(app: &tauri::AppHandle, message: &str) -> Result<Option<Oid>, String> {
+    let records = with_store(app, |store| crate::sync::local_records(&store.conn))?;
+    let repo = open(&repo_path(app)?).map_err(|e| e.to_string())?;
+    commit(&repo, &records, message).map_err(|e| e.to_string())
+}
+
+// 备份成功后调用：开启了数据历史时提交一次
+pub fn record_backup(app: &tauri::AppHandle, reason: &str) {
+    if !crate::settings::get(app).git_history {
+        return;
+    }
+    match commit_current(app, reason) {
+        Ok(Some(id)) => log::info!("已提交数据历史 {}", id),
+        Ok(None) => {}
+        Err(e) => log::warn!("提交数据历史失败：{}", e),
+    }
+}
+
+#[tauri::command]
+pub fn set_git_history(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    crate::settings::update(&app, |settings| settings.git_history = enabled)?;
+    if enabled {
+        commit_current(&app, "enable")?;
+        crate::telemetry::record(&app, "history.enable");
+    }
+    Ok(())
+}
+
+// 最近的提交在前，默认最多 100 条
+#[tauri::command]
+pub fn list_history(app: tauri::AppHandle, limit: Option<usize>) -> Result<Vec<HistoryCommit>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let path = repo_path(&app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let repo = open(&path).map_err(|e| e.to_string())?;
+    list(&repo, limit.unwrap_or(DEFAULT_LIMIT)).map_err(|e| e.to_string())
+}
+
+// 把咖啡豆和笔记恢复为指定提交时的数据（先备份当前数据；每条记录的变化都可以撤销），返回变化的记录数
+#[tauri::command]
+pub fn restore_history(app: tauri::AppHandle, id: String) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let repo = open(&repo_path(&app)?).map_err(|e| e.to_string())?;
+    let records = Oid::from_str(&id)
+        .and_then(|oid| repo.find_commit(oid))
+        .and_then(|commit| read_records(&repo, &commit.tree()?))
+        .map_err(|_| format!("找不到历史记录：{}", id))?;
+
+    crate::backups::snapshot_before(&app, "history-restore");
+    let changed = with_store(&app, |store| {
+        let local = crate::sync::local_records(&store.conn)?;
+        let keys: BTreeSet<&String> = local.keys().chain(records.keys()).collect();
+        let changes: Vec<(String, Option<Value>)> = keys
+            .into_iter()
+            .filter(|key| local.get(*key) != records.get(*key))
+            .map(|key| (key.clone(), records.get(key).cloned()))
+            .collect();
+        crate::sync::apply(&store.conn, &changes)?;
+        Ok(changes.len())
+    })?;
+    if changed > 0 {
+        crate::store::publish_changes(&app)?;
+    }
+    crate::telemetry::record(&app, "history.restore");
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn commits_only_changes_and_reads_them_back() {
+        let dir = std::env::temp_dir().join(format!("brew-guide-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let repo = open(&dir).unwrap();
+        let mut records = Records::new();
+        records.insert("bean:b1".to_string(), json!({ "id": "b1", "name": "耶加雪菲" }));
+        records.insert("note:n/1".to_string(), json!({ "id": "n/1", "method": "V60" }));
+
+        let first = commit(&repo, &records, "daily").unwrap().unwrap();
+        assert!(commit(&repo, &records, "daily").unwrap().is_none());
+        records.insert("bean:b1".to_string(), json!({ "id": "b1", "name": "耶加雪菲 G1" }));
+        commit(&repo, &records, "manual").unwrap().unwrap();
+
+        let history = list(&repo, 10).unwrap();
+        assert_eq!(history.iter().map(|c| c.message.as_str()).collect::<Vec<_>>(), vec!["manual", "daily"]);
+        assert_eq!((history[1].beans, history[1].notes), (1, 1));
+        let old = read_records(&repo, &repo.find_commit(first).unwrap().tree().unwrap()).unwrap();
+        assert_eq!(old["bean:b1"]["name"], "耶加雪菲");
+        assert_eq!(old["note:n/1"]["method"], "V60");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,155 @@
+use serde::Deserialize;
+
+use crate::settings::HotkeySettings;
+
+// 全局快捷键：按设置在后端注册，应用在后台（主窗口隐藏到托盘）时也有效
+// 修改设置后全部注销再重新注册；被其他应用占用的快捷键记录到诊断日志，不影响其他快捷键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    ToggleTimer,
+    NextStep,
+    QuickLog,
+    QuickPanel,
+}
+
+#[cfg(desktop)]
+const ACTIONS: [HotkeyAction; 4] = [
+    HotkeyAction::ToggleTimer,
+    HotkeyAction::NextStep,
+    HotkeyAction::QuickLog,
+    HotkeyAction::QuickPanel,
+];
+
+#[cfg(desktop)]
+fn binding(settings: &HotkeySettings, action: HotkeyAction) -> Option<&str> {
+    let binding = match action {
+        HotkeyAction::ToggleTimer => &settings.toggle_timer,
+        HotkeyAction::NextStep => &settings.next_step,
+        HotkeyAction::QuickLog => &settings.quick_log,
+        HotkeyAction::QuickPanel => &settings.quick_panel,
+    };
+    binding.as_deref().map(str::trim).filter(|binding| !binding.is_empty())
+}
+
+fn binding_mut(settings: &mut HotkeySettings, action: HotkeyAction) -> &mut Option<String> {
+    match action {
+        HotkeyAction::ToggleTimer => &mut settings.toggle_timer,
+        HotkeyAction::NextStep => &mut settings.next_step,
+        HotkeyAction::QuickLog => &mut settings.quick_log,
+        HotkeyAction::QuickPanel => &mut settings.quick_panel,
+    }
+}
+
+// 解析全部快捷键，格式错误或两个操作用了同一个快捷键时报错
+#[cfg(desktop)]
+fn parse_bindings(settings: &HotkeySettings) -> Result<Vec<(HotkeyAction, tauri_plugin_global_shortcut::Shortcut)>, String> {
+    let mut bindings: Vec<(HotkeyAction, tauri_plugin_global_shortcut::Shortcut)> = Vec::new();
+    for action in ACTIONS {
+        let Some(text) = binding(settings, action) else {
+            continue;
+        };
+        let shortcut: tauri_plugin_global_shortcut::Shortcut =
+            text.parse().map_err(|e| format!("无效的快捷键 {}：{}", text, e))?;
+        if bindings.iter().any(|(_, other)| *other == shortcut) {
+            return Err(format!("快捷键 {} 重复", text));
+        }
+        bindings.push((action, shortcut));
+    }
+    Ok(bindings)
+}
+
+#[cfg(desktop)]
+fn toggle_quick_panel(app: &tauri::AppHandle) -> Result<(), String> {
+    // 快速面板显示在托盘图标旁，托盘隐藏时改为显示主窗口
+    let rect = app.tray_by_id("main-tray").and_then(|tray| tray.rect().ok().flatten());
+    match rect {
+        Some(rect) => crate::quick_panel::toggle(app, &rect),
+        None => {
+            crate::show_main_window(app);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(desktop)]
+fn run(app: &tauri::AppHandle, action: HotkeyAction) {
+    let result = match action {
+        HotkeyAction::ToggleTimer => crate::timer::toggle(app),
+        HotkeyAction::NextStep => crate::timer::next_step(app),
+        HotkeyAction::QuickLog => crate::timer::quick_log(app),
+        HotkeyAction::QuickPanel => toggle_quick_panel(app),
+    };
+    if let Err(e) = result {
+        log::warn!("快捷键操作 {:?} 失败：{}", action, e);
+    }
+}
+
+// 按当前设置重新注册全部快捷键，返回注册失败的操作
+#[cfg(desktop)]
+pub fn register(app: &tauri::AppHandle) -> Result<Vec<HotkeyAction>, String> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    let bindings = parse_bindings(&crate::settings::get(app).hotkeys)?;
+    let shortcuts = app.global_shortcut();
+    shortcuts.unregister_all().map_err(|e| e.to_string())?;
+    let mut failed = Vec::new();
+    for (action, shortcut) in bindings {
+        let result = shortcuts.on_shortcut(shortcut, move |app, _, event| {
+            if event.state() == ShortcutState::Pressed {
+                run(app, action);
+            }
+        });
+        if let Err(e) = result {
+            log::warn!("注册快捷键 {} 失败：{}", shortcut, e);
+            crate::diagnostics::record_error(app, "hotkeys", format!("{}: {}", shortcut, e));
+            failed.push(action);
+        }
+    }
+    Ok(failed)
+}
+
+// 修改一个快捷键，binding 为 None 或空字符串时取消；新快捷键注册失败时恢复原来的设置
+#[tauri::command]
+pub fn set_hotkey(app: tauri::AppHandle, action: HotkeyAction, binding: Option<String>) -> Result<(), String> {
+    let previous = crate::settings::get(&app).hotkeys;
+    let mut next = previous.clone();
+    *binding_mut(&mut next, action) = binding.map(|b| b.trim().to_string()).filter(|b| !b.is_empty());
+    #[cfg(desktop)]
+    parse_bindings(&next)?;
+    crate::settings::update(&app, |s| s.hotkeys = next)?;
+    #[cfg(desktop)]
+    {
+        let failed = register(&app)?;
+        if failed.contains(&action) {
+            crate::settings::update(&app, |s| s.hotkeys = previous)?;
+            register(&app)?;
+            return Err("快捷键注册失败，可能已被其他应用占用".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, desktop))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hotkey_bindings() {
+        let defaults = parse_bindings(&HotkeySettings::default()).unwrap();
+        assert_eq!(defaults.len(), 4);
+        assert_eq!(defaults[0].0, HotkeyAction::ToggleTimer);
+
+        let mut settings = HotkeySettings {
+            quick_panel: Some("  ".to_string()),
+            next_step: Some("CmdOrCtrl+Shift+Right".to_string()),
+            ..HotkeySettings::default()
+        };
+        assert_eq!(parse_bindings(&settings).unwrap().len(), 3);
+
+        settings.quick_log = Some("alt+shift+t".to_string());
+        assert!(parse_bindings(&settings).unwrap_err().contains("重复"));
+        settings.quick_log = Some("Shift+NotAKey".to_string());
+        assert!(parse_bindings(&settings).unwrap_err().contains("无效"));
+    }
+}
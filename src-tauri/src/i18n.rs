@@ -0,0 +1,295 @@
+// 后端文案与数字格式化（托盘菜单等原生界面使用）
+// 文案目前只有简体中文和英文，其它语言使用英文文案，但数字格式跟随语言习惯
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Zh,
+    En,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Locale {
+    pub language: Language,
+    decimal_separator: char,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            language: Language::Zh,
+            decimal_separator: '.',
+        }
+    }
+}
+
+// 使用逗号作为小数点的语言
+const DECIMAL_COMMA_LANGUAGES: [&str; 14] = [
+    "de", "fr", "es", "it", "pt", "nl", "ru", "pl", "cs", "sv", "da", "nb", "fi", "tr",
+];
+
+impl Locale {
+    // 解析 BCP 47 语言标签，如 "zh-CN"、"en-US"、"de-DE"
+    pub fn parse(tag: &str) -> Self {
+        let primary = tag
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let language = if primary == "zh" { Language::Zh } else { Language::En };
+        let decimal_separator = if DECIMAL_COMMA_LANGUAGES.contains(&primary.as_str()) {
+            ','
+        } else {
+            '.'
+        };
+        Self {
+            language,
+            decimal_separator,
+        }
+    }
+
+    // 按语言选择静态文案
+    pub fn tr<'a>(&self, zh: &'a str, en: &'a str) -> &'a str {
+        match self.language {
+            Language::Zh => zh,
+            Language::En => en,
+        }
+    }
+
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value);
+        if self.decimal_separator == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+
+    // 重量：不足 1000g 显示克，否则显示千克
+    pub fn format_weight(&self, grams: f64) -> String {
+        if grams >= 1000.0 {
+            let kg = self.format_number(grams / 1000.0, 2);
+            match self.language {
+                Language::Zh => format!("{} 公斤", kg),
+                Language::En => format!("{} kg", kg),
+            }
+        } else {
+            let g = grams as i32;
+            match self.language {
+                Language::Zh => format!("{} 克", g),
+                Language::En => format!("{} g", g),
+            }
+        }
+    }
+
+    pub fn format_days(&self, days: i32) -> String {
+        match self.language {
+            Language::Zh => format!("{:>2} 天", days),
+            Language::En if days.abs() == 1 => format!("{} day", days),
+            Language::En => format!("{} days", days),
+        }
+    }
+
+    // 烘焙日期不精确时显示区间（如 "约 12–18 天"）
+    pub fn format_day_span(&self, min_days: i32, max_days: i32) -> String {
+        if min_days == max_days {
+            return self.format_days(min_days);
+        }
+        match self.language {
+            Language::Zh => format!("约 {}–{} 天", min_days, max_days),
+            Language::En => format!("~{}–{} days", min_days, max_days),
+        }
+    }
+
+    // 在途咖啡豆的预计到货时间（如 "预计 3 天后到货"），已过预计日期时显示超出天数
+    pub fn format_arrival(&self, days: i32) -> String {
+        match self.language {
+            Language::Zh if days > 0 => format!("预计 {} 天后到货", days),
+            Language::Zh if days == 0 => "预计今天到货".to_string(),
+            Language::Zh => format!("预计到货已过 {} 天", -days),
+            Language::En if days == 1 => "Arrives tomorrow".to_string(),
+            Language::En if days > 0 => format!("Arrives in {} days", days),
+            Language::En if days == 0 => "Arrives today".to_string(),
+            Language::En if days == -1 => "1 day overdue".to_string(),
+            Language::En => format!("{} days overdue", -days),
+        }
+    }
+
+    // 超过赏味期的天数（如 "+3 天" / "约 +3–9 天"）
+    pub fn format_overdue_span(&self, min_days: i32, max_days: i32) -> String {
+        match self.language {
+            Language::Zh if min_days == max_days => format!("+{} 天", min_days),
+            Language::Zh => format!("约 +{}–{} 天", min_days, max_days),
+            Language::En if min_days == max_days && min_days == 1 => "+1 day".to_string(),
+            Language::En if min_days == max_days => format!("+{} days", min_days),
+            Language::En => format!("~+{}–{} days", min_days, max_days),
+        }
+    }
+
+    // 托盘分区标题（如 "赏味期（3 款）" / "Optimal (3 beans)"）
+    pub fn format_section_title(&self, title: &str, count: usize) -> String {
+        match self.language {
+            Language::Zh => format!("{}（{}）", title, self.format_bean_count(count)),
+            Language::En => format!("{} ({})", title, self.format_bean_count(count)),
+        }
+    }
+
+    pub fn format_bean_count(&self, count: usize) -> String {
+        match self.language {
+            Language::Zh => format!("{} 款", count),
+            Language::En if count == 1 => "1 bean".to_string(),
+            Language::En => format!("{} beans", count),
+        }
+    }
+
+    pub fn format_cup_count(&self, count: usize) -> String {
+        match self.language {
+            Language::Zh => format!("{} 杯", count),
+            Language::En if count == 1 => "1 cup".to_string(),
+            Language::En => format!("{} cups", count),
+        }
+    }
+
+    // 分区截断后的最后一项（如 "查看全部（86 款）…"）
+    pub fn format_show_all(&self, count: usize) -> String {
+        match self.language {
+            Language::Zh => format!("查看全部（{}）…", self.format_bean_count(count)),
+            Language::En => format!("View all ({})…", self.format_bean_count(count)),
+        }
+    }
+
+    // 隐私模式下代替咖啡豆名称（如 "咖啡豆 3"）
+    pub fn format_private_bean_name(&self, index: usize) -> String {
+        match self.language {
+            Language::Zh => format!("咖啡豆 {}", index),
+            Language::En => format!("Bean {}", index),
+        }
+    }
+
+    // 赏味期变化通知
+    pub fn format_entered_optimal(&self, bean_name: &str) -> String {
+        match self.language {
+            Language::Zh => format!("{}今天进入最佳赏味期！", bean_name),
+            Language::En => format!("{} reaches its peak flavor today!", bean_name),
+        }
+    }
+
+    pub fn format_resting_complete(&self, bean_name: &str) -> String {
+        match self.language {
+            Language::Zh => format!("{}养豆完成，今天起进入赏味期", bean_name),
+            Language::En => format!("{} has finished resting and is ready to brew", bean_name),
+        }
+    }
+
+    pub fn format_arrival_check(&self, bean_name: &str) -> String {
+        match self.language {
+            Language::Zh => format!("{}预计今天到货，收到了吗？", bean_name),
+            Language::En => format!("{} should arrive today. Has it arrived?", bean_name),
+        }
+    }
+
+    pub fn format_entered_decline(&self, bean_name: &str) -> String {
+        match self.language {
+            Language::Zh => format!("{}今天起过了最佳赏味期，尽快喝完吧", bean_name),
+            Language::En => format!("{} is past its peak from today. Enjoy it soon.", bean_name),
+        }
+    }
+
+    // 库存提醒通知
+    pub fn format_low_stock(&self, bean_name: &str, grams: f64) -> String {
+        match self.language {
+            Language::Zh => format!("{}只剩 {} 了", bean_name, self.format_weight(grams)),
+            Language::En => format!("Only {} of {} left", self.format_weight(grams), bean_name),
+        }
+    }
+
+    // 周报通知
+    pub fn format_weekly_report(&self, cups: usize, grams: f64) -> String {
+        match self.language {
+            Language::Zh => format!(
+                "上周冲煮 {}，用豆 {}，点击查看周报",
+                self.format_cup_count(cups),
+                self.format_weight(grams)
+            ),
+            Language::En => format!(
+                "Last week: {} brewed, {} used. Click to view the report",
+                self.format_cup_count(cups),
+                self.format_weight(grams)
+            ),
+        }
+    }
+
+    // 托盘「隐藏 7 天」
+    pub fn format_snooze(&self, days: u64) -> String {
+        match self.language {
+            Language::Zh => format!("隐藏 {} 天", days),
+            Language::En => format!("Hide for {} days", days),
+        }
+    }
+
+    // 托盘「标记为已喝完」的确认提示
+    pub fn format_finish_confirm(&self, bean_name: &str) -> String {
+        match self.language {
+            Language::Zh => format!("将「{}」标记为已喝完？剩余量会清零。", bean_name),
+            Language::En => format!("Mark \"{}\" as finished? The remaining amount will be set to zero.", bean_name),
+        }
+    }
+
+    // 相对时间（如 "3 分钟前" / "2h ago"）
+    pub fn format_time_ago(&self, minutes: i64) -> String {
+        let (value, zh_unit, en_unit) = match minutes {
+            0 => return self.tr("刚刚", "just now").to_string(),
+            1..=59 => (minutes, "分钟", "m"),
+            60..=1439 => (minutes / 60, "小时", "h"),
+            _ => (minutes / 1440, "天", "d"),
+        };
+        match self.language {
+            Language::Zh => format!("{} {}前", value, zh_unit),
+            Language::En => format!("{}{} ago", value, en_unit),
+        }
+    }
+
+    // 托盘图标提示文字（如 "12 款 · 2.30 公斤 · 3 款即将过赏味期"）
+    // grams 为 None 时不显示重量（隐私模式）
+    pub fn format_inventory_summary(&self, count: usize, grams: Option<f64>, expiring_soon: usize) -> String {
+        let mut parts = vec![self.format_bean_count(count)];
+        parts.extend(grams.map(|grams| self.format_weight(grams)));
+        if expiring_soon > 0 {
+            parts.push(match self.language {
+                Language::Zh => format!("{} 款即将过赏味期", expiring_soon),
+                Language::En => format!("{} leaving optimal soon", self.format_bean_count(expiring_soon)),
+            });
+        }
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roast_date::parse_roast_date;
+
+    #[test]
+    fn formats_day_spans_for_imprecise_roast_dates() {
+        // 烘焙周 2024-W45（11 月 4–10 日）到 11 月 22 日：烘焙后 12–18 天
+        let range = parse_roast_date("2024年第45周").unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 11, 22).unwrap();
+        let min_days = (today - range.latest).num_days() as i32;
+        let max_days = (today - range.earliest).num_days() as i32;
+        let zh = Locale::default();
+        let en = Locale::parse("en-US");
+        assert_eq!(zh.format_day_span(min_days, max_days), "约 12–18 天");
+        assert_eq!(en.format_day_span(min_days, max_days), "~12–18 days");
+
+        let cases = [
+            (zh.format_day_span(5, 5), " 5 天"),
+            (en.format_day_span(1, 1), "1 day"),
+            (zh.format_overdue_span(3, 3), "+3 天"),
+            (zh.format_overdue_span(3, 9), "约 +3–9 天"),
+            (en.format_overdue_span(1, 1), "+1 day"),
+            (en.format_overdue_span(3, 9), "~+3–9 days"),
+        ];
+        for (label, expected) in cases {
+            assert_eq!(label, expected);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+use tauri::Manager;
+
+use crate::journal;
+use crate::roast_date::parse_roast_date;
+use crate::store::{atomically, number_field, text_field, with_store, write_record, RecordKind};
+
+// 数据检查：关联的咖啡豆已不存在的笔记、剩余量为负数、无法解析的烘焙日期、找不到的图片文件
+// repair_data 先备份数据库，再执行可以安全自动修复的部分（每项修复都记入修改记录，可以撤销）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    OrphanedNote,
+    NegativeRemaining,
+    InvalidRoastDate,
+    MissingImage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub kind: RecordKind,
+    pub id: String,
+    pub name: String,
+    pub issue: IssueKind,
+    pub detail: String, // 有问题的值
+    pub fixable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub bean_count: usize,
+    pub note_count: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairResult {
+    pub backup_path: Option<String>, // 没有可自动修复的问题时不备份
+    pub fixed: Vec<IntegrityIssue>,
+    pub remaining: Vec<IntegrityIssue>, // 需要手动处理的问题
+}
+
+const BEAN_IMAGE_FIELDS: &[&str] = &["image", "backImage"];
+const NOTE_IMAGE_FIELDS: &[&str] = &["image"]; // 笔记的多张图片在 images 中
+
+fn rows(conn: &Connection, sql: &str) -> rusqlite::Result<Vec<(String, Value)>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut records = Vec::new();
+    for row in rows {
+        let (id, data) = row?;
+        if let Ok(value) = serde_json::from_str(&data) {
+            records.push((id, value));
+        }
+    }
+    Ok(records)
+}
+
+// 图片引用中的本地文件路径；data: 和网络地址不检查
+fn image_path(reference: &str, data_dir: &Path) -> Option<std::path::PathBuf> {
+    let reference = reference.trim();
+    if reference.is_empty() || reference.starts_with("data:") || reference.contains("://") {
+        return None;
+    }
+    Some(data_dir.join(reference))
+}
+
+fn image_references(value: &Value, fields: &[&str]) -> Vec<String> {
+    let mut references: Vec<String> = fields
+        .iter()
+        .filter_map(|field| value.get(*field)?.as_str().map(str::to_string))
+        .collect();
+    if let Some(images) = value.get("images").and_then(Value::as_array) {
+        references.extend(images.iter().filter_map(Value::as_str).map(str::to_string));
+    }
+    references
+}
+
+fn issue(kind: RecordKind, id: &str, name: &str, issue: IssueKind, detail: String, fixable: bool) -> IntegrityIssue {
+    IntegrityIssue {
+        kind,
+        id: id.to_string(),
+        name: name.to_string(),
+        issue,
+        detail,
+        fixable,
+    }
+}
+
+fn check(conn: &Connection, data_dir: &Path) -> rusqlite::Result<IntegrityReport> {
+    let beans = rows(conn, "SELECT id, data FROM beans")?;
+    let notes = rows(conn, "SELECT id, data FROM notes")?;
+    let bean_ids: HashSet<&str> = beans.iter().map(|(id, _)| id.as_str()).collect();
+    let trashed: HashSet<String> = rows(conn, "SELECT id, data FROM trash WHERE kind = 'bean'")?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let missing = |reference: &String| image_path(reference, data_dir).is_some_and(|path| !path.exists());
+    let mut issues = Vec::new();
+
+    for (id, bean) in &beans {
+        let name = text_field(bean, "name").unwrap_or_default();
+        if let Some(remaining) = number_field(bean, "remaining").filter(|r| *r < 0.0) {
+            issues.push(issue(RecordKind::Bean, id, name, IssueKind::NegativeRemaining, remaining.to_string(), true));
+        }
+        if let Some(roast_date) = text_field(bean, "roastDate").filter(|d| parse_roast_date(d).is_none()) {
+            issues.push(issue(RecordKind::Bean, id, name, IssueKind::InvalidRoastDate, roast_date.to_string(), false));
+        }
+        for reference in image_references(bean, BEAN_IMAGE_FIELDS).iter().filter(|r| missing(r)) {
+            issues.push(issue(RecordKind::Bean, id, name, IssueKind::MissingImage, reference.clone(), true));
+        }
+    }
+    for (id, note) in &notes {
+        let name = note
+            .get("coffeeBeanInfo")
+            .and_then(|info| text_field(info, "name"))
+            .unwrap_or_default();
+        if let Some(bean_id) = text_field(note, "beanId").filter(|bean_id| !bean_ids.contains(bean_id)) {
+            // 咖啡豆还在回收站时可能会被恢复，不自动解除关联
+            let fixable = !trashed.contains(bean_id);
+            issues.push(issue(RecordKind::Note, id, name, IssueKind::OrphanedNote, bean_id.to_string(), fixable));
+        }
+        for reference in image_references(note, NOTE_IMAGE_FIELDS).iter().filter(|r| missing(r)) {
+            issues.push(issue(RecordKind::Note, id, name, IssueKind::MissingImage, reference.clone(), true));
+        }
+    }
+    Ok(IntegrityReport {
+        bean_count: beans.len(),
+        note_count: notes.len(),
+        issues,
+    })
+}
+
+// 修复一项问题后的数据：剩余量归零、解除笔记与咖啡豆的关联、去掉找不到的图片
+fn fixed_value(mut value: Value, issue: &IntegrityIssue) -> Value {
+    let Some(object) = value.as_object_mut() else {
+        return value;
+    };
+    match issue.issue {
+        IssueKind::NegativeRemaining => {
+            object.insert("remaining".to_string(), Value::String("0".to_string()));
+        }
+        IssueKind::OrphanedNote => {
+            object.remove("beanId");
+        }
+        IssueKind::MissingImage => {
+            for field in BEAN_IMAGE_FIELDS {
+                if object.get(*field).and_then(Value::as_str) == Some(issue.detail.as_str()) {
+                    object.remove(*field);
+                }
+            }
+            if let Some(images) = object.get_mut("images").and_then(Value::as_array_mut) {
+                images.retain(|v| v.as_str() != Some(issue.detail.as_str()));
+            }
+        }
+        IssueKind::InvalidRoastDate => {}
+    }
+    value
+}
+
+fn repair(conn: &Connection, data_dir: &Path) -> Result<RepairResult, String> {
+    let report = check(conn, data_dir).map_err(|e| e.to_string())?;
+    let (fixable, remaining): (Vec<_>, Vec<_>) = report.issues.into_iter().partition(|issue| issue.fixable);
+    if fixable.is_empty() {
+        return Ok(RepairResult {
+            backup_path: None,
+            fixed: Vec::new(),
+            remaining,
+        });
+    }
+    let backup_path = crate::store::backup(conn, data_dir, "repair").map_err(|e| format!("修复前备份数据库失败：{}", e))?;
+
+    let fix = |issue: &IntegrityIssue| -> rusqlite::Result<()> {
+        atomically(conn, || {
+            journal::track(conn, issue.kind, &issue.id, || {
+                let Some(data) = crate::store::record_data(conn, issue.kind, &issue.id)? else {
+                    return Ok(());
+                };
+                let Ok(value) = serde_json::from_str::<Value>(&data) else {
+                    return Ok(());
+                };
+                write_record(conn, issue.kind, &fixed_value(value, issue))
+            })
+        })
+    };
+    let mut fixed = Vec::new();
+    for issue in fixable {
+        match fix(&issue) {
+            Ok(()) => fixed.push(issue),
+            Err(e) => log::warn!("修复 {} 失败：{}", issue.id, e),
+        }
+    }
+    Ok(RepairResult {
+        backup_path: Some(backup_path.to_string_lossy().into_owned()),
+        fixed,
+        remaining,
+    })
+}
+
+fn data_dir(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    app.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn check_data_integrity(app: tauri::AppHandle) -> Result<IntegrityReport, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let dir = data_dir(&app)?;
+    with_store(&app, |store| check(&store.conn, &dir))
+}
+
+#[tauri::command]
+pub fn repair_data(app: tauri::AppHandle) -> Result<RepairResult, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let dir = data_dir(&app)?;
+    let result = with_store(&app, |store| Ok(repair(&store.conn, &dir)))??;
+    crate::telemetry::record(&app, "data.repair");
+    if !result.fixed.is_empty() {
+        crate::store::publish_changes(&app)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn finds_and_fixes_broken_records() {
+        let conn = crate::store::test_connection();
+        let dir = std::env::temp_dir();
+        crate::store::write_bean(
+            &conn,
+            &json!({ "id": "b1", "name": "耶加雪菲", "remaining": "-5", "roastDate": "上周", "image": "attachments/missing.jpg" }),
+        )
+        .unwrap();
+        let report = check(&conn, &dir).unwrap();
+        let issues: Vec<IssueKind> = report.issues.iter().map(|i| i.issue).collect();
+        assert_eq!(
+            issues,
+            vec![IssueKind::NegativeRemaining, IssueKind::InvalidRoastDate, IssueKind::MissingImage]
+        );
+
+        let bean = report
+            .issues
+            .iter()
+            .fold(json!({ "remaining": "-5", "image": "attachments/missing.jpg" }), |value, issue| {
+                fixed_value(value, issue)
+            });
+        assert_eq!(bean, json!({ "remaining": "0" }));
+    }
+}
@@ -0,0 +1,222 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::store::{atomically, record_data, with_store, write_record, RecordKind};
+
+// 修改记录：本地数据库中咖啡豆、冲煮笔记的每次修改（新建、修改、删除、从回收站恢复）都追加到 journal 表，
+// 保存修改前后的完整数据，用于撤销、重做和查看单条记录的修改历史
+// 撤销时把记录恢复为修改前的数据（修改前不存在则移到回收站），重做时恢复为修改后的数据
+const STATE_APPLIED: &str = "applied";
+const STATE_UNDONE: &str = "undone";
+const STATE_DISCARDED: &str = "discarded";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEntry {
+    pub seq: i64,
+    pub kind: RecordKind,
+    pub entity_id: String,
+    pub action: &'static str, // create / update / delete
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub changed_at: i64, // 毫秒
+    pub state: String,   // applied / undone / discarded
+}
+
+const ENTRY_COLUMNS: &str = "seq, kind, entity_id, before, after, changed_at, state";
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<Option<ChangeEntry>> {
+    let parse = |text: Option<String>| text.and_then(|t| serde_json::from_str::<Value>(&t).ok());
+    let Some(kind) = RecordKind::from_key(&row.get::<_, String>(1)?) else {
+        return Ok(None);
+    };
+    let before = parse(row.get(3)?);
+    let after = parse(row.get(4)?);
+    let action = match (&before, &after) {
+        (None, _) => "create",
+        (_, None) => "delete",
+        _ => "update",
+    };
+    Ok(Some(ChangeEntry {
+        seq: row.get(0)?,
+        kind,
+        entity_id: row.get(2)?,
+        action,
+        before,
+        after,
+        changed_at: row.get(5)?,
+        state: row.get(6)?,
+    }))
+}
+
+// 执行一次修改并记录前后的数据；数据没有变化时不记录
+// 新的修改会让之前撤销的修改不能再重做
+pub fn track<T>(conn: &Connection, kind: RecordKind, id: &str, f: impl FnOnce() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    let before = record_data(conn, kind, id)?;
+    let result = f()?;
+    let after = record_data(conn, kind, id)?;
+    if before != after {
+        conn.execute(
+            "UPDATE journal SET state = ?1 WHERE state = ?2",
+            [STATE_DISCARDED, STATE_UNDONE],
+        )?;
+        conn.execute(
+            "INSERT INTO journal (kind, entity_id, before, after, changed_at, state) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![kind.key(), id, before, after, chrono::Utc::now().timestamp_millis(), STATE_APPLIED],
+        )?;
+    }
+    Ok(result)
+}
+
+// 把记录恢复为指定的数据，None 表示移到回收站
+pub fn apply(conn: &Connection, kind: RecordKind, id: &str, data: Option<&Value>) -> rusqlite::Result<()> {
+    let Some(value) = data else {
+        crate::trash::move_to_trash(conn, kind, id)?;
+        return Ok(());
+    };
+    atomically(conn, || {
+        write_record(conn, kind, value)?;
+        conn.execute("DELETE FROM trash WHERE kind = ?1 AND id = ?2", [kind.key(), id])?;
+        Ok(())
+    })
+}
+
+// 修改记录中与哈希相同的历史版本，同步时用作字段级合并的基准
+pub fn version_with_hash(conn: &Connection, kind: RecordKind, id: &str, hash: &str) -> rusqlite::Result<Option<Value>> {
+    let mut stmt = conn.prepare("SELECT after, before FROM journal WHERE kind = ?1 AND entity_id = ?2 ORDER BY seq DESC")?;
+    let mut rows = stmt.query(params![kind.key(), id])?;
+    while let Some(row) = rows.next()? {
+        for column in 0..2 {
+            let Some(text) = row.get::<_, Option<String>>(column)? else {
+                continue;
+            };
+            if let Ok(value) = serde_json::from_str::<Value>(&text) {
+                if crate::sync::hash(&value) == hash {
+                    return Ok(Some(value));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn find(conn: &Connection, condition: &str) -> rusqlite::Result<Option<ChangeEntry>> {
+    conn.query_row(&format!("SELECT {} FROM journal {}", ENTRY_COLUMNS, condition), [], entry_from_row)
+        .optional()
+        .map(Option::flatten)
+}
+
+fn set_state(conn: &Connection, seq: i64, state: &str) -> rusqlite::Result<()> {
+    conn.execute("UPDATE journal SET state = ?1 WHERE seq = ?2", params![state, seq])?;
+    Ok(())
+}
+
+fn undo(conn: &Connection) -> rusqlite::Result<Option<ChangeEntry>> {
+    let Some(mut entry) = find(conn, "WHERE state = 'applied' ORDER BY seq DESC LIMIT 1")? else {
+        return Ok(None);
+    };
+    atomically(conn, || {
+        apply(conn, entry.kind, &entry.entity_id, entry.before.as_ref())?;
+        set_state(conn, entry.seq, STATE_UNDONE)
+    })?;
+    entry.state = STATE_UNDONE.to_string();
+    Ok(Some(entry))
+}
+
+// 重做最早一条已撤销的修改，即按撤销的相反顺序重做
+fn redo_next(conn: &Connection) -> rusqlite::Result<Option<ChangeEntry>> {
+    let Some(mut entry) = find(conn, "WHERE state = 'undone' ORDER BY seq ASC LIMIT 1")? else {
+        return Ok(None);
+    };
+    atomically(conn, || {
+        apply(conn, entry.kind, &entry.entity_id, entry.after.as_ref())?;
+        set_state(conn, entry.seq, STATE_APPLIED)
+    })?;
+    entry.state = STATE_APPLIED.to_string();
+    Ok(Some(entry))
+}
+
+fn history(conn: &Connection, entity_id: &str) -> rusqlite::Result<Vec<ChangeEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM journal WHERE entity_id = ?1 ORDER BY seq DESC",
+        ENTRY_COLUMNS
+    ))?;
+    let rows = stmt.query_map([entity_id], entry_from_row)?;
+    let mut entries = Vec::new();
+    for entry in rows {
+        entries.extend(entry?);
+    }
+    Ok(entries)
+}
+
+// 撤销或重做后，把有变化的记录交给前端
+fn after_change(app: &tauri::AppHandle, entry: &Option<ChangeEntry>) -> Result<(), String> {
+    if entry.is_some() {
+        crate::store::publish_changes(app)?;
+    }
+    Ok(())
+}
+
+// 撤销最近一次修改，没有可撤销的修改时返回 None
+#[tauri::command]
+pub fn undo_last_change(app: tauri::AppHandle) -> Result<Option<ChangeEntry>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let entry = with_store(&app, |store| undo(&store.conn))?;
+    after_change(&app, &entry)?;
+    Ok(entry)
+}
+
+// 重做最近撤销的修改，没有可重做的修改时返回 None
+#[tauri::command]
+pub fn redo(app: tauri::AppHandle) -> Result<Option<ChangeEntry>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let entry = with_store(&app, |store| redo_next(&store.conn))?;
+    after_change(&app, &entry)?;
+    Ok(entry)
+}
+
+// 一条咖啡豆或笔记的修改历史，最近的在前
+#[tauri::command]
+pub fn get_change_history(app: tauri::AppHandle, entity_id: String) -> Result<Vec<ChangeEntry>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, |store| history(&store.conn, &entity_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn name(conn: &Connection) -> Option<String> {
+        record_data(conn, RecordKind::Bean, "b1")
+            .unwrap()
+            .map(|data| serde_json::from_str::<Value>(&data).unwrap()["name"].as_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn undoes_and_redoes_changes_in_order() {
+        let conn = crate::store::test_connection();
+        let write = |name: &str| {
+            let bean = json!({ "id": "b1", "name": name });
+            track(&conn, RecordKind::Bean, "b1", || crate::store::write_bean(&conn, &bean)).unwrap();
+        };
+        write("耶加雪菲");
+        write("耶加雪菲 G1");
+        track(&conn, RecordKind::Bean, "b1", || crate::trash::move_to_trash(&conn, RecordKind::Bean, "b1")).unwrap();
+        assert_eq!(name(&conn), None);
+
+        assert_eq!(undo(&conn).unwrap().unwrap().action, "delete");
+        assert_eq!(name(&conn).as_deref(), Some("耶加雪菲 G1"));
+        undo(&conn).unwrap();
+        assert_eq!(name(&conn).as_deref(), Some("耶加雪菲"));
+        assert_eq!(redo_next(&conn).unwrap().unwrap().action, "update");
+        assert_eq!(name(&conn).as_deref(), Some("耶加雪菲 G1"));
+
+        // 撤销后的新修改让剩下的撤销记录不能重做
+        undo(&conn).unwrap();
+        write("肯尼亚");
+        assert!(redo_next(&conn).unwrap().is_none());
+        assert_eq!(history(&conn, "b1").unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,165 @@
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+// 冲煮计时中保持唤醒：开启后计时开始时阻止显示器和系统休眠，计时结束（完成或停止）时自动释放
+// macOS 使用 IOKit 电源断言；Windows 使用 SetThreadExecutionState（只对调用线程有效，所以放在独立线程中保持）；
+// Linux 使用 systemd-inhibit，结束进程即释放
+#[derive(Default)]
+pub struct KeepAwakeState {
+    lock: Option<Wakelock>,
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CString};
+
+    type CFStringRef = *const c_void;
+    const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(alloc: *const c_void, text: *const c_char, encoding: u32) -> CFStringRef;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(kind: CFStringRef, level: u32, name: CFStringRef, id: *mut u32) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    fn cf_string(text: &str) -> Result<CFStringRef, String> {
+        let text = CString::new(text).map_err(|e| e.to_string())?;
+        let string = unsafe { CFStringCreateWithCString(std::ptr::null(), text.as_ptr(), CF_STRING_ENCODING_UTF8) };
+        if string.is_null() {
+            return Err("创建 CFString 失败".to_string());
+        }
+        Ok(string)
+    }
+
+    pub struct Wakelock(u32);
+
+    impl Wakelock {
+        pub fn acquire(reason: &str) -> Result<Self, String> {
+            // 阻止显示器因空闲变暗休眠，同时也会阻止系统空闲休眠
+            let kind = cf_string("PreventUserIdleDisplaySleep")?;
+            let name = cf_string(reason)?;
+            let mut id = 0;
+            let result = unsafe { IOPMAssertionCreateWithName(kind, IOPM_ASSERTION_LEVEL_ON, name, &mut id) };
+            unsafe {
+                CFRelease(kind);
+                CFRelease(name);
+            }
+            if result != 0 {
+                return Err(format!("创建电源断言失败：{:#x}", result));
+            }
+            Ok(Self(id))
+        }
+    }
+
+    impl Drop for Wakelock {
+        fn drop(&mut self) {
+            unsafe {
+                IOPMAssertionRelease(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::sync::mpsc;
+    use windows::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED};
+
+    // 发送端被丢弃时线程恢复执行状态并退出
+    pub struct Wakelock {
+        _release: mpsc::Sender<()>,
+    }
+
+    impl Wakelock {
+        pub fn acquire(_reason: &str) -> Result<Self, String> {
+            let (sender, receiver) = mpsc::channel::<()>();
+            let (ready, acquired) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("keep-awake".into())
+                .spawn(move || {
+                    let previous = unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED) };
+                    let _ = ready.send(previous.0 != 0);
+                    while receiver.recv().is_ok() {}
+                    unsafe {
+                        SetThreadExecutionState(ES_CONTINUOUS);
+                    }
+                })
+                .map_err(|e| e.to_string())?;
+            match acquired.recv() {
+                Ok(true) => Ok(Self { _release: sender }),
+                _ => Err("SetThreadExecutionState 失败".to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::process::{Child, Command, Stdio};
+
+    pub struct Wakelock(Child);
+
+    impl Wakelock {
+        pub fn acquire(reason: &str) -> Result<Self, String> {
+            Command::new("systemd-inhibit")
+                .args(["--what=idle:sleep", "--who=Brew Guide", "--mode=block"])
+                .arg(format!("--why={}", reason))
+                .args(["sleep", "infinity"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map(Self)
+                .map_err(|e| format!("无法运行 systemd-inhibit：{}", e))
+        }
+    }
+
+    impl Drop for Wakelock {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+}
+
+// 移动端由前端处理（屏幕常亮），这里什么都不做
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub struct Wakelock;
+
+    impl Wakelock {
+        pub fn acquire(_reason: &str) -> Result<Self, String> {
+            Ok(Self)
+        }
+    }
+}
+
+use platform::Wakelock;
+
+// 计时状态或设置变化后调用：开启且正在计时时持有唤醒锁，否则释放
+pub fn sync(app: &tauri::AppHandle) -> Result<(), String> {
+    let wanted = crate::settings::get(app).keep_awake && crate::timer::get(app).running;
+    let state = app
+        .try_state::<Arc<Mutex<KeepAwakeState>>>()
+        .ok_or("保持唤醒未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    match (wanted, state.lock.is_some()) {
+        (true, false) => state.lock = Some(Wakelock::acquire("Brew Guide 冲煮计时中")?),
+        (false, true) => state.lock = None,
+        _ => {}
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_keep_awake(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.keep_awake = enabled)?;
+    sync(&app)
+}
@@ -0,0 +1,216 @@
+use serde_json::Value;
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::settings::{LabelPrinterSettings, PrinterConnection};
+use crate::store::{number_field, record_data, text_field, with_store, RecordKind};
+
+// 用 ESC/POS 热敏打印机打印贴在分装罐上的小标签：咖啡豆名称、烘焙商、烘焙日期和赏味期
+// 网络打印机直接连接 9100 端口，USB 打印机写入设备文件；中文打印机默认使用 GBK 编码
+const DEFAULT_PORT: u16 = 9100;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_COPIES: u32 = 20;
+const MAX_NAME_LINES: usize = 2;
+
+const INIT: &[u8] = &[0x1B, 0x40];
+const CHINESE_MODE: &[u8] = &[0x1C, 0x26];
+const ALIGN_LEFT: &[u8] = &[0x1B, 0x61, 0];
+const ALIGN_CENTER: &[u8] = &[0x1B, 0x61, 1];
+const DOUBLE_SIZE: &[u8] = &[0x1D, 0x21, 0x11];
+const NORMAL_SIZE: &[u8] = &[0x1D, 0x21, 0];
+const FEED_AND_CUT: &[u8] = &[0x1D, 0x56, 66, 3]; // 走纸 3 行后半切
+
+struct Label {
+    bytes: Vec<u8>,
+    gbk: bool,
+}
+
+impl Label {
+    fn command(&mut self, command: &[u8]) {
+        self.bytes.extend_from_slice(command);
+    }
+
+    // 编码不了的字符打印为 ?
+    fn line(&mut self, text: &str) {
+        if self.gbk {
+            let mut buffer = [0u8; 4];
+            for c in text.chars() {
+                let (encoded, _, had_errors) = encoding_rs::GBK.encode(c.encode_utf8(&mut buffer));
+                if had_errors {
+                    self.bytes.push(b'?');
+                } else {
+                    self.bytes.extend_from_slice(&encoded);
+                }
+            }
+        } else {
+            self.bytes.extend_from_slice(text.as_bytes());
+        }
+        self.bytes.push(b'\n');
+    }
+}
+
+// 打印宽度（半角字符数），中文占两个
+fn columns(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn wrap_columns(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for c in text.trim().chars() {
+        if columns(&line) + columns(&c.to_string()) > width {
+            lines.push(std::mem::take(&mut line));
+            if lines.len() == max_lines {
+                // 放不下的部分省略
+                if let Some(last) = lines.last_mut() {
+                    last.pop();
+                    last.push('…');
+                }
+                return lines;
+            }
+        }
+        line.push(c);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn bean_label(bean: &Value, settings: &LabelPrinterSettings) -> Vec<u8> {
+    // Font A 每行的字符数：58mm 纸 32 个，80mm 纸 48 个
+    let width = if settings.paper_width >= 80 { 48 } else { 32 };
+    let mut label = Label {
+        bytes: Vec::new(),
+        gbk: settings.gbk,
+    };
+    label.command(INIT);
+    if settings.gbk {
+        label.command(CHINESE_MODE);
+    }
+    label.command(ALIGN_CENTER);
+    label.command(DOUBLE_SIZE);
+    for line in wrap_columns(text_field(bean, "name").unwrap_or_default(), width / 2, MAX_NAME_LINES) {
+        label.line(&line);
+    }
+    label.command(NORMAL_SIZE);
+    if let Some(roaster) = text_field(bean, "roaster") {
+        label.line(&wrap_columns(roaster, width, 1).concat());
+    }
+    label.command(ALIGN_LEFT);
+    label.line(&"-".repeat(width));
+    let roast_date = text_field(bean, "roastDate");
+    if let Some(date) = roast_date {
+        label.line(&format!("烘焙日期 {}", date));
+    }
+    let window = roast_date.and_then(|date| {
+        crate::roast_date::flavor_window(
+            date,
+            number_field(bean, "startDay").map(|d| d as i32),
+            number_field(bean, "endDay").map(|d| d as i32),
+        )
+    });
+    if let Some((start, end)) = window {
+        label.line(&format!("赏味期 {} ~ {}", start.format("%m-%d"), end.format("%m-%d")));
+    }
+    if settings.cut {
+        label.command(FEED_AND_CUT);
+    } else {
+        label.line("\n\n");
+    }
+    label.bytes
+}
+
+fn send(connection: &PrinterConnection, data: &[u8]) -> Result<(), String> {
+    match connection {
+        PrinterConnection::Network { host, port } => {
+            let address = (host.as_str(), port.unwrap_or(DEFAULT_PORT))
+                .to_socket_addrs()
+                .map_err(|e| e.to_string())?
+                .next()
+                .ok_or_else(|| format!("无法解析打印机地址：{}", host))?;
+            let mut stream =
+                TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| format!("无法连接打印机：{}", e))?;
+            stream.set_write_timeout(Some(WRITE_TIMEOUT)).map_err(|e| e.to_string())?;
+            stream.write_all(data).map_err(|e| e.to_string())?;
+            stream.flush().map_err(|e| e.to_string())
+        }
+        PrinterConnection::Device { path } => {
+            let mut device = std::fs::OpenOptions::new()
+                .write(true)
+                .open(path)
+                .map_err(|e| format!("无法打开打印机 {}：{}", path, e))?;
+            device.write_all(data).map_err(|e| e.to_string())?;
+            device.flush().map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+pub fn set_label_printer(app: tauri::AppHandle, printer: LabelPrinterSettings) -> Result<(), String> {
+    if let Some(PrinterConnection::Network { host, .. }) = &printer.connection {
+        if host.trim().is_empty() {
+            return Err("请填写打印机地址".to_string());
+        }
+    }
+    crate::settings::update(&app, |settings| settings.label_printer = printer)?;
+    Ok(())
+}
+
+// 打印咖啡豆标签，copies 默认 1 张
+#[tauri::command]
+pub async fn print_bean_label(app: tauri::AppHandle, bean_id: String, copies: Option<u32>) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let settings = crate::settings::get(&app).label_printer;
+    let connection = settings.connection.clone().ok_or("还没有设置标签打印机")?;
+    let bean: Value = with_store(&app, |store| record_data(&store.conn, RecordKind::Bean, &bean_id))?
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .ok_or_else(|| format!("找不到咖啡豆：{}", bean_id))?;
+    let label = bean_label(&bean, &settings);
+    let data = label.repeat(copies.unwrap_or(1).clamp(1, MAX_COPIES) as usize);
+    tauri::async_runtime::spawn_blocking(move || send(&connection, &data))
+        .await
+        .map_err(|e| e.to_string())??;
+    crate::telemetry::record(&app, "label.print");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Read;
+
+    #[test]
+    fn sends_gbk_encoded_labels_to_a_network_printer() {
+        let bean = json!({ "name": "埃塞俄比亚 耶加雪菲 科契尔 水洗 G1", "roaster": "Tim", "roastDate": "2026-10-01" });
+        let settings = LabelPrinterSettings::default();
+        let label = bean_label(&bean, &settings);
+        let gbk = |text: &str| encoding_rs::GBK.encode(text).0.into_owned();
+
+        assert!(label.starts_with(&[INIT, CHINESE_MODE, ALIGN_CENTER, DOUBLE_SIZE].concat()));
+        // 双倍大小时每行 16 个半角字符，名称最多两行
+        let name = [gbk("埃塞俄比亚 耶加"), b"\n".to_vec(), gbk("雪菲 科契尔 水…"), b"\n".to_vec()].concat();
+        assert!(label.windows(name.len()).any(|w| w == name.as_slice()));
+        let window = gbk("赏味期 10-08 ~ 10-31");
+        assert!(label.windows(window.len()).any(|w| w == window.as_slice()));
+        assert!(label.ends_with(FEED_AND_CUT));
+        assert_eq!(wrap_columns("一二三四五六", 4, 2), vec!["一二", "三…"]);
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let printer = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            listener.accept().unwrap().0.read_to_end(&mut received).unwrap();
+            received
+        });
+        let connection = PrinterConnection::Network {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+        };
+        send(&connection, &label).unwrap();
+        assert_eq!(printer.join().unwrap(), label);
+    }
+}
@@ -0,0 +1,616 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::channel::{hex, read_frame, unhex, write_frame, Channel, HANDSHAKE_FRAME_BYTES};
+use crate::json_file;
+use crate::store::with_store;
+use crate::sync::{hashes, plan, Records, SyncFile};
+
+// 局域网同步：通过 mDNS 发现同一网络中的其它 Brew Guide，直接用 TCP 交换咖啡豆和笔记
+// 连接时用 X25519 交换密钥（临时密钥和设备的长期密钥各做一次），之后的消息经 channel 加密；
+// 第一次连接某台设备时，两边显示由会话密钥生成的 6 位配对码，用户确认一致后才记住这台设备
+// 合并规则同同步文件夹；两边修改了同一字段的记录在接受连接的一方记为冲突，由用户在那台设备上选择，
+// 发起连接的一方先采用对方当前的版本
+const SERVICE_TYPE: &str = "_brewguide._tcp.local.";
+const IDENTITY_ACCOUNT: &str = "lan-sync-identity";
+const PEERS_FILE: &str = "lan-sync-peers.json";
+const BASE_FILE: &str = "lan-sync-base.json";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(120);
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// 同时处理的来访连接数；超过时直接断开，避免被大量连接占满线程
+const MAX_INCOMING: usize = 4;
+// 同一地址两次配对提示之间至少间隔的时间，同一时间只显示一个配对提示
+const PAIRING_PROMPT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanPeer {
+    pub device_id: String,
+    pub name: String,
+    pub address: String,
+    pub paired: bool,
+    #[serde(skip)]
+    public_key: String, // mDNS 广播的长期公钥，与配对记录一致才算已配对
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSyncReport {
+    pub device_id: String,
+    pub name: String,
+    pub applied: usize,   // 采用对方的修改
+    pub conflicts: usize, // 两边都修改过的记录，冲突记在接受连接的一方
+}
+
+// lan-sync-pairing 事件，前端显示配对码并调用 confirm_lan_pairing
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PairingRequest<'a> {
+    device_id: &'a str,
+    name: &'a str,
+    code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairedPeer {
+    device_id: String,
+    name: String,
+    public_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Hello {
+    device_id: String,
+    name: String,
+    public_key: String,    // 设备的长期公钥
+    ephemeral_key: String, // 本次连接的临时公钥
+}
+
+#[derive(Default)]
+pub struct LanSyncState {
+    daemon: Option<ServiceDaemon>,
+    generation: u64, // 每次停止后加一，旧的监听线程随之退出
+    peers: BTreeMap<String, LanPeer>, // mDNS 服务全名 -> 设备
+    pending: HashMap<String, mpsc::Sender<bool>>, // 等待用户确认配对码的设备
+    incoming: usize, // 正在处理的来访连接
+    prompted: HashMap<IpAddr, Instant>, // 来访地址上次提示配对的时间
+}
+
+// 来访的未配对设备能否弹出配对提示：已有等待确认的配对或这个地址刚提示过时拒绝
+fn allow_prompt(state: &mut LanSyncState, address: IpAddr, now: Instant) -> bool {
+    if !state.pending.is_empty() {
+        return false;
+    }
+    state.prompted.retain(|_, at| now.saturating_duration_since(*at) < PAIRING_PROMPT_INTERVAL);
+    if state.prompted.contains_key(&address) {
+        return false;
+    }
+    state.prompted.insert(address, now);
+    true
+}
+
+struct Identity {
+    device_id: String,
+    secret: StaticSecret,
+}
+
+struct Session {
+    channel: Channel,
+    key: [u8; 32],
+    peer: Hello,
+}
+
+fn public_key(text: &str) -> Option<PublicKey> {
+    let bytes: [u8; 32] = unhex(text)?.try_into().ok()?;
+    Some(PublicKey::from(bytes))
+}
+
+fn random_secret() -> StaticSecret {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    StaticSecret::from(bytes)
+}
+
+fn device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "Brew Guide".to_string())
+}
+
+// 设备 ID 和长期密钥保存在系统钥匙串中，第一次使用时生成
+fn identity() -> Result<Identity, String> {
+    if let Some(saved) = crate::credentials::load(IDENTITY_ACCOUNT)? {
+        let parsed = saved
+            .split_once(':')
+            .and_then(|(id, secret)| Some((id.to_string(), public_key(secret)?.to_bytes())));
+        if let Some((device_id, secret)) = parsed {
+            return Ok(Identity {
+                device_id,
+                secret: StaticSecret::from(secret),
+            });
+        }
+    }
+    let mut id = [0u8; 8];
+    OsRng.fill_bytes(&mut id);
+    let identity = Identity {
+        device_id: hex(&id),
+        secret: random_secret(),
+    };
+    crate::credentials::save(
+        IDENTITY_ACCOUNT,
+        &format!("{}:{}", identity.device_id, hex(identity.secret.as_bytes())),
+    )?;
+    Ok(identity)
+}
+
+// 交换公钥并计算会话密钥：临时密钥保证每次连接的密钥不同，长期密钥用于确认对方是配对过的设备
+fn handshake(mut stream: TcpStream, identity: &Identity, is_client: bool) -> Result<Session, String> {
+    stream.set_read_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+    let ephemeral = random_secret();
+    let mine = Hello {
+        device_id: identity.device_id.clone(),
+        name: device_name(),
+        public_key: hex(PublicKey::from(&identity.secret).as_bytes()),
+        ephemeral_key: hex(PublicKey::from(&ephemeral).as_bytes()),
+    };
+    write_frame(&mut stream, &serde_json::to_vec(&mine).map_err(|e| e.to_string())?)?;
+    let theirs: Hello = serde_json::from_slice(&read_frame(&mut stream, HANDSHAKE_FRAME_BYTES)?).map_err(|e| e.to_string())?;
+    let their_static = public_key(&theirs.public_key).ok_or("无效的设备公钥")?;
+    let their_ephemeral = public_key(&theirs.ephemeral_key).ok_or("无效的临时公钥")?;
+
+    let (client, server) = if is_client { (&mine, &theirs) } else { (&theirs, &mine) };
+    let mut hasher = Sha256::new();
+    hasher.update(b"brew-guide-lan-sync-1");
+    hasher.update(ephemeral.diffie_hellman(&their_ephemeral).as_bytes());
+    hasher.update(identity.secret.diffie_hellman(&their_static).as_bytes());
+    for hello in [client, server] {
+        hasher.update(hello.public_key.as_bytes());
+        hasher.update(hello.ephemeral_key.as_bytes());
+    }
+    let key: [u8; 32] = hasher.finalize().into();
+    Ok(Session {
+        channel: Channel::new(stream, &key, is_client)?,
+        key,
+        peer: theirs,
+    })
+}
+
+fn pairing_code(key: &[u8; 32]) -> String {
+    let digest = Sha256::new().chain_update(b"brew-guide-pairing").chain_update(key).finalize();
+    let number = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    format!("{:06}", number % 1_000_000)
+}
+
+fn data_file(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(name))
+        .map_err(|e| e.to_string())
+}
+
+fn load_peers(app: &tauri::AppHandle) -> Result<Vec<PairedPeer>, String> {
+    json_file::load(&data_file(app, PEERS_FILE)?).map_err(|e| e.to_string())
+}
+
+fn with_state<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut LanSyncState) -> T) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<LanSyncState>>>()
+        .ok_or("局域网同步未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    Ok(f(&mut state))
+}
+
+// 没有配对过（或对方的公钥变了）时，两边都要确认配对码一致
+// incoming 为来访连接的地址，这类连接的配对提示受 allow_prompt 限制；本机发起的连接不受限制
+fn ensure_paired(app: &tauri::AppHandle, session: &mut Session, incoming: Option<IpAddr>) -> Result<(), String> {
+    let mut peers = load_peers(app)?;
+    let known = peers
+        .iter()
+        .any(|p| p.device_id == session.peer.device_id && p.public_key == session.peer.public_key);
+    session.channel.send(&known)?;
+    let known_by_peer: bool = session.channel.receive()?;
+    if known && known_by_peer {
+        session.channel.authenticated();
+        return Ok(());
+    }
+
+    let device_id = session.peer.device_id.clone();
+    let (sender, receiver) = mpsc::channel();
+    let allowed = with_state(app, |s| {
+        let allowed = incoming.map_or(s.pending.is_empty(), |address| allow_prompt(s, address, Instant::now()));
+        if allowed {
+            s.pending.insert(device_id.clone(), sender);
+        }
+        allowed
+    })?;
+    if !allowed {
+        return Err("已有等待确认的配对，或这台设备刚请求过配对".to_string());
+    }
+    let request = PairingRequest {
+        device_id: &device_id,
+        name: &session.peer.name,
+        code: pairing_code(&session.key),
+    };
+    let _ = app.emit("lan-sync-pairing", &request);
+    let accepted = receiver.recv_timeout(PAIRING_TIMEOUT).unwrap_or(false);
+    with_state(app, |s| s.pending.remove(&device_id))?;
+
+    // 对方可能还在等待用户确认
+    session
+        .channel
+        .stream
+        .set_read_timeout(Some(PAIRING_TIMEOUT + IO_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    session.channel.send(&accepted)?;
+    let accepted_by_peer: bool = session.channel.receive()?;
+    session.channel.stream.set_read_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+    if !(accepted && accepted_by_peer) {
+        return Err("配对未确认".to_string());
+    }
+    peers.retain(|p| p.device_id != device_id);
+    peers.push(PairedPeer {
+        device_id,
+        name: session.peer.name.clone(),
+        public_key: session.peer.public_key.clone(),
+    });
+    json_file::save(&data_file(app, PEERS_FILE)?, &peers).map_err(|e| e.to_string())?;
+    session.channel.authenticated();
+    Ok(())
+}
+
+// 每台设备上次同步后的记录哈希
+fn load_base(app: &tauri::AppHandle, device_id: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut bases: BTreeMap<String, BTreeMap<String, String>> =
+        json_file::load(&data_file(app, BASE_FILE)?).map_err(|e| e.to_string())?;
+    Ok(bases.remove(device_id).unwrap_or_default())
+}
+
+fn save_base(app: &tauri::AppHandle, device_id: &str, base: BTreeMap<String, String>) -> Result<(), String> {
+    let path = data_file(app, BASE_FILE)?;
+    let mut bases: BTreeMap<String, BTreeMap<String, String>> = json_file::load(&path).map_err(|e| e.to_string())?;
+    bases.insert(device_id.to_string(), base);
+    json_file::save(&path, &bases).map_err(|e| e.to_string())
+}
+
+fn export(store: &crate::store::Store) -> rusqlite::Result<(Records, SyncFile)> {
+    let records = crate::sync::local_records(&store.conn)?;
+    let mut file = SyncFile::from_records(&records);
+    file.versions = crate::sync::local_versions(&store.conn)?;
+    Ok((records, file))
+}
+
+// 接受连接的一方：合并对方的记录并记录冲突，把合并结果发回去
+fn serve_sync(app: &tauri::AppHandle, session: &mut Session) -> Result<LanSyncReport, String> {
+    let remote: SyncFile = session.channel.receive()?;
+    let base = load_base(app, &session.peer.device_id)?;
+    let (plan, conflicts, merged, file) = with_store(app, |store| {
+        let local = crate::sync::local_records(&store.conn)?;
+        let plan = plan(&base, &local, &remote.records());
+        crate::sync::apply(&store.conn, &plan.apply)?;
+        let conflicts = crate::conflicts::detect(&store.conn, "lan-sync", &base, &plan.conflicts, &remote.versions)?;
+        let (merged, file) = export(store)?;
+        Ok((plan, conflicts, merged, file))
+    })?;
+    session.channel.send(&file)?;
+    save_base(app, &session.peer.device_id, hashes(&merged))?;
+    finish(app, session, plan.apply.len(), conflicts)
+}
+
+// 发起连接的一方：先发送本地记录，再采用对方合并后的结果
+fn client_sync(app: &tauri::AppHandle, session: &mut Session) -> Result<LanSyncReport, String> {
+    let base = load_base(app, &session.peer.device_id)?;
+    let (local, file) = with_store(app, export)?;
+    session.channel.send(&file)?;
+    let remote: SyncFile = session.channel.receive()?;
+    let (plan, merged) = with_store(app, |store| {
+        let plan = plan(&base, &local, &remote.records());
+        let mut changes = plan.apply.clone();
+        changes.extend(plan.conflicts.iter().map(|(key, value)| (key.clone(), Some(value.clone()))));
+        crate::sync::apply(&store.conn, &changes)?;
+        Ok((plan, crate::sync::local_records(&store.conn)?))
+    })?;
+    save_base(app, &session.peer.device_id, hashes(&merged))?;
+    finish(app, session, plan.apply.len() + plan.conflicts.len(), plan.conflicts.len())
+}
+
+fn finish(app: &tauri::AppHandle, session: &Session, applied: usize, conflicts: usize) -> Result<LanSyncReport, String> {
+    if applied > 0 {
+        crate::store::publish_changes(app)?;
+    }
+    let report = LanSyncReport {
+        device_id: session.peer.device_id.clone(),
+        name: session.peer.name.clone(),
+        applied,
+        conflicts,
+    };
+    let _ = app.emit("lan-sync-completed", &report);
+    Ok(report)
+}
+
+// 应用锁定时不接受同步，等解锁后由用户再发起
+fn handle_incoming(app: &tauri::AppHandle, stream: TcpStream, address: IpAddr) -> Result<LanSyncReport, String> {
+    if crate::app_lock::is_locked(app) {
+        return Err("应用已锁定".to_string());
+    }
+    stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+    let mut session = handshake(stream, &identity()?, false)?;
+    ensure_paired(app, &mut session, Some(address))?;
+    serve_sync(app, &mut session)
+}
+
+fn emit_peers(app: &tauri::AppHandle) {
+    if let Ok(peers) = list_lan_peers(app.clone()) {
+        let _ = app.emit("lan-sync-peers", &peers);
+    }
+}
+
+fn spawn_accept_loop(app: tauri::AppHandle, listener: TcpListener, generation: u64) {
+    thread::spawn(move || loop {
+        if with_state(&app, |s| s.generation) != Ok(generation) {
+            break;
+        }
+        match listener.accept() {
+            Ok((stream, address)) => {
+                let admitted = with_state(&app, |s| {
+                    let admitted = s.incoming < MAX_INCOMING;
+                    if admitted {
+                        s.incoming += 1;
+                    }
+                    admitted
+                });
+                if admitted != Ok(true) {
+                    log::warn!("局域网同步连接过多，已断开 {}", address);
+                    continue;
+                }
+                let app = app.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_incoming(&app, stream, address.ip()) {
+                        log::warn!("局域网同步（来自 {}）失败：{}", address, e);
+                    }
+                    let _ = with_state(&app, |s| s.incoming -= 1);
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+            Err(e) => {
+                log::warn!("局域网同步监听失败：{}", e);
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+        }
+    });
+}
+
+// 记录 mDNS 发现的设备，停止同步后 daemon 关闭，事件通道随之结束
+fn spawn_browse_loop(app: tauri::AppHandle, events: mdns_sd::Receiver<ServiceEvent>, own_id: String) {
+    thread::spawn(move || {
+        while let Ok(event) = events.recv() {
+            match event {
+                ServiceEvent::ServiceResolved(service) => {
+                    let Some(device_id) = service.get_property_val_str("id").map(str::to_string) else {
+                        continue;
+                    };
+                    let address = service
+                        .get_addresses_v4()
+                        .into_iter()
+                        .next()
+                        .map(std::net::IpAddr::V4)
+                        .or_else(|| service.get_addresses().iter().next().map(|ip| ip.to_ip_addr()));
+                    let Some(address) = address.filter(|_| device_id != own_id) else {
+                        continue;
+                    };
+                    let peer = LanPeer {
+                        name: service.get_property_val_str("name").unwrap_or_default().to_string(),
+                        device_id,
+                        address: SocketAddr::new(address, service.get_port()).to_string(),
+                        paired: false,
+                        public_key: service.get_property_val_str("key").unwrap_or_default().to_string(),
+                    };
+                    let _ = with_state(&app, |s| s.peers.insert(service.get_fullname().to_string(), peer));
+                    emit_peers(&app);
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    let _ = with_state(&app, |s| s.peers.remove(&fullname));
+                    emit_peers(&app);
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+// 开始在局域网中广播本机并发现其它设备
+#[tauri::command]
+pub fn start_lan_sync(app: tauri::AppHandle) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if with_state(&app, |s| s.daemon.is_some())? {
+        return Ok(());
+    }
+    let identity = identity()?;
+    let listener = TcpListener::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let daemon = ServiceDaemon::new().map_err(|e| e.to_string())?;
+    let name = device_name();
+    let key = hex(PublicKey::from(&identity.secret).as_bytes());
+    let properties = [("id", identity.device_id.as_str()), ("name", name.as_str()), ("key", key.as_str())];
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &identity.device_id,
+        &format!("brew-guide-{}.local.", identity.device_id),
+        "",
+        port,
+        &properties[..],
+    )
+    .map_err(|e| e.to_string())?
+    .enable_addr_auto();
+    daemon.register(service).map_err(|e| e.to_string())?;
+    let events = daemon.browse(SERVICE_TYPE).map_err(|e| e.to_string())?;
+
+    let generation = with_state(&app, |s| {
+        s.daemon = Some(daemon);
+        s.generation
+    })?;
+    spawn_accept_loop(app.clone(), listener, generation);
+    spawn_browse_loop(app, events, identity.device_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_lan_sync(app: tauri::AppHandle) -> Result<(), String> {
+    let daemon = with_state(&app, |s| {
+        s.generation += 1;
+        s.peers.clear();
+        s.daemon.take()
+    })?;
+    if let Some(daemon) = daemon {
+        let _ = daemon.shutdown();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_lan_peers(app: tauri::AppHandle) -> Result<Vec<LanPeer>, String> {
+    let paired = load_peers(&app)?;
+    with_state(&app, |s| {
+        s.peers
+            .values()
+            .map(|peer| LanPeer {
+                paired: paired
+                    .iter()
+                    .any(|p| p.device_id == peer.device_id && p.public_key == peer.public_key),
+                ..peer.clone()
+            })
+            .collect()
+    })
+}
+
+// 用户核对配对码后调用
+#[tauri::command]
+pub fn confirm_lan_pairing(app: tauri::AppHandle, device_id: String, accepted: bool) -> Result<(), String> {
+    let sender = with_state(&app, |s| s.pending.remove(&device_id))?.ok_or("没有等待确认的配对")?;
+    sender.send(accepted).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sync_with_peer(app: tauri::AppHandle, device_id: String) -> Result<LanSyncReport, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let address = with_state(&app, |s| {
+        s.peers
+            .values()
+            .find(|peer| peer.device_id == device_id)
+            .map(|peer| peer.address.clone())
+    })?
+    .ok_or_else(|| format!("找不到设备：{}", device_id))?;
+    let address: SocketAddr = address.parse().map_err(|e: std::net::AddrParseError| e.to_string())?;
+    let handle = app.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+        let mut session = handshake(stream, &identity()?, true)?;
+        ensure_paired(&handle, &mut session, None)?;
+        client_sync(&handle, &mut session)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    crate::telemetry::record(&app, "lan_sync.sync");
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_pairing_prompts_from_incoming_connections() {
+        let mut state = LanSyncState::default();
+        let first: IpAddr = "192.168.1.20".parse().unwrap();
+        let second: IpAddr = "192.168.1.21".parse().unwrap();
+        let now = Instant::now();
+        assert!(allow_prompt(&mut state, first, now));
+        // 同一地址短时间内不再提示
+        assert!(!allow_prompt(&mut state, first, now + Duration::from_secs(5)));
+        assert!(allow_prompt(&mut state, first, now + PAIRING_PROMPT_INTERVAL));
+
+        // 已有等待确认的配对时，其它地址也不能提示
+        let (sender, _receiver) = mpsc::channel();
+        state.pending.insert("device".to_string(), sender);
+        assert!(!allow_prompt(&mut state, second, now + PAIRING_PROMPT_INTERVAL));
+        state.pending.clear();
+        assert!(allow_prompt(&mut state, second, now + PAIRING_PROMPT_INTERVAL));
+    }
+
+    #[test]
+    fn both_sides_derive_the_same_session_key() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let identity = Identity {
+                device_id: "server".to_string(),
+                secret: random_secret(),
+            };
+            let mut session = handshake(stream, &identity, false).unwrap();
+            let message: String = session.channel.receive().unwrap();
+            session.channel.send(&format!("{}!", message)).unwrap();
+            session.key
+        });
+        let identity = Identity {
+            device_id: "client".to_string(),
+            secret: random_secret(),
+        };
+        let mut session = handshake(TcpStream::connect(address).unwrap(), &identity, true).unwrap();
+        assert_eq!(session.peer.device_id, "server");
+        session.channel.send(&"你好".to_string()).unwrap();
+        assert_eq!(session.channel.receive::<String>().unwrap(), "你好!");
+        let server_key = server.join().unwrap();
+        assert_eq!(pairing_code(&session.key), pairing_code(&server_key));
+    }
+
+    #[test]
+    fn large_messages_need_an_authenticated_peer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let identity = Identity {
+                device_id: "server".to_string(),
+                secret: random_secret(),
+            };
+            let mut results = Vec::new();
+            for authenticated in [false, true] {
+                let (stream, _) = listener.accept().unwrap();
+                let mut session = handshake(stream, &identity, false).unwrap();
+                if authenticated {
+                    session.channel.authenticated();
+                }
+                results.push(session.channel.receive::<String>().is_ok());
+            }
+            results
+        });
+        let identity = Identity {
+            device_id: "client".to_string(),
+            secret: random_secret(),
+        };
+        let large = "豆".repeat(HANDSHAKE_FRAME_BYTES);
+        for _ in 0..2 {
+            let mut session = handshake(TcpStream::connect(address).unwrap(), &identity, true).unwrap();
+            session.channel.send(&large).unwrap();
+        }
+        assert_eq!(server.join().unwrap(), vec![false, true]);
+    }
+}
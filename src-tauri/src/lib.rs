@@ -1,12 +1,95 @@
 use tauri::{
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder, SubmenuBuilder},
+    menu::{CheckMenuItemBuilder, IconMenuItemBuilder, MenuBuilder, MenuItem, MenuItemBuilder, MenuItemKind, Submenu, SubmenuBuilder},
     tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState},
     Manager, Emitter, Listener,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+mod app_lock;
+mod artisan;
+mod attachments;
+mod audio;
+mod background;
+mod backups;
+mod barcode;
+mod beanconqueror;
+mod bean_search;
+mod bean_share;
+mod brews;
+mod bulk;
+mod calendar;
+mod cards;
+mod channel;
+mod clipboard_bean;
+mod clock;
+mod conflicts;
+mod credentials;
+mod csv_export;
+mod csv_import;
+mod diagnostics;
+mod duplicates;
+mod encrypted_backup;
+mod extensions;
+mod file_import;
+mod history;
+mod hotkeys;
+mod i18n;
+mod integrity;
+mod journal;
+mod json_file;
+mod keep_awake;
+mod label_printer;
+mod lan_sync;
+mod markdown_export;
+mod mini_timer;
+mod navigation;
+mod nfc;
+mod notes;
+mod notifications;
+mod pdf;
+mod quick_add;
+mod quick_panel;
+mod recipe;
+mod reminders;
+mod roast_date;
+mod roaster_page;
+mod s3;
+mod search;
+mod settings;
+mod share_inbox;
+mod snooze;
+mod speech;
+mod store;
+mod sync;
+mod sync_folder;
+mod telemetry;
+mod timer;
+mod timer_notification;
+mod trash;
+mod transfer;
+mod tray_icon;
+mod updater;
+mod voice_log;
+mod watch_bridge;
+mod webdav;
+mod weekly_report;
+mod widget;
+mod xlsx;
+mod xml;
+
+use background::BeanCache;
+use clock::{calendar_days_between, parse_timezone, ClockState};
+use i18n::Locale;
+use navigation::{navigate_to, NavigationTarget};
+use roast_date::parse_roast_date;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
 
@@ -21,6 +104,12 @@ impl Default for TrayState {
     }
 }
 
+// 小组件快照目录（iOS App Group / Android 共享目录），未设置时写入应用数据目录
+#[derive(Default)]
+struct WidgetState {
+    container_dir: Option<PathBuf>,
+}
+
 // 咖啡豆数据结构（简化版，用于菜单栏显示）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -34,6 +123,43 @@ pub struct CoffeeBean {
     pub end_day: Option<i32>,
     pub is_frozen: Option<bool>,
     pub is_in_transit: Option<bool>,  // 是否在途状态
+    pub expected_arrival: Option<String>, // 在途咖啡豆的预计到货日期（YYYY-MM-DD）
+    pub pinned: Option<bool>,         // 是否置顶到托盘菜单顶层
+    pub roaster: Option<String>,      // 烘焙商
+    pub origin: Option<String>,       // 产地（拼配豆为多个产地）
+    pub process: Option<String>,      // 处理法
+}
+
+impl CoffeeBean {
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.unwrap_or(false)
+    }
+    
+    // 托盘分组用的字段，未填写时返回 None
+    pub fn group_key(&self, grouping: settings::TrayGrouping) -> Option<&str> {
+        let value = match grouping {
+            settings::TrayGrouping::State => None,
+            settings::TrayGrouping::Roaster => self.roaster.as_deref(),
+            settings::TrayGrouping::Origin => self.origin.as_deref(),
+            settings::TrayGrouping::Process => self.process.as_deref(),
+        };
+        value.map(str::trim).filter(|v| !v.is_empty())
+    }
+    
+    // 剩余克数（前端以字符串保存，可能为空或无法解析）
+    pub fn remaining_grams(&self) -> Option<f64> {
+        self.remaining.as_ref()?.trim().parse().ok()
+    }
+    
+    // 在途咖啡豆距预计到货还有几天（已过预计日期时为负数），未填写预计日期时返回 None
+    pub fn days_until_arrival(&self, today: chrono::NaiveDate) -> Option<i32> {
+        if !self.is_in_transit.unwrap_or(false) {
+            return None;
+        }
+        let date = self.expected_arrival.as_deref()?.trim();
+        let arrival = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+        Some(calendar_days_between(today, arrival))
+    }
 }
 
 // 计算赏味期状态
@@ -41,10 +167,27 @@ pub struct CoffeeBean {
 pub struct BeanFreshnessInfo {
     pub bean: CoffeeBean,
     pub days_since_roast: i32,
+    pub days_since_roast_max: i32,        // 烘焙日期只精确到周/月时的最大天数（精确日期时与 days_since_roast 相同）
     pub start_day: i32,
     pub end_day: i32,
     pub freshness_state: FreshnessState,  // 赏味期状态
     pub progress_percent: f32,            // 赏味期进度 (0-100)
+    pub days_until_arrival: Option<i32>,  // 在途咖啡豆距预计到货的天数
+    pub menu_id: Option<String>,          // 托盘菜单项 ID（缺少咖啡豆 ID 或 ID 重复时为 None，菜单项不可点击）
+}
+
+// 咖啡豆 ID 检查结果，返回给前端并通过 tray-bean-id-issues 事件通知
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeanIdReport {
+    pub duplicate_ids: Vec<String>,
+    pub missing_id_count: usize,
+}
+
+impl BeanIdReport {
+    fn has_issues(&self) -> bool {
+        !self.duplicate_ids.is_empty() || self.missing_id_count > 0
+    }
 }
 
 // 赏味期状态分类（与前端 FlavorPeriodStatus 保持一致）
@@ -58,15 +201,194 @@ pub enum FreshnessState {
     Unknown,    // 未知（没有烘焙日期）
 }
 
+// 显示并聚焦主窗口
+pub(crate) fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// 隐藏主窗口到托盘
+#[cfg(desktop)]
+fn hide_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    // macOS: 窗口隐藏后重新隐藏 Dock 图标
+    #[cfg(target_os = "macos")]
+    {
+        let _ = app.set_activation_policy(ActivationPolicy::Accessory);
+    }
+}
+
+// 打开托盘菜单中的小工具窗口（快速添加、搜索），已打开时直接聚焦
+// 页面路径与窗口 label 相同
+#[cfg(desktop)]
+pub(crate) fn open_tray_window(
+    app: &tauri::AppHandle,
+    label: &str,
+    title: &str,
+    width: f64,
+    height: f64,
+) -> tauri::Result<()> {
+    if let Some(window) = app.get_webview_window(label) {
+        window.show()?;
+        return window.set_focus();
+    }
+    tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::App(label.into()))
+        .title(title)
+        .inner_size(width, height)
+        .resizable(false)
+        .minimizable(false)
+        .maximizable(false)
+        .always_on_top(true)
+        .center()
+        .build()?;
+    Ok(())
+}
+
+// 关闭主窗口时询问：退出应用或隐藏到托盘
+#[cfg(desktop)]
+fn ask_close_behavior(app: &tauri::AppHandle) {
+    let locale = current_locale(app);
+    let handle = app.clone();
+    app.dialog()
+        .message(locale.tr(
+            "退出 Brew Guide，还是隐藏到托盘继续在后台运行？",
+            "Quit Brew Guide, or hide it to the tray and keep it running?",
+        ))
+        .title(locale.tr("关闭窗口", "Close Window"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            locale.tr("退出", "Quit").to_string(),
+            locale.tr("隐藏到托盘", "Hide to Tray").to_string(),
+        ))
+        .show(move |quit| {
+            if quit {
+                handle.exit(0);
+            } else {
+                hide_main_window(&handle);
+            }
+        });
+}
+
+// 设置点击关闭按钮的行为：quit / hide / ask
+#[tauri::command]
+fn set_close_behavior(app: tauri::AppHandle, behavior: settings::CloseBehavior) -> Result<(), String> {
+    settings::update(&app, |s| s.close_behavior = behavior)?;
+    Ok(())
+}
+
+// 执行点击托盘图标的操作
+#[cfg(desktop)]
+fn run_tray_click_action(app: &tauri::AppHandle, action: settings::TrayClickAction, icon_rect: &tauri::Rect) {
+    match action {
+        settings::TrayClickAction::ShowWindow => show_main_window(app),
+        settings::TrayClickAction::ToggleWindow => {
+            let visible = app
+                .get_webview_window("main")
+                .and_then(|window| window.is_visible().ok())
+                .unwrap_or(false);
+            if visible {
+                hide_main_window(app);
+            } else {
+                show_main_window(app);
+            }
+        }
+        settings::TrayClickAction::QuickPanel => {
+            if let Err(e) = quick_panel::toggle(app, icon_rect) {
+                log::warn!("打开快速面板失败：{}", e);
+            }
+        }
+        // 菜单由系统在左键单击时弹出
+        settings::TrayClickAction::None | settings::TrayClickAction::OpenMenu => {}
+    }
+}
+
+// 设置点击托盘图标的操作：button 为 left / double / middle
+#[tauri::command]
+fn set_tray_click_action(
+    app: tauri::AppHandle,
+    button: settings::TrayClickButton,
+    action: settings::TrayClickAction,
+) -> Result<(), String> {
+    if action == settings::TrayClickAction::OpenMenu && button != settings::TrayClickButton::Left {
+        return Err("只有左键单击可以设置为打开菜单".to_string());
+    }
+    let settings = settings::update(&app, |s| match button {
+        settings::TrayClickButton::Left => s.tray.left_click = action,
+        settings::TrayClickButton::Double => s.tray.double_click = action,
+        settings::TrayClickButton::Middle => s.tray.middle_click = action,
+    })?;
+    apply_tray_click(&app, &settings.tray)
+}
+
+fn apply_tray_click(app: &tauri::AppHandle, tray_settings: &settings::TraySettings) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        if let Some(tray) = app.tray_by_id("main-tray") {
+            let open_menu = tray_settings.left_click == settings::TrayClickAction::OpenMenu;
+            tray.set_show_menu_on_left_click(open_menu).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
 // 从前端获取咖啡豆数据的命令
 #[tauri::command]
-fn update_tray_menu(app: tauri::AppHandle, beans: Vec<CoffeeBean>) -> Result<(), String> {
-    update_tray_with_beans(&app, beans).map_err(|e| e.to_string())
+fn update_tray_menu(app: tauri::AppHandle, beans: Vec<CoffeeBean>) -> Result<BeanIdReport, String> {
+    apply_beans(&app, beans)
+}
+
+// 前端推送咖啡豆数据后更新缓存、检查通知并刷新托盘（托盘缓存只以前端数据为准）
+fn apply_beans(app: &tauri::AppHandle, beans: Vec<CoffeeBean>) -> Result<BeanIdReport, String> {
+    // 缓存一份，供后台刷新在没有前端推送时使用
+    // 记下更新前的剩余量，用于判断是否刚跌破库存提醒阈值
+    let mut previous = Vec::new();
+    if let Some(state) = app.try_state::<Arc<Mutex<BeanCache>>>() {
+        if let Ok(mut cache) = state.lock() {
+            previous = cache.beans.clone();
+            cache.replace(beans.clone(), today(app));
+        }
+    }
+    background::save_cache(app, &beans);
+    notifications::check_low_stock(app, &previous, &beans);
+    weekly_report::record_finished(app, &previous, &beans);
+    if let Err(e) = notifications::check_freshness_changes(app) {
+        log::warn!("检查赏味期变化失败：{}", e);
+    }
+    if let Err(e) = weekly_report::check(app) {
+        log::warn!("生成周报失败：{}", e);
+    }
+    if let Err(e) = calendar::refresh_feed(app, &beans) {
+        log::warn!("更新赏味期日历失败：{}", e);
+    }
+    update_tray_with_beans(app, beans).map_err(|e| e.to_string())
+}
+
+// 在文件管理器（访达 / 资源管理器 / 文件）中打开应用数据目录，便于手动备份和排查问题
+fn open_app_data_dir(app: &tauri::AppHandle) -> Result<(), String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn open_data_dir(app: tauri::AppHandle) -> Result<(), String> {
+    open_app_data_dir(&app)
 }
 
 // 设置托盘图标可见性
 #[tauri::command]
 fn set_tray_visible(app: tauri::AppHandle, visible: bool) -> Result<(), String> {
+    settings::update(&app, |s| s.tray.visible = visible)?;
+    apply_tray_visible(&app, visible)
+}
+
+fn apply_tray_visible(app: &tauri::AppHandle, visible: bool) -> Result<(), String> {
     #[cfg(desktop)]
     {
         if let Some(tray) = app.tray_by_id("main-tray") {
@@ -95,17 +417,191 @@ fn set_tray_visible(app: tauri::AppHandle, visible: bool) -> Result<(), String>
     Ok(())
 }
 
-fn calculate_freshness(bean: &CoffeeBean) -> BeanFreshnessInfo {
-    let today = chrono::Local::now().date_naive();
-    
-    let days_since_roast = if let Some(ref roast_date) = bean.roast_date {
-        if let Ok(date) = chrono::NaiveDate::parse_from_str(roast_date, "%Y-%m-%d") {
-            (today - date).num_days() as i32
-        } else {
-            0
+// 设置托盘咖啡豆条目是否显示剩余克数
+#[tauri::command]
+fn set_tray_show_remaining(app: tauri::AppHandle, show: bool) -> Result<(), String> {
+    settings::update(&app, |s| s.tray.show_remaining = show)?;
+    background::refresh(&app)
+}
+
+// 设置赏味期内的咖啡豆是否显示赏味期进度条
+#[tauri::command]
+fn set_tray_show_progress(app: tauri::AppHandle, show: bool) -> Result<(), String> {
+    settings::update(&app, |s| s.tray.show_progress = show)?;
+    background::refresh(&app)
+}
+
+// 隐私模式：托盘菜单和图标提示文字中隐藏咖啡豆名称和重量
+fn set_privacy_mode(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    settings::update(app, |s| s.tray.privacy_mode = enabled)?;
+    background::refresh(app)
+}
+
+#[tauri::command]
+fn set_tray_privacy_mode(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    set_privacy_mode(&app, enabled)
+}
+
+// 设置每个托盘分区最多显示的咖啡豆数量，0 表示不限制
+#[tauri::command]
+fn set_tray_max_items(app: tauri::AppHandle, max_items: usize) -> Result<(), String> {
+    if max_items > settings::MAX_TRAY_ITEMS_PER_SECTION {
+        return Err(format!("每个分区最多显示 {} 款咖啡豆", settings::MAX_TRAY_ITEMS_PER_SECTION));
+    }
+    settings::update(&app, |s| s.tray.max_items_per_section = max_items)?;
+    background::refresh(&app)
+}
+
+// 设置菜单栏图标旁显示的文字：off / count / countdown
+#[tauri::command]
+fn set_tray_title_mode(app: tauri::AppHandle, mode: settings::TrayTitleMode) -> Result<(), String> {
+    settings::update(&app, |s| s.tray.title_mode = mode)?;
+    background::refresh(&app)
+}
+
+// 设置「即将喝完」分区的剩余量阈值（克）
+#[tauri::command]
+fn set_tray_low_stock_threshold(app: tauri::AppHandle, grams: f64) -> Result<(), String> {
+    if !grams.is_finite() || grams < 0.0 {
+        return Err(format!("无效的阈值：{}", grams));
+    }
+    settings::update(&app, |s| s.tray.low_stock_grams = grams)?;
+    background::refresh(&app)
+}
+
+// 设置托盘咖啡豆的分组方式：state / roaster / origin / process
+#[tauri::command]
+fn set_tray_grouping(app: tauri::AppHandle, grouping: settings::TrayGrouping) -> Result<(), String> {
+    settings::update(&app, |s| s.tray.grouping = grouping)?;
+    background::refresh(&app)
+}
+
+// 设置托盘分区的顺序、隐藏的分区和分区内排序方式
+#[tauri::command]
+fn set_tray_preferences(app: tauri::AppHandle, preferences: settings::TrayPreferences) -> Result<(), String> {
+    settings::update(&app, |s| {
+        s.tray.section_order = preferences.section_order;
+        s.tray.hidden_sections = preferences.hidden_sections;
+        s.tray.sort = preferences.sort;
+    })?;
+    background::refresh(&app)
+}
+
+// 设置计算烘焙天数所用的时区（IANA 名称，如 "Asia/Shanghai"；传 null 使用系统时区）
+#[tauri::command]
+fn set_timezone(app: tauri::AppHandle, timezone: Option<String>) -> Result<(), String> {
+    timezone.as_deref().map(parse_timezone).transpose()?;
+    settings::update(&app, |s| s.timezone = timezone.clone())?;
+    apply_timezone(&app, timezone.as_deref())
+}
+
+fn apply_timezone(app: &tauri::AppHandle, timezone: Option<&str>) -> Result<(), String> {
+    let timezone = timezone.map(parse_timezone).transpose()?;
+    if let Some(state) = app.try_state::<Arc<Mutex<ClockState>>>() {
+        if let Ok(mut clock) = state.lock() {
+            clock.set_timezone(timezone);
         }
-    } else {
-        0
+    }
+    Ok(())
+}
+
+// 设置托盘等原生界面的语言（BCP 47 标签，如 "zh-CN"、"en-US"）
+#[tauri::command]
+fn set_locale(app: tauri::AppHandle, locale: String) -> Result<(), String> {
+    settings::update(&app, |s| s.locale = Some(locale.clone()))?;
+    apply_locale(&app, Some(&locale));
+    Ok(())
+}
+
+fn apply_locale(app: &tauri::AppHandle, locale: Option<&str>) {
+    if let Some(state) = app.try_state::<Arc<Mutex<Locale>>>() {
+        if let Ok(mut current) = state.lock() {
+            *current = locale.map(Locale::parse).unwrap_or_default();
+        }
+    }
+}
+
+// 把保存的设置应用到运行时状态（启动时和 set_settings 后调用）
+pub(crate) fn apply_settings(app: &tauri::AppHandle, settings: &settings::AppSettings) {
+    if let Err(e) = apply_tray_visible(app, settings.tray.visible) {
+        log::warn!("应用托盘设置失败：{}", e);
+    }
+    if let Err(e) = apply_tray_click(app, &settings.tray) {
+        log::warn!("应用托盘点击设置失败：{}", e);
+    }
+    if let Err(e) = apply_timezone(app, settings.timezone.as_deref()) {
+        log::warn!("应用时区设置失败：{}", e);
+    }
+    apply_locale(app, settings.locale.as_deref());
+}
+
+pub(crate) fn current_locale(app: &tauri::AppHandle) -> Locale {
+    app.try_state::<Arc<Mutex<Locale>>>()
+        .and_then(|state| state.lock().ok().map(|locale| *locale))
+        .unwrap_or_default()
+}
+
+// 设置小组件快照的共享目录（由原生壳在启动时传入 App Group 容器路径）
+#[tauri::command]
+fn set_widget_container_dir(app: tauri::AppHandle, path: Option<String>) -> Result<(), String> {
+    if let Some(state) = app.try_state::<Arc<Mutex<WidgetState>>>() {
+        if let Ok(mut s) = state.lock() {
+            s.container_dir = path.map(PathBuf::from);
+        }
+    }
+    Ok(())
+}
+
+// 更新小组件快照，失败只记录日志，不影响托盘
+fn refresh_widget_snapshot(app: &tauri::AppHandle, snapshot: &widget::WidgetSnapshot) {
+    let container_dir = app
+        .try_state::<Arc<Mutex<WidgetState>>>()
+        .and_then(|state| state.lock().ok().and_then(|s| s.container_dir.clone()));
+    let dir = match container_dir {
+        Some(dir) => dir,
+        None => match app.path().app_data_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::warn!("无法获取应用数据目录：{}", e);
+                return;
+            }
+        },
+    };
+    
+    if let Err(e) = widget::write_snapshot(&dir, snapshot) {
+        log::warn!("写入小组件快照失败：{}", e);
+    }
+}
+
+// 获取当前日历日期（按配置时区，且不会因系统时钟回拨而倒退）
+pub(crate) fn today(app: &tauri::AppHandle) -> chrono::NaiveDate {
+    app.try_state::<Arc<Mutex<ClockState>>>()
+        .and_then(|state| state.lock().ok().map(|mut clock| clock.today()))
+        .unwrap_or_else(|| chrono::Local::now().date_naive())
+}
+
+pub(crate) fn date_of(app: &tauri::AppHandle, at: chrono::DateTime<chrono::Utc>) -> chrono::NaiveDate {
+    app.try_state::<Arc<Mutex<ClockState>>>()
+        .and_then(|state| state.lock().ok().map(|clock| clock.date_of(at)))
+        .unwrap_or_else(|| at.with_timezone(&chrono::Local).date_naive())
+}
+
+pub(crate) fn time_of(app: &tauri::AppHandle, at: chrono::DateTime<chrono::Utc>) -> chrono::NaiveTime {
+    app.try_state::<Arc<Mutex<ClockState>>>()
+        .and_then(|state| state.lock().ok().map(|clock| clock.time_of(at)))
+        .unwrap_or_else(|| at.with_timezone(&chrono::Local).time())
+}
+
+fn calculate_freshness(bean: &CoffeeBean, today: chrono::NaiveDate) -> BeanFreshnessInfo {
+    // 烘焙日期不精确时得到一个区间：
+    // days_since_roast 取最少天数（最保守，避免提前判定进入赏味期），days_since_roast_max 取最多天数
+    let roast_range = bean.roast_date.as_deref().and_then(parse_roast_date);
+    let (days_since_roast, days_since_roast_max) = match roast_range {
+        Some(range) => (
+            calendar_days_between(range.latest, today),
+            calendar_days_between(range.earliest, today),
+        ),
+        None => (0, 0),
     };
     
     let start_day = bean.start_day.unwrap_or(7);
@@ -119,7 +615,7 @@ fn calculate_freshness(bean: &CoffeeBean) -> BeanFreshnessInfo {
         FreshnessState::InTransit
     } else if is_frozen {
         FreshnessState::Frozen
-    } else if bean.roast_date.is_none() {
+    } else if roast_range.is_none() {
         FreshnessState::Unknown
     } else if days_since_roast < start_day {
         FreshnessState::Resting
@@ -133,7 +629,7 @@ fn calculate_freshness(bean: &CoffeeBean) -> BeanFreshnessInfo {
     let optimal_duration = (end_day - start_day) as f32;
     let days_in_optimal = (days_since_roast - start_day) as f32;
     let progress_percent = if optimal_duration > 0.0 && freshness_state == FreshnessState::Optimal {
-        (days_in_optimal / optimal_duration * 100.0).min(100.0).max(0.0)
+        (days_in_optimal / optimal_duration * 100.0).clamp(0.0, 100.0)
     } else if days_since_roast > end_day {
         100.0
     } else {
@@ -143,10 +639,242 @@ fn calculate_freshness(bean: &CoffeeBean) -> BeanFreshnessInfo {
     BeanFreshnessInfo {
         bean: bean.clone(),
         days_since_roast,
+        days_since_roast_max,
         start_day,
         end_day,
         freshness_state,
         progress_percent,
+        days_until_arrival: bean.days_until_arrival(today),
+        menu_id: None,
+    }
+}
+
+// 为每个咖啡豆分配唯一的菜单项 ID
+// - 重复的 ID：只有第一次出现的可以点击；前端按 ID 找咖啡豆，后出现的无法区分，点击会扣减或打开第一款
+// - 缺失的 ID：不分配菜单项 ID，对应菜单项不可点击
+fn assign_menu_ids(beans: &[CoffeeBean]) -> (Vec<Option<String>>, BeanIdReport) {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut report = BeanIdReport::default();
+    
+    let menu_ids = beans
+        .iter()
+        .map(|bean| {
+            let id = bean.id.trim();
+            if id.is_empty() {
+                report.missing_id_count += 1;
+                return None;
+            }
+            
+            let count = seen.entry(id).or_insert(0);
+            *count += 1;
+            if *count == 2 {
+                report.duplicate_ids.push(id.to_string());
+            }
+            (*count == 1).then(|| format!("bean:{}", id))
+        })
+        .collect();
+    
+    (menu_ids, report)
+}
+
+// 从菜单项 ID 中解析咖啡豆 ID（去掉 bean: 前缀）
+fn parse_bean_menu_id(menu_id: &str) -> Option<&str> {
+    menu_id.strip_prefix("bean:")
+}
+
+// 解析扣减用量菜单项 ID："consume:15|bean:xxx" -> (咖啡豆 ID, Some(15.0))，自定义用量时为 None
+fn parse_consume_menu_id(menu_id: &str) -> Option<(&str, Option<f64>)> {
+    let (amount, bean_menu_id) = menu_id.strip_prefix("consume:")?.split_once('|')?;
+    let amount = match amount {
+        "custom" => None,
+        grams => Some(grams.parse().ok()?),
+    };
+    Some((parse_bean_menu_id(bean_menu_id)?, amount))
+}
+
+// 托盘快捷扣减的常用粉量（克）
+const DOSE_PRESETS: [u32; 3] = [15, 18, 20];
+
+// 咖啡豆的操作子菜单：查看详情、快速扣减一次用量（置顶的咖啡豆还有取消置顶），前面带赏味期状态圆点
+fn bean_action_submenu(
+    app: &tauri::AppHandle,
+    locale: Locale,
+    info: &BeanFreshnessInfo,
+    menu_id: &str,
+    label: String,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let open = MenuItemBuilder::with_id(menu_id, locale.tr("查看详情", "View details"))
+        .build(app)?;
+    let mut submenu = SubmenuBuilder::new(app, label)
+        .submenu_icon(tray_icon::state_dot(&info.freshness_state))
+        .item(&open);
+    if matches!(info.freshness_state, FreshnessState::InTransit) {
+        let arrived = MenuItemBuilder::with_id(format!("arrived|{}", menu_id), locale.tr("已收到", "Arrived"))
+            .build(app)?;
+        submenu = submenu.item(&arrived);
+    }
+    submenu = submenu.separator();
+    for grams in DOSE_PRESETS {
+        let label = format!("{} {}", locale.tr("消耗", "Use"), locale.format_weight(grams as f64));
+        let item = MenuItemBuilder::with_id(format!("consume:{}|{}", grams, menu_id), label)
+            .build(app)?;
+        submenu = submenu.item(&item);
+    }
+    let custom = MenuItemBuilder::with_id(format!("consume:custom|{}", menu_id), locale.tr("自定义…", "Custom…"))
+        .build(app)?;
+    let finish = MenuItemBuilder::with_id(format!("finish|{}", menu_id), locale.tr("标记为已喝完", "Mark as finished"))
+        .build(app)?;
+    let snooze = MenuItemBuilder::with_id(format!("snooze|{}", menu_id), locale.format_snooze(snooze::SNOOZE_DAYS))
+        .build(app)?;
+    let copy = MenuItemBuilder::with_id(format!("copy|{}", menu_id), locale.tr("复制信息", "Copy Info"))
+        .build(app)?;
+    submenu = submenu.item(&custom).separator().item(&finish).item(&snooze).item(&copy);
+    if info.bean.is_pinned() {
+        let unpin = MenuItemBuilder::with_id(format!("unpin|{}", menu_id), locale.tr("取消置顶", "Unpin"))
+            .build(app)?;
+        submenu = submenu.separator().item(&unpin);
+    }
+    submenu.build()
+}
+
+// 取消置顶：通知前端保存，同时更新缓存让托盘立即刷新
+fn unpin_from_tray(app: &tauri::AppHandle, bean_id: &str) {
+    telemetry::record(app, "tray.unpin");
+    let _ = app.emit("tray-unpin-bean", bean_id);
+    if let Some(state) = app.try_state::<Arc<Mutex<BeanCache>>>() {
+        if let Ok(mut cache) = state.lock() {
+            cache.beans.iter_mut().filter(|b| b.id == bean_id).for_each(|b| b.pinned = Some(false));
+        }
+    }
+    if let Err(e) = background::refresh(app) {
+        log::warn!("取消置顶后刷新托盘失败：{}", e);
+    }
+}
+
+// 标记为已喝完：确认后通知前端把剩余量清零，缓存同步清零让该豆立即从托盘移除
+fn finish_from_tray(app: &tauri::AppHandle, bean_id: &str) {
+    let locale = current_locale(app);
+    let bean_name = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| {
+            let cache = state.lock().ok()?;
+            cache.beans.iter().find(|b| b.id == bean_id).map(|b| b.name.clone())
+        })
+        .unwrap_or_default();
+    let handle = app.clone();
+    let bean_id = bean_id.to_string();
+    app.dialog()
+        .message(locale.format_finish_confirm(&bean_name))
+        .title(locale.tr("标记为已喝完", "Mark as finished"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            locale.tr("已喝完", "Finished").to_string(),
+            locale.tr("取消", "Cancel").to_string(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            telemetry::record(&handle, "tray.finish");
+            let _ = handle.emit("finish-bean", &bean_id);
+            if let Some(state) = handle.try_state::<Arc<Mutex<BeanCache>>>() {
+                if let Ok(mut cache) = state.lock() {
+                    cache.beans.iter_mut().filter(|b| b.id == bean_id).for_each(|b| b.remaining = Some("0".to_string()));
+                }
+            }
+            if let Err(e) = background::refresh(&handle) {
+                log::warn!("标记喝完后刷新托盘失败：{}", e);
+            }
+        });
+}
+
+// 复制咖啡豆信息到系统剪贴板
+fn copy_from_tray(app: &tauri::AppHandle, bean_id: &str) -> Result<(), String> {
+    let bean = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| {
+            let cache = state.lock().ok()?;
+            cache.beans.iter().find(|b| b.id == bean_id).cloned()
+        })
+        .ok_or("找不到咖啡豆")?;
+    let info = calculate_freshness(&bean, today(app));
+    telemetry::record(app, "tray.copy");
+    app.clipboard()
+        .write_text(bean_share_text(&info, current_locale(app)))
+        .map_err(|e| e.to_string())
+}
+
+// bean-arrived 事件，date 为收到当天（YYYY-MM-DD）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BeanArrivedEvent {
+    bean_id: String,
+    date: String,
+}
+
+// 在途咖啡豆标记为已收到；没有烘焙日期时以收到日期开始计算赏味期（与前端一致）
+fn arrived_from_tray(app: &tauri::AppHandle, bean_id: &str) {
+    telemetry::record(app, "tray.arrived");
+    let date = today(app).format("%Y-%m-%d").to_string();
+    let event = BeanArrivedEvent {
+        bean_id: bean_id.to_string(),
+        date: date.clone(),
+    };
+    let _ = app.emit("bean-arrived", &event);
+    if let Some(state) = app.try_state::<Arc<Mutex<BeanCache>>>() {
+        if let Ok(mut cache) = state.lock() {
+            for bean in cache.beans.iter_mut().filter(|b| b.id == bean_id) {
+                bean.is_in_transit = Some(false);
+                if bean.roast_date.as_deref().map_or(true, |d| d.trim().is_empty()) {
+                    bean.roast_date = Some(date.clone());
+                }
+            }
+        }
+    }
+    if let Err(e) = background::refresh(app) {
+        log::warn!("标记已收到后刷新托盘失败：{}", e);
+    }
+}
+
+// tray-consume-bean 事件，amount 为空时由前端弹窗输入用量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TrayConsumeEvent {
+    bean_id: String,
+    amount: Option<f64>,
+}
+
+fn consume_from_tray(app: &tauri::AppHandle, bean_id: &str, amount: Option<f64>) {
+    telemetry::record(app, "tray.consume");
+    consume_bean(app, bean_id, amount);
+}
+
+// 扣减咖啡豆用量：通知前端保存，amount 为空时打开主窗口让用户自行填写
+pub fn consume_bean(app: &tauri::AppHandle, bean_id: &str, amount: Option<f64>) {
+    let event = TrayConsumeEvent {
+        bean_id: bean_id.to_string(),
+        amount,
+    };
+    let _ = app.emit("tray-consume-bean", &event);
+    
+    let Some(grams) = amount else {
+        show_main_window(app);
+        return;
+    };
+    // 先扣减缓存里的剩余量并刷新托盘，不等前端回推
+    if let Some(state) = app.try_state::<Arc<Mutex<BeanCache>>>() {
+        let beans = state.lock().ok().map(|mut cache| {
+            let previous = cache.beans.clone();
+            cache.deduct_remaining(bean_id, grams);
+            (previous, cache.beans.clone())
+        });
+        if let Some((previous, current)) = beans {
+            notifications::check_low_stock(app, &previous, &current);
+            weekly_report::record_finished(app, &previous, &current);
+        }
+    }
+    if let Err(e) = background::refresh(app) {
+        log::warn!("扣减用量后刷新托盘失败：{}", e);
     }
 }
 
@@ -175,51 +903,356 @@ fn truncate_name(name: &str, max_width: usize) -> String {
     result
 }
 
-// 格式化容量显示
-fn format_capacity(grams: f64) -> String {
-    if grams >= 1000.0 {
-        format!("{:.2}kg", grams / 1000.0)
-    } else {
-        format!("{}g", grams as i32)
+// 托盘分区构建失败的诊断信息，通过 tray-diagnostic 事件发送给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayDiagnostic {
+    pub section: String,
+    pub error: String,
+}
+
+impl TrayDiagnostic {
+    fn new(section: &str, error: tauri::Error) -> Self {
+        Self {
+            section: section.to_string(),
+            error: error.to_string(),
+        }
     }
 }
 
-fn update_tray_with_beans(app: &tauri::AppHandle, beans: Vec<CoffeeBean>) -> Result<(), Box<dyn std::error::Error>> {
-    // 过滤出有剩余量的咖啡豆
-    let active_beans: Vec<BeanFreshnessInfo> = beans
-        .iter()
-        .filter(|b| {
-            if let Some(ref remaining) = b.remaining {
-                let amount: f64 = remaining.parse().unwrap_or(0.0);
-                amount > 0.0
-            } else {
-                false
-            }
-        })
-        .map(|b| calculate_freshness(b))
-        .collect();
+// 剩余天数不超过该值的赏味期咖啡豆算作"即将过赏味期"
+const EXPIRING_SOON_DAYS: i32 = 3;
+
+// 托盘底部操作的快捷键（CmdOrCtrl 在 macOS 上为 Cmd，其它平台为 Ctrl）
+const ACCELERATOR_START_TIMER: &str = "CmdOrCtrl+T";
+const ACCELERATOR_OPEN_APP: &str = "CmdOrCtrl+O";
+const ACCELERATOR_QUIT: &str = "CmdOrCtrl+Q";
+
+// 衰退期或剩余天数不超过该值的赏味期咖啡豆，托盘图标切换为提醒样式
+const ATTENTION_DAYS: i32 = 2;
+
+// 库存统计，菜单统计区和图标提示文字共用，保证两处一致
+// 隐私模式下 total_grams 为 None，不显示任何重量
+struct InventorySummary {
+    bean_count: usize,
+    total_grams: Option<f64>,
+    expiring_soon: usize,
+}
+
+impl InventorySummary {
+    fn new(active_beans: &[BeanFreshnessInfo], private: bool) -> Self {
+        Self {
+            bean_count: active_beans.len(),
+            total_grams: (!private).then(|| active_beans.iter().filter_map(|b| b.bean.remaining_grams()).sum()),
+            expiring_soon: active_beans
+                .iter()
+                .filter(|b| b.freshness_state == FreshnessState::Optimal)
+                .filter(|b| b.end_day - b.days_since_roast_max <= EXPIRING_SOON_DAYS)
+                .count(),
+        }
+    }
     
-    // 按赏味期状态分类
-    let mut optimal_beans: Vec<&BeanFreshnessInfo> = active_beans
+    fn tooltip(&self, locale: Locale) -> String {
+        locale.format_inventory_summary(self.bean_count, self.total_grams, self.expiring_soon)
+    }
+}
+
+fn build_stats_items(
+    app: &tauri::AppHandle,
+    locale: Locale,
+    summary: &InventorySummary,
+    daily: &brews::DailyBrewStats,
+) -> tauri::Result<Vec<MenuItem<tauri::Wry>>> {
+    let count_label = format!(
+        "{}{}",
+        locale.tr("库存数量：", "In stock: "),
+        locale.format_bean_count(summary.bean_count)
+    );
+    let count_item = MenuItemBuilder::with_id("stat_count", count_label)
+        .enabled(false)
+        .build(app)?;
+    
+    let mut items = vec![count_item];
+    if let Some(total_grams) = summary.total_grams {
+        let capacity_label = format!(
+            "{}{}",
+            locale.tr("库存容量：", "Total weight: "),
+            locale.format_weight(total_grams)
+        );
+        let capacity_item = MenuItemBuilder::with_id("stat_capacity", capacity_label)
+            .enabled(false)
+            .build(app)?;
+        items.push(capacity_item);
+    }
+    
+    let mut daily_label = format!(
+        "{}{}",
+        locale.tr("今日冲煮：", "Brewed today: "),
+        locale.format_cup_count(daily.count)
+    );
+    if summary.total_grams.is_some() {
+        daily_label = format!("{} / {}", daily_label, locale.format_weight(daily.grams));
+    }
+    let daily_item = MenuItemBuilder::with_id("stat_daily", daily_label)
+        .enabled(false)
+        .build(app)?;
+    items.push(daily_item);
+    
+    Ok(items)
+}
+
+// 咖啡豆菜单项的文案生成函数（每个分区一种）
+type BeanLabelFn = fn(&BeanFreshnessInfo, Locale) -> String;
+
+fn build_bean_submenu(
+    app: &tauri::AppHandle,
+    locale: Locale,
+    tray_settings: &settings::TraySettings,
+    section: Option<settings::TraySection>,
+    title: String,
+    beans: &[&BeanFreshnessInfo],
+    label: BeanLabelFn,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    // 库存很多时每个分区（分组）只构建前几项，完整列表到应用里查看
+    let limit = match tray_settings.max_items_per_section {
+        0 => beans.len(),
+        max => max.min(beans.len()),
+    };
+    let mut submenu = SubmenuBuilder::new(app, title);
+    for info in beans[..limit].iter() {
+        let entry = bean_entry(app, locale, tray_settings, info, label(info, locale))?;
+        submenu = submenu.item(&entry);
+    }
+    if limit < beans.len() {
+        // 分组没有对应的筛选条件，打开完整的咖啡豆列表
+        let id = match section {
+            Some(section) => format!("show_all:{}", section.id()),
+            None => "show_all".to_string(),
+        };
+        let show_all = MenuItemBuilder::with_id(id, locale.format_show_all(beans.len())).build(app)?;
+        submenu = submenu.separator().item(&show_all);
+    }
+    submenu.build()
+}
+
+// 赏味期进度条（如 "▓▓▓░░ 62%"）
+fn progress_bar(percent: f32) -> String {
+    const CELLS: usize = 5;
+    let percent = percent.clamp(0.0, 100.0);
+    let filled = (percent / 100.0 * CELLS as f32).round() as usize;
+    format!("{}{} {:.0}%", "▓".repeat(filled), "░".repeat(CELLS - filled), percent)
+}
+
+// 单个咖啡豆的菜单项，缺少 ID 的咖啡豆显示为不可点击
+fn bean_entry(
+    app: &tauri::AppHandle,
+    locale: Locale,
+    tray_settings: &settings::TraySettings,
+    info: &BeanFreshnessInfo,
+    mut text: String,
+) -> tauri::Result<MenuItemKind<tauri::Wry>> {
+    // 可选：赏味期内的咖啡豆显示进度条
+    if tray_settings.show_progress && info.freshness_state == FreshnessState::Optimal {
+        text = format!("{} · {}", text, progress_bar(info.progress_percent));
+    }
+    // 可选：在末尾显示剩余克数
+    if tray_settings.show_remaining {
+        if let Some(grams) = info.bean.remaining_grams() {
+            text = format!("{} · {}", text, locale.format_weight(grams));
+        }
+    }
+    match info.menu_id {
+        Some(ref menu_id) => bean_action_submenu(app, locale, info, menu_id, text).map(MenuItemKind::Submenu),
+        None => IconMenuItemBuilder::new(text)
+            .icon(tray_icon::state_dot(&info.freshness_state))
+            .enabled(false)
+            .build(app)
+            .map(MenuItemKind::Icon),
+    }
+}
+
+// 最近冲煮子菜单，点击跳转到笔记详情
+fn build_recent_brews_submenu(
+    app: &tauri::AppHandle,
+    locale: Locale,
+    recent_brews: &[brews::RecentBrew],
+    private: bool,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut submenu = SubmenuBuilder::new(app, locale.tr("最近冲煮", "Recent Brews"));
+    for brew in recent_brews {
+        let item = MenuItemBuilder::with_id(format!("note:{}", brew.id), brew.label(locale, now, private)).build(app)?;
+        submenu = submenu.item(&item);
+    }
+    submenu.build()
+}
+
+// 不在赏味期分区中显示的咖啡豆（置顶、按烘焙商等分组），文案前加上状态名
+fn state_label(info: &BeanFreshnessInfo, locale: Locale) -> String {
+    format!("{} · {}", freshness_state_name(&info.freshness_state, locale), days_label(info, locale))
+}
+
+// 赏味期状态对应的天数：赏味期显示距离结束的天数，养豆期显示距离进入赏味期的天数，
+// 衰退期显示超过赏味期的天数，其它状态没有天数
+fn freshness_days(info: &BeanFreshnessInfo, locale: Locale) -> Option<String> {
+    match info.freshness_state {
+        FreshnessState::Optimal => Some(locale.format_day_span(
+            info.end_day - info.days_since_roast_max,
+            info.end_day - info.days_since_roast,
+        )),
+        FreshnessState::Resting => Some(locale.format_day_span(
+            (info.start_day - info.days_since_roast_max).max(0),
+            info.start_day - info.days_since_roast,
+        )),
+        FreshnessState::Decline => Some(locale.format_overdue_span(
+            info.days_since_roast - info.end_day,
+            info.days_since_roast_max - info.end_day,
+        )),
+        FreshnessState::InTransit => info.days_until_arrival.map(|days| locale.format_arrival(days)),
+        FreshnessState::Frozen | FreshnessState::Unknown => None,
+    }
+}
+
+// 赏味期 / 养豆期 / 衰退期显示天数 + 名称，在途中显示预计到货时间（未填写时只显示名称），冷冻中只显示名称
+fn days_label(info: &BeanFreshnessInfo, locale: Locale) -> String {
+    let name = truncate_name(&info.bean.name, 16);
+    match freshness_days(info, locale) {
+        Some(days) => format!("{} · {}", days, name),
+        None => name,
+    }
+}
+
+fn freshness_state_name(state: &FreshnessState, locale: Locale) -> &'static str {
+    match state {
+        FreshnessState::Frozen => locale.tr("冷冻中", "Frozen"),
+        FreshnessState::Optimal => locale.tr("赏味期", "Optimal"),
+        FreshnessState::Resting => locale.tr("养豆期", "Resting"),
+        FreshnessState::Decline => locale.tr("衰退期", "Past peak"),
+        FreshnessState::InTransit => locale.tr("在途中", "In transit"),
+        FreshnessState::Unknown => locale.tr("未知", "Unknown"),
+    }
+}
+
+// 托盘「复制信息」：名称、烘焙商、烘焙日期、剩余量和赏味期状态，每项一行
+fn bean_share_text(info: &BeanFreshnessInfo, locale: Locale) -> String {
+    let bean = &info.bean;
+    let mut lines = vec![bean.name.clone()];
+    if let Some(roaster) = bean.roaster.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        lines.push(format!("{}{}", locale.tr("烘焙商：", "Roaster: "), roaster));
+    }
+    if let Some(roast_date) = bean.roast_date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        lines.push(format!("{}{}", locale.tr("烘焙日期：", "Roasted: "), roast_date));
+    }
+    if let Some(remaining) = bean.remaining_grams() {
+        let capacity = bean.capacity.as_deref().and_then(|c| c.trim().parse::<f64>().ok());
+        let amount = match capacity {
+            Some(capacity) => format!("{} / {}", locale.format_weight(remaining), locale.format_weight(capacity)),
+            None => locale.format_weight(remaining),
+        };
+        lines.push(format!("{}{}", locale.tr("剩余：", "Remaining: "), amount));
+    }
+    let state = freshness_state_name(&info.freshness_state, locale);
+    let status = match freshness_days(info, locale) {
+        Some(days) => format!("{} · {}", state, days),
+        None => state.to_string(),
+    };
+    lines.push(format!("{}{}", locale.tr("状态：", "Status: "), status));
+    lines.join("\n")
+}
+
+// 菜单栏标题（如 "☕ 3 天"）
+fn tray_title(mode: settings::TrayTitleMode, locale: Locale, active_beans: &[BeanFreshnessInfo]) -> Option<String> {
+    let optimal_beans: Vec<&BeanFreshnessInfo> = active_beans
         .iter()
         .filter(|b| b.freshness_state == FreshnessState::Optimal)
         .collect();
-    let mut resting_beans: Vec<&BeanFreshnessInfo> = active_beans
-        .iter()
-        .filter(|b| b.freshness_state == FreshnessState::Resting)
-        .collect();
-    let mut decline_beans: Vec<&BeanFreshnessInfo> = active_beans
-        .iter()
-        .filter(|b| b.freshness_state == FreshnessState::Decline)
-        .collect();
-    let frozen_beans: Vec<&BeanFreshnessInfo> = active_beans
+    match mode {
+        settings::TrayTitleMode::Off => None,
+        settings::TrayTitleMode::Count => Some(format!("☕ {}", optimal_beans.len())),
+        settings::TrayTitleMode::Countdown => {
+            // 烘焙日期不精确时按最早离开赏味期计算
+            let days_left = optimal_beans.iter().map(|b| b.end_day - b.days_since_roast_max).min()?;
+            Some(format!("☕ {}", locale.format_days(days_left.max(0)).trim_start()))
+        }
+    }
+}
+
+pub(crate) fn update_tray_with_beans(app: &tauri::AppHandle, beans: Vec<CoffeeBean>) -> Result<BeanIdReport, Box<dyn std::error::Error>> {
+    let today = today(app);
+    let locale = current_locale(app);
+    let tray_settings = settings::get(app).tray;
+    
+    // 检查重复/缺失的咖啡豆 ID
+    let (menu_ids, id_report) = assign_menu_ids(&beans);
+    if id_report.has_issues() {
+        log::warn!(
+            "托盘咖啡豆 ID 异常：重复 {:?}，缺失 {} 个",
+            id_report.duplicate_ids,
+            id_report.missing_id_count
+        );
+        let _ = app.emit("tray-bean-id-issues", &id_report);
+    }
+    
+    // 过滤出有剩余量的咖啡豆
+    let mut active_beans: Vec<BeanFreshnessInfo> = beans
         .iter()
-        .filter(|b| b.freshness_state == FreshnessState::Frozen)
+        .zip(menu_ids)
+        .filter(|(b, _)| b.remaining_grams().unwrap_or(0.0) > 0.0)
+        .map(|(b, menu_id)| BeanFreshnessInfo {
+            menu_id,
+            ..calculate_freshness(b, today)
+        })
         .collect();
-    let in_transit_beans: Vec<&BeanFreshnessInfo> = active_beans
+    
+    // 小组件和快速面板同样遵循隐私模式
+    let snapshot = widget::build_snapshot(&active_beans, today, tray_settings.privacy_mode.then_some(locale));
+    refresh_widget_snapshot(app, &snapshot);
+    quick_panel::publish(app, &snapshot);
+    
+    // 隐私模式：名称换成编号（在隐藏之前编号，与小组件一致），不显示烘焙商等信息和剩余克数
+    let tray_settings = if tray_settings.privacy_mode {
+        for (index, info) in active_beans.iter_mut().enumerate() {
+            info.bean.name = locale.format_private_bean_name(index + 1);
+            info.bean.roaster = None;
+            info.bean.origin = None;
+            info.bean.process = None;
+        }
+        settings::TraySettings {
+            show_remaining: false,
+            ..tray_settings
+        }
+    } else {
+        tray_settings
+    };
+    
+    // 暂时隐藏的咖啡豆只从托盘移除，小组件和快速面板照常显示
+    snooze::hide_snoozed(app, &mut active_beans, today);
+    
+    // 置顶的咖啡豆单独显示在顶层，不再出现在分区里
+    let (pinned_beans, sectioned_beans): (Vec<&BeanFreshnessInfo>, Vec<&BeanFreshnessInfo>) =
+        active_beans.iter().partition(|b| b.bean.is_pinned());
+    
+    // 按赏味期状态分类
+    let in_state = |state: FreshnessState| -> Vec<&BeanFreshnessInfo> {
+        sectioned_beans.iter().copied().filter(|b| b.freshness_state == state).collect()
+    };
+    let mut optimal_beans = in_state(FreshnessState::Optimal);
+    let mut resting_beans = in_state(FreshnessState::Resting);
+    let mut decline_beans = in_state(FreshnessState::Decline);
+    let mut frozen_beans = in_state(FreshnessState::Frozen);
+    let mut in_transit_beans = in_state(FreshnessState::InTransit);
+    
+    // 即将喝完：剩余量低于阈值，按剩余克数升序，同时也保留在各自的状态分区里
+    let mut low_stock_beans: Vec<&BeanFreshnessInfo> = sectioned_beans
         .iter()
-        .filter(|b| b.freshness_state == FreshnessState::InTransit)
+        .copied()
+        .filter(|b| b.bean.remaining_grams().is_some_and(|g| g < tray_settings.low_stock_grams))
         .collect();
+    low_stock_beans.sort_by(|a, b| {
+        let a_remaining = a.bean.remaining_grams().unwrap_or(0.0);
+        let b_remaining = b.bean.remaining_grams().unwrap_or(0.0);
+        a_remaining.total_cmp(&b_remaining)
+    });
     
     // 排序：最佳赏味期按剩余天数升序（快过期的排前面）
     optimal_beans.sort_by(|a, b| {
@@ -236,129 +1269,232 @@ fn update_tray_with_beans(app: &tauri::AppHandle, beans: Vec<CoffeeBean>) -> Res
     });
     
     // 衰退期按过期天数升序
-    decline_beans.sort_by(|a, b| a.days_since_roast.cmp(&b.days_since_roast));
+    decline_beans.sort_by_key(|b| b.days_since_roast);
     
-    // === 统计数据 ===
-    let bean_count = active_beans.len();
-    let total_capacity: f64 = active_beans
+    // 用户选择了其它排序方式时覆盖上面的默认排序
+    for section_beans in [&mut frozen_beans, &mut optimal_beans, &mut resting_beans, &mut decline_beans, &mut in_transit_beans] {
+        match tray_settings.sort {
+            settings::TraySort::DaysLeft => {}
+            settings::TraySort::Remaining => section_beans.sort_by(|a, b| {
+                let a_remaining = a.bean.remaining_grams().unwrap_or(0.0);
+                let b_remaining = b.bean.remaining_grams().unwrap_or(0.0);
+                a_remaining.total_cmp(&b_remaining)
+            }),
+            settings::TraySort::Name => section_beans.sort_by_key(|b| b.bean.name.to_lowercase()),
+        }
+    }
+    
+    // 图标角标：赏味期内的咖啡豆数量；有咖啡豆进入衰退期或即将离开赏味期时显示提醒样式
+    let optimal_count = active_beans
         .iter()
-        .filter_map(|b| b.bean.remaining.as_ref()?.parse::<f64>().ok())
-        .sum();
+        .filter(|b| b.freshness_state == FreshnessState::Optimal)
+        .count();
+    let attention = active_beans.iter().any(|b| match b.freshness_state {
+        FreshnessState::Decline => true,
+        FreshnessState::Optimal => b.end_day - b.days_since_roast_max <= ATTENTION_DAYS,
+        _ => false,
+    });
+    let icon_result = tray_icon::update(
+        app,
+        tray_icon::TrayIconParams {
+            badge: tray_settings.badge.then_some(optimal_count),
+            attention,
+        },
+    );
+    
+    // === 统计数据 ===
+    let summary = InventorySummary::new(&active_beans, tray_settings.privacy_mode);
     
     // 构建菜单
     let mut menu_builder = MenuBuilder::new(app);
+    let mut diagnostics: Vec<TrayDiagnostic> = Vec::new();
+    if let Err(e) = icon_result {
+        diagnostics.push(TrayDiagnostic::new("icon", e));
+    }
     
     // === 第一块：统计信息 ===
-    let count_item = MenuItemBuilder::with_id("stat_count", format!("库存数量：{} 款", bean_count))
-        .enabled(false)
-        .build(app)?;
-    
-    let capacity_item = MenuItemBuilder::with_id("stat_capacity", format!("库存容量：{}", format_capacity(total_capacity)))
-        .enabled(false)
-        .build(app)?;
+    let daily = brews::daily_stats(app, today);
+    match build_stats_items(app, locale, &summary, &daily) {
+        Ok(items) => {
+            for item in items.iter() {
+                menu_builder = menu_builder.item(item);
+            }
+            menu_builder = menu_builder.separator();
+        }
+        Err(e) => diagnostics.push(TrayDiagnostic::new("stats", e)),
+    }
     
-    menu_builder = menu_builder
-        .item(&count_item)
-        .item(&capacity_item)
-        .separator();
+    // === 置顶的咖啡豆 ===
+    if !pinned_beans.is_empty() {
+        for info in pinned_beans.iter() {
+            match bean_entry(app, locale, &tray_settings, info, state_label(info, locale)) {
+                Ok(entry) => menu_builder = menu_builder.item(&entry),
+                Err(e) => diagnostics.push(TrayDiagnostic::new("pinned", e)),
+            }
+        }
+        menu_builder = menu_builder.separator();
+    }
     
     // === 第二块：按赏味期分类的子菜单 ===
-    // 排序：冷冻中 / 赏味期 / 养豆期 / 衰退期 / 在途中
-    
-    // 1. 冷冻中
-    if !frozen_beans.is_empty() {
-        let mut submenu = SubmenuBuilder::new(app, format!("冷冻中（{} 款）", frozen_beans.len()));
-        for info in frozen_beans.iter() {
-            let name = truncate_name(&info.bean.name, 16);
-            let item = MenuItemBuilder::with_id(format!("bean:{}", info.bean.id), name).build(app)?;
-            submenu = submenu.item(&item);
-        }
-        menu_builder = menu_builder.item(&submenu.build()?);
-    }
-    
-    // 2. 赏味期
-    if !optimal_beans.is_empty() {
-        let mut submenu = SubmenuBuilder::new(app, format!("赏味期（{} 款）", optimal_beans.len()));
-        for info in optimal_beans.iter() {
-            let days_left = info.end_day - info.days_since_roast;
-            let name = truncate_name(&info.bean.name, 16);
-            let label = format!("{:>2} 天 · {}", days_left, name);
-            // 使用 bean: 前缀 + 咖啡豆 ID 作为菜单项 ID
-            let item = MenuItemBuilder::with_id(format!("bean:{}", info.bean.id), label).build(app)?;
-            submenu = submenu.item(&item);
+    // 默认顺序：冷冻中 / 赏味期 / 养豆期 / 衰退期 / 在途中 / 即将喝完，可在托盘偏好中调整或隐藏
+    // 每个分区独立构建，某个分区失败时跳过该分区，其余分区照常显示
+    let sections: Vec<(settings::TraySection, &str, &[&BeanFreshnessInfo], BeanLabelFn)> = tray_settings
+        .visible_sections()
+        .into_iter()
+        .map(|section| -> (settings::TraySection, &str, &[&BeanFreshnessInfo], BeanLabelFn) {
+            match section {
+                settings::TraySection::Frozen => (section, locale.tr("冷冻中", "Frozen"), &frozen_beans, days_label),
+                settings::TraySection::Optimal => (section, locale.tr("赏味期", "Optimal"), &optimal_beans, days_label),
+                settings::TraySection::Resting => (section, locale.tr("养豆期", "Resting"), &resting_beans, days_label),
+                settings::TraySection::Decline => (section, locale.tr("衰退期", "Past peak"), &decline_beans, days_label),
+                settings::TraySection::InTransit => (section, locale.tr("在途中", "In transit"), &in_transit_beans, days_label),
+                settings::TraySection::LowStock => (section, locale.tr("即将喝完", "Running low"), &low_stock_beans, state_label),
+            }
+        })
+        .collect();
+    
+    if tray_settings.grouping == settings::TrayGrouping::State {
+        // 「即将喝完」分区总是显示剩余克数（隐私模式除外）
+        let low_stock_settings = settings::TraySettings {
+            show_remaining: !tray_settings.privacy_mode,
+            ..tray_settings.clone()
+        };
+        for (section, title, section_beans, label) in sections {
+            if section_beans.is_empty() {
+                continue;
+            }
+            let title = locale.format_section_title(title, section_beans.len());
+            let section_settings = match section {
+                settings::TraySection::LowStock => &low_stock_settings,
+                _ => &tray_settings,
+            };
+            match build_bean_submenu(app, locale, section_settings, Some(section), title, section_beans, label) {
+                Ok(submenu) => menu_builder = menu_builder.item(&submenu),
+                Err(e) => diagnostics.push(TrayDiagnostic::new(section.id(), e)),
+            }
         }
-        menu_builder = menu_builder.item(&submenu.build()?);
-    }
-    
-    // 3. 养豆期
-    if !resting_beans.is_empty() {
-        let mut submenu = SubmenuBuilder::new(app, format!("养豆期（{} 款）", resting_beans.len()));
-        for info in resting_beans.iter() {
-            let days_until_optimal = info.start_day - info.days_since_roast;
-            let name = truncate_name(&info.bean.name, 16);
-            let label = format!("{:>2} 天 · {}", days_until_optimal, name);
-            let item = MenuItemBuilder::with_id(format!("bean:{}", info.bean.id), label).build(app)?;
-            submenu = submenu.item(&item);
+    } else {
+        // 按烘焙商 / 产地 / 处理法分组，组内沿用上面各分区的顺序，未填写的放在最后
+        let mut groups: BTreeMap<&str, Vec<&BeanFreshnessInfo>> = BTreeMap::new();
+        let mut ungrouped: Vec<&BeanFreshnessInfo> = Vec::new();
+        let state_sections = sections
+            .iter()
+            .filter(|(section, _, _, _)| *section != settings::TraySection::LowStock);
+        for info in state_sections.flat_map(|(_, _, section_beans, _)| section_beans.iter().copied()) {
+            match info.bean.group_key(tray_settings.grouping) {
+                Some(key) => groups.entry(key).or_default().push(info),
+                None => ungrouped.push(info),
+            }
         }
-        menu_builder = menu_builder.item(&submenu.build()?);
-    }
-    
-    // 4. 衰退期
-    if !decline_beans.is_empty() {
-        let mut submenu = SubmenuBuilder::new(app, format!("衰退期（{} 款）", decline_beans.len()));
-        for info in decline_beans.iter() {
-            let days_over = info.days_since_roast - info.end_day;
-            let name = truncate_name(&info.bean.name, 16);
-            let label = format!("+{} 天 · {}", days_over, name);
-            let item = MenuItemBuilder::with_id(format!("bean:{}", info.bean.id), label).build(app)?;
-            submenu = submenu.item(&item);
+        let other = (locale.tr("未填写", "Other"), ungrouped);
+        for (title, group_beans) in groups.into_iter().chain(std::iter::once(other)) {
+            if group_beans.is_empty() {
+                continue;
+            }
+            let title = locale.format_section_title(title, group_beans.len());
+            match build_bean_submenu(app, locale, &tray_settings, None, title, &group_beans, state_label) {
+                Ok(submenu) => menu_builder = menu_builder.item(&submenu),
+                Err(e) => diagnostics.push(TrayDiagnostic::new("group", e)),
+            }
         }
-        menu_builder = menu_builder.item(&submenu.build()?);
     }
     
-    // 5. 在途中
-    if !in_transit_beans.is_empty() {
-        let mut submenu = SubmenuBuilder::new(app, format!("在途中（{} 款）", in_transit_beans.len()));
-        for info in in_transit_beans.iter() {
-            let name = truncate_name(&info.bean.name, 16);
-            let item = MenuItemBuilder::with_id(format!("bean:{}", info.bean.id), name).build(app)?;
-            submenu = submenu.item(&item);
+    // === 最近冲煮 ===
+    let recent_brews = brews::recent(app);
+    if !recent_brews.is_empty() {
+        match build_recent_brews_submenu(app, locale, &recent_brews, tray_settings.privacy_mode) {
+            Ok(submenu) => menu_builder = menu_builder.separator().item(&submenu),
+            Err(e) => diagnostics.push(TrayDiagnostic::new("recent_brews", e)),
         }
-        menu_builder = menu_builder.item(&submenu.build()?);
     }
     
     // 如果没有任何咖啡豆
     if active_beans.is_empty() {
-        let empty = MenuItemBuilder::with_id("empty", "暂无咖啡豆库存")
+        let empty = MenuItemBuilder::with_id("empty", locale.tr("暂无咖啡豆库存", "No beans in stock"))
             .enabled(false)
             .build(app)?;
         menu_builder = menu_builder.item(&empty);
     }
     
+    // 部分分区构建失败：显示提示并通知前端
+    if !diagnostics.is_empty() {
+        for diagnostic in diagnostics.iter() {
+            log::error!("托盘分区 {} 构建失败：{}", diagnostic.section, diagnostic.error);
+            diagnostics::record_error(app, "tray", format!("{}: {}", diagnostic.section, diagnostic.error));
+        }
+        let partial = MenuItemBuilder::with_id("partial_failure", locale.tr("部分数据加载失败", "Some data failed to load"))
+            .enabled(false)
+            .build(app)?;
+        menu_builder = menu_builder.separator().item(&partial);
+        let _ = app.emit("tray-diagnostic", &diagnostics);
+    }
+    
+    // 有新版本时显示更新入口
+    if let Some(update) = updater::available_update(app) {
+        let label = format!("{} {}", locale.tr("有新版本", "Update available:"), update.version);
+        let update_item = MenuItemBuilder::with_id("update_available", label).build(app)?;
+        menu_builder = menu_builder.separator().item(&update_item);
+    }
+    
     // === 底部操作 ===
-    let open_app = MenuItemBuilder::with_id("open_app", "打开 Brew Guide")
+    // 计时进行中时禁用开始计时
+    let start_timer = MenuItemBuilder::with_id("start_timer", locale.tr("开始冲煮计时", "Start Brew Timer"))
+        .enabled(!timer::get(app).running)
+        .accelerator(ACCELERATOR_START_TIMER)
+        .build(app)?;
+    let quick_add = MenuItemBuilder::with_id("quick_add_bean", locale.tr("快速添加咖啡豆…", "Quick Add Bean…"))
+        .build(app)?;
+    let search = MenuItemBuilder::with_id("search_beans", locale.tr("搜索咖啡豆…", "Search Beans…"))
+        .build(app)?;
+    let open_app = MenuItemBuilder::with_id("open_app", locale.tr("打开 Brew Guide", "Open Brew Guide"))
+        .accelerator(ACCELERATOR_OPEN_APP)
+        .build(app)?;
+    let quit = MenuItemBuilder::with_id("quit", locale.tr("退出", "Quit"))
+        .accelerator(ACCELERATOR_QUIT)
+        .build(app)?;
+    let open_data_dir = MenuItemBuilder::with_id("open_data_dir", locale.tr("打开数据目录", "Open Data Folder"))
         .build(app)?;
-    let quit = MenuItemBuilder::with_id("quit", "退出")
+    let privacy_mode = CheckMenuItemBuilder::with_id("privacy_mode", locale.tr("隐私模式", "Privacy Mode"))
+        .checked(tray_settings.privacy_mode)
         .build(app)?;
+    let advanced = SubmenuBuilder::new(app, locale.tr("高级", "Advanced"))
+        .item(&open_data_dir)
+        .build()?;
     
     menu_builder = menu_builder
         .separator()
+        .item(&privacy_mode)
+        .item(&advanced)
+        .separator()
+        .item(&start_timer)
+        .item(&quick_add)
+        .item(&search)
         .item(&open_app)
         .item(&quit);
     
     let menu = menu_builder.build()?;
     
-    // 更新托盘菜单
+    // 更新托盘菜单、菜单栏标题（Windows 不显示标题）和图标提示文字
     if let Some(tray) = app.tray_by_id("main-tray") {
         tray.set_menu(Some(menu))?;
+        tray.set_title(tray_title(tray_settings.title_mode, locale, &active_beans))?;
+        tray.set_tooltip(Some(summary.tooltip(locale)))?;
+    }
+    // 冲煮计时中保持显示倒计时，不被咖啡豆变化后的刷新覆盖
+    if let Some(progress) = timer::progress(app) {
+        timer::show_countdown(app, &progress)?;
     }
     
-    Ok(())
+    Ok(id_report)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
@@ -368,18 +1504,93 @@ pub fn run() {
                 )?;
             }
             
+            // 设置需要最先加载，其它模块初始化时会读取
+            app.manage(Arc::new(Mutex::new(settings::SettingsStore::load(app.handle()))));
+            
             // 初始化托盘状态
             app.manage(Arc::new(Mutex::new(TrayState::default())));
+            app.manage(Arc::new(Mutex::new(tray_icon::TrayIconState::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(diagnostics::DiagnosticsState::load(app.handle()))));
+            diagnostics::install_panic_hook(app.handle().clone());
+            app.manage(Arc::new(Mutex::new(ClockState::default())));
+            app.manage(Arc::new(Mutex::new(Locale::default())));
+            app.manage(Arc::new(Mutex::new(WidgetState::default())));
+            app.manage(Arc::new(Mutex::new(BeanCache::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(brews::BrewCache::default())));
+            app.manage(Arc::new(Mutex::new(app_lock::AppLockState::load(app.handle()))));
+            app_lock::spawn_auto_lock_watcher(app.handle().clone());
+            app.manage(Arc::new(Mutex::new(updater::UpdaterState::default())));
+            app.manage(extensions::ExtensionRegistry::with_defaults());
+            app.manage(Arc::new(Mutex::new(telemetry::TelemetryState::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(timer::TimerState::default())));
+            app.manage(Arc::new(Mutex::new(snooze::SnoozeState::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(notifications::NotificationState::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(reminders::ReminderState::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(weekly_report::WeeklyReportState::load(app.handle()))));
+            app.manage(Arc::new(Mutex::new(sync_folder::SyncFolderState::default())));
+            app.manage(Arc::new(Mutex::new(lan_sync::LanSyncState::default())));
+            app.manage(Arc::new(Mutex::new(transfer::TransferState::default())));
+            app.manage(Arc::new(Mutex::new(file_import::FileImportState::default())));
+            app.manage(Arc::new(Mutex::new(mini_timer::MiniTimerState::default())));
+            app.manage(Arc::new(Mutex::new(watch_bridge::WatchSessionState::default())));
+            app.manage(Arc::new(Mutex::new(audio::AudioPlayer::spawn())));
+            app.manage(Arc::new(Mutex::new(speech::SpeechState::spawn())));
+            app.manage(Arc::new(Mutex::new(keep_awake::KeepAwakeState::default())));
+            match store::Store::open(app.handle()) {
+                Ok(store) => {
+                    app.manage(Arc::new(Mutex::new(store)));
+                    if let Err(e) = store::seed_outbox(app.handle()) {
+                        log::warn!("初始化数据库修改队列失败：{}", e);
+                    }
+                }
+                Err(e) => log::warn!("打开本地数据库失败：{}", e),
+            }
+            notifications::register_click_handler(app.handle());
+            telemetry::spawn_flush_loop(app.handle().clone());
+            
+            // 自动检查更新（仅桌面端）
+            #[cfg(desktop)]
+            updater::spawn_update_checker(app.handle().clone());
+            
+            // 定期检查日期变化，重新计算赏味期（仅桌面端，移动端由系统后台任务触发）
+            #[cfg(desktop)]
+            background::spawn_refresh_loop(app.handle().clone());
+
+            // 冲煮提醒（仅桌面端）
+            #[cfg(desktop)]
+            reminders::spawn_reminder_loop(app.handle().clone());
+
+            // 每日自动备份（仅桌面端）
+            #[cfg(desktop)]
+            backups::spawn_backup_loop(app.handle().clone());
+            #[cfg(desktop)]
+            webdav::spawn_upload_loop(app.handle().clone());
+
+            // 同步文件夹（仅桌面端）
+            #[cfg(desktop)]
+            sync_folder::start_from_settings(app.handle());
+
+            // 全局快捷键（仅桌面端）
+            #[cfg(desktop)]
+            {
+                app.handle().plugin(tauri_plugin_global_shortcut::Builder::new().build())?;
+                match hotkeys::register(app.handle()) {
+                    Ok(failed) if !failed.is_empty() => log::warn!("部分快捷键注册失败：{:?}", failed),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("注册快捷键失败：{}", e),
+                }
+            }
+
+            // 通过 .brewguide 文件启动（Windows / Linux）
+            #[cfg(all(desktop, not(target_os = "macos")))]
+            file_import::open_files(app.handle(), file_import::paths_from_args(std::env::args_os()));
             
             // 监听应用激活事件（点击 Dock 图标时显示窗口）
             #[cfg(desktop)]
             {
                 let app_handle = app.handle().clone();
                 app.listen("tauri://activated", move |_| {
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
+                    show_main_window(&app_handle);
                 });
             }
             
@@ -389,6 +1600,12 @@ pub fn run() {
                 if let Some(window) = app.get_webview_window("main") {
                     let app_handle = app.handle().clone();
                     window.on_window_event(move |event| {
+                        // 系统主题变化：托盘图标跟随任务栏颜色（Windows）
+                        if let tauri::WindowEvent::ThemeChanged(_) = event {
+                            if let Err(e) = tray_icon::redraw(&app_handle) {
+                                log::warn!("更新托盘图标失败：{}", e);
+                            }
+                        }
                         if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                             // 检查托盘图标是否可见
                             let tray_visible = if let Some(state) = app_handle.try_state::<Arc<Mutex<TrayState>>>() {
@@ -397,18 +1614,18 @@ pub fn run() {
                                 false
                             };
                             
-                            if tray_visible {
-                                // 托盘图标可见时：阻止关闭，隐藏窗口
-                                api.prevent_close();
-                                let _ = app_handle.get_webview_window("main").unwrap().hide();
-                                
-                                // macOS: 窗口隐藏后重新隐藏 Dock 图标
-                                #[cfg(target_os = "macos")]
-                                {
-                                    let _ = app_handle.set_activation_policy(ActivationPolicy::Accessory);
-                                }
+                            // 托盘图标不可见时总是退出；快速面板等窗口可能还在，需要主动退出应用
+                            let behavior = if tray_visible {
+                                settings::get(&app_handle).close_behavior
+                            } else {
+                                settings::CloseBehavior::Quit
+                            };
+                            api.prevent_close();
+                            match behavior {
+                                settings::CloseBehavior::Hide => hide_main_window(&app_handle),
+                                settings::CloseBehavior::Quit => app_handle.exit(0),
+                                settings::CloseBehavior::Ask => ask_close_behavior(&app_handle),
                             }
-                            // 托盘图标不可见时：允许关闭（退出应用）
                         }
                     });
                 }
@@ -428,8 +1645,10 @@ pub fn run() {
                     .enabled(false)
                     .build(app)?;
                 let open_app = MenuItemBuilder::with_id("open_app", "打开 Brew Guide")
+                    .accelerator(ACCELERATOR_OPEN_APP)
                     .build(app)?;
                 let quit = MenuItemBuilder::with_id("quit", "退出")
+                    .accelerator(ACCELERATOR_QUIT)
                     .build(app)?;
                 
                 let menu = MenuBuilder::new(app)
@@ -465,37 +1684,116 @@ pub fn run() {
                     .on_menu_event(|app, event| {
                         match event.id().as_ref() {
                             "open_app" => {
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let _ = window.show();
-                                    let _ = window.set_focus();
-                                }
+                                show_main_window(app);
                             }
                             "quit" => {
                                 app.exit(0);
                             }
-                            id if id.starts_with("bean:") => {
-                                // 解析咖啡豆 ID
-                                let bean_id = id.strip_prefix("bean:").unwrap_or("");
-                                
-                                // 显示窗口
-                                if let Some(window) = app.get_webview_window("main") {
-                                    let _ = window.show();
-                                    let _ = window.set_focus();
+                            "update_available" => {
+                                telemetry::record(app, "tray.update");
+                                updater::open_update(app);
+                            }
+                            "quick_add_bean" => {
+                                if let Err(e) = quick_add::open(app) {
+                                    log::warn!("打开快速添加窗口失败：{}", e);
                                 }
-                                
-                                // 发送事件给前端，携带咖啡豆 ID
-                                let _ = app.emit("navigate-to-bean", bean_id);
+                            }
+                            "privacy_mode" => {
+                                let enabled = !settings::get(app).tray.privacy_mode;
+                                if let Err(e) = set_privacy_mode(app, enabled) {
+                                    log::warn!("切换隐私模式失败：{}", e);
+                                }
+                            }
+                            "open_data_dir" => {
+                                telemetry::record(app, "tray.open_data_dir");
+                                if let Err(e) = open_app_data_dir(app) {
+                                    log::warn!("打开数据目录失败：{}", e);
+                                }
+                            }
+                            "search_beans" => {
+                                if let Err(e) = bean_search::open(app) {
+                                    log::warn!("打开搜索窗口失败：{}", e);
+                                }
+                            }
+                            "start_timer" => {
+                                telemetry::record(app, "tray.start_timer");
+                                timer::start_from_tray(app);
+                            }
+                            id if id.starts_with("note:") => {
+                                let note_id = id.trim_start_matches("note:").to_string();
+                                telemetry::record(app, "tray.open_note");
+                                let _ = navigate_to(app, NavigationTarget::Note { note_id });
+                            }
+                            id if id.starts_with("unpin|") => {
+                                if let Some(bean_id) = id.strip_prefix("unpin|").and_then(parse_bean_menu_id) {
+                                    unpin_from_tray(app, bean_id);
+                                }
+                            }
+                            id if id.starts_with("arrived|") => {
+                                if let Some(bean_id) = id.strip_prefix("arrived|").and_then(parse_bean_menu_id) {
+                                    arrived_from_tray(app, bean_id);
+                                }
+                            }
+                            id if id.starts_with("snooze|") => {
+                                if let Some(bean_id) = id.strip_prefix("snooze|").and_then(parse_bean_menu_id) {
+                                    telemetry::record(app, "tray.snooze");
+                                    if let Err(e) = snooze::snooze(app, bean_id, snooze::SNOOZE_DAYS) {
+                                        log::warn!("隐藏咖啡豆失败：{}", e);
+                                    }
+                                }
+                            }
+                            id if id.starts_with("copy|") => {
+                                if let Some(bean_id) = id.strip_prefix("copy|").and_then(parse_bean_menu_id) {
+                                    if let Err(e) = copy_from_tray(app, bean_id) {
+                                        log::warn!("复制咖啡豆信息失败：{}", e);
+                                    }
+                                }
+                            }
+                            id if id.starts_with("finish|") => {
+                                if let Some(bean_id) = id.strip_prefix("finish|").and_then(parse_bean_menu_id) {
+                                    finish_from_tray(app, bean_id);
+                                }
+                            }
+                            id if id.starts_with("consume:") => {
+                                if let Some((bean_id, amount)) = parse_consume_menu_id(id) {
+                                    consume_from_tray(app, bean_id, amount);
+                                }
+                            }
+                            "show_all" => {
+                                // 分组模式：打开完整的咖啡豆列表
+                                telemetry::record(app, "tray.show_all");
+                                let _ = navigate_to(app, NavigationTarget::BeanList { section: None });
+                            }
+                            id if id.starts_with("show_all:") => {
+                                // 打开应用中按该状态筛选的咖啡豆列表
+                                if let Some(section) = id.strip_prefix("show_all:").and_then(settings::TraySection::from_id) {
+                                    telemetry::record(app, "tray.show_all");
+                                    let _ = navigate_to(app, NavigationTarget::BeanList { section: Some(section) });
+                                }
+                            }
+                            id if id.starts_with("bean:") => {
+                                // 解析咖啡豆 ID，显示窗口并跳转到咖啡豆详情
+                                let bean_id = parse_bean_menu_id(id).unwrap_or("").to_string();
+                                telemetry::record(app, "tray.open_bean");
+                                let _ = navigate_to(app, NavigationTarget::Bean { bean_id });
                             }
                             _ => {}
                         }
                     })
                     .on_tray_icon_event(|tray, event| {
-                        if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
-                            let app = tray.app_handle();
-                            if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.show();
-                                let _ = window.set_focus();
+                        let app = tray.app_handle();
+                        let tray_settings = settings::get(app).tray;
+                        match event {
+                            TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, rect, .. } => {
+                                run_tray_click_action(app, tray_settings.left_click, &rect);
+                            }
+                            TrayIconEvent::DoubleClick { button: MouseButton::Left, rect, .. } => {
+                                run_tray_click_action(app, tray_settings.double_click, &rect);
                             }
+                            TrayIconEvent::Click { button: MouseButton::Middle, button_state: MouseButtonState::Up, rect, .. } => {
+                                run_tray_click_action(app, tray_settings.middle_click, &rect);
+                            }
+                            _ => {}
                         }
                     })
                     .build(app)?;
@@ -507,18 +1805,231 @@ pub fn run() {
                 }
             }
             
+            // 应用保存的设置（托盘可见性、时区、语言）
+            apply_settings(app.handle(), &settings::get(app.handle()));
+            background::render_saved(app.handle());
+            
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![update_tray_menu, set_tray_visible])
+        .invoke_handler(tauri::generate_handler![
+            update_tray_menu,
+            set_tray_visible,
+            set_tray_show_remaining,
+            set_tray_show_progress,
+            set_tray_privacy_mode,
+            set_tray_title_mode,
+            set_tray_max_items,
+            set_tray_preferences,
+            set_tray_grouping,
+            set_tray_low_stock_threshold,
+            set_tray_click_action,
+            set_close_behavior,
+            notifications::set_freshness_notifications,
+            notifications::set_low_stock_threshold,
+            notifications::set_low_stock_notifications,
+            notifications::watch_bean_freshness,
+            notifications::unwatch_bean_freshness,
+            notifications::get_watched_beans,
+            notifications::set_quiet_hours,
+            reminders::schedule_brew_reminder,
+            reminders::list_brew_reminders,
+            reminders::update_brew_reminder,
+            reminders::delete_brew_reminder,
+            weekly_report::get_weekly_report,
+            store::list_beans,
+            store::query_beans,
+            store::upsert_bean,
+            store::delete_bean,
+            store::take_store_changes,
+            notes::create_brew_note,
+            notes::update_brew_note,
+            notes::list_brew_notes,
+            notes::delete_brew_note,
+            search::search_all,
+            trash::list_trash,
+            trash::restore_from_trash,
+            trash::purge_trash_older_than,
+            journal::undo_last_change,
+            journal::redo,
+            journal::get_change_history,
+            integrity::check_data_integrity,
+            integrity::repair_data,
+            attachments::save_attachment,
+            attachments::gc_attachments,
+            bulk::bulk_update_beans,
+            bulk::bulk_delete,
+            duplicates::find_duplicate_beans,
+            duplicates::merge_beans,
+            backups::create_backup,
+            backups::list_backups,
+            backups::restore_backup,
+            encrypted_backup::export_backup_encrypted,
+            encrypted_backup::import_backup_encrypted,
+            webdav::configure_webdav,
+            webdav::backup_to_webdav,
+            webdav::list_webdav_backups,
+            webdav::restore_from_webdav,
+            s3::configure_s3,
+            s3::backup_to_s3,
+            s3::list_s3_backups,
+            s3::restore_from_s3,
+            sync_folder::set_sync_folder,
+            sync_folder::sync_folder_now,
+            sync_folder::get_sync_status,
+            conflicts::list_conflicts,
+            conflicts::resolve_conflict,
+            csv_export::export_csv,
+            csv_import::import_csv,
+            history::set_git_history,
+            history::list_history,
+            history::restore_history,
+            lan_sync::start_lan_sync,
+            lan_sync::stop_lan_sync,
+            lan_sync::list_lan_peers,
+            lan_sync::confirm_lan_pairing,
+            lan_sync::sync_with_peer,
+            markdown_export::export_markdown,
+            cards::generate_bean_card_pdf,
+            cards::generate_recipe_pdf,
+            label_printer::set_label_printer,
+            label_printer::print_bean_label,
+            file_import::take_file_import_requests,
+            file_import::confirm_file_import,
+            file_import::dismiss_file_import,
+            bean_share::share_bean,
+            bean_share::decode_bean_link,
+            clipboard_bean::parse_bean_from_clipboard,
+            barcode::lookup_barcode,
+            roaster_page::fetch_bean_from_url,
+            artisan::import_artisan_log,
+            artisan::list_roast_batches,
+            calendar::export_ics,
+            calendar::set_calendar_feed,
+            timer::start_brew_recipe,
+            timer::stop_brew_recipe,
+            timer::pause_brew_recipe,
+            timer::resume_brew_recipe,
+            timer::get_brew_progress,
+            timer::get_recoverable_timer,
+            timer::resume_recoverable_timer,
+            timer::discard_recoverable_timer,
+            timer_notification::get_timer_notification,
+            timer_notification::handle_timer_notification_action,
+            watch_bridge::get_watch_snapshot,
+            watch_bridge::set_watch_bean,
+            watch_bridge::watch_timer_command,
+            voice_log::log_brew_by_voice,
+            mini_timer::show_mini_timer,
+            mini_timer::hide_mini_timer,
+            hotkeys::set_hotkey,
+            audio::set_audio_cues,
+            speech::list_speech_voices,
+            speech::set_speech_settings,
+            speech::set_speech_muted,
+            keep_awake::set_keep_awake,
+            transfer::start_transfer_server,
+            transfer::stop_transfer_server,
+            transfer::transfer_with_device,
+            set_timezone,
+            set_locale,
+            set_widget_container_dir,
+            open_data_dir,
+            brews::update_tray_recent_brews,
+            brews::update_tray_daily_stats,
+            tray_icon::set_tray_icon,
+            tray_icon::reset_tray_icon,
+            quick_add::submit_quick_add_bean,
+            beanconqueror::import_beanconqueror,
+            bean_search::search_beans,
+            bean_search::fuzzy_search_beans,
+            bean_search::open_searched_bean,
+            quick_panel::get_quick_panel_snapshot,
+            quick_panel::open_from_quick_panel,
+            nfc::link_nfc_tag,
+            nfc::unlink_nfc_tag,
+            nfc::list_nfc_tags,
+            nfc::handle_nfc_tag,
+            navigation::list_app_shortcuts,
+            navigation::handle_app_shortcut,
+            background::run_background_refresh,
+            share_inbox::enqueue_shared_item,
+            share_inbox::take_shared_items,
+            app_lock::get_app_lock_status,
+            app_lock::configure_app_lock,
+            app_lock::touch_app_activity,
+            app_lock::lock_app,
+            app_lock::unlock_app,
+            app_lock::unlock_app_with_biometrics,
+            updater::check_for_updates,
+            updater::get_update_channel,
+            updater::set_update_channel,
+            diagnostics::get_diagnostics_consent,
+            diagnostics::set_diagnostics_consent,
+            diagnostics::create_diagnostics_bundle,
+            extensions::list_extensions,
+            extensions::match_scale_protocol,
+            extensions::parse_scale_weight,
+            extensions::import_with_extension,
+            extensions::export_with_extension,
+            settings::get_settings,
+            settings::set_settings,
+            telemetry::track_feature,
+            telemetry::get_telemetry_preview,
+            telemetry::set_telemetry_enabled,
+            timer::get_brew_timer_state,
+            timer::set_brew_timer_state,
+        ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|_app, _event| {
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { .. } = _event {
-                if let Some(window) = _app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+                show_main_window(_app);
+            }
+            // 打开 .brewguide 文件或分享的图片（macOS / iOS）
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            if let tauri::RunEvent::Opened { urls } = &_event {
+                file_import::open_urls(_app, urls);
+                share_inbox::open_urls(_app, urls);
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bean(id: &str) -> CoffeeBean {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": "测试" })).unwrap()
+    }
+
+    #[test]
+    fn duplicate_and_missing_ids_are_not_clickable() {
+        let beans = [bean("b1"), bean(" b2 "), bean("b1"), bean(""), bean("b1"), bean("b3#dup1")];
+        let (menu_ids, report) = assign_menu_ids(&beans);
+        assert_eq!(
+            menu_ids,
+            vec![
+                Some("bean:b1".to_string()),
+                Some("bean:b2".to_string()),
+                None,
+                None,
+                None,
+                Some("bean:b3#dup1".to_string()),
+            ]
+        );
+        assert_eq!(report.duplicate_ids, vec!["b1".to_string()]);
+        assert_eq!(report.missing_id_count, 1);
+    }
+
+    #[test]
+    fn parses_bean_menu_ids_verbatim() {
+        assert_eq!(parse_bean_menu_id("bean:b1"), Some("b1"));
+        // ID 本身带 "#dup" 时不能被截断
+        assert_eq!(parse_bean_menu_id("bean:b3#dup1"), Some("b3#dup1"));
+        assert_eq!(parse_bean_menu_id("note:n1"), None);
+        assert_eq!(parse_consume_menu_id("consume:18|bean:b3#dup1"), Some(("b3#dup1", Some(18.0))));
+        assert_eq!(parse_consume_menu_id("consume:custom|bean:a|b"), Some(("a|b", None)));
+        assert_eq!(parse_consume_menu_id("consume:lots|bean:b1"), None);
+    }
+}
@@ -0,0 +1,344 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+use tauri::Manager;
+
+use crate::attachments::ATTACHMENTS_DIR;
+use crate::store::{join_components, number_field, text_field, with_store};
+
+// 导出为 Markdown 文件夹（可以直接作为 Obsidian / Logseq 的库）：每款咖啡豆一个文件，front matter 为咖啡豆信息，
+// 正文为备注、图片和这款豆子的冲煮笔记（最新的在前），没有关联咖啡豆的笔记放在“其它笔记.md”；图片复制到 attachments/
+// 文件夹中的 .brew-guide-export.json 记录上次导出的文件和内容哈希，再次导出时只改写变化的文件，删除已经不存在的咖啡豆的文件
+const MANIFEST_FILE: &str = ".brew-guide-export.json";
+const UNLINKED_FILE: &str = "其它笔记.md";
+const MAX_FILE_NAME_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarkdownOptions {
+    pub bean_ids: Option<Vec<String>>, // 只导出这些咖啡豆，默认全部
+    pub skip_images: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownExportReport {
+    pub written: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub images: usize, // 新复制的图片
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, String>, // 相对路径 -> 内容的 BLAKE3 哈希
+}
+
+pub(crate) fn file_stem(name: &str, id: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']') || c.is_control() { ' ' } else { c })
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').trim();
+    if cleaned.is_empty() {
+        id.to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn format_time(millis: i64, timezone: Option<Tz>) -> String {
+    let Some(at) = DateTime::<Utc>::from_timestamp_millis(millis) else {
+        return String::new();
+    };
+    match timezone {
+        Some(tz) => at.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string(),
+        None => at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string(),
+    }
+}
+
+// 记录中引用的附件图片（旧数据中的 data URL 不导出）
+fn images<'a>(record: &'a Value, keys: &[&str]) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    for key in keys {
+        match record.get(*key) {
+            Some(Value::String(path)) => found.push(path.as_str()),
+            Some(Value::Array(paths)) => found.extend(paths.iter().filter_map(Value::as_str)),
+            _ => {}
+        }
+    }
+    found
+        .into_iter()
+        .filter(|path| path.starts_with(&format!("{}/", ATTACHMENTS_DIR)) && !path.contains(".."))
+        .collect()
+}
+
+fn front_matter(bean: &Value, out: &mut String) {
+    out.push_str("---\n");
+    for key in ["id", "name", "roaster", "roastLevel", "roastDate", "capacity", "remaining", "price", "beanType"] {
+        if let Some(text) = text_field(bean, key) {
+            let _ = writeln!(out, "{}: {}", key, yaml_string(text));
+        }
+    }
+    let origin = join_components(bean, |c| text_field(c, "origin").or_else(|| text_field(c, "country")));
+    let process = join_components(bean, |c| text_field(c, "process"));
+    let variety = join_components(bean, |c| text_field(c, "variety"));
+    for (key, value) in [("origin", origin), ("process", process), ("variety", variety)] {
+        if let Some(value) = value {
+            let _ = writeln!(out, "{}: {}", key, yaml_string(&value));
+        }
+    }
+    if let Some(rating) = number_field(bean, "overallRating") {
+        let _ = writeln!(out, "rating: {}", rating);
+    }
+    let flavor: Vec<&str> = bean.get("flavor").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect();
+    if !flavor.is_empty() {
+        out.push_str("flavor:\n");
+        for item in flavor {
+            let _ = writeln!(out, "  - {}", yaml_string(item));
+        }
+    }
+    out.push_str("tags:\n  - brew-guide/bean\n---\n\n");
+}
+
+fn write_images(paths: &[&str], out: &mut String) {
+    for path in paths {
+        let _ = writeln!(out, "![]({})", path);
+    }
+    if !paths.is_empty() {
+        out.push('\n');
+    }
+}
+
+fn write_note(note: &Value, timezone: Option<Tz>, include_images: bool, out: &mut String) {
+    let mut title = vec![note.get("timestamp").and_then(Value::as_i64).map(|t| format_time(t, timezone)).unwrap_or_default()];
+    title.extend(["equipment", "method"].iter().filter_map(|key| text_field(note, key)).map(str::to_string));
+    let _ = writeln!(out, "### {}\n", title.join(" · "));
+
+    let params = note.get("params").unwrap_or(&Value::Null);
+    let mut details: Vec<String> = [("粉量", "coffee"), ("水量", "water"), ("粉水比", "ratio"), ("研磨度", "grindSize"), ("水温", "temp")]
+        .iter()
+        .filter_map(|(label, key)| text_field(params, key).map(|value| format!("{} {}", label, value)))
+        .collect();
+    if let Some(seconds) = number_field(note, "totalTime").filter(|t| *t > 0.0) {
+        let seconds = seconds.round() as i64;
+        details.push(format!("用时 {}:{:02}", seconds / 60, seconds % 60));
+    }
+    if let Some(rating) = number_field(note, "rating").filter(|r| *r > 0.0) {
+        details.push(format!("评分 {}", rating));
+    }
+    if !details.is_empty() {
+        let _ = writeln!(out, "- {}", details.join(" · "));
+    }
+    let tags: Vec<&str> = note.get("tastingTags").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect();
+    if !tags.is_empty() {
+        let _ = writeln!(out, "- 风味：{}", tags.join("、"));
+    }
+    if !details.is_empty() || !tags.is_empty() {
+        out.push('\n');
+    }
+    if let Some(text) = text_field(note, "notes") {
+        let _ = writeln!(out, "{}\n", text.trim());
+    }
+    if include_images {
+        write_images(&images(note, &["image", "images"]), out);
+    }
+}
+
+fn bean_page(bean: &Value, notes: &[Value], timezone: Option<Tz>, include_images: bool) -> String {
+    let mut out = String::new();
+    front_matter(bean, &mut out);
+    let _ = writeln!(out, "# {}\n", text_field(bean, "name").unwrap_or_default());
+    if let Some(text) = text_field(bean, "notes") {
+        let _ = writeln!(out, "{}\n", text.trim());
+    }
+    if include_images {
+        write_images(&images(bean, &["image", "backImage"]), &mut out);
+    }
+    if !notes.is_empty() {
+        out.push_str("## 冲煮笔记\n\n");
+        for note in notes {
+            write_note(note, timezone, include_images, &mut out);
+        }
+    }
+    out
+}
+
+fn load_notes(conn: &Connection) -> rusqlite::Result<Vec<Value>> {
+    let mut stmt = conn.prepare("SELECT data FROM notes ORDER BY timestamp DESC")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut notes = Vec::new();
+    for data in rows {
+        if let Ok(note) = serde_json::from_str::<Value>(&data?) {
+            notes.push(note);
+        }
+    }
+    Ok(notes)
+}
+
+// 生成全部文件：相对路径 -> 内容
+fn render(beans: &[Value], notes: Vec<Value>, options: &MarkdownOptions, timezone: Option<Tz>) -> BTreeMap<String, String> {
+    let include_images = !options.skip_images;
+    let known: HashSet<&str> = beans.iter().filter_map(|bean| text_field(bean, "id")).collect();
+    let mut by_bean: HashMap<String, Vec<Value>> = HashMap::new();
+    for note in notes {
+        let key = text_field(&note, "beanId").filter(|id| known.contains(id)).unwrap_or_default().to_string();
+        by_bean.entry(key).or_default().push(note);
+    }
+
+    let mut selected: Vec<&Value> = beans
+        .iter()
+        .filter(|bean| {
+            let id = text_field(bean, "id").unwrap_or_default();
+            options.bean_ids.as_ref().map_or(true, |ids| ids.iter().any(|selected| selected == id))
+        })
+        .collect();
+    selected.sort_by_key(|bean| (text_field(bean, "name").unwrap_or_default(), text_field(bean, "id").unwrap_or_default()));
+
+    let mut stems: HashMap<String, usize> = HashMap::new();
+    for bean in &selected {
+        *stems.entry(file_stem(text_field(bean, "name").unwrap_or_default(), "")).or_insert(0) += 1;
+    }
+    let mut files = BTreeMap::new();
+    for bean in selected {
+        let id = text_field(bean, "id").unwrap_or_default();
+        let stem = file_stem(text_field(bean, "name").unwrap_or_default(), id);
+        // 同名的咖啡豆在文件名后加上 ID
+        let name = if stems.get(&stem).copied().unwrap_or(0) > 1 {
+            format!("{} ({}).md", stem, file_stem(id, id))
+        } else {
+            format!("{}.md", stem)
+        };
+        let notes = by_bean.get(id).map_or(&[][..], Vec::as_slice);
+        files.insert(name, bean_page(bean, notes, timezone, include_images));
+    }
+    if options.bean_ids.is_none() {
+        if let Some(notes) = by_bean.get("").filter(|notes| !notes.is_empty()) {
+            let mut out = String::from("---\ntags:\n  - brew-guide/notes\n---\n\n# 其它笔记\n\n");
+            for note in notes {
+                write_note(note, timezone, include_images, &mut out);
+            }
+            files.insert(UNLINKED_FILE.to_string(), out);
+        }
+    }
+    files
+}
+
+// 写入变化的文件、复制新的图片；prune 时删除上次导出而这次没有的文件（只导出部分咖啡豆时保留其它文件）
+fn write_vault(dir: &Path, data_dir: &Path, files: &BTreeMap<String, String>, prune: bool) -> Result<MarkdownExportReport, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let previous: Manifest = crate::json_file::load(&manifest_path).unwrap_or_default();
+    let mut manifest = Manifest::default();
+    let mut report = MarkdownExportReport::default();
+
+    for (name, content) in files {
+        let hash = blake3::hash(content.as_bytes()).to_hex().to_string();
+        let path = dir.join(name);
+        if previous.files.get(name) == Some(&hash) && path.exists() {
+            report.unchanged += 1;
+        } else {
+            std::fs::write(&path, content).map_err(|e| e.to_string())?;
+            report.written += 1;
+        }
+        for line in content.lines() {
+            let Some(image) = line.strip_prefix("![](").and_then(|rest| rest.strip_suffix(')')) else {
+                continue;
+            };
+            let target = dir.join(image);
+            if target.exists() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            match std::fs::copy(data_dir.join(image), &target) {
+                Ok(_) => report.images += 1,
+                Err(e) => log::warn!("复制图片 {} 失败：{}", image, e),
+            }
+        }
+        manifest.files.insert(name.clone(), hash);
+    }
+    for (name, hash) in previous.files.iter().filter(|(name, _)| !files.contains_key(*name)) {
+        if !prune {
+            manifest.files.insert(name.clone(), hash.clone());
+            continue;
+        }
+        match std::fs::remove_file(dir.join(name)) {
+            Ok(()) => report.removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    crate::json_file::save(&manifest_path, &manifest).map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+// 导出到文件夹 path（再次导出到同一文件夹时只改写变化的文件）
+#[tauri::command]
+pub fn export_markdown(app: tauri::AppHandle, path: String, options: Option<MarkdownOptions>) -> Result<MarkdownExportReport, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let options = options.unwrap_or_default();
+    let timezone = crate::settings::get(&app)
+        .timezone
+        .and_then(|name| crate::clock::parse_timezone(&name).ok());
+    let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let (beans, notes) = with_store(&app, |store| Ok((crate::store::all_beans(&store.conn)?, load_notes(&store.conn)?)))?;
+    let files = render(&beans, notes, &options, timezone);
+    let report = write_vault(Path::new(&path), &data_dir, &files, options.bean_ids.is_none())?;
+    crate::telemetry::record(&app, "export.markdown");
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_bean_pages_and_rewrites_only_changed_files() {
+        let beans = vec![
+            json!({ "id": "b1", "name": "耶加雪菲", "roaster": "Tim \"W\"", "flavor": ["花香"], "image": "attachments/missing.jpg" }),
+            json!({ "id": "b2", "name": "肯尼亚 AA" }),
+        ];
+        let notes = vec![
+            json!({ "id": "n1", "timestamp": 1_792_110_600_000_i64, "beanId": "b1", "equipment": "V60",
+                    "params": { "coffee": "15g", "water": "240g" }, "totalTime": 150, "rating": 4, "notes": "甜感好" }),
+            json!({ "id": "n2", "timestamp": 1_792_110_600_000_i64, "beanId": "gone" }),
+        ];
+        let tz = Some(chrono_tz::Asia::Shanghai);
+        let files = render(&beans, notes.clone(), &MarkdownOptions::default(), tz);
+        assert_eq!(files.keys().map(String::as_str).collect::<Vec<_>>(), vec!["其它笔记.md", "耶加雪菲.md", "肯尼亚 AA.md"]);
+        let page = &files["耶加雪菲.md"];
+        assert!(page.starts_with("---\nid: \"b1\"\nname: \"耶加雪菲\"\nroaster: \"Tim \\\"W\\\"\"\nflavor:\n  - \"花香\"\n"));
+        assert!(page.contains("![](attachments/missing.jpg)"));
+        assert!(page.contains("### 2026-10-16 08:30 · V60\n\n- 粉量 15g · 水量 240g · 用时 2:30 · 评分 4\n\n甜感好\n"));
+
+        let dir = std::env::temp_dir().join(format!("brew-guide-markdown-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let report = write_vault(&dir, &dir, &files, true).unwrap();
+        assert_eq!((report.written, report.unchanged, report.images), (3, 0, 0));
+
+        let options = MarkdownOptions {
+            bean_ids: Some(vec!["b1".to_string()]),
+            ..Default::default()
+        };
+        let report = write_vault(&dir, &dir, &render(&beans, notes.clone(), &options, tz), false).unwrap();
+        assert_eq!((report.written, report.unchanged, report.removed), (0, 1, 0));
+
+        let report = write_vault(&dir, &dir, &render(&beans[..1], notes, &MarkdownOptions::default(), tz), true).unwrap();
+        assert_eq!((report.written, report.unchanged, report.removed), (0, 2, 1));
+        assert!(!dir.join("肯尼亚 AA.md").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
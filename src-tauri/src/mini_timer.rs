@@ -0,0 +1,170 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::settings::WindowPosition;
+
+// 迷你计时器：无边框、始终置顶的小窗口，显示当前冲煮步骤和倒计时（数据来自 timer 的后端计时）
+// 窗口由后端创建和管理，不依赖主窗口，主窗口隐藏到托盘后仍然保留；拖动后记住位置
+const LABEL: &str = "mini-timer";
+
+// 窗口尺寸和默认位置与屏幕边缘的距离（逻辑像素）
+const WINDOW_WIDTH: f64 = 220.0;
+const WINDOW_HEIGHT: f64 = 84.0;
+const EDGE_MARGIN: f64 = 16.0;
+// 至少有这么多（物理像素）在屏幕内才使用保存的位置，避免显示器断开后窗口跑到屏幕外
+const MIN_VISIBLE: i32 = 48;
+// 拖动时 Moved 事件很密集，停下来一会儿再保存
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+// 每次移动加一，延迟保存时只保存最后一次移动的位置
+#[derive(Debug, Default)]
+pub struct MiniTimerState {
+    moves: u64,
+}
+
+// 屏幕可用区域（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Area {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+fn is_visible(position: WindowPosition, width: i32, areas: &[Area]) -> bool {
+    areas.iter().any(|area| {
+        let overlap = (position.x + width).min(area.x + area.width) - position.x.max(area.x);
+        overlap >= MIN_VISIBLE && position.y >= area.y && position.y + MIN_VISIBLE <= area.y + area.height
+    })
+}
+
+// 默认放在主屏幕可用区域的右上角
+fn default_position(area: Area, width: i32, margin: i32) -> WindowPosition {
+    WindowPosition {
+        x: area.x + area.width - width - margin,
+        y: area.y + margin,
+    }
+}
+
+#[cfg(desktop)]
+fn area(monitor: &tauri::Monitor) -> Area {
+    let work_area = monitor.work_area();
+    Area {
+        x: work_area.position.x,
+        y: work_area.position.y,
+        width: work_area.size.width as i32,
+        height: work_area.size.height as i32,
+    }
+}
+
+#[cfg(desktop)]
+fn initial_position(app: &tauri::AppHandle) -> Option<WindowPosition> {
+    let monitors = app.available_monitors().unwrap_or_default();
+    let areas: Vec<Area> = monitors.iter().map(area).collect();
+    if let Some(saved) = crate::settings::get(app).mini_timer_position {
+        let scale = monitors.first().map_or(1.0, |monitor| monitor.scale_factor());
+        if is_visible(saved, (WINDOW_WIDTH * scale) as i32, &areas) {
+            return Some(saved);
+        }
+    }
+    let primary = app.primary_monitor().ok().flatten().or_else(|| monitors.into_iter().next())?;
+    let scale = primary.scale_factor();
+    Some(default_position(area(&primary), (WINDOW_WIDTH * scale) as i32, (EDGE_MARGIN * scale) as i32))
+}
+
+#[cfg(desktop)]
+fn remember_position(app: &tauri::AppHandle, position: WindowPosition) {
+    let Some(state) = app.try_state::<Arc<Mutex<MiniTimerState>>>() else {
+        return;
+    };
+    let Ok(generation) = state.lock().map(|mut state| {
+        state.moves += 1;
+        state.moves
+    }) else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        let latest = app
+            .try_state::<Arc<Mutex<MiniTimerState>>>()
+            .and_then(|state| state.lock().ok().map(|state| state.moves == generation))
+            .unwrap_or(false);
+        if latest {
+            if let Err(e) = crate::settings::update(&app, |s| s.mini_timer_position = Some(position)) {
+                log::warn!("保存迷你计时器位置失败：{}", e);
+            }
+        }
+    });
+}
+
+#[cfg(desktop)]
+fn create(app: &tauri::AppHandle) -> tauri::Result<tauri::WebviewWindow> {
+    let window = tauri::WebviewWindowBuilder::new(app, LABEL, tauri::WebviewUrl::App(LABEL.into()))
+        .title("Brew Guide")
+        .inner_size(WINDOW_WIDTH, WINDOW_HEIGHT)
+        .resizable(false)
+        .minimizable(false)
+        .maximizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .visible_on_all_workspaces(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()?;
+    if let Some(position) = initial_position(app) {
+        window.set_position(tauri::PhysicalPosition::new(position.x, position.y))?;
+    }
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Moved(position) = event {
+            remember_position(&handle, WindowPosition { x: position.x, y: position.y });
+        }
+    });
+    Ok(window)
+}
+
+#[tauri::command]
+pub fn show_mini_timer(app: tauri::AppHandle) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        let window = match app.get_webview_window(LABEL) {
+            Some(window) => window,
+            None => create(&app).map_err(|e| e.to_string())?,
+        };
+        window.show().map_err(|e| e.to_string())?;
+    }
+    #[cfg(not(desktop))]
+    let _ = app;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn hide_mini_timer(app: tauri::AppHandle) -> Result<(), String> {
+    match app.get_webview_window(LABEL) {
+        Some(window) => window.hide().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restores_position_only_when_on_screen() {
+        let laptop = Area { x: 0, y: 25, width: 1440, height: 875 };
+        let external = Area { x: 1440, y: 0, width: 2560, height: 1440 };
+        let at = |x, y| WindowPosition { x, y };
+
+        assert!(is_visible(at(1200, 40), 220, &[laptop]));
+        // 窗口一部分在屏幕外也可以，只要还能拖回来
+        assert!(is_visible(at(1380, 40), 220, &[laptop]));
+        assert!(!is_visible(at(2000, 200), 220, &[laptop]));
+        assert!(is_visible(at(2000, 200), 220, &[laptop, external]));
+        assert!(!is_visible(at(1200, 880), 220, &[laptop]));
+
+        assert_eq!(default_position(laptop, 220, 16), at(1204, 41));
+    }
+}
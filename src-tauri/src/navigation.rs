@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::i18n::Locale;
+use crate::settings::TraySection;
+
+// 应用内导航目标：托盘、NFC、桌面快捷操作等入口统一通过 navigate_to 跳转
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum NavigationTarget {
+    #[serde(rename_all = "camelCase")]
+    Bean { bean_id: String },
+    #[serde(rename_all = "camelCase")]
+    Note { note_id: String },
+    #[serde(rename_all = "camelCase")]
+    NewBrewLog { bean_id: Option<String> },
+    StartTimer,
+    AddBean,
+    // 咖啡豆列表，按赏味期状态筛选；分组模式下不筛选
+    BeanList { section: Option<TraySection> },
+}
+
+// 显示主窗口并通知前端跳转
+// 咖啡豆详情、笔记详情分别使用 navigate-to-bean / navigate-to-note 事件（payload 为 ID），其余目标通过 navigate-to 事件发送
+pub fn navigate_to(app: &tauri::AppHandle, target: NavigationTarget) -> tauri::Result<()> {
+    crate::show_main_window(app);
+    match target {
+        NavigationTarget::Bean { bean_id } => app.emit("navigate-to-bean", bean_id),
+        NavigationTarget::Note { note_id } => app.emit("navigate-to-note", note_id),
+        target => app.emit("navigate-to", target),
+    }
+}
+
+// 移动端长按图标的快捷操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppShortcut {
+    StartTimer,
+    LogBrew,
+    AddBean,
+}
+
+const APP_SHORTCUTS: [AppShortcut; 3] = [AppShortcut::StartTimer, AppShortcut::LogBrew, AppShortcut::AddBean];
+
+impl AppShortcut {
+    fn id(self) -> &'static str {
+        match self {
+            AppShortcut::StartTimer => "start-timer",
+            AppShortcut::LogBrew => "log-brew",
+            AppShortcut::AddBean => "add-bean",
+        }
+    }
+
+    fn title(self, locale: Locale) -> &'static str {
+        match self {
+            AppShortcut::StartTimer => locale.tr("开始计时", "Start Timer"),
+            AppShortcut::LogBrew => locale.tr("记录冲煮", "Log a Brew"),
+            AppShortcut::AddBean => locale.tr("添加咖啡豆", "Add Beans"),
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        APP_SHORTCUTS.into_iter().find(|shortcut| shortcut.id() == id)
+    }
+
+    fn target(self) -> NavigationTarget {
+        match self {
+            AppShortcut::StartTimer => NavigationTarget::StartTimer,
+            AppShortcut::LogBrew => NavigationTarget::NewBrewLog { bean_id: None },
+            AppShortcut::AddBean => NavigationTarget::AddBean,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppShortcutItem {
+    pub id: &'static str,
+    pub title: &'static str,
+}
+
+// 原生壳启动时获取需要注册的快捷操作（UIApplicationShortcutItem / ShortcutManager）
+#[tauri::command]
+pub fn list_app_shortcuts(app: tauri::AppHandle) -> Vec<AppShortcutItem> {
+    let locale = crate::current_locale(&app);
+    APP_SHORTCUTS
+        .into_iter()
+        .map(|shortcut| AppShortcutItem {
+            id: shortcut.id(),
+            title: shortcut.title(locale),
+        })
+        .collect()
+}
+
+// 用户点击快捷操作后由原生壳调用
+#[tauri::command]
+pub fn handle_app_shortcut(app: tauri::AppHandle, shortcut_id: String) -> Result<(), String> {
+    let shortcut = AppShortcut::from_id(&shortcut_id)
+        .ok_or_else(|| format!("未知的快捷操作：{}", shortcut_id))?;
+    crate::telemetry::record(&app, &format!("shortcut.{}", shortcut.id()));
+    navigate_to(&app, shortcut.target()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::json_file;
+use crate::navigation::{navigate_to, NavigationTarget};
+
+// NFC 标签与咖啡豆的绑定关系（每个分装罐贴一个标签）
+// 标签的读写由移动端 NFC 插件完成，这里只负责绑定关系和碰一碰后的路由
+const NFC_TAGS_FILE: &str = "nfc-tags.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NfcTagLink {
+    pub tag_id: String,
+    pub bean_id: String,
+    pub linked_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NfcTagRegistry {
+    tags: BTreeMap<String, NfcTagLink>,
+}
+
+// 碰一碰标签后的动作
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum NfcTagAction {
+    OpenBean,
+    BrewWithBean,
+}
+
+// 统一标签 UID 格式："04:a2:3b" / "04A23B" -> "04A23B"
+fn normalize_tag_id(raw: &str) -> String {
+    raw.chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn registry_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(NFC_TAGS_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn load_registry(app: &tauri::AppHandle) -> Result<NfcTagRegistry, String> {
+    json_file::load(&registry_path(app)?).map_err(|e| e.to_string())
+}
+
+fn save_registry(app: &tauri::AppHandle, registry: &NfcTagRegistry) -> Result<(), String> {
+    json_file::save(&registry_path(app)?, registry).map_err(|e| e.to_string())
+}
+
+// 绑定标签到咖啡豆（同一个标签重新绑定时覆盖）
+#[tauri::command]
+pub fn link_nfc_tag(app: tauri::AppHandle, tag_id: String, bean_id: String) -> Result<NfcTagLink, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let tag_id = normalize_tag_id(&tag_id);
+    if tag_id.is_empty() {
+        return Err("无效的 NFC 标签 ID".to_string());
+    }
+
+    let mut registry = load_registry(&app)?;
+    let link = NfcTagLink {
+        tag_id: tag_id.clone(),
+        bean_id,
+        linked_at: chrono::Local::now().to_rfc3339(),
+    };
+    registry.tags.insert(tag_id, link.clone());
+    save_registry(&app, &registry)?;
+    Ok(link)
+}
+
+#[tauri::command]
+pub fn unlink_nfc_tag(app: tauri::AppHandle, tag_id: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let mut registry = load_registry(&app)?;
+    registry.tags.remove(&normalize_tag_id(&tag_id));
+    save_registry(&app, &registry)
+}
+
+#[tauri::command]
+pub fn list_nfc_tags(app: tauri::AppHandle) -> Result<Vec<NfcTagLink>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    Ok(load_registry(&app)?.tags.into_values().collect())
+}
+
+// 处理碰一碰：打开对应咖啡豆，或用它新建一条冲煮记录
+// 返回绑定的咖啡豆 ID，未绑定的标签返回 None（前端可引导用户绑定）
+#[tauri::command]
+pub fn handle_nfc_tag(app: tauri::AppHandle, tag_id: String, action: NfcTagAction) -> Result<Option<String>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let registry = load_registry(&app)?;
+    let Some(link) = registry.tags.get(&normalize_tag_id(&tag_id)) else {
+        return Ok(None);
+    };
+
+    let bean_id = link.bean_id.clone();
+    let (target, feature) = match action {
+        NfcTagAction::OpenBean => (NavigationTarget::Bean { bean_id: bean_id.clone() }, "nfc.open_bean"),
+        NfcTagAction::BrewWithBean => (NavigationTarget::NewBrewLog { bean_id: Some(bean_id.clone()) }, "nfc.brew"),
+    };
+    crate::telemetry::record(&app, feature);
+    navigate_to(&app, target).map_err(|e| e.to_string())?;
+    Ok(Some(bean_id))
+}
@@ -0,0 +1,311 @@
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::journal;
+use crate::store::{atomically, number_field, text_field, with_store, RecordKind};
+
+// 冲煮笔记保存在本地数据库，前端按条件分页读取，不必把全部历史一次性传给 webview
+// 笔记以前端的完整 JSON 保存，方案、咖啡豆、粉量、液重、时间、评分另存一列，风味标签存在 note_tags
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+
+// 从前端笔记中取出用于筛选的字段
+#[derive(Debug)]
+struct NoteColumns {
+    id: String,
+    timestamp: i64,
+    method: Option<String>,
+    bean_id: Option<String>,
+    dose: Option<f64>,        // 粉量（g）
+    yield_grams: Option<f64>, // 液重（g）
+    brew_time: Option<f64>,   // 秒
+    rating: Option<f64>,
+    tags: Vec<String>,
+}
+
+// 前端的粉量、水量是 "15g"、"225g" 这样的文字
+fn grams(value: &Value, key: &str) -> Option<f64> {
+    if let Some(n) = value.get(key).and_then(Value::as_f64) {
+        return Some(n);
+    }
+    let text = text_field(value, key)?;
+    let end = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+impl NoteColumns {
+    fn from_note(note: &Value) -> Result<Self, String> {
+        let params = note.get("params").unwrap_or(&Value::Null);
+        let mut tags: Vec<String> = Vec::new();
+        for tag in note.get("tastingTags").and_then(Value::as_array).into_iter().flatten() {
+            if let Some(tag) = tag.as_str().map(str::trim).filter(|t| !t.is_empty()) {
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+        }
+        Ok(Self {
+            id: text_field(note, "id").ok_or("冲煮笔记缺少 id")?.to_string(),
+            timestamp: note
+                .get("timestamp")
+                .and_then(Value::as_i64)
+                .ok_or("冲煮笔记缺少时间")?,
+            method: text_field(note, "method").map(str::to_string),
+            bean_id: text_field(note, "beanId").map(str::to_string),
+            dose: grams(params, "coffee"),
+            yield_grams: grams(params, "water"),
+            brew_time: number_field(note, "totalTime"),
+            rating: number_field(note, "rating").filter(|r| *r > 0.0),
+            tags,
+        })
+    }
+}
+
+// 写入笔记、风味标签、搜索索引和附件引用；需在 atomically 中调用
+fn write_note(conn: &Connection, note: &Value, columns: &NoteColumns) -> rusqlite::Result<()> {
+    let data = serde_json::to_string(note).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        "INSERT INTO notes (id, timestamp, method, bean_id, dose, yield, brew_time, rating, data, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(id) DO UPDATE SET timestamp = ?2, method = ?3, bean_id = ?4, dose = ?5, yield = ?6,
+             brew_time = ?7, rating = ?8, data = ?9, updated_at = ?10",
+        params![
+            columns.id,
+            columns.timestamp,
+            columns.method,
+            columns.bean_id,
+            columns.dose,
+            columns.yield_grams,
+            columns.brew_time,
+            columns.rating,
+            data,
+            now
+        ],
+    )?;
+    conn.execute("DELETE FROM note_tags WHERE note_id = ?1", [&columns.id])?;
+    for tag in &columns.tags {
+        conn.execute("INSERT INTO note_tags (note_id, tag) VALUES (?1, ?2)", params![columns.id, tag])?;
+    }
+    crate::search::index_note(conn, &columns.id, note)?;
+    crate::attachments::sync_refs(conn, RecordKind::Note, &columns.id, note)
+}
+
+// 写入之前保存过的笔记（从回收站恢复、撤销时使用）
+pub(crate) fn restore_note(conn: &Connection, note: &Value) -> rusqlite::Result<()> {
+    let columns = NoteColumns::from_note(note).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+    write_note(conn, note, &columns)
+}
+
+fn note_exists(conn: &Connection, id: &str) -> rusqlite::Result<bool> {
+    conn.query_row("SELECT EXISTS (SELECT 1 FROM notes WHERE id = ?1)", [id], |row| row.get(0))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NoteFilter {
+    pub bean_id: Option<String>,
+    pub method: Option<String>,
+    pub tag: Option<String>,
+    pub query: Option<String>, // 笔记文字、咖啡豆名称包含的文字（忽略大小写）
+    pub min_rating: Option<f64>,
+    pub from: Option<i64>, // 时间范围（毫秒，含首尾）
+    pub to: Option<i64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl NoteFilter {
+    // WHERE 子句和对应的参数
+    fn where_clause(&self) -> (String, Vec<SqlValue>) {
+        let mut conditions: Vec<&str> = Vec::new();
+        let mut values: Vec<SqlValue> = Vec::new();
+        let text = |s: &Option<String>| s.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+        if let Some(bean_id) = text(&self.bean_id) {
+            conditions.push("bean_id = ?");
+            values.push(bean_id.into());
+        }
+        if let Some(method) = text(&self.method) {
+            conditions.push("method = ?");
+            values.push(method.into());
+        }
+        if let Some(tag) = text(&self.tag) {
+            conditions.push("id IN (SELECT note_id FROM note_tags WHERE tag = ?)");
+            values.push(tag.into());
+        }
+        if let Some(query) = text(&self.query) {
+            conditions.push(
+                "(instr(lower(json_extract(data, '$.notes')), lower(?)) > 0
+                  OR instr(lower(json_extract(data, '$.coffeeBeanInfo.name')), lower(?)) > 0)",
+            );
+            values.push(query.clone().into());
+            values.push(query.into());
+        }
+        if let Some(min_rating) = self.min_rating {
+            conditions.push("rating >= ?");
+            values.push(min_rating.into());
+        }
+        if let Some(from) = self.from {
+            conditions.push("timestamp >= ?");
+            values.push(from.into());
+        }
+        if let Some(to) = self.to {
+            conditions.push("timestamp <= ?");
+            values.push(to.into());
+        }
+        if conditions.is_empty() {
+            return (String::new(), values);
+        }
+        (format!(" WHERE {}", conditions.join(" AND ")), values)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotePage {
+    pub notes: Vec<Value>,
+    pub total: usize, // 符合条件的笔记总数，用于分页
+}
+
+fn query_notes(conn: &Connection, filter: &NoteFilter) -> rusqlite::Result<NotePage> {
+    let (clause, values) = filter.where_clause();
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM notes{}", clause),
+        params_from_iter(values.iter()),
+        |row| row.get(0),
+    )?;
+    let limit = filter.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let mut page_values = values;
+    page_values.push((limit as i64).into());
+    page_values.push((filter.offset.unwrap_or(0) as i64).into());
+    let mut stmt = conn.prepare(&format!(
+        "SELECT data FROM notes{} ORDER BY timestamp DESC LIMIT ? OFFSET ?",
+        clause
+    ))?;
+    let rows = stmt.query_map(params_from_iter(page_values.iter()), |row| row.get::<_, String>(0))?;
+    let mut notes = Vec::new();
+    for data in rows {
+        match serde_json::from_str(&data?) {
+            Ok(note) => notes.push(note),
+            Err(e) => log::warn!("跳过无法解析的冲煮笔记：{}", e),
+        }
+    }
+    Ok(NotePage {
+        notes,
+        total: total.max(0) as usize,
+    })
+}
+
+// 新建冲煮笔记；没有 id、时间时自动生成
+#[tauri::command]
+pub fn create_brew_note(app: tauri::AppHandle, mut note: Value) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if !note.is_object() {
+        return Err("冲煮笔记格式错误".to_string());
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    if text_field(&note, "id").is_none() {
+        note["id"] = Value::String(now.to_string());
+    }
+    if note.get("timestamp").is_none() {
+        note["timestamp"] = Value::from(now);
+    }
+    let columns = NoteColumns::from_note(&note)?;
+    let created = with_store(&app, |store| {
+        if note_exists(&store.conn, &columns.id)? {
+            return Ok(false);
+        }
+        atomically(&store.conn, || {
+            journal::track(&store.conn, RecordKind::Note, &columns.id, || {
+                write_note(&store.conn, &note, &columns)
+            })
+        })?;
+        Ok(true)
+    })?;
+    if !created {
+        return Err(format!("冲煮笔记已存在：{}", columns.id));
+    }
+    crate::store::publish_changes(&app)?;
+    crate::telemetry::record(&app, "note.create");
+    Ok(note)
+}
+
+// 整体替换已有的冲煮笔记
+#[tauri::command]
+pub fn update_brew_note(app: tauri::AppHandle, note: Value) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let columns = NoteColumns::from_note(&note)?;
+    let found = with_store(&app, |store| {
+        if !note_exists(&store.conn, &columns.id)? {
+            return Ok(false);
+        }
+        atomically(&store.conn, || {
+            journal::track(&store.conn, RecordKind::Note, &columns.id, || {
+                write_note(&store.conn, &note, &columns)
+            })
+        })?;
+        Ok(true)
+    })?;
+    if !found {
+        return Err(format!("找不到冲煮笔记：{}", columns.id));
+    }
+    crate::store::publish_changes(&app)?;
+    Ok(note)
+}
+
+// 按条件分页读取冲煮笔记，按冲煮时间从新到旧排序
+#[tauri::command]
+pub fn list_brew_notes(app: tauri::AppHandle, filter: Option<NoteFilter>) -> Result<NotePage, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let filter = filter.unwrap_or_default();
+    with_store(&app, |store| query_notes(&store.conn, &filter))
+}
+
+// 删除冲煮笔记（移到回收站），返回是否存在
+#[tauri::command]
+pub fn delete_brew_note(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let existed = with_store(&app, |store| {
+        atomically(&store.conn, || {
+            journal::track(&store.conn, RecordKind::Note, &id, || {
+                crate::trash::move_to_trash(&store.conn, RecordKind::Note, &id)
+            })
+        })
+    })?;
+    if existed {
+        crate::store::publish_changes(&app)?;
+    }
+    Ok(existed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_filter_columns_from_frontend_notes() {
+        let columns = NoteColumns::from_note(&json!({
+            "id": "1760600000000",
+            "timestamp": 1760600000000_i64,
+            "method": "一刀流",
+            "beanId": "b1",
+            "params": { "coffee": "15g", "water": "225g", "ratio": "1:15" },
+            "totalTime": 150,
+            "rating": 4.5,
+            "tastingTags": ["柑橘", " 茉莉 ", "柑橘", ""],
+            "notes": "",
+        }))
+        .unwrap();
+        assert_eq!(columns.dose, Some(15.0));
+        assert_eq!(columns.yield_grams, Some(225.0));
+        assert_eq!(columns.brew_time, Some(150.0));
+        assert_eq!(columns.tags, vec!["柑橘", "茉莉"]);
+
+        assert!(NoteColumns::from_note(&json!({ "timestamp": 1 })).is_err());
+    }
+}
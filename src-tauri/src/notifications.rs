@@ -0,0 +1,549 @@
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::background::BeanCache;
+use crate::json_file;
+use crate::navigation::{navigate_to, NavigationTarget};
+use crate::settings::QuietHours;
+use crate::widget::state_key;
+use crate::FreshnessState;
+
+// 系统通知：
+// - 每天第一次检查时，对比前一次记录的每款咖啡豆赏味期状态，进入赏味期或衰退期时发送通知
+// - 剩余量更新后跌破提醒阈值时发送通知，同一款咖啡豆在冷却时间内只提醒一次
+// - 「提醒我这支豆子进入赏味期」：订阅的咖啡豆进入赏味期当天单独提醒一次，之后自动取消订阅
+// - 在途咖啡豆到了预计到货日仍未标记收到时，询问一次是否已到货
+// 免打扰时段内（以及 macOS 专注模式开启时）的通知先排队，之后由后台线程补发
+// 状态记录和待发送队列保存在 notification-state.json
+const NOTIFICATION_STATE_FILE: &str = "notification-state.json";
+
+const TARGET_KEY: &str = "target";
+
+// 同一款咖啡豆两次库存提醒的最短间隔
+const LOW_STOCK_COOLDOWN_MS: i64 = 3 * 24 * 60 * 60 * 1000;
+
+// 待发送队列上限，超出时丢弃最早的通知
+const MAX_PENDING: usize = 20;
+
+// 点击通知后打开的内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum NotificationTarget {
+    #[serde(rename_all = "camelCase")]
+    Bean { bean_id: String },
+    WeeklyReport,
+    // 冲煮提醒，带「记录一杯」「稍后提醒」按钮
+    #[serde(rename_all = "camelCase")]
+    BrewReminder { reminder_id: String },
+}
+
+impl NotificationTarget {
+    fn bean(bean_id: &str) -> Option<Self> {
+        Some(NotificationTarget::Bean { bean_id: bean_id.to_string() })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingNotification {
+    title: String,
+    body: String,
+    #[serde(default)]
+    target: Option<NotificationTarget>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationState {
+    day: Option<String>,                       // 上次检查的日期（YYYY-MM-DD）
+    states: BTreeMap<String, String>,          // 咖啡豆 ID -> 状态（与小组件快照的状态名一致）
+    low_stock_notified: BTreeMap<String, i64>, // 咖啡豆 ID -> 上次库存提醒时间（毫秒）
+    watches: BTreeSet<String>,                 // 订阅了进入赏味期提醒的咖啡豆 ID
+    pending: Vec<PendingNotification>,         // 免打扰期间推迟的通知
+    arrival_asked: BTreeSet<String>,           // 已询问过是否到货的在途咖啡豆 ID
+}
+
+impl NotificationState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        state_path(app)
+            .ok()
+            .and_then(|path| {
+                json_file::load(&path)
+                    .map_err(|e| log::warn!("读取通知状态失败：{}", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(NOTIFICATION_STATE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+// 是否处于免打扰时段：开始晚于结束时跨过零点，开始等于结束时视为未设置
+fn in_quiet_hours(start: NaiveTime, end: NaiveTime, now: NaiveTime) -> bool {
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+// macOS 专注模式（勿扰）是否开启：系统没有公开接口，读取 DoNotDisturb 的断言记录
+// 只能识别手动开启的专注模式，按计划自动开启的无法判断
+#[cfg(target_os = "macos")]
+fn focus_active() -> bool {
+    let Some(home) = std::env::var_os("HOME") else {
+        return false;
+    };
+    let path = PathBuf::from(home).join("Library/DoNotDisturb/DB/Assertions.json");
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    serde_json::from_str::<serde_json::Value>(&contents)
+        .ok()
+        .and_then(|value| {
+            let data = value.get("data")?.as_array()?;
+            Some(data.iter().any(|entry| {
+                entry
+                    .get("storeAssertionRecords")
+                    .and_then(|records| records.as_array())
+                    .is_some_and(|records| !records.is_empty())
+            }))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn focus_active() -> bool {
+    false
+}
+
+fn should_defer(app: &tauri::AppHandle) -> bool {
+    let quiet = crate::settings::get(app).notifications.quiet_hours.and_then(|hours| {
+        let start = NaiveTime::parse_from_str(&hours.start, "%H:%M").ok()?;
+        let end = NaiveTime::parse_from_str(&hours.end, "%H:%M").ok()?;
+        Some(in_quiet_hours(start, end, crate::time_of(app, chrono::Utc::now())))
+    });
+    quiet.unwrap_or(false) || focus_active()
+}
+
+// 发送通知；免打扰期间放入待发送队列
+pub fn show(app: &tauri::AppHandle, title: &str, body: &str, target: Option<NotificationTarget>) {
+    if !should_defer(app) {
+        send(app, title, body, target.as_ref());
+        return;
+    }
+    let Some(state) = app.try_state::<Arc<Mutex<NotificationState>>>() else {
+        return;
+    };
+    let Ok(mut saved) = state.lock() else {
+        return;
+    };
+    let pending = PendingNotification {
+        title: title.to_string(),
+        body: body.to_string(),
+        target,
+    };
+    if saved.pending.contains(&pending) {
+        return;
+    }
+    saved.pending.push(pending);
+    if saved.pending.len() > MAX_PENDING {
+        let overflow = saved.pending.len() - MAX_PENDING;
+        saved.pending.drain(..overflow);
+    }
+    if let Err(e) = state_path(app).and_then(|path| json_file::save(&path, &*saved).map_err(|e| e.to_string())) {
+        log::warn!("保存通知状态失败：{}", e);
+    }
+}
+
+// 免打扰结束后补发排队的通知
+pub fn flush_pending(app: &tauri::AppHandle) {
+    if should_defer(app) {
+        return;
+    }
+    let Some(state) = app.try_state::<Arc<Mutex<NotificationState>>>() else {
+        return;
+    };
+    let pending = {
+        let Ok(mut saved) = state.lock() else {
+            return;
+        };
+        if saved.pending.is_empty() {
+            return;
+        }
+        let pending = std::mem::take(&mut saved.pending);
+        if let Err(e) = state_path(app).and_then(|path| json_file::save(&path, &*saved).map_err(|e| e.to_string())) {
+            log::warn!("保存通知状态失败：{}", e);
+        }
+        pending
+    };
+    for notification in pending {
+        send(app, &notification.title, &notification.body, notification.target.as_ref());
+    }
+}
+
+// 通知附带点击后打开的内容（见 register_click_handler）
+// 立即发送通知，不受免打扰影响；用于用户刚刚主动操作的确认（如 Siri 记录冲煮）
+pub fn show_now(app: &tauri::AppHandle, title: &str, body: &str, target: Option<NotificationTarget>) {
+    send(app, title, body, target.as_ref());
+}
+
+fn send(app: &tauri::AppHandle, title: &str, body: &str, target: Option<&NotificationTarget>) {
+    let mut builder = app.notification().builder().title(title).body(body);
+    if let Some(target) = target {
+        builder = builder.extra(TARGET_KEY, target);
+    }
+    if matches!(target, Some(NotificationTarget::BrewReminder { .. })) {
+        builder = builder.action_type_id(crate::reminders::ACTION_TYPE);
+    }
+    if let Err(e) = builder.show() {
+        log::warn!("发送通知失败：{}", e);
+    }
+}
+
+// 点击通知时打开对应内容：咖啡豆通知跳转到详情（与托盘点击咖啡豆一致），周报通知打开周报窗口
+// 通知上的按钮（目前只有冲煮提醒有）交给对应模块处理；需在发送第一条通知前注册
+pub fn register_click_handler(app: &tauri::AppHandle) {
+    crate::reminders::register_actions(app);
+    let handle = app.clone();
+    let result = app.notification().on_action(move |action| {
+        let target = action
+            .notification()
+            .and_then(|n| n.extra().get(TARGET_KEY))
+            .and_then(|target| serde_json::from_value::<NotificationTarget>(target.clone()).ok());
+        let result = match (target, action.action_id()) {
+            (Some(NotificationTarget::BrewReminder { reminder_id }), action_id) if action_id != "tap" => {
+                crate::reminders::handle_action(&handle, &reminder_id, action_id)
+            }
+            (_, action_id) if action_id != "tap" => return,
+            (None | Some(NotificationTarget::BrewReminder { .. }), _) => {
+                crate::show_main_window(&handle);
+                return;
+            }
+            (Some(NotificationTarget::Bean { bean_id }), _) => {
+                crate::telemetry::record(&handle, "notification.open");
+                navigate_to(&handle, NavigationTarget::Bean { bean_id })
+            }
+            (Some(NotificationTarget::WeeklyReport), _) => {
+                crate::telemetry::record(&handle, "notification.open");
+                crate::weekly_report::open(&handle)
+            }
+        };
+        if let Err(e) = result {
+            log::warn!("通知跳转失败：{}", e);
+        }
+    });
+    if let Err(e) = result {
+        log::warn!("注册通知点击回调失败：{}", e);
+    }
+}
+
+// 对比缓存的咖啡豆今天和上次检查时的状态，跨天时对状态变化发送通知
+// 同一天内的变化（如修改烘焙日期）只更新记录，不发通知
+pub fn check_freshness_changes(app: &tauri::AppHandle) -> Result<(), String> {
+    let today = crate::today(app);
+    let day = today.format("%Y-%m-%d").to_string();
+    let beans = match app.try_state::<Arc<Mutex<BeanCache>>>() {
+        Some(state) => state.lock().map_err(|e| e.to_string())?.beans.clone(),
+        None => return Ok(()),
+    };
+    // 启动后前端还没推送数据时不覆盖上次的记录
+    if beans.is_empty() {
+        return Ok(());
+    }
+    let current: Vec<(crate::CoffeeBean, FreshnessState)> = beans
+        .into_iter()
+        .filter(|bean| !bean.id.is_empty() && bean.remaining_grams().unwrap_or(0.0) > 0.0)
+        .map(|bean| {
+            let state = crate::calculate_freshness(&bean, today).freshness_state;
+            (bean, state)
+        })
+        .collect();
+
+    let state = app
+        .try_state::<Arc<Mutex<NotificationState>>>()
+        .ok_or("通知模块未初始化")?;
+    // (咖啡豆 ID, 名称, 新状态, 是否为订阅的提醒)
+    let changes: Vec<(String, String, FreshnessState, bool)> = {
+        let mut saved = state.lock().map_err(|e| e.to_string())?;
+        let new_day = saved.day.as_deref().is_some_and(|d| d != day);
+        let changes: Vec<(String, String, FreshnessState, bool)> = current
+            .iter()
+            .filter(|_| new_day)
+            .filter(|(bean, state)| {
+                matches!(state, FreshnessState::Optimal | FreshnessState::Decline)
+                    && saved.states.get(&bean.id).is_some_and(|previous| previous != state_key(state))
+            })
+            .map(|(bean, state)| {
+                let watched = *state == FreshnessState::Optimal && saved.watches.contains(&bean.id);
+                (bean.id.clone(), bean.name.clone(), state.clone(), watched)
+            })
+            .collect();
+        let states: BTreeMap<String, String> = current
+            .iter()
+            .map(|(bean, state)| (bean.id.clone(), state_key(state).to_string()))
+            .collect();
+        // 已提醒过的订阅和已喝完 / 删除的咖啡豆的订阅一并清理
+        let watches: BTreeSet<String> = saved
+            .watches
+            .iter()
+            .filter(|id| states.contains_key(*id))
+            .filter(|id| !changes.iter().any(|(changed, _, _, watched)| *watched && changed == *id))
+            .cloned()
+            .collect();
+        if saved.day.as_deref() != Some(day.as_str()) || saved.states != states || saved.watches != watches {
+            saved.day = Some(day);
+            saved.states = states;
+            saved.watches = watches;
+            json_file::save(&state_path(app)?, &*saved).map_err(|e| e.to_string())?;
+        }
+        changes
+    };
+
+    // 订阅的提醒不受赏味期通知开关影响
+    let freshness = crate::settings::get(app).notifications.freshness;
+    let locale = crate::current_locale(app);
+    for (id, name, state, watched) in changes {
+        let body = match state {
+            _ if watched => locale.format_resting_complete(&name),
+            _ if !freshness => continue,
+            FreshnessState::Optimal => locale.format_entered_optimal(&name),
+            _ => locale.format_entered_decline(&name),
+        };
+        show(app, "Brew Guide", &body, NotificationTarget::bean(&id));
+    }
+    check_arrivals(app, today, &current)
+}
+
+// 在途咖啡豆到了预计到货日时询问一次；收到后（不再在途）清理记录，再次在途时可以重新询问
+fn check_arrivals(
+    app: &tauri::AppHandle,
+    today: chrono::NaiveDate,
+    current: &[(crate::CoffeeBean, FreshnessState)],
+) -> Result<(), String> {
+    let in_transit: Vec<&crate::CoffeeBean> = current
+        .iter()
+        .filter(|(_, state)| *state == FreshnessState::InTransit)
+        .map(|(bean, _)| bean)
+        .collect();
+    let state = app
+        .try_state::<Arc<Mutex<NotificationState>>>()
+        .ok_or("通知模块未初始化")?;
+    let arrived: Vec<(String, String)> = {
+        let mut saved = state.lock().map_err(|e| e.to_string())?;
+        let arrived: Vec<(String, String)> = in_transit
+            .iter()
+            .filter(|bean| bean.days_until_arrival(today).is_some_and(|days| days <= 0))
+            .filter(|bean| !saved.arrival_asked.contains(&bean.id))
+            .map(|bean| (bean.id.clone(), bean.name.clone()))
+            .collect();
+        let asked: BTreeSet<String> = saved
+            .arrival_asked
+            .iter()
+            .filter(|id| in_transit.iter().any(|bean| bean.id == **id))
+            .cloned()
+            .chain(arrived.iter().map(|(id, _)| id.clone()))
+            .collect();
+        if saved.arrival_asked != asked {
+            saved.arrival_asked = asked;
+            json_file::save(&state_path(app)?, &*saved).map_err(|e| e.to_string())?;
+        }
+        arrived
+    };
+
+    if !crate::settings::get(app).notifications.arrival {
+        return Ok(());
+    }
+    let locale = crate::current_locale(app);
+    for (id, name) in arrived {
+        show(app, "Brew Guide", &locale.format_arrival_check(&name), NotificationTarget::bean(&id));
+    }
+    Ok(())
+}
+
+// 订阅咖啡豆进入赏味期的提醒；只能订阅还在养豆期（或在途、冷冻）的咖啡豆
+#[tauri::command]
+pub fn watch_bean_freshness(app: tauri::AppHandle, bean_id: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let today = crate::today(&app);
+    let bean = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok()?.beans.iter().find(|b| b.id == bean_id).cloned())
+        .ok_or_else(|| format!("找不到咖啡豆：{}", bean_id))?;
+    let freshness = crate::calculate_freshness(&bean, today).freshness_state;
+    if matches!(freshness, FreshnessState::Optimal | FreshnessState::Decline) {
+        return Err(crate::current_locale(&app)
+            .tr("这支咖啡豆已经过了养豆期", "This bean has already finished resting")
+            .to_string());
+    }
+
+    let state = app
+        .try_state::<Arc<Mutex<NotificationState>>>()
+        .ok_or("通知模块未初始化")?;
+    let mut saved = state.lock().map_err(|e| e.to_string())?;
+    // 记下当前状态，避免首次检查前没有记录时错过进入赏味期的那天
+    saved.states.entry(bean_id.clone()).or_insert_with(|| state_key(&freshness).to_string());
+    saved.watches.insert(bean_id);
+    json_file::save(&state_path(&app)?, &*saved).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn unwatch_bean_freshness(app: tauri::AppHandle, bean_id: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let state = app
+        .try_state::<Arc<Mutex<NotificationState>>>()
+        .ok_or("通知模块未初始化")?;
+    let mut saved = state.lock().map_err(|e| e.to_string())?;
+    if saved.watches.remove(&bean_id) {
+        json_file::save(&state_path(&app)?, &*saved).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_watched_beans(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    Ok(app
+        .try_state::<Arc<Mutex<NotificationState>>>()
+        .and_then(|state| Some(state.lock().ok()?.watches.iter().cloned().collect()))
+        .unwrap_or_default())
+}
+
+// 剩余量从阈值以上降到阈值以下（且未喝完）时提醒；新添加的咖啡豆不提醒
+pub fn check_low_stock(app: &tauri::AppHandle, previous: &[crate::CoffeeBean], current: &[crate::CoffeeBean]) {
+    let settings = crate::settings::get(app).notifications;
+    if !settings.low_stock {
+        return;
+    }
+    let dropped: Vec<&crate::CoffeeBean> = current
+        .iter()
+        .filter(|bean| !bean.id.is_empty())
+        .filter(|bean| {
+            let threshold = settings.low_stock_threshold(&bean.id);
+            let Some(remaining) = bean.remaining_grams() else {
+                return false;
+            };
+            let before = previous
+                .iter()
+                .find(|b| b.id == bean.id)
+                .and_then(|b| b.remaining_grams());
+            remaining > 0.0 && remaining < threshold && before.is_some_and(|before| before >= threshold)
+        })
+        .collect();
+    if dropped.is_empty() {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let Some(state) = app.try_state::<Arc<Mutex<NotificationState>>>() else {
+        return;
+    };
+    let due: Vec<&crate::CoffeeBean> = {
+        let Ok(mut saved) = state.lock() else {
+            return;
+        };
+        let due: Vec<&crate::CoffeeBean> = dropped
+            .into_iter()
+            .filter(|bean| {
+                saved
+                    .low_stock_notified
+                    .get(&bean.id)
+                    .map_or(true, |last| now - last >= LOW_STOCK_COOLDOWN_MS)
+            })
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+        for bean in &due {
+            saved.low_stock_notified.insert(bean.id.clone(), now);
+        }
+        if let Err(e) = state_path(app).and_then(|path| json_file::save(&path, &*saved).map_err(|e| e.to_string())) {
+            log::warn!("保存通知状态失败：{}", e);
+        }
+        due
+    };
+
+    let locale = crate::current_locale(app);
+    for bean in due {
+        let remaining = bean.remaining_grams().unwrap_or(0.0);
+        show(app, "Brew Guide", &locale.format_low_stock(&bean.name, remaining), NotificationTarget::bean(&bean.id));
+    }
+}
+
+// 设置库存提醒阈值：bean_id 为空时设置全局阈值，否则设置该咖啡豆的单独阈值（grams 为空时取消单独阈值）
+#[tauri::command]
+pub fn set_low_stock_threshold(app: tauri::AppHandle, grams: Option<f64>, bean_id: Option<String>) -> Result<(), String> {
+    if let Some(grams) = grams.filter(|g| !g.is_finite() || *g < 0.0) {
+        return Err(format!("无效的阈值：{}", grams));
+    }
+    crate::settings::update(&app, |s| match (bean_id, grams) {
+        (Some(bean_id), Some(grams)) => {
+            s.notifications.low_stock_overrides.insert(bean_id, grams);
+        }
+        (Some(bean_id), None) => {
+            s.notifications.low_stock_overrides.remove(&bean_id);
+        }
+        (None, Some(grams)) => s.notifications.low_stock_grams = grams,
+        (None, None) => {}
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_low_stock_notifications(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.notifications.low_stock = enabled)?;
+    Ok(())
+}
+
+// 设置免打扰时段（HH:MM），start / end 为空时关闭
+#[tauri::command]
+pub fn set_quiet_hours(app: tauri::AppHandle, start: Option<String>, end: Option<String>) -> Result<(), String> {
+    let quiet_hours = match (start, end) {
+        (Some(start), Some(end)) => {
+            for time in [&start, &end] {
+                NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("无效的时间：{}", time))?;
+            }
+            Some(QuietHours { start, end })
+        }
+        _ => None,
+    };
+    crate::settings::update(&app, |s| s.notifications.quiet_hours = quiet_hours)?;
+    flush_pending(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_freshness_notifications(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.notifications.freshness = enabled)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_can_span_midnight() {
+        let (start, end) = (time("22:00"), time("08:00"));
+        assert!(in_quiet_hours(start, end, time("23:30")));
+        assert!(in_quiet_hours(start, end, time("07:59")));
+        assert!(!in_quiet_hours(start, end, time("08:00")));
+        assert!(!in_quiet_hours(start, end, time("12:00")));
+        assert!(in_quiet_hours(time("13:00"), time("14:00"), time("13:30")));
+        assert!(!in_quiet_hours(time("09:00"), time("09:00"), time("09:00")));
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::Write;
+
+// 最简单的 PDF 写入：页面由文字、线条和填充矩形组成，坐标以毫米为单位、原点在左上角
+// 文字统一使用 PDF 阅读器自带的 STSong-Light（UniGB-UCS2-H 编码，不嵌入字体，文件很小），不支持基本多文种平面以外的字符
+const PT_PER_MM: f64 = 72.0 / 25.4;
+const FONT_OBJECTS: &str = "<< /Type /Font /Subtype /Type0 /BaseFont /STSong-Light /Encoding /UniGB-UCS2-H /DescendantFonts [4 0 R] >>";
+const CID_FONT: &str = "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /STSong-Light \
+/CIDSystemInfo << /Registry (Adobe) /Ordering (GB1) /Supplement 2 >> /FontDescriptor 5 0 R /DW 1000 /W [1 95 500] >>";
+const FONT_DESCRIPTOR: &str = "<< /Type /FontDescriptor /FontName /STSong-Light /Flags 6 /FontBBox [-25 -254 1000 880] \
+/ItalicAngle 0 /Ascent 880 /Descent -120 /CapHeight 880 /StemV 93 >>";
+
+pub struct Page {
+    height: f64,
+    content: String,
+}
+
+pub struct PdfDocument {
+    width: f64,
+    height: f64,
+    pages: Vec<Page>,
+}
+
+// 文字宽度（毫米）：ASCII 字符为半角，其余为全角
+pub fn text_width(text: &str, size: f64) -> f64 {
+    let ems: f64 = text.chars().map(|c| if c.is_ascii() { 0.5 } else { 1.0 }).sum();
+    ems * size / PT_PER_MM
+}
+
+// 把一个单词加到当前行，放不下时换行；单词本身比一行还长时按字符拆开
+fn push_word(line: &mut String, word: &mut String, lines: &mut Vec<String>, size: f64, max_width: f64) {
+    if word.is_empty() {
+        return;
+    }
+    if !line.is_empty() && text_width(&format!("{}{}", line, word), size) > max_width {
+        lines.push(line.trim_end().to_string());
+        line.clear();
+    }
+    for c in word.chars() {
+        if !line.is_empty() && text_width(&format!("{}{}", line, c), size) > max_width {
+            lines.push(line.trim_end().to_string());
+            line.clear();
+        }
+        if !(line.is_empty() && c == ' ') {
+            line.push(c);
+        }
+    }
+    word.clear();
+}
+
+// 按宽度折行（英文单词不拆开，中文逐字），保留原有的换行
+pub fn wrap(text: &str, size: f64, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut word = String::new();
+        for c in paragraph.chars() {
+            word.push(c);
+            if !(c.is_ascii_alphanumeric() || (c.is_ascii_punctuation() && c != '/')) {
+                push_word(&mut line, &mut word, &mut lines, size, max_width);
+            }
+        }
+        push_word(&mut line, &mut word, &mut lines, size, max_width);
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+fn hex_text(text: &str) -> String {
+    let mut hex = String::with_capacity(text.len() * 4);
+    for c in text.chars() {
+        if let Ok(code) = u16::try_from(c as u32) {
+            let _ = write!(hex, "{:04X}", code);
+        }
+    }
+    hex
+}
+
+impl Page {
+    fn x(x: f64) -> f64 {
+        x * PT_PER_MM
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        (self.height - y) * PT_PER_MM
+    }
+
+    // y 为文字基线的位置，gray 为灰度（0 为黑色）
+    pub fn text(&mut self, x: f64, y: f64, size: f64, gray: f64, text: &str) {
+        let _ = writeln!(
+            self.content,
+            "BT {:.2} g /F1 {:.2} Tf {:.2} {:.2} Td <{}> Tj ET",
+            gray,
+            size,
+            Self::x(x),
+            self.y(y),
+            hex_text(text)
+        );
+    }
+
+    pub fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, gray: f64) {
+        let _ = writeln!(
+            self.content,
+            "{:.2} g {:.2} {:.2} {:.2} {:.2} re f",
+            gray,
+            Self::x(x),
+            self.y(y + height),
+            width * PT_PER_MM,
+            height * PT_PER_MM
+        );
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), width: f64, gray: f64) {
+        let _ = writeln!(
+            self.content,
+            "{:.2} G {:.2} w {:.2} {:.2} m {:.2} {:.2} l S",
+            gray,
+            width * PT_PER_MM,
+            Self::x(from.0),
+            self.y(from.1),
+            Self::x(to.0),
+            self.y(to.1)
+        );
+    }
+
+    // 二维码：modules 为按行排列的深色标记，size 为整个二维码的边长
+    pub fn qr_code(&mut self, x: f64, y: f64, size: f64, width: usize, modules: &[bool]) {
+        if width == 0 {
+            return;
+        }
+        let module = size / width as f64;
+        for (row, dark) in modules.chunks(width).enumerate() {
+            // 同一行连续的深色块合并为一个矩形
+            let mut column = 0;
+            while column < width {
+                if !dark[column] {
+                    column += 1;
+                    continue;
+                }
+                let start = column;
+                while column < width && dark[column] {
+                    column += 1;
+                }
+                self.fill_rect(
+                    x + start as f64 * module,
+                    y + row as f64 * module,
+                    (column - start) as f64 * module,
+                    module,
+                    0.0,
+                );
+            }
+        }
+    }
+}
+
+impl PdfDocument {
+    // 页面大小（毫米）
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            pages: Vec::new(),
+        }
+    }
+
+    pub fn add_page(&mut self) -> &mut Page {
+        self.pages.push(Page {
+            height: self.height,
+            content: String::new(),
+        });
+        let last = self.pages.len() - 1;
+        &mut self.pages[last]
+    }
+
+    pub fn page(&mut self, index: usize) -> &mut Page {
+        &mut self.pages[index]
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // 对象编号：1 目录，2 页面树，3-5 字体，之后每页两个对象（页面、内容）
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|i| 6 + i * 2).collect();
+        let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), self.pages.len()).into_bytes(),
+            FONT_OBJECTS.as_bytes().to_vec(),
+            CID_FONT.as_bytes().to_vec(),
+            FONT_DESCRIPTOR.as_bytes().to_vec(),
+        ];
+        for (page, id) in self.pages.iter().zip(&page_ids) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                    self.width * PT_PER_MM,
+                    self.height * PT_PER_MM,
+                    id + 1
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(page.content.as_bytes());
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(table, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            table,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_pages_with_a_valid_xref_table() {
+        let mut document = PdfDocument::new(105.0, 148.0);
+        let page = document.add_page();
+        page.text(8.0, 20.0, 16.0, 0.0, "耶加雪菲 G1");
+        page.qr_code(70.0, 110.0, 27.0, 3, &[true, true, false, false, true, false, true, false, true]);
+        let bytes = document.to_bytes();
+        let text = String::from_utf8_lossy(&bytes);
+
+        assert!(text.contains("<803652A096EA83F2002000470031> Tj"));
+        assert!(text.contains("/MediaBox [0 0 297.64 419.53]"));
+        // 第一行的两个深色块合并为一个矩形
+        assert_eq!(text.matches(" re f").count(), 4);
+        // 二进制注释行之后的内容都是 ASCII，偏移量按字节检查
+        let find = |pattern: &[u8]| bytes.windows(pattern.len()).position(|w| w == pattern).unwrap();
+        let xref: usize = text.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+        assert_eq!(xref, find(b"xref\n0 8\n"));
+        let table = String::from_utf8_lossy(&bytes[xref..]).to_string();
+        assert!(table.contains(&format!("{:010} 00000 n ", find(b"1 0 obj"))));
+
+        assert_eq!(wrap("埃塞俄比亚 Yirgacheffe", 10.0, 20.0), vec!["埃塞俄比亚", "Yirgacheffe"]);
+    }
+}
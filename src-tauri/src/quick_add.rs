@@ -0,0 +1,79 @@
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::roast_date::{parse_roast_date, RoastDatePrecision};
+
+// 托盘「快速添加咖啡豆…」打开的小窗口，提交后由主窗口完成保存
+const LABEL: &str = "quick-add";
+
+// quick-add-bean 事件，发给主窗口
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuickAddBeanEvent {
+    name: String,
+    roast_date: Option<String>,
+    weight: Option<f64>,
+}
+
+// 解析重量，允许带单位（"200" / "200g" / "200 克"）
+fn parse_weight(raw: &str) -> Option<f64> {
+    let s = raw.trim();
+    let number = s
+        .strip_suffix('克')
+        .or_else(|| s.strip_suffix(['g', 'G']))
+        .unwrap_or(s)
+        .trim();
+    number.parse::<f64>().ok().filter(|grams| grams.is_finite() && *grams > 0.0)
+}
+
+#[cfg(desktop)]
+pub fn open(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let title = crate::current_locale(app).tr("快速添加咖啡豆", "Quick Add Bean");
+    crate::open_tray_window(app, LABEL, title, 360.0, 320.0)
+}
+
+// 快速添加窗口提交：校验后发给主窗口并关闭窗口，校验失败时返回错误提示
+#[tauri::command]
+pub fn submit_quick_add_bean(
+    app: tauri::AppHandle,
+    name: String,
+    roast_date: String,
+    weight: String,
+) -> Result<(), String> {
+    let locale = crate::current_locale(&app);
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(locale.tr("请填写咖啡豆名称", "Please enter a bean name").to_string());
+    }
+
+    // 精确到天的日期统一为 YYYY-MM-DD，月份/烘焙周保留原文
+    let roast_date = roast_date.trim();
+    let roast_date = match roast_date {
+        "" => None,
+        raw => match parse_roast_date(raw) {
+            Some(range) if range.precision == RoastDatePrecision::Day => {
+                Some(range.earliest.format("%Y-%m-%d").to_string())
+            }
+            Some(_) => Some(raw.to_string()),
+            None => return Err(locale.tr("无法识别的烘焙日期", "Unrecognized roast date").to_string()),
+        },
+    };
+
+    let weight = match weight.trim() {
+        "" => None,
+        raw => Some(parse_weight(raw).ok_or_else(|| locale.tr("无效的重量", "Invalid weight").to_string())?),
+    };
+
+    let event = QuickAddBeanEvent {
+        name: name.to_string(),
+        roast_date,
+        weight,
+    };
+    crate::telemetry::record(&app, "tray.quick_add");
+    app.emit_to("main", "quick-add-bean", &event)
+        .map_err(|e| e.to_string())?;
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
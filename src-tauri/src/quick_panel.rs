@@ -0,0 +1,181 @@
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+use crate::background::BeanCache;
+use crate::widget::{self, WidgetSnapshot};
+
+// 快速面板：点击托盘图标时在图标旁弹出的小窗口，不打开主窗口即可查看库存
+const LABEL: &str = "quick-panel";
+
+// 面板尺寸（逻辑像素）和与托盘图标的间距
+const PANEL_WIDTH: f64 = 320.0;
+const PANEL_HEIGHT: f64 = 440.0;
+const PANEL_GAP: f64 = 8.0;
+
+// 物理像素的矩形区域
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bounds {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+// 计算面板左上角位置：水平居中对齐托盘图标，图标在屏幕上半部分（macOS 菜单栏、顶部任务栏）时
+// 显示在图标下方，否则显示在上方；最后限制在屏幕可用区域内
+fn anchor_position(icon: Bounds, panel_width: f64, panel_height: f64, gap: f64, area: Bounds) -> (f64, f64) {
+    let x = icon.x + icon.width / 2.0 - panel_width / 2.0;
+    let y = if icon.y + icon.height / 2.0 < area.y + area.height / 2.0 {
+        icon.y + icon.height + gap
+    } else {
+        icon.y - panel_height - gap
+    };
+    let max_x = (area.x + area.width - panel_width).max(area.x);
+    let max_y = (area.y + area.height - panel_height).max(area.y);
+    (x.clamp(area.x, max_x), y.clamp(area.y, max_y))
+}
+
+// 当前库存概览，数据来自前端最近一次推送的咖啡豆
+fn snapshot(app: &tauri::AppHandle) -> WidgetSnapshot {
+    let today = crate::today(app);
+    let beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.beans.clone()))
+        .unwrap_or_default();
+    let infos: Vec<_> = beans
+        .iter()
+        .filter(|bean| bean.remaining_grams().unwrap_or(0.0) > 0.0)
+        .map(|bean| crate::calculate_freshness(bean, today))
+        .collect();
+    let privacy = crate::settings::get(app).tray.privacy_mode.then(|| crate::current_locale(app));
+    widget::build_snapshot(&infos, today, privacy)
+}
+
+// 托盘刷新后把新的快照推给已打开的面板
+pub fn publish(app: &tauri::AppHandle, snapshot: &WidgetSnapshot) {
+    if app.get_webview_window(LABEL).is_some() {
+        let _ = app.emit_to(LABEL, "quick-panel-snapshot", snapshot);
+    }
+}
+
+#[cfg(desktop)]
+fn create(app: &tauri::AppHandle) -> tauri::Result<tauri::WebviewWindow> {
+    let window = tauri::WebviewWindowBuilder::new(app, LABEL, tauri::WebviewUrl::App("quick-panel".into()))
+        .title("Brew Guide")
+        .inner_size(PANEL_WIDTH, PANEL_HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()?;
+    // 失去焦点（点击面板外部）时自动隐藏
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(false) = event {
+            let _ = handle.hide();
+        }
+    });
+    Ok(window)
+}
+
+// 托盘图标点击：面板已显示时隐藏，否则移动到图标旁并显示
+#[cfg(desktop)]
+pub fn toggle(app: &tauri::AppHandle, icon_rect: &tauri::Rect) -> Result<(), String> {
+    let window = match app.get_webview_window(LABEL) {
+        Some(window) => window,
+        None => create(app).map_err(|e| e.to_string())?,
+    };
+    if window.is_visible().unwrap_or(false) {
+        return window.hide().map_err(|e| e.to_string());
+    }
+
+    // 托盘事件中的图标位置和尺寸本身就是物理像素
+    let position = icon_rect.position.to_physical::<f64>(1.0);
+    let size = icon_rect.size.to_physical::<f64>(1.0);
+    let icon = Bounds {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+    let monitor = app
+        .monitor_from_point(icon.x, icon.y)
+        .ok()
+        .flatten()
+        .or_else(|| app.primary_monitor().ok().flatten());
+    if let Some(monitor) = monitor {
+        let scale = monitor.scale_factor();
+        let work_area = monitor.work_area();
+        let area = Bounds {
+            x: work_area.position.x as f64,
+            y: work_area.position.y as f64,
+            width: work_area.size.width as f64,
+            height: work_area.size.height as f64,
+        };
+        let (x, y) = anchor_position(icon, PANEL_WIDTH * scale, PANEL_HEIGHT * scale, PANEL_GAP * scale, area);
+        window
+            .set_position(tauri::PhysicalPosition::new(x, y))
+            .map_err(|e| e.to_string())?;
+    }
+
+    publish(app, &snapshot(app));
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_quick_panel_snapshot(app: tauri::AppHandle) -> Result<WidgetSnapshot, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    Ok(snapshot(&app))
+}
+
+// 面板中的「打开 Brew Guide」：隐藏面板并显示主窗口，bean_id 不为空时跳转到该咖啡豆
+#[tauri::command]
+pub fn open_from_quick_panel(app: tauri::AppHandle, bean_id: Option<String>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.hide().map_err(|e| e.to_string())?;
+    }
+    match bean_id {
+        Some(bean_id) => crate::navigation::navigate_to(&app, crate::navigation::NavigationTarget::Bean { bean_id })
+            .map_err(|e| e.to_string()),
+        None => {
+            crate::show_main_window(&app);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Bounds = Bounds {
+        x: 0.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 1080.0,
+    };
+
+    #[test]
+    fn opens_below_icon_in_top_menu_bar() {
+        let icon = Bounds {
+            x: 1500.0,
+            y: 0.0,
+            width: 24.0,
+            height: 24.0,
+        };
+        assert_eq!(anchor_position(icon, 320.0, 440.0, 8.0, SCREEN), (1352.0, 32.0));
+    }
+
+    #[test]
+    fn opens_above_icon_in_bottom_taskbar_and_stays_on_screen() {
+        let icon = Bounds {
+            x: 1900.0,
+            y: 1050.0,
+            width: 20.0,
+            height: 30.0,
+        };
+        assert_eq!(anchor_position(icon, 320.0, 440.0, 8.0, SCREEN), (1600.0, 602.0));
+    }
+}
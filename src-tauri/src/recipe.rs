@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::store::{number_field, text_field};
+
+// 冲煮方案的步骤模型：前端的 params.stages 解析成带类型的阶段，开始计时前校验一次
+// 新格式 water/duration 为每一步的注水量和用时；旧格式 time/water 为累计时间和累计水量
+// bypass/beverage 步骤不在滤杯中注水，不参与计时
+const WATER_TOLERANCE: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StageKind {
+    Bloom,
+    Pour,
+    Wait,
+    Stir,
+    Drawdown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeStage {
+    pub index: usize,
+    pub kind: StageKind,
+    pub label: String,
+    pub detail: String,
+    // 阶段开始和结束时间（秒，从开始计时算起）
+    pub start: f64,
+    pub end: f64,
+    // 本阶段注水量和到本阶段结束时的累计水量（克）
+    pub water: f64,
+    pub cumulative_water: f64,
+    pub valve_status: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    pub name: Option<String>,
+    pub coffee: Option<f64>,
+    pub water: Option<f64>,
+    pub total_time: f64,
+    pub stages: Vec<RecipeStage>,
+}
+
+// "15g"、"225" 这样的克数
+fn grams(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(text) => {
+            let number: String = text.trim().chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+            number.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+fn stage_kind(stage: &Value, water: f64) -> StageKind {
+    let pour_type = text_field(stage, "pourType").unwrap_or_default();
+    let label = text_field(stage, "label").unwrap_or_default().to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| label.contains(word));
+    if pour_type == "wait" || (water <= 0.0 && has(&["等待", "静置", "wait"])) {
+        StageKind::Wait
+    } else if has(&["焖蒸", "闷蒸", "bloom"]) {
+        StageKind::Bloom
+    } else if water <= 0.0 && has(&["搅拌", "stir", "swirl", "摇晃"]) {
+        StageKind::Stir
+    } else if water <= 0.0 && has(&["下渗", "滴滤", "drawdown", "放水"]) {
+        StageKind::Drawdown
+    } else if water <= 0.0 {
+        StageKind::Wait
+    } else {
+        StageKind::Pour
+    }
+}
+
+fn is_legacy(stages: &[Value]) -> bool {
+    let first = stages.iter().find(|stage| stage.is_object());
+    first.is_some_and(|stage| number_field(stage, "duration").is_none() && number_field(stage, "time").is_some())
+}
+
+impl Recipe {
+    // method 为前端冲煮方案的完整数据（name、params.coffee/water/stages 等）
+    pub fn from_method(method: &Value) -> Result<Recipe, String> {
+        let params = method.get("params").unwrap_or(&Value::Null);
+        let raw: &[Value] = params.get("stages").and_then(Value::as_array).map_or(&[], Vec::as_slice);
+        let legacy = is_legacy(raw);
+        let total_water = grams(params.get("water")).filter(|w| *w > 0.0);
+
+        let mut stages = Vec::new();
+        let (mut time, mut water) = (0.0, 0.0);
+        for (position, stage) in raw.iter().enumerate() {
+            let step = position + 1;
+            let pour_type = text_field(stage, "pourType").unwrap_or_default();
+            if matches!(pour_type, "bypass" | "beverage") {
+                continue;
+            }
+            let (duration, stage_water) = if legacy {
+                let end = number_field(stage, "time").ok_or(format!("第 {} 步缺少时间", step))?;
+                if !end.is_finite() || end < time {
+                    return Err(format!("第 {} 步的时间早于上一步", step));
+                }
+                // 等待步骤的旧数据可能不写累计水量
+                let cumulative = grams(stage.get("water")).unwrap_or(water);
+                (end - time, cumulative - water)
+            } else {
+                let duration = number_field(stage, "duration").unwrap_or(0.0);
+                let stage_water = if pour_type == "wait" { 0.0 } else { grams(stage.get("water")).unwrap_or(0.0) };
+                (duration, stage_water)
+            };
+            if !duration.is_finite() || duration < 0.0 {
+                return Err(format!("第 {} 步的用时无效", step));
+            }
+            if !stage_water.is_finite() || stage_water < 0.0 {
+                return Err(format!("第 {} 步的注水量无效", step));
+            }
+            water += stage_water;
+            stages.push(RecipeStage {
+                index: stages.len(),
+                kind: stage_kind(stage, stage_water),
+                label: text_field(stage, "label").unwrap_or_default().to_string(),
+                detail: text_field(stage, "detail").unwrap_or_default().to_string(),
+                start: time,
+                end: time + duration,
+                water: stage_water,
+                cumulative_water: water,
+                valve_status: text_field(stage, "valveStatus").map(str::to_string),
+            });
+            time += duration;
+        }
+
+        if stages.is_empty() {
+            return Err("冲煮方案没有可计时的步骤".to_string());
+        }
+        if time <= 0.0 {
+            return Err("冲煮方案的步骤都没有设置用时".to_string());
+        }
+        if let Some(total) = total_water {
+            if water > total + WATER_TOLERANCE {
+                return Err(format!("步骤注水总量 {}g 超过方案水量 {}g", water, total));
+            }
+        }
+        Ok(Recipe {
+            name: text_field(method, "name").map(str::to_string),
+            coffee: grams(params.get("coffee")),
+            water: total_water,
+            total_time: time,
+            stages,
+        })
+    }
+
+    // 计时到 elapsed 秒时所在的阶段，全部结束后返回 None；用时为 0 的阶段会被直接跳过
+    pub fn stage_at(&self, elapsed: f64) -> Option<&RecipeStage> {
+        self.stages.iter().find(|stage| elapsed < stage.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_and_validates_stages() {
+        let method = json!({
+            "name": "一刀流",
+            "params": {
+                "coffee": "15g",
+                "water": "225g",
+                "stages": [
+                    { "pourType": "circle", "label": "焖蒸", "water": "30", "duration": 10, "detail": "" },
+                    { "pourType": "wait", "label": "等待", "duration": 20, "detail": "" },
+                    { "pourType": "circle", "label": "绕圈注水", "water": "195", "duration": 30, "detail": "中心向外" },
+                    { "pourType": "other", "label": "搅拌", "duration": 5, "detail": "" },
+                    { "pourType": "bypass", "label": "加冰", "water": "60", "detail": "" },
+                    { "pourType": "wait", "label": "下渗", "duration": 60, "detail": "" },
+                ],
+            },
+        });
+        let recipe = Recipe::from_method(&method).unwrap();
+        let kinds: Vec<_> = recipe.stages.iter().map(|stage| stage.kind).collect();
+        assert_eq!(kinds, [StageKind::Bloom, StageKind::Wait, StageKind::Pour, StageKind::Stir, StageKind::Wait]);
+        assert_eq!(recipe.total_time, 125.0);
+        assert_eq!(recipe.stages[2].start, 30.0);
+        assert_eq!(recipe.stages[2].cumulative_water, 225.0);
+        assert_eq!(recipe.stage_at(29.9).map(|stage| stage.index), Some(1));
+        assert_eq!(recipe.stage_at(30.0).map(|stage| stage.index), Some(2));
+        assert!(recipe.stage_at(125.0).is_none());
+
+        // 旧格式：累计时间和累计水量
+        let legacy = json!({ "params": { "water": "225g", "stages": [
+            { "label": "焖蒸", "time": 25, "water": "30", "detail": "" },
+            { "label": "注水", "time": 60, "water": "225", "detail": "" },
+            { "label": "下渗", "time": 120, "detail": "" },
+        ] } });
+        let recipe = Recipe::from_method(&legacy).unwrap();
+        assert_eq!(recipe.stages[1].water, 195.0);
+        assert_eq!(recipe.stages[2].kind, StageKind::Drawdown);
+        assert_eq!(recipe.stages[2].end - recipe.stages[2].start, 60.0);
+
+        let over = json!({ "params": { "water": "200g", "stages": [{ "label": "注水", "water": "250", "duration": 30 }] } });
+        assert!(Recipe::from_method(&over).unwrap_err().contains("超过方案水量"));
+        let backwards = json!({ "params": { "stages": [{ "label": "a", "time": 30 }, { "label": "b", "time": 20 }] } });
+        assert!(Recipe::from_method(&backwards).is_err());
+        assert!(Recipe::from_method(&json!({ "params": { "stages": [] } })).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::{Action, ActionType, NotificationExt};
+
+use crate::json_file;
+use crate::navigation::{navigate_to, NavigationTarget};
+use crate::notifications::NotificationTarget;
+
+// 冲煮提醒（如「每天 07:30 提醒我手冲」）：保存在 brew-reminders.json，由后台线程按时发送系统通知
+const REMINDERS_FILE: &str = "brew-reminders.json";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// 错过提醒时间（休眠、应用未运行）超过这个时长就不再补发
+const MISSED_GRACE_MINUTES: i64 = 30;
+
+// 提醒通知上的按钮：「记录一杯」「稍后提醒」
+pub const ACTION_TYPE: &str = "brew-reminder";
+const ACTION_LOG_BREW: &str = "log-brew";
+const ACTION_SNOOZE: &str = "snooze";
+const SNOOZE_MINUTES: i64 = 10;
+
+// brew-reminder-action 事件，点击提醒通知上的按钮后发给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BrewReminderActionEvent {
+    reminder_id: String,
+    action: &'static str, // logBrew / snooze
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewReminder {
+    pub id: String,
+    pub time: String,        // HH:MM
+    pub weekdays: Vec<u32>,  // 1 = 周一 … 7 = 周日，为空表示每天
+    pub message: Option<String>,
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_fired: Option<String>, // 最近一次提醒的日期（YYYY-MM-DD）
+    #[serde(default)]
+    pub snoozed_until: Option<i64>, // 「稍后提醒」的再次提醒时间（毫秒）
+}
+
+impl BrewReminder {
+    // now 所在的日期是否应该提醒、且已经到了提醒时间
+    fn is_due(&self, today: NaiveDate, now: NaiveTime) -> bool {
+        let Ok(time) = NaiveTime::parse_from_str(&self.time, "%H:%M") else {
+            return false;
+        };
+        let weekday = today.weekday().number_from_monday();
+        let elapsed = (now - time).num_minutes();
+        self.enabled
+            && (self.weekdays.is_empty() || self.weekdays.contains(&weekday))
+            && now >= time
+            && elapsed < MISSED_GRACE_MINUTES
+            && self.last_fired.as_deref() != Some(today.format("%Y-%m-%d").to_string().as_str())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReminderState {
+    reminders: Vec<BrewReminder>,
+}
+
+impl ReminderState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        reminders_path(app)
+            .ok()
+            .and_then(|path| {
+                json_file::load(&path)
+                    .map_err(|e| log::warn!("读取冲煮提醒失败：{}", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn reminders_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(REMINDERS_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn validate(time: &str, weekdays: &[u32]) -> Result<(), String> {
+    NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("无效的时间：{}", time))?;
+    if let Some(day) = weekdays.iter().find(|day| !(1..=7).contains(*day)) {
+        return Err(format!("无效的星期：{}", day));
+    }
+    Ok(())
+}
+
+// 修改提醒列表并保存
+fn update<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut Vec<BrewReminder>) -> Result<T, String>) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<ReminderState>>>()
+        .ok_or("冲煮提醒未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let result = f(&mut state.reminders)?;
+    json_file::save(&reminders_path(app)?, &*state).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+// 发送到点的提醒（包括「稍后提醒」到时间的），并记下发送日期
+fn fire_due(app: &tauri::AppHandle) -> Result<(), String> {
+    let today = crate::today(app);
+    let now_millis = chrono::Utc::now().timestamp_millis();
+    let now = crate::time_of(app, chrono::Utc::now());
+    let is_due = |r: &BrewReminder| r.is_due(today, now) || r.snoozed_until.is_some_and(|at| at <= now_millis);
+    let due: Vec<BrewReminder> = {
+        let state = app
+            .try_state::<Arc<Mutex<ReminderState>>>()
+            .ok_or("冲煮提醒未初始化")?;
+        let state = state.lock().map_err(|e| e.to_string())?;
+        state.reminders.iter().filter(|r| is_due(r)).cloned().collect()
+    };
+    if due.is_empty() {
+        return Ok(());
+    }
+
+    let day = today.format("%Y-%m-%d").to_string();
+    update(app, |reminders| {
+        for reminder in reminders.iter_mut().filter(|r| due.iter().any(|d| d.id == r.id)) {
+            reminder.last_fired = Some(day.clone());
+            reminder.snoozed_until = None;
+        }
+        Ok(())
+    })?;
+    let locale = crate::current_locale(app);
+    for reminder in due {
+        let body = reminder
+            .message
+            .unwrap_or_else(|| locale.tr("该冲一杯咖啡了", "Time for a brew").to_string());
+        let target = NotificationTarget::BrewReminder { reminder_id: reminder.id };
+        crate::notifications::show(app, "Brew Guide", &body, Some(target));
+    }
+    Ok(())
+}
+
+// 注册提醒通知上的按钮，需在发送第一条提醒前调用
+pub fn register_actions(app: &tauri::AppHandle) {
+    let locale = crate::current_locale(app);
+    let action_type = ActionType::builder(ACTION_TYPE)
+        .actions(vec![
+            Action::builder(ACTION_LOG_BREW, locale.tr("记录一杯", "Log a Brew")).build(),
+            Action::builder(ACTION_SNOOZE, locale.tr("稍后提醒", "Remind Me Later")).build(),
+        ])
+        .build();
+    if let Err(e) = app.notification().register_action_types(vec![action_type]) {
+        log::warn!("注册提醒按钮失败：{}", e);
+    }
+}
+
+// 提醒通知上的按钮：「记录一杯」打开主窗口新建冲煮记录，「稍后提醒」10 分钟后再提醒一次
+// 两种操作都以 brew-reminder-action 事件通知前端
+pub fn handle_action(app: &tauri::AppHandle, reminder_id: &str, action_id: &str) -> tauri::Result<()> {
+    let action = match action_id {
+        ACTION_LOG_BREW => "logBrew",
+        ACTION_SNOOZE => "snooze",
+        _ => return Ok(()),
+    };
+    crate::telemetry::record(app, &format!("reminder.{}", action_id));
+    if action_id == ACTION_SNOOZE {
+        let until = chrono::Utc::now().timestamp_millis() + SNOOZE_MINUTES * 60 * 1000;
+        let result = update(app, |reminders| {
+            if let Some(reminder) = reminders.iter_mut().find(|r| r.id == reminder_id) {
+                reminder.snoozed_until = Some(until);
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            log::warn!("稍后提醒失败：{}", e);
+        }
+    } else {
+        navigate_to(app, NavigationTarget::NewBrewLog { bean_id: None })?;
+    }
+    app.emit(
+        "brew-reminder-action",
+        BrewReminderActionEvent {
+            reminder_id: reminder_id.to_string(),
+            action,
+        },
+    )
+}
+
+// 桌面端：后台线程每 30 秒检查一次是否有到点的提醒
+pub fn spawn_reminder_loop(app: tauri::AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        if let Err(e) = fire_due(&app) {
+            log::warn!("发送冲煮提醒失败：{}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn schedule_brew_reminder(
+    app: tauri::AppHandle,
+    time: String,
+    weekdays: Vec<u32>,
+    message: Option<String>,
+) -> Result<BrewReminder, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    validate(&time, &weekdays)?;
+    let reminder = BrewReminder {
+        id: format!("reminder-{}", chrono::Local::now().timestamp_millis()),
+        time,
+        weekdays,
+        message: message.filter(|m| !m.trim().is_empty()),
+        enabled: true,
+        last_fired: None,
+        snoozed_until: None,
+    };
+    crate::telemetry::record(&app, "reminder.schedule");
+    update(&app, |reminders| {
+        reminders.push(reminder.clone());
+        Ok(reminder)
+    })
+}
+
+#[tauri::command]
+pub fn list_brew_reminders(app: tauri::AppHandle) -> Result<Vec<BrewReminder>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    Ok(app
+        .try_state::<Arc<Mutex<ReminderState>>>()
+        .and_then(|state| state.lock().ok().map(|state| state.reminders.clone()))
+        .unwrap_or_default())
+}
+
+// 修改时间、星期、文字或启用状态；发送记录保留，避免改完后当天重复提醒
+#[tauri::command]
+pub fn update_brew_reminder(app: tauri::AppHandle, reminder: BrewReminder) -> Result<BrewReminder, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    validate(&reminder.time, &reminder.weekdays)?;
+    update(&app, |reminders| {
+        let existing = reminders
+            .iter_mut()
+            .find(|r| r.id == reminder.id)
+            .ok_or_else(|| format!("找不到冲煮提醒：{}", reminder.id))?;
+        *existing = BrewReminder {
+            last_fired: existing.last_fired.take(),
+            snoozed_until: existing.snoozed_until.take(),
+            ..reminder
+        };
+        Ok(existing.clone())
+    })
+}
+
+#[tauri::command]
+pub fn delete_brew_reminder(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    update(&app, |reminders| {
+        reminders.retain(|r| r.id != id);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder(time: &str, weekdays: Vec<u32>) -> BrewReminder {
+        BrewReminder {
+            id: "reminder-1".to_string(),
+            time: time.to_string(),
+            weekdays,
+            message: None,
+            enabled: true,
+            last_fired: None,
+            snoozed_until: None,
+        }
+    }
+
+    fn at(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn fires_once_within_grace_period_on_matching_days() {
+        // 2026-10-16 是周五
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let daily = reminder("07:30", vec![]);
+        assert!(!daily.is_due(friday, at("07:29")));
+        assert!(daily.is_due(friday, at("07:30")));
+        assert!(!daily.is_due(friday, at("08:00")));
+
+        let fired = BrewReminder {
+            last_fired: Some("2026-10-16".to_string()),
+            ..daily
+        };
+        assert!(!fired.is_due(friday, at("07:31")));
+
+        assert!(!reminder("07:30", vec![1, 2, 3, 4]).is_due(friday, at("07:30")));
+        assert!(reminder("07:30", vec![5]).is_due(friday, at("07:30")));
+    }
+}
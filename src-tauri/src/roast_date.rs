@@ -0,0 +1,180 @@
+use chrono::{NaiveDate, Weekday};
+
+// 烘焙日期精度：部分烘焙商只印了月份（"2024年11月"）或烘焙周
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoastDatePrecision {
+    Day,
+    Week,
+    Month,
+}
+
+// 解析后的烘焙日期区间（精确到天时 earliest == latest）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoastDateRange {
+    pub earliest: NaiveDate,
+    pub latest: NaiveDate,
+    pub precision: RoastDatePrecision,
+}
+
+impl RoastDateRange {
+    fn day(date: NaiveDate) -> Self {
+        Self {
+            earliest: date,
+            latest: date,
+            precision: RoastDatePrecision::Day,
+        }
+    }
+}
+
+// 解析烘焙日期，支持以下格式：
+// - 精确日期：2024-11-05 / 2024/11/05 / 2024年11月5日（允许带时间后缀）
+// - 月份：2024-11 / 2024/11 / 2024年11月
+// - 烘焙周：2024-W45 / 2024W45 / 2024年第45周
+pub fn parse_roast_date(raw: &str) -> Option<RoastDateRange> {
+    let s = raw.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    // 精确日期（兼容 ISO 时间戳，只取日期部分）
+    let date_part = s.split(['T', ' ']).next().unwrap_or(s);
+    for fmt in ["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%Y年%m月%d日"] {
+        if let Ok(date) = NaiveDate::parse_from_str(date_part, fmt) {
+            return Some(RoastDateRange::day(date));
+        }
+    }
+
+    if let Some(range) = parse_week(s) {
+        return Some(range);
+    }
+
+    parse_month(s)
+}
+
+fn parse_week(s: &str) -> Option<RoastDateRange> {
+    let (year, week) = if let Some((y, w)) = s.split_once('W').or_else(|| s.split_once('w')) {
+        (y.trim_end_matches('-'), w)
+    } else if let Some(rest) = s.strip_suffix('周') {
+        rest.split_once("年第")?
+    } else {
+        return None;
+    };
+
+    let year: i32 = year.trim().parse().ok()?;
+    let week: u32 = week.trim().parse().ok()?;
+    let earliest = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+    let latest = NaiveDate::from_isoywd_opt(year, week, Weekday::Sun)?;
+
+    Some(RoastDateRange {
+        earliest,
+        latest,
+        precision: RoastDatePrecision::Week,
+    })
+}
+
+fn parse_month(s: &str) -> Option<RoastDateRange> {
+    let s = s.strip_suffix('月').unwrap_or(s);
+    let (year, month) = s
+        .split_once('-')
+        .or_else(|| s.split_once('/'))
+        .or_else(|| s.split_once('年'))?;
+
+    let year: i32 = year.trim().parse().ok()?;
+    let month: u32 = month.trim().parse().ok()?;
+    let earliest = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+
+    Some(RoastDateRange {
+        earliest,
+        latest: next_month.pred_opt()?,
+        precision: RoastDatePrecision::Month,
+    })
+}
+
+// 最佳赏味期的日期区间：从烘焙日期最晚可能的一天加 start_day 开始，到最早可能的一天加 end_day 结束
+// 默认养豆 7 天、赏味期到第 30 天（与 calculate_freshness 一致）
+pub fn flavor_window(roast_date: &str, start_day: Option<i32>, end_day: Option<i32>) -> Option<(NaiveDate, NaiveDate)> {
+    let range = parse_roast_date(roast_date)?;
+    let start = range.latest.checked_add_signed(chrono::Duration::days(start_day.unwrap_or(7).into()))?;
+    let end = range.earliest.checked_add_signed(chrono::Duration::days(end_day.unwrap_or(30).into()))?;
+    Some((start, end.max(start)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn parses_supported_formats() {
+        use RoastDatePrecision::*;
+        let cases = [
+            ("2024-11-05", date(2024, 11, 5), date(2024, 11, 5), Day),
+            ("2024/11/05", date(2024, 11, 5), date(2024, 11, 5), Day),
+            ("2024.11.05", date(2024, 11, 5), date(2024, 11, 5), Day),
+            ("2024年11月5日", date(2024, 11, 5), date(2024, 11, 5), Day),
+            // ISO 时间戳只取日期部分
+            ("2024-11-05T08:30:00.000Z", date(2024, 11, 5), date(2024, 11, 5), Day),
+            ("2024-11-05 08:30", date(2024, 11, 5), date(2024, 11, 5), Day),
+            (" 2024-11 ", date(2024, 11, 1), date(2024, 11, 30), Month),
+            ("2024/02", date(2024, 2, 1), date(2024, 2, 29), Month),
+            ("2024年11月", date(2024, 11, 1), date(2024, 11, 30), Month),
+            // 十二月的最后一天在下一年一月一日之前
+            ("2024-12", date(2024, 12, 1), date(2024, 12, 31), Month),
+            ("2024-W45", date(2024, 11, 4), date(2024, 11, 10), Week),
+            ("2024w45", date(2024, 11, 4), date(2024, 11, 10), Week),
+            ("2024年第45周", date(2024, 11, 4), date(2024, 11, 10), Week),
+            // ISO 周可能跨年
+            ("2025-W01", date(2024, 12, 30), date(2025, 1, 5), Week),
+            ("2020-W53", date(2020, 12, 28), date(2021, 1, 3), Week),
+        ];
+        for (raw, earliest, latest, precision) in cases {
+            let range = parse_roast_date(raw).unwrap_or_else(|| panic!("无法解析 {}", raw));
+            assert_eq!(
+                range,
+                RoastDateRange {
+                    earliest,
+                    latest,
+                    precision
+                },
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        // 2024 年只有 52 个 ISO 周
+        for raw in ["", "  ", "2024-W53", "2024-W00", "2024年第0周", "2024-13", "2024年13月", "2024-02-30", "十一月", "next week"] {
+            assert_eq!(parse_roast_date(raw), None, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn flavor_window_uses_the_conservative_end_of_the_range() {
+        let cases = [
+            (("2024-11-05", None, None), Some((date(2024, 11, 12), date(2024, 12, 5)))),
+            (("2024-11-05", Some(3), Some(14)), Some((date(2024, 11, 8), date(2024, 11, 19)))),
+            // 烘焙周：从周日算开始，从周一算结束
+            (("2024年第45周", None, None), Some((date(2024, 11, 17), date(2024, 12, 4)))),
+            (("2024年11月", Some(3), Some(45)), Some((date(2024, 12, 3), date(2024, 12, 16)))),
+            (("2024-12", Some(3), Some(45)), Some((date(2025, 1, 3), date(2025, 1, 15)))),
+            // 只知道月份时默认的 7–30 天区间收成一天
+            (("2024-12", None, None), Some((date(2025, 1, 7), date(2025, 1, 7)))),
+            // 结束早于开始时区间收成一天
+            (("2024-11-05", Some(20), Some(10)), Some((date(2024, 11, 25), date(2024, 11, 25)))),
+            (("2024-W53", None, None), None),
+        ];
+        for ((raw, start_day, end_day), expected) in cases {
+            assert_eq!(flavor_window(raw, start_day, end_day), expected, "{}", raw);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::store::{number_field, text_field};
+
+// 从烘焙商的商品页面生成预填的新咖啡豆：优先用 Shopify 商品接口（很多精品烘焙商使用），
+// 其次是页面里的 JSON-LD 商品数据和 OpenGraph 标签；商品描述再交给剪贴板识别补充产地、处理法、风味等
+const MAX_PAGE_BYTES: usize = 3 * 1024 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+// 已知使用 Shopify 的烘焙商；商品接口没有烘焙商名称时使用这里的名称
+const SHOPIFY_ROASTERS: [(&str, &str); 5] = [
+    ("onyxcoffeelab.com", "Onyx Coffee Lab"),
+    ("timwendelboe.no", "Tim Wendelboe"),
+    ("lacabra.dk", "La Cabra"),
+    ("seycoffee.com", "Sey Coffee"),
+    ("manhattancoffeeroasters.com", "Manhattan Coffee Roasters"),
+];
+
+// 页面解析用到的正则只编译一次
+static ENTITY: OnceLock<Regex> = OnceLock::new();
+static BLOCK_TAG: OnceLock<Regex> = OnceLock::new();
+static ANY_TAG: OnceLock<Regex> = OnceLock::new();
+static META_TAG: OnceLock<Regex> = OnceLock::new();
+static META_ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+static JSON_LD_SCRIPT: OnceLock<Regex> = OnceLock::new();
+
+fn cached(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(concat!("BrewGuide/", env!("CARGO_PKG_VERSION")))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// 边下载边检查大小，超过 MAX_PAGE_BYTES 时立即停止（响应头声明的长度过大时不开始下载）
+async fn fetch_text(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    if response.content_length().is_some_and(|length| length > MAX_PAGE_BYTES as u64) {
+        return Err("页面过大".to_string());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if bytes.len() + chunk.len() > MAX_PAGE_BYTES {
+            return Err("页面过大".to_string());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn decode_entities(text: &str) -> String {
+    let entity = cached(&ENTITY, r"&(#x[0-9a-fA-F]+|#\d+|amp|lt|gt|quot|apos|nbsp);");
+    entity
+        .replace_all(text, |c: &regex::Captures| {
+            let name = &c[1];
+            let code = if let Some(hex) = name.strip_prefix("#x") {
+                u32::from_str_radix(hex, 16).ok()
+            } else if let Some(decimal) = name.strip_prefix('#') {
+                decimal.parse().ok()
+            } else {
+                None
+            };
+            match name {
+                "amp" => "&".to_string(),
+                "lt" => "<".to_string(),
+                "gt" => ">".to_string(),
+                "quot" => "\"".to_string(),
+                "apos" => "'".to_string(),
+                "nbsp" => " ".to_string(),
+                _ => code.and_then(char::from_u32).map(String::from).unwrap_or_default(),
+            }
+        })
+        .into_owned()
+}
+
+// 去掉标签，块级元素换行，便于按行识别“风味：xxx”
+fn html_to_text(html: &str) -> String {
+    let blocks = cached(&BLOCK_TAG, r"(?i)<\s*(br|/p|/div|/li|/h\d|/tr)[^>]*>");
+    let tags = cached(&ANY_TAG, r"(?s)<[^>]*>");
+    let lines = blocks.replace_all(html, "\n");
+    decode_entities(&tags.replace_all(&lines, ""))
+}
+
+fn meta_tags(html: &str) -> Map<String, Value> {
+    let tag = cached(&META_TAG, r"(?is)<meta\s[^>]*>");
+    let attribute = cached(&META_ATTRIBUTE, r#"(?is)(property|name|content)\s*=\s*["']([^"']*)["']"#);
+    let mut meta = Map::new();
+    for found in tag.find_iter(html) {
+        let (mut key, mut content) = (None, None);
+        for c in attribute.captures_iter(found.as_str()) {
+            match c[1].to_lowercase().as_str() {
+                "content" => content = Some(decode_entities(&c[2])),
+                _ => key = Some(c[2].to_lowercase()),
+            }
+        }
+        if let (Some(key), Some(content)) = (key, content) {
+            meta.entry(key).or_insert(json!(content.trim()));
+        }
+    }
+    meta
+}
+
+// 页面中的 JSON-LD 可能是单个对象、数组或 @graph，找出第一个 Product
+fn json_ld_product(html: &str) -> Option<Value> {
+    let script = cached(&JSON_LD_SCRIPT, r#"(?is)<script[^>]*type\s*=\s*["']application/ld\+json["'][^>]*>(.*?)</script>"#);
+    fn find(value: &Value) -> Option<&Value> {
+        match value {
+            Value::Array(items) => items.iter().find_map(find),
+            Value::Object(object) => {
+                let is_product = match object.get("@type") {
+                    Some(Value::String(kind)) => kind == "Product",
+                    Some(Value::Array(kinds)) => kinds.iter().any(|k| k == "Product"),
+                    _ => false,
+                };
+                if is_product {
+                    Some(value)
+                } else {
+                    object.get("@graph").and_then(find)
+                }
+            }
+            _ => None,
+        }
+    }
+    let product = script
+        .captures_iter(html)
+        .filter_map(|c| serde_json::from_str::<Value>(c[1].trim()).ok())
+        .find_map(|value| find(&value).cloned());
+    product
+}
+
+fn number_text(n: f64) -> String {
+    let rounded = (n * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        rounded.to_string()
+    }
+}
+
+fn price_of(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(text) => text.trim().replace(',', "").parse().ok(),
+        _ => None,
+    }
+}
+
+// 合并后的原始信息，最后统一生成咖啡豆
+#[derive(Default)]
+struct Product {
+    name: Option<String>,
+    roaster: Option<String>,
+    price: Option<f64>,
+    grams: Option<f64>,
+    description: String,
+}
+
+fn from_json_ld(product: &Value) -> Product {
+    let offers = match product.get("offers") {
+        Some(Value::Array(offers)) => offers.first(),
+        other => other,
+    };
+    let brand = match product.get("brand") {
+        Some(Value::String(brand)) => Some(brand.clone()),
+        Some(brand) => text_field(brand, "name").map(str::to_string),
+        None => None,
+    };
+    let weight = product.get("weight");
+    let grams = weight.and_then(|w| number_field(w, "value")).map(|value| {
+        match weight.and_then(|w| text_field(w, "unitCode").or_else(|| text_field(w, "unitText"))) {
+            Some("KGM") | Some("kg") => value * 1000.0,
+            _ => value,
+        }
+    });
+    Product {
+        name: text_field(product, "name").map(str::to_string),
+        roaster: brand,
+        price: offers.and_then(|o| price_of(o.get("price")).or_else(|| price_of(o.get("lowPrice")))),
+        grams,
+        description: text_field(product, "description").map(html_to_text).unwrap_or_default(),
+    }
+}
+
+fn from_open_graph(meta: &Map<String, Value>) -> Product {
+    let field = |key: &str| meta.get(key).and_then(Value::as_str).filter(|v| !v.is_empty()).map(str::to_string);
+    Product {
+        name: field("og:title").or_else(|| field("twitter:title")),
+        roaster: field("og:site_name"),
+        price: field("product:price:amount").or_else(|| field("og:price:amount")).and_then(|p| p.parse().ok()),
+        grams: None,
+        description: field("og:description").or_else(|| field("description")).unwrap_or_default(),
+    }
+}
+
+// Shopify 的 /products/{handle}.js：价格以分为单位，重量以克为单位
+fn from_shopify(product: &Value) -> Product {
+    let variant = product.get("variants").and_then(Value::as_array).and_then(|v| v.first());
+    Product {
+        name: text_field(product, "title").map(str::to_string),
+        roaster: text_field(product, "vendor").map(str::to_string),
+        price: variant
+            .and_then(|v| number_field(v, "price"))
+            .or_else(|| number_field(product, "price"))
+            .map(|cents| cents / 100.0),
+        grams: variant.and_then(|v| number_field(v, "grams").or_else(|| number_field(v, "weight"))).filter(|g| *g > 0.0),
+        description: text_field(product, "description").map(html_to_text).unwrap_or_default(),
+    }
+}
+
+fn merge(primary: Product, fallback: Product) -> Product {
+    Product {
+        name: primary.name.or(fallback.name),
+        roaster: primary.roaster.or(fallback.roaster),
+        price: primary.price.or(fallback.price),
+        grams: primary.grams.or(fallback.grams),
+        description: if primary.description.trim().is_empty() { fallback.description } else { primary.description },
+    }
+}
+
+fn bean_draft(product: Product, today: NaiveDate) -> Option<Value> {
+    let name = product.name?.trim().to_string();
+    // 描述中识别出的字段（产地、处理法、风味、重量等）作为补充，结构化数据优先
+    let mut bean = crate::clipboard_bean::parse_bean_text(&format!("{}\n{}", name, product.description), today)
+        .and_then(|bean| bean.as_object().cloned())
+        .unwrap_or_default();
+    bean.insert("name".into(), json!(name));
+    if let Some(roaster) = product.roaster.filter(|r| !r.trim().is_empty()) {
+        bean.insert("roaster".into(), json!(roaster.trim()));
+    }
+    if let Some(price) = product.price.filter(|p| *p > 0.0) {
+        bean.insert("price".into(), json!(number_text(price)));
+    }
+    if let Some(grams) = product.grams {
+        bean.insert("capacity".into(), json!(number_text(grams.round())));
+        bean.insert("remaining".into(), json!(number_text(grams.round())));
+    }
+    // 商品页上的日期多是上架时间，不作为烘焙日期
+    bean.remove("roastDate");
+    bean.insert("beanState".into(), json!("roasted"));
+    Some(Value::Object(bean))
+}
+
+fn shopify_product_url(url: &reqwest::Url) -> Option<String> {
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    let index = segments.iter().position(|s| *s == "products")?;
+    let handle = segments.get(index + 1).filter(|h| !h.is_empty())?;
+    Some(format!("{}://{}/products/{}.js", url.scheme(), url.host_str()?, handle))
+}
+
+fn known_roaster(url: &reqwest::Url) -> Option<&'static str> {
+    let host = url.host_str()?.trim_start_matches("www.");
+    SHOPIFY_ROASTERS.iter().find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain))).map(|(_, name)| *name)
+}
+
+#[tauri::command]
+pub async fn fetch_bean_from_url(app: tauri::AppHandle, url: String) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|_| format!("无效的链接：{}", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("无效的链接：{}", url));
+    }
+    let client = http_client()?;
+    let html = fetch_text(&client, parsed.as_str()).await?;
+
+    let mut product = Product::default();
+    let is_shopify = known_roaster(&parsed).is_some() || html.contains("cdn.shopify.com");
+    if let Some(product_url) = shopify_product_url(&parsed).filter(|_| is_shopify) {
+        match fetch_text(&client, &product_url).await.and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string())) {
+            Ok(shopify) => product = from_shopify(&shopify),
+            Err(e) => log::warn!("读取 Shopify 商品数据失败：{}", e),
+        }
+    }
+    if let Some(json_ld) = json_ld_product(&html) {
+        product = merge(product, from_json_ld(&json_ld));
+    }
+    product = merge(product, from_open_graph(&meta_tags(&html)));
+    if product.roaster.is_none() {
+        product.roaster = known_roaster(&parsed).map(str::to_string);
+    }
+
+    let bean = bean_draft(product, crate::today(&app)).ok_or("没有在页面中找到商品信息")?;
+    crate::telemetry::record(&app, "import.url");
+    Ok(bean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_a_bean_from_json_ld_and_open_graph() {
+        let html = r#"<html><head>
+<meta property="og:title" content="Kenya Gatomboya &amp; Co">
+<meta property="og:site_name" content="Example Roasters">
+<meta name="description" content="Juicy and bright">
+<script type="application/ld+json">{"@context":"https://schema.org","@graph":[{"@type":"WebSite"},
+{"@type":"Product","name":"Kenya Gatomboya AA","offers":{"@type":"Offer","price":"21.50"},
+"weight":{"value":0.25,"unitCode":"KGM"},
+"description":"<p>Process: Washed</p><p>Tasting notes: Blackcurrant, Grapefruit, Black tea</p>"}]}</script>
+</head></html>"#;
+        let product = merge(from_json_ld(&json_ld_product(html).unwrap()), from_open_graph(&meta_tags(html)));
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(
+            bean_draft(product, today).unwrap(),
+            json!({
+                "name": "Kenya Gatomboya AA", "roaster": "Example Roasters", "price": "21.5",
+                "capacity": "250", "remaining": "250", "flavor": ["Blackcurrant", "Grapefruit", "Black tea"],
+                "blendComponents": [{ "country": "肯尼亚", "process": "Washed" }], "beanState": "roasted",
+            })
+        );
+
+        let url = reqwest::Url::parse("https://www.onyxcoffeelab.com/collections/coffee/products/geometry?variant=1").unwrap();
+        assert_eq!(shopify_product_url(&url).unwrap(), "https://www.onyxcoffeelab.com/products/geometry.js");
+        assert_eq!(known_roaster(&url), Some("Onyx Coffee Lab"));
+        let shopify = from_shopify(&json!({ "title": "Geometry", "price": 2450, "variants": [{ "price": 2450, "grams": 283 }] }));
+        assert_eq!((shopify.price, shopify.grams), (Some(24.5), Some(283.0)));
+    }
+}
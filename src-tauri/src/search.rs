@@ -0,0 +1,283 @@
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::store::{atomically, join_components, text_field, with_store};
+
+// 全文搜索：咖啡豆（名称、烘焙商、产地、处理法、风味、备注）和冲煮笔记（咖啡豆、方案、风味标签、笔记）
+// 使用 SQLite FTS5；unicode61 分词器不会切分中文，写入索引前把中日韩文字逐字用空格分开，
+// 搜索词也按同样的方式处理成短语，这样「柑橘」只匹配连在一起的「柑橘」
+const KIND_BEAN: &str = "bean";
+const KIND_NOTE: &str = "note";
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+
+// 摘要在第一个匹配前后保留的字数
+const SNIPPET_BEFORE: usize = 16;
+const SNIPPET_LENGTH: usize = 64;
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F
+    )
+}
+
+fn segment(text: &str) -> String {
+    let mut segmented = String::with_capacity(text.len() * 2);
+    for c in text.chars() {
+        if is_cjk(c) {
+            segmented.push(' ');
+            segmented.push(c);
+            segmented.push(' ');
+        } else {
+            segmented.push(c);
+        }
+    }
+    segmented
+}
+
+// 搜索词转换为 FTS5 查询：每个词是一个短语，最后一个字允许前缀匹配，词之间为「且」
+fn match_query(terms: &[String]) -> Option<String> {
+    let phrases: Vec<String> = terms
+        .iter()
+        .map(|term| format!("\"{}\" *", segment(term).trim().replace('"', "\"\"")))
+        .collect();
+    (!phrases.is_empty()).then(|| phrases.join(" "))
+}
+
+fn search_terms(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .filter(|term| term.chars().any(char::is_alphanumeric))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn write_entry(conn: &Connection, kind: &str, id: &str, title: &str, body: &[String]) -> rusqlite::Result<()> {
+    remove(conn, kind, id)?;
+    let body = body.join(" · ");
+    conn.execute(
+        "INSERT INTO search_index (kind, item_id, title, body, raw_title, raw_body) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![kind, id, segment(title), segment(&body), title, body],
+    )?;
+    Ok(())
+}
+
+fn remove(conn: &Connection, kind: &str, id: &str) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM search_index WHERE kind = ?1 AND item_id = ?2", [kind, id])?;
+    Ok(())
+}
+
+pub fn index_bean(conn: &Connection, id: &str, bean: &Value) -> rusqlite::Result<()> {
+    let mut body: Vec<String> = Vec::new();
+    body.extend(text_field(bean, "roaster").map(str::to_string));
+    body.extend(join_components(bean, |c| text_field(c, "origin").or_else(|| text_field(c, "country"))));
+    body.extend(join_components(bean, |c| text_field(c, "region")));
+    body.extend(join_components(bean, |c| text_field(c, "process")));
+    let flavors: Vec<&str> = bean
+        .get("flavor")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if !flavors.is_empty() {
+        body.push(flavors.join(" / "));
+    }
+    body.extend(text_field(bean, "notes").map(str::to_string));
+    write_entry(conn, KIND_BEAN, id, text_field(bean, "name").unwrap_or_default(), &body)
+}
+
+pub fn index_note(conn: &Connection, id: &str, note: &Value) -> rusqlite::Result<()> {
+    let bean_name = note.get("coffeeBeanInfo").and_then(|info| text_field(info, "name"));
+    let method = text_field(note, "method");
+    let mut body: Vec<String> = Vec::new();
+    body.extend(method.filter(|_| bean_name.is_some()).map(str::to_string));
+    let tags: Vec<&str> = note
+        .get("tastingTags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if !tags.is_empty() {
+        body.push(tags.join(" / "));
+    }
+    body.extend(text_field(note, "notes").map(str::to_string));
+    write_entry(conn, KIND_NOTE, id, bean_name.or(method).unwrap_or_default(), &body)
+}
+
+pub fn remove_bean(conn: &Connection, id: &str) -> rusqlite::Result<()> {
+    remove(conn, KIND_BEAN, id)
+}
+
+pub fn remove_note(conn: &Connection, id: &str) -> rusqlite::Result<()> {
+    remove(conn, KIND_NOTE, id)
+}
+
+// 索引条数与咖啡豆、笔记总数不一致时（如刚升级到带索引的版本）重建整个索引
+pub fn rebuild_if_needed(conn: &Connection) -> rusqlite::Result<()> {
+    let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, i64>(0));
+    let indexed = count("SELECT COUNT(*) FROM search_index")?;
+    let expected = count("SELECT (SELECT COUNT(*) FROM beans) + (SELECT COUNT(*) FROM notes)")?;
+    if indexed == expected {
+        return Ok(());
+    }
+
+    atomically(conn, || {
+        conn.execute("DELETE FROM search_index", [])?;
+        for (table, index) in [
+            ("beans", index_bean as fn(&Connection, &str, &Value) -> rusqlite::Result<()>),
+            ("notes", index_note),
+        ] {
+            let mut stmt = conn.prepare(&format!("SELECT id, data FROM {}", table))?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+            for row in rows {
+                let (id, data) = row?;
+                if let Ok(value) = serde_json::from_str::<Value>(&data) {
+                    index(conn, &id, &value)?;
+                }
+            }
+        }
+        Ok(())
+    })?;
+    log::info!("已重建搜索索引");
+    Ok(())
+}
+
+// 带高亮的文字，ranges 为匹配部分的字符区间 [start, end)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlighted {
+    pub text: String,
+    pub ranges: Vec<[usize; 2]>,
+}
+
+impl Highlighted {
+    fn new(text: &str, terms: &[String]) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        Self {
+            ranges: match_ranges(&chars, terms),
+            text: text.to_string(),
+        }
+    }
+
+    // 从第一个匹配前不远处截取一段；没有匹配时返回 None
+    fn snippet(text: &str, terms: &[String]) -> Option<Self> {
+        let chars: Vec<char> = text.chars().collect();
+        let first = match_ranges(&chars, terms).first()?[0];
+        let start = first.saturating_sub(SNIPPET_BEFORE);
+        let end = (start + SNIPPET_LENGTH).min(chars.len());
+        let mut excerpt: String = chars[start..end].iter().collect();
+        if start > 0 {
+            excerpt.insert(0, '…');
+        }
+        if end < chars.len() {
+            excerpt.push('…');
+        }
+        Some(Self::new(&excerpt, terms))
+    }
+}
+
+// 搜索词在文字中出现的位置（忽略大小写），按位置排序并合并重叠部分
+fn match_ranges(chars: &[char], terms: &[String]) -> Vec<[usize; 2]> {
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let mut ranges: Vec<[usize; 2]> = Vec::new();
+    for term in terms {
+        let term: Vec<char> = term.chars().collect();
+        if term.is_empty() || term.len() > lower.len() {
+            continue;
+        }
+        for start in 0..=lower.len() - term.len() {
+            if lower[start..start + term.len()] == term[..] {
+                ranges.push([start, start + term.len()]);
+            }
+        }
+    }
+    ranges.sort();
+    let mut merged: Vec<[usize; 2]> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range[0] <= last[1] => last[1] = last[1].max(range[1]),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub kind: &'static str, // bean / note
+    pub id: String,
+    pub title: Highlighted,
+    pub snippet: Option<Highlighted>, // 其他字段中匹配的部分
+    pub score: f64,                   // 越大越相关
+}
+
+fn search(conn: &Connection, query: &str, limit: usize) -> rusqlite::Result<Vec<SearchResult>> {
+    let terms = search_terms(query);
+    let Some(expression) = match_query(&terms) else {
+        return Ok(Vec::new());
+    };
+    // 名称的权重高于其他字段
+    let mut stmt = conn.prepare(
+        "SELECT kind, item_id, raw_title, raw_body, bm25(search_index, 0.0, 0.0, 10.0, 1.0, 0.0, 0.0) AS rank
+         FROM search_index WHERE search_index MATCH ?1 ORDER BY rank LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(params![expression, limit as i64], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, f64>(4)?,
+        ))
+    })?;
+    let mut results = Vec::new();
+    for row in rows {
+        let (kind, id, title, body, rank) = row?;
+        results.push(SearchResult {
+            kind: if kind == KIND_NOTE { KIND_NOTE } else { KIND_BEAN },
+            id,
+            title: Highlighted::new(&title, &terms),
+            snippet: Highlighted::snippet(&body, &terms),
+            score: -rank,
+        });
+    }
+    Ok(results)
+}
+
+// 搜索咖啡豆和冲煮笔记，按相关度排序
+#[tauri::command]
+pub fn search_all(app: tauri::AppHandle, query: String, limit: Option<usize>) -> Result<Vec<SearchResult>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    with_store(&app, |store| search(&store.conn, &query, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_chinese_phrases_and_highlights_them() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../migrations/0003_create_search_index.sql")).unwrap();
+        write_entry(&conn, KIND_BEAN, "b1", "耶加雪菲 Chelbesa", &["柑橘 / 茉莉".to_string()]).unwrap();
+        write_entry(&conn, KIND_NOTE, "n1", "曼特宁", &["橘子皮的余韵".to_string()]).unwrap();
+
+        let results = search(&conn, "柑橘", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        let snippet = results[0].snippet.clone().unwrap();
+        assert_eq!(snippet.ranges, vec![[0, 2]]);
+
+        let results = search(&conn, "chel", 10).unwrap();
+        assert_eq!(results[0].title.ranges, vec![[5, 9]]);
+        assert_eq!(search(&conn, "橘 ;", 10).unwrap().len(), 2);
+    }
+}
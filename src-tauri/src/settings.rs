@@ -0,0 +1,787 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+use crate::json_file;
+use crate::updater::UpdateChannel;
+
+// 后端设置：带版本号保存在 settings.json，启动时按顺序执行迁移
+// 修改后通过 settings-changed 事件把完整设置发给前端
+const SETTINGS_FILE: &str = "settings.json";
+
+// 每个迁移把设置从版本 i 升级到 i + 1，迁移只能追加，不能修改已发布的迁移
+type Migration = fn(&mut Value, &Path);
+const MIGRATIONS: [Migration; 1] = [migrate_v0_import_legacy_files];
+const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+// 托盘每个分区最多显示的咖啡豆数量上限（0 表示不限制）
+pub const MAX_TRAY_ITEMS_PER_SECTION: usize = 100;
+
+// 菜单栏图标旁的文字（macOS / Linux）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayTitleMode {
+    #[default]
+    Off,
+    Count,     // 赏味期内的咖啡豆数量
+    Countdown, // 最快离开赏味期的咖啡豆剩余天数
+}
+
+// 点击托盘图标的操作
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayClickAction {
+    None,
+    ShowWindow,
+    ToggleWindow,
+    OpenMenu,   // 只支持左键单击（由系统弹出菜单，Linux 不支持）
+    QuickPanel, // 在托盘图标旁弹出快速面板
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayClickButton {
+    Left,
+    Double,
+    Middle,
+}
+
+// 点击主窗口关闭按钮的行为（托盘图标可见时生效，托盘隐藏时总是退出）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloseBehavior {
+    #[default]
+    Hide, // 隐藏到托盘
+    Quit,
+    Ask, // 弹出对话框询问
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub freshness: bool, // 咖啡豆进入赏味期 / 衰退期时通知
+    pub arrival: bool,   // 在途咖啡豆到了预计到货日时询问是否已收到
+    pub low_stock: bool, // 剩余量低于阈值时通知
+    pub low_stock_grams: f64,
+    pub low_stock_overrides: BTreeMap<String, f64>, // 咖啡豆 ID -> 单独设置的阈值
+    pub quiet_hours: Option<QuietHours>,            // 免打扰时段内的通知推迟到时段结束后发送
+}
+
+// 免打扰时段，时间格式 HH:MM；开始晚于结束时表示跨过零点（如 22:00–08:00）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl NotificationSettings {
+    pub fn low_stock_threshold(&self, bean_id: &str) -> f64 {
+        self.low_stock_overrides
+            .get(bean_id)
+            .copied()
+            .unwrap_or(self.low_stock_grams)
+    }
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            freshness: true,
+            arrival: true,
+            low_stock: true,
+            low_stock_grams: 50.0,
+            low_stock_overrides: BTreeMap::new(),
+            quiet_hours: None,
+        }
+    }
+}
+
+// 托盘咖啡豆的分组方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayGrouping {
+    #[default]
+    State, // 按赏味期状态
+    Roaster,
+    Origin,
+    Process,
+}
+
+// 托盘中按赏味期状态划分的分区
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraySection {
+    Frozen,
+    Optimal,
+    Resting,
+    Decline,
+    InTransit,
+    LowStock, // 即将喝完，与其它分区重叠
+}
+
+impl TraySection {
+    // 菜单项 ID 中使用的名称
+    pub fn id(self) -> &'static str {
+        match self {
+            TraySection::Frozen => "frozen",
+            TraySection::Optimal => "optimal",
+            TraySection::Resting => "resting",
+            TraySection::Decline => "decline",
+            TraySection::InTransit => "in_transit",
+            TraySection::LowStock => "low_stock",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        DEFAULT_SECTION_ORDER.into_iter().find(|section| section.id() == id)
+    }
+}
+
+const DEFAULT_SECTION_ORDER: [TraySection; 6] = [
+    TraySection::Frozen,
+    TraySection::Optimal,
+    TraySection::Resting,
+    TraySection::Decline,
+    TraySection::InTransit,
+    TraySection::LowStock,
+];
+
+// 分区内的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraySort {
+    #[default]
+    DaysLeft,  // 按距离状态变化的天数
+    Remaining, // 按剩余克数，快喝完的在前
+    Name,
+}
+
+// set_tray_preferences 的参数
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayPreferences {
+    pub section_order: Vec<TraySection>,
+    pub hidden_sections: Vec<TraySection>,
+    pub sort: TraySort,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TraySettings {
+    pub visible: bool,
+    pub show_remaining: bool, // 咖啡豆条目末尾显示剩余克数
+    pub show_progress: bool,  // 赏味期内的咖啡豆显示赏味期进度条
+    pub privacy_mode: bool,   // 隐私模式：托盘和提示文字中不显示咖啡豆名称和重量（屏幕共享时使用）
+    pub badge: bool,          // 图标角标显示赏味期内的咖啡豆数量
+    pub title_mode: TrayTitleMode,
+    pub section_order: Vec<TraySection>,
+    pub hidden_sections: Vec<TraySection>,
+    pub sort: TraySort,
+    pub max_items_per_section: usize, // 每个分区最多显示的咖啡豆数量，0 表示不限制
+    pub grouping: TrayGrouping,
+    pub low_stock_grams: f64, // 剩余量低于该值时显示在「即将喝完」分区
+    pub left_click: TrayClickAction,
+    pub double_click: TrayClickAction,
+    pub middle_click: TrayClickAction,
+}
+
+impl TraySettings {
+    // 按用户设置的顺序返回要显示的分区，设置里漏掉的分区按默认顺序补在后面
+    pub fn visible_sections(&self) -> Vec<TraySection> {
+        let mut sections: Vec<TraySection> = Vec::new();
+        for section in self.section_order.iter().chain(DEFAULT_SECTION_ORDER.iter()) {
+            if !sections.contains(section) {
+                sections.push(*section);
+            }
+        }
+        sections.retain(|section| !self.hidden_sections.contains(section));
+        sections
+    }
+}
+
+impl Default for TraySettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            show_remaining: false,
+            show_progress: false,
+            privacy_mode: false,
+            badge: true,
+            title_mode: TrayTitleMode::Off,
+            section_order: DEFAULT_SECTION_ORDER.to_vec(),
+            hidden_sections: Vec::new(),
+            sort: TraySort::DaysLeft,
+            max_items_per_section: 20,
+            grouping: TrayGrouping::State,
+            low_stock_grams: 50.0,
+            left_click: TrayClickAction::ShowWindow,
+            double_click: TrayClickAction::None,
+            middle_click: TrayClickAction::None,
+        }
+    }
+}
+
+// WebDAV 备份（坚果云、Nextcloud 等），口令保存在系统钥匙串中
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebDavSettings {
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub auto_upload: bool, // 每天自动上传一份备份
+}
+
+// S3 兼容的对象存储备份（AWS、MinIO、Backblaze B2、阿里云 OSS 等），Secret Access Key 保存在系统钥匙串中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct S3Settings {
+    pub endpoint: Option<String>, // 如 https://s3.us-east-1.amazonaws.com
+    pub region: String,
+    pub bucket: Option<String>,
+    pub prefix: String,   // 备份对象的键前缀
+    pub path_style: bool, // 使用 {endpoint}/{bucket}/ 形式的地址（MinIO 等），否则使用 {bucket}.{endpoint}
+    pub access_key_id: Option<String>,
+}
+
+impl Default for S3Settings {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            region: "us-east-1".to_string(),
+            bucket: None,
+            prefix: "brew-guide/".to_string(),
+            path_style: false,
+            access_key_id: None,
+        }
+    }
+}
+
+// ESC/POS 热敏标签打印机的连接方式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PrinterConnection {
+    Network { host: String, port: Option<u16> }, // 默认端口 9100
+    Device { path: String }, // USB 打印机的设备文件（/dev/usb/lp0）或 Windows 共享打印机（\\localhost\共享名）
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LabelPrinterSettings {
+    pub connection: Option<PrinterConnection>,
+    pub paper_width: u16, // 纸宽（毫米），58 或 80
+    pub gbk: bool,        // 中文打印机使用 GBK 编码，否则按 UTF-8 发送
+    pub cut: bool,        // 打印后切纸
+}
+
+impl Default for LabelPrinterSettings {
+    fn default() -> Self {
+        Self {
+            connection: None,
+            paper_width: 58,
+            gbk: true,
+            cut: true,
+        }
+    }
+}
+
+// 全局快捷键（应用在后台时也有效），None 表示不注册；格式与菜单快捷键相同，如 CmdOrCtrl+Shift+T
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeySettings {
+    pub toggle_timer: Option<String>, // 开始 / 停止冲煮计时
+    pub next_step: Option<String>,    // 跳到下一个冲煮步骤
+    pub quick_log: Option<String>,    // 把刚结束的冲煮记录为笔记
+    pub quick_panel: Option<String>,  // 显示 / 隐藏快速面板
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            toggle_timer: Some("Alt+Shift+T".to_string()),
+            next_step: Some("Alt+Shift+N".to_string()),
+            quick_log: Some("Alt+Shift+L".to_string()),
+            quick_panel: Some("Alt+Shift+B".to_string()),
+        }
+    }
+}
+
+// 冲煮计时的提示音，由后端按计时播放；节拍器按 BPM 打拍，设置了注水流速（克/秒）时改为每 10g 一拍
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioSettings {
+    pub step_chime: bool, // 切换步骤时提示
+    pub countdown: bool,  // 每个步骤结束前 3、2、1 秒倒数
+    pub metronome: bool,  // 注水步骤中的节拍器
+    pub metronome_bpm: u32,
+    pub flow_target: Option<f64>,
+    pub volume: f32, // 0–1
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            step_chime: true,
+            countdown: true,
+            metronome: false,
+            metronome_bpm: 60,
+            flow_target: None,
+            volume: 0.6,
+        }
+    }
+}
+
+// 冲煮步骤的语音播报；language 为 BCP 47 标签，None 时跟随界面语言；voice 为系统语音名称，None 时按语言选择
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeechSettings {
+    pub enabled: bool,
+    pub muted: bool, // 临时静音，不改变是否开启
+    pub language: Option<String>,
+    pub voice: Option<String>,
+    pub rate: f64, // 1 为正常语速，0.5–2
+}
+
+impl Default for SpeechSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            muted: false,
+            language: None,
+            voice: None,
+            rate: 1.0,
+        }
+    }
+}
+
+// 窗口左上角位置（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub tray: TraySettings,
+    pub timezone: Option<String>, // IANA 时区名，None 表示跟随系统
+    pub locale: Option<String>,   // BCP 47 标签，None 表示默认中文
+    pub update_channel: UpdateChannel,
+    pub close_behavior: CloseBehavior,
+    pub notifications: NotificationSettings,
+    pub diagnostics_consent: bool,
+    pub telemetry_enabled: bool,
+    pub webdav: WebDavSettings,
+    pub s3: S3Settings,
+    pub sync_folder: Option<String>, // 同步文件夹（iCloud Drive、OneDrive、Dropbox 等）
+    pub git_history: bool,           // 每次备份时把数据提交到本地 git 仓库
+    pub label_printer: LabelPrinterSettings,
+    pub calendar_feed: bool,         // 咖啡豆变化时自动更新赏味期日历订阅文件
+    pub mini_timer_position: Option<WindowPosition>, // 迷你计时器窗口上次拖动到的位置
+    pub hotkeys: HotkeySettings,
+    pub audio: AudioSettings,
+    pub speech: SpeechSettings,
+    pub keep_awake: bool, // 冲煮计时中阻止显示器和系统休眠
+}
+
+fn check_threshold(grams: f64) -> Result<(), String> {
+    if !grams.is_finite() || grams < 0.0 {
+        return Err(format!("无效的阈值：{}", grams));
+    }
+    Ok(())
+}
+
+fn check_http_url(url: &str, label: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("无效的 {} 地址：{}", label, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("无效的 {} 地址：{}", label, url));
+    }
+    Ok(())
+}
+
+impl AppSettings {
+    // 与各个单项设置命令的检查一致，set_settings 合并后整体检查
+    pub fn validate(&self) -> Result<(), String> {
+        check_threshold(self.tray.low_stock_grams)?;
+        if self.tray.max_items_per_section > MAX_TRAY_ITEMS_PER_SECTION {
+            return Err(format!("每个分区最多显示 {} 款咖啡豆", MAX_TRAY_ITEMS_PER_SECTION));
+        }
+        if let Some(timezone) = self.timezone.as_deref() {
+            crate::clock::parse_timezone(timezone)?;
+        }
+        check_threshold(self.notifications.low_stock_grams)?;
+        for grams in self.notifications.low_stock_overrides.values() {
+            check_threshold(*grams)?;
+        }
+        if let Some(hours) = &self.notifications.quiet_hours {
+            for time in [&hours.start, &hours.end] {
+                chrono::NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("无效的时间：{}", time))?;
+            }
+        }
+        if let Some(url) = self.webdav.url.as_deref() {
+            check_http_url(url, "WebDAV")?;
+        }
+        if let Some(endpoint) = self.s3.endpoint.as_deref() {
+            check_http_url(endpoint, "S3")?;
+            if !self.s3.bucket.as_deref().is_some_and(|bucket| !bucket.trim().is_empty()) {
+                return Err("S3 存储桶不能为空".to_string());
+            }
+        }
+        // 前缀保存时已规范为不以 / 开头、以 / 结尾
+        let prefix = &self.s3.prefix;
+        if prefix.starts_with('/') || !(prefix.is_empty() || prefix.ends_with('/')) {
+            return Err(format!("无效的 S3 前缀：{}", prefix));
+        }
+        if let Some(PrinterConnection::Network { host, .. }) = &self.label_printer.connection {
+            if host.trim().is_empty() {
+                return Err("请填写打印机地址".to_string());
+            }
+        }
+        if !(20..=240).contains(&self.audio.metronome_bpm) {
+            return Err("节拍器速度应在 20–240 BPM 之间".to_string());
+        }
+        if self.audio.flow_target.is_some_and(|flow| !flow.is_finite() || flow <= 0.0) {
+            return Err("注水流速应大于 0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.audio.volume) {
+            return Err("音量应在 0–1 之间".to_string());
+        }
+        if !(0.5..=2.0).contains(&self.speech.rate) {
+            return Err("语速应在 0.5–2 之间".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SettingsFile<'a> {
+    version: u64,
+    #[serde(flatten)]
+    settings: &'a AppSettings,
+}
+
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: AppSettings,
+    // 被更高版本写过的设置文件原文；保存时在原文上更新已知字段，保留版本号和不认识的字段
+    newer_file: Option<Value>,
+}
+
+// 读取设置文件的结果
+struct Loaded {
+    settings: AppSettings,
+    migrated: bool,            // 执行过迁移，需要写回
+    newer_file: Option<Value>, // 文件版本高于当前支持的版本
+}
+
+// 版本 0 -> 1：合并之前分散保存的 updater.json、diagnostics.json
+fn migrate_v0_import_legacy_files(value: &mut Value, data_dir: &Path) {
+    let read = |name: &str| -> Option<Value> {
+        let bytes = std::fs::read(data_dir.join(name)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    };
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    if let Some(channel) = read("updater.json").and_then(|v| v.get("channel").cloned()) {
+        object.entry("updateChannel").or_insert(channel);
+    }
+    if let Some(consent) = read("diagnostics.json").and_then(|v| v.get("consent").cloned()) {
+        object.entry("diagnosticsConsent").or_insert(consent);
+    }
+}
+
+// 读取原始 JSON 并执行尚未执行的迁移
+fn load_migrated(path: &Path) -> Result<Loaded, String> {
+    let mut value: Value = match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string())?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Value::Object(Default::default()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    let newer_file = if version > SCHEMA_VERSION {
+        // 被新版本写过（降级安装），按现有字段尽量读取，保存时保留新版本的内容
+        log::warn!("设置文件版本 {} 高于当前支持的 {}", version, SCHEMA_VERSION);
+        Some(value.clone())
+    } else {
+        None
+    };
+
+    let data_dir = path.parent().unwrap_or(Path::new("."));
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(&mut value, data_dir);
+    }
+    let settings = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok(Loaded {
+        settings,
+        migrated: version < SCHEMA_VERSION,
+        newer_file,
+    })
+}
+
+// 要写入设置文件的内容
+fn file_value(settings: &AppSettings, newer_file: Option<&Value>) -> Result<Value, String> {
+    let Some(original) = newer_file else {
+        let file = SettingsFile {
+            version: SCHEMA_VERSION,
+            settings,
+        };
+        return serde_json::to_value(file).map_err(|e| e.to_string());
+    };
+    let mut value = original.clone();
+    let mut known = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    // 保留文件原来的版本号
+    if let Some(object) = known.as_object_mut() {
+        object.remove("version");
+    }
+    merge_patch(&mut value, known);
+    Ok(value)
+}
+
+impl SettingsStore {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(SETTINGS_FILE))
+            .map_err(|e| log::warn!("无法获取应用数据目录：{}", e))
+            .ok();
+        let mut store = Self {
+            path,
+            settings: AppSettings::default(),
+            newer_file: None,
+        };
+        let Some(path) = store.path.clone() else {
+            return store;
+        };
+
+        match load_migrated(&path) {
+            Ok(loaded) => {
+                store.settings = loaded.settings;
+                store.newer_file = loaded.newer_file;
+                if loaded.migrated {
+                    if let Err(e) = store.save() {
+                        log::warn!("保存迁移后的设置失败：{}", e);
+                    }
+                }
+            }
+            Err(e) => {
+                // 文件损坏时保留原文件供排查，使用默认设置
+                log::error!("读取设置失败，使用默认设置：{}", e);
+                let mut backup = path.clone().into_os_string();
+                backup.push(".corrupt");
+                let _ = std::fs::rename(&path, backup);
+            }
+        }
+        store
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = self.path.as_deref() else {
+            return Err("无法获取应用数据目录".to_string());
+        };
+        let file = file_value(&self.settings, self.newer_file.as_ref())?;
+        json_file::save(path, &file).map_err(|e| e.to_string())
+    }
+}
+
+pub fn get(app: &tauri::AppHandle) -> AppSettings {
+    app.try_state::<Arc<Mutex<SettingsStore>>>()
+        .and_then(|state| state.lock().ok().map(|store| store.settings.clone()))
+        .unwrap_or_default()
+}
+
+// 修改并保存设置，有变化时发送 settings-changed 事件
+pub fn update(app: &tauri::AppHandle, f: impl FnOnce(&mut AppSettings)) -> Result<AppSettings, String> {
+    let state = app
+        .try_state::<Arc<Mutex<SettingsStore>>>()
+        .ok_or("设置未初始化")?;
+    let (settings, changed) = {
+        let mut store = state.lock().map_err(|e| e.to_string())?;
+        let mut next = store.settings.clone();
+        f(&mut next);
+        let changed = next != store.settings;
+        if changed {
+            let previous = std::mem::replace(&mut store.settings, next);
+            if let Err(e) = store.save() {
+                store.settings = previous;
+                return Err(e);
+            }
+        }
+        (store.settings.clone(), changed)
+    };
+    if changed {
+        let _ = app.emit("settings-changed", &settings);
+    }
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    Ok(get(&app))
+}
+
+// 按字段合并修改（如 { "tray": { "visible": false } }），null 表示恢复默认值；合并结果需要符合设置结构并通过检查
+#[tauri::command]
+pub fn set_settings(app: tauri::AppHandle, patch: Value) -> Result<AppSettings, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let next = merged_settings(&get(&app), patch)?;
+    let settings = update(&app, |settings| *settings = next)?;
+    crate::apply_settings(&app, &settings);
+    // 托盘文案相关的设置需要重建菜单
+    crate::background::refresh(&app)?;
+    Ok(settings)
+}
+
+fn merged_settings(current: &AppSettings, patch: Value) -> Result<AppSettings, String> {
+    let mut merged = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    merge_patch(&mut merged, patch);
+    let next: AppSettings = serde_json::from_value(merged).map_err(|e| format!("无效的设置：{}", e))?;
+    next.validate()?;
+    Ok(next)
+}
+
+// JSON Merge Patch（RFC 7396）：对象逐字段合并，null 删除字段，其它值直接替换
+fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            merge_patch(target.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("brew-guide-settings-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn imports_legacy_files_once() {
+        let dir = temp_dir("legacy");
+        std::fs::write(dir.join("updater.json"), r#"{ "channel": "beta" }"#).unwrap();
+        std::fs::write(dir.join("diagnostics.json"), r#"{ "consent": true }"#).unwrap();
+
+        let mut value = json!({});
+        migrate_v0_import_legacy_files(&mut value, &dir);
+        assert_eq!(value, json!({ "updateChannel": "beta", "diagnosticsConsent": true }));
+
+        // 已有的设置优先于旧文件
+        let mut value = json!({ "diagnosticsConsent": false });
+        migrate_v0_import_legacy_files(&mut value, &dir);
+        assert_eq!(value["diagnosticsConsent"], false);
+
+        let loaded = load_migrated(&dir.join(SETTINGS_FILE)).unwrap();
+        assert!(loaded.migrated);
+        assert!(loaded.settings.diagnostics_consent);
+        assert!(loaded.newer_file.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc_7396() {
+        let mut target = json!({
+            "tray": { "visible": true, "lowStockGrams": 30.0 },
+            "timezone": "Asia/Shanghai",
+            "locale": "zh-CN",
+        });
+        merge_patch(
+            &mut target,
+            json!({ "tray": { "visible": false, "badge": true }, "timezone": null, "locale": "en-US" }),
+        );
+        assert_eq!(
+            target,
+            json!({
+                "tray": { "visible": false, "lowStockGrams": 30.0, "badge": true },
+                "locale": "en-US",
+            })
+        );
+
+        // 补丁不是对象时整体替换，目标不是对象时先变成空对象
+        let mut target = json!({ "a": 1 });
+        merge_patch(&mut target, json!([1, 2]));
+        assert_eq!(target, json!([1, 2]));
+        let mut target = json!("text");
+        merge_patch(&mut target, json!({ "a": { "b": null, "c": 1 } }));
+        assert_eq!(target, json!({ "a": { "c": 1 } }));
+    }
+
+    #[test]
+    fn merged_settings_are_validated() {
+        let current = AppSettings::default();
+        let next = merged_settings(&current, json!({ "notifications": { "quietHours": { "start": "22:00", "end": "07:30" } } })).unwrap();
+        assert_eq!(next.notifications.quiet_hours.unwrap().end, "07:30");
+
+        // null 恢复默认值
+        let changed = merged_settings(&current, json!({ "speech": { "rate": 1.5 } })).unwrap();
+        let reset = merged_settings(&changed, json!({ "speech": null })).unwrap();
+        assert_eq!(reset.speech, SpeechSettings::default());
+
+        for patch in [
+            json!({ "notifications": { "quietHours": { "start": "10pm", "end": "07:30" } } }),
+            json!({ "notifications": { "lowStockGrams": -1 } }),
+            json!({ "webdav": { "url": "ftp://example.com/dav" } }),
+            json!({ "s3": { "endpoint": "https://s3.example.com", "bucket": "beans", "prefix": "/backups" } }),
+            json!({ "tray": { "maxItemsPerSection": MAX_TRAY_ITEMS_PER_SECTION + 1 } }),
+            json!({ "timezone": "Mars/Olympus" }),
+        ] {
+            assert!(merged_settings(&current, patch.clone()).is_err(), "{}", patch);
+        }
+    }
+
+    #[test]
+    fn keeps_newer_settings_files_intact() {
+        let dir = temp_dir("newer");
+        let path = dir.join(SETTINGS_FILE);
+        let newer = json!({
+            "version": SCHEMA_VERSION + 1,
+            "keepAwake": true,
+            "futureFeature": { "enabled": true },
+            "tray": { "visible": true, "futureTrayOption": 3 },
+        });
+        std::fs::write(&path, newer.to_string()).unwrap();
+
+        let loaded = load_migrated(&path).unwrap();
+        assert!(!loaded.migrated);
+        assert!(loaded.settings.keep_awake);
+        assert_eq!(loaded.newer_file.as_ref(), Some(&newer));
+
+        let mut settings = loaded.settings;
+        settings.keep_awake = false;
+        settings.tray.visible = false;
+        let saved = file_value(&settings, loaded.newer_file.as_ref()).unwrap();
+        assert_eq!(saved["version"], SCHEMA_VERSION + 1);
+        assert_eq!(saved["keepAwake"], false);
+        assert_eq!(saved["futureFeature"], json!({ "enabled": true }));
+        assert_eq!(saved["tray"]["visible"], false);
+        assert_eq!(saved["tray"]["futureTrayOption"], 3);
+
+        // 当前版本的文件按当前版本号写入
+        let current = file_value(&settings, None).unwrap();
+        assert_eq!(current["version"], SCHEMA_VERSION);
+        assert!(current.get("futureFeature").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
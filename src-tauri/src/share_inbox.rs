@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{Emitter, Manager};
+
+use crate::json_file;
+
+// 从其它应用分享进来的内容（包装照片、冲煮方案、订单确认文字）
+// 先落盘排队，前端就绪后通过 take_shared_items 取走并交给对应的导入流程
+const SHARE_INBOX_FILE: &str = "share-inbox.json";
+const SHARE_INBOX_DIR: &str = "share-inbox";
+// 原生分享扩展把图片放到缓存目录下的这个文件夹再调用 enqueue_shared_item，
+// 其它位置的路径一律拒绝，避免页面借分享把任意文件复制进应用数据目录
+const SHARE_HANDOFF_DIR: &str = "share-handoff";
+const IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "heic", "heif", "webp"];
+
+// 同一毫秒内收到多项分享时用序号区分
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SharedItemKind {
+    Image,
+    Text,
+}
+
+// 分享内容应交给哪个导入流程
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareRoute {
+    BeanRecognition, // 包装照片 -> 咖啡豆图片识别
+    MethodImport,    // 冲煮方案文字 -> 方案导入
+    BeanImport,      // 其它文字（订单确认、商品描述）-> 咖啡豆导入
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedItem {
+    pub id: String,
+    pub kind: SharedItemKind,
+    pub route: ShareRoute,
+    pub text: Option<String>,
+    pub image_path: Option<String>,
+    pub received_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ShareInbox {
+    items: Vec<SharedItem>,
+}
+
+// 冲煮方案常见关键词：出现两个及以上时按方案处理
+const RECIPE_KEYWORDS: [&str; 12] = [
+    "粉水比", "闷蒸", "注水", "水温", "研磨", "萃取", "ratio", "bloom", "pour", "grind", "brew time", "°c",
+];
+
+fn route_for_text(text: &str) -> ShareRoute {
+    let lower = text.to_lowercase();
+    let hits = RECIPE_KEYWORDS.iter().filter(|k| lower.contains(*k)).count();
+    if hits >= 2 {
+        ShareRoute::MethodImport
+    } else {
+        ShareRoute::BeanImport
+    }
+}
+
+fn inbox_paths(app: &tauri::AppHandle) -> Result<(PathBuf, PathBuf), String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok((dir.join(SHARE_INBOX_FILE), dir.join(SHARE_INBOX_DIR)))
+}
+
+fn new_id() -> String {
+    format!(
+        "share-{}-{}",
+        chrono::Local::now().timestamp_millis(),
+        NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.iter().any(|ext| e.eq_ignore_ascii_case(ext)))
+}
+
+// 只接受交接目录中的图片文件（解析符号链接和 .. 之后再判断）
+fn handed_over_image(path: &Path, handoff_dir: &Path) -> Result<PathBuf, String> {
+    let dir = handoff_dir.canonicalize().map_err(|_| "没有待接收的分享内容".to_string())?;
+    let path = path.canonicalize().map_err(|e| e.to_string())?;
+    if !path.starts_with(&dir) || !path.is_file() || !is_image(&path) {
+        return Err("只能接收系统分享的图片".to_string());
+    }
+    Ok(path)
+}
+
+// 分享扩展给的临时文件可能很快被系统清理，复制一份到应用数据目录
+fn copy_into_inbox(source: &Path, inbox_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    fs::create_dir_all(inbox_dir).map_err(|e| e.to_string())?;
+    let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+    let target = inbox_dir.join(format!("{}.{}", id, extension));
+    fs::copy(source, &target).map_err(|e| e.to_string())?;
+    Ok(target)
+}
+
+fn enqueue(app: &tauri::AppHandle, kind: SharedItemKind, content: String) -> Result<SharedItem, String> {
+    let (inbox_file, inbox_dir) = inbox_paths(app)?;
+    let id = new_id();
+
+    crate::telemetry::record(app, match kind {
+        SharedItemKind::Image => "share.image",
+        SharedItemKind::Text => "share.text",
+    });
+    let item = match kind {
+        SharedItemKind::Image => {
+            let path = copy_into_inbox(Path::new(&content), &inbox_dir, &id)?;
+            SharedItem {
+                id,
+                kind,
+                route: ShareRoute::BeanRecognition,
+                text: None,
+                image_path: Some(path.to_string_lossy().into_owned()),
+                received_at: chrono::Local::now().to_rfc3339(),
+            }
+        }
+        SharedItemKind::Text => SharedItem {
+            id,
+            kind,
+            route: route_for_text(&content),
+            text: Some(content),
+            image_path: None,
+            received_at: chrono::Local::now().to_rfc3339(),
+        },
+    };
+
+    let mut inbox: ShareInbox = json_file::load(&inbox_file).map_err(|e| e.to_string())?;
+    inbox.items.push(item.clone());
+    json_file::save(&inbox_file, &inbox).map_err(|e| e.to_string())?;
+
+    // 主界面已打开时立即通知，否则等前端启动后自行拉取
+    let ui_visible = app
+        .get_webview_window("main")
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false);
+    if ui_visible {
+        let _ = app.emit("share-received", &item);
+    }
+
+    Ok(item)
+}
+
+// 由原生分享扩展调用：image 传交接目录中的文件路径，text 传文字内容
+#[tauri::command]
+pub fn enqueue_shared_item(app: tauri::AppHandle, kind: SharedItemKind, content: String) -> Result<SharedItem, String> {
+    if kind == SharedItemKind::Text {
+        return enqueue(&app, kind, content);
+    }
+    let handoff_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join(SHARE_HANDOFF_DIR);
+    let source = handed_over_image(Path::new(&content), &handoff_dir)?;
+    let item = enqueue(&app, kind, source.to_string_lossy().into_owned())?;
+    // 已经复制进收件箱，交接的文件不再需要
+    let _ = fs::remove_file(&source);
+    Ok(item)
+}
+
+// 通过“打开方式”或分享菜单交给应用的图片（macOS / iOS 的 RunEvent::Opened）
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn open_urls(app: &tauri::AppHandle, urls: &[tauri::Url]) {
+    for path in urls.iter().filter_map(|url| url.to_file_path().ok()) {
+        if !is_image(&path) {
+            continue;
+        }
+        if let Err(e) = enqueue(app, SharedItemKind::Image, path.to_string_lossy().into_owned()) {
+            log::warn!("接收分享的图片 {} 失败：{}", path.display(), e);
+        }
+    }
+}
+
+// 前端取走所有待处理的分享内容（取走后从队列移除）
+#[tauri::command]
+pub fn take_shared_items(app: tauri::AppHandle) -> Result<Vec<SharedItem>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let (inbox_file, _) = inbox_paths(&app)?;
+    let inbox: ShareInbox = json_file::load(&inbox_file).map_err(|e| e.to_string())?;
+    if !inbox.items.is_empty() {
+        json_file::save(&inbox_file, &ShareInbox::default()).map_err(|e| e.to_string())?;
+    }
+    Ok(inbox.items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_recipes_and_orders() {
+        assert_eq!(route_for_text("粉水比 1:15，闷蒸 30 秒，水温 92°C"), ShareRoute::MethodImport);
+        assert_eq!(route_for_text("Ratio 1:16, bloom 40s"), ShareRoute::MethodImport);
+        assert_eq!(route_for_text("订单已确认：埃塞俄比亚 耶加雪菲 200g"), ShareRoute::BeanImport);
+        assert_eq!(route_for_text("研磨度偏细"), ShareRoute::BeanImport);
+    }
+
+    #[test]
+    fn ids_are_unique_within_a_millisecond() {
+        let ids: std::collections::HashSet<String> = (0..100).map(|_| new_id()).collect();
+        assert_eq!(ids.len(), 100);
+    }
+
+    #[test]
+    fn accepts_only_images_in_the_handoff_dir() {
+        let root = std::env::temp_dir().join(new_id());
+        let handoff = root.join(SHARE_HANDOFF_DIR);
+        fs::create_dir_all(&handoff).unwrap();
+        for name in ["photo.JPG", "notes.txt"] {
+            fs::write(handoff.join(name), b"data").unwrap();
+        }
+        fs::write(root.join("secret.png"), b"data").unwrap();
+
+        assert!(handed_over_image(&handoff.join("photo.JPG"), &handoff).is_ok());
+        assert!(handed_over_image(&handoff.join("notes.txt"), &handoff).is_err());
+        assert!(handed_over_image(&root.join("secret.png"), &handoff).is_err());
+        assert!(handed_over_image(&handoff.join("../secret.png"), &handoff).is_err());
+        assert!(handed_over_image(&handoff.join("missing.png"), &handoff).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
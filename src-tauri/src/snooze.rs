@@ -0,0 +1,129 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::json_file;
+
+// 托盘中暂时隐藏的咖啡豆：咖啡豆 ID -> 恢复显示的日期，保存在 tray-snooze.json
+const SNOOZE_FILE: &str = "tray-snooze.json";
+
+// 托盘「隐藏 7 天」
+pub const SNOOZE_DAYS: u64 = 7;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SnoozeState {
+    until: BTreeMap<String, String>, // 日期格式 YYYY-MM-DD
+}
+
+impl SnoozeState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        snooze_path(app)
+            .ok()
+            .and_then(|path| {
+                json_file::load(&path)
+                    .map_err(|e| log::warn!("读取托盘隐藏列表失败：{}", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    fn is_snoozed(&self, bean_id: &str, today: NaiveDate) -> bool {
+        self.until
+            .get(bean_id)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .is_some_and(|until| today < until)
+    }
+
+    fn active_ids(&self, today: NaiveDate) -> HashSet<String> {
+        self.until.keys().filter(|id| self.is_snoozed(id, today)).cloned().collect()
+    }
+
+    fn retain_visible<T>(&self, items: &mut Vec<T>, id: impl Fn(&T) -> &str, today: NaiveDate) {
+        items.retain(|item| !self.is_snoozed(id(item), today));
+    }
+
+    // 隐藏到 until（不含当天），顺便清理已过期的记录
+    fn insert(&mut self, bean_id: &str, until: NaiveDate, today: NaiveDate) {
+        let active = self.active_ids(today);
+        self.until.retain(|id, _| active.contains(id));
+        self.until.insert(bean_id.to_string(), until.format("%Y-%m-%d").to_string());
+    }
+}
+
+fn snooze_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SNOOZE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+// 从托盘要显示的咖啡豆中去掉今天仍处于隐藏状态的
+pub fn hide_snoozed(app: &tauri::AppHandle, beans: &mut Vec<crate::BeanFreshnessInfo>, today: NaiveDate) {
+    if let Some(state) = app.try_state::<Arc<Mutex<SnoozeState>>>() {
+        if let Ok(state) = state.lock() {
+            state.retain_visible(beans, |info| &info.bean.id, today);
+        }
+    }
+}
+
+// 隐藏咖啡豆 days 天，顺便清理已过期的记录
+pub fn snooze(app: &tauri::AppHandle, bean_id: &str, days: u64) -> Result<(), String> {
+    let today = crate::today(app);
+    let until = today + chrono::Days::new(days);
+    let state = app
+        .try_state::<Arc<Mutex<SnoozeState>>>()
+        .ok_or("托盘隐藏列表未初始化")?;
+    {
+        let mut state = state.lock().map_err(|e| e.to_string())?;
+        state.insert(bean_id, until, today);
+        json_file::save(&snooze_path(app)?, &*state).map_err(|e| e.to_string())?;
+    }
+    crate::background::refresh(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn expired_snoozes_are_dropped() {
+        let mut state = SnoozeState::default();
+        let today = date(2026, 10, 1);
+        state.insert("b1", today + chrono::Days::new(SNOOZE_DAYS), today);
+        state.insert("b2", date(2026, 10, 3), today);
+        assert!(state.is_snoozed("b1", date(2026, 10, 7)));
+        // 到了恢复日期当天重新显示
+        assert!(!state.is_snoozed("b1", date(2026, 10, 8)));
+
+        // 之后再隐藏其它咖啡豆时清理过期的记录
+        state.insert("b3", date(2026, 10, 12), date(2026, 10, 5));
+        assert_eq!(state.until.keys().collect::<Vec<_>>(), vec!["b1", "b3"]);
+
+        // 日期格式损坏的记录视为已过期
+        state.until.insert("b4".to_string(), "下周".to_string());
+        assert!(!state.is_snoozed("b4", date(2026, 10, 5)));
+    }
+
+    #[test]
+    fn tray_filter_follows_expiry() {
+        let mut state = SnoozeState::default();
+        state.insert("b1", date(2026, 10, 8), date(2026, 10, 1));
+        state.insert("b2", date(2026, 10, 4), date(2026, 10, 1));
+        let visible = |today: NaiveDate| {
+            let mut beans = vec!["b1", "b2", "b3"];
+            state.retain_visible(&mut beans, |id| id, today);
+            beans
+        };
+        assert_eq!(visible(date(2026, 10, 2)), vec!["b3"]);
+        assert_eq!(visible(date(2026, 10, 4)), vec!["b2", "b3"]);
+        assert_eq!(visible(date(2026, 10, 8)), vec!["b1", "b2", "b3"]);
+    }
+}
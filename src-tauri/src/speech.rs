@@ -0,0 +1,379 @@
+use serde::Serialize;
+use std::sync::{mpsc, Arc, Mutex};
+use tauri::Manager;
+
+use crate::i18n::{Language, Locale};
+use crate::recipe::{Recipe, StageKind};
+use crate::settings::SpeechSettings;
+
+// 冲煮步骤的语音播报（“开始第二段注水，注入至 150 克”），由后端计时在切换步骤时触发
+// Windows 使用 WinRT SpeechSynthesizer；macOS 使用系统自带的 say；Linux 使用 speech-dispatcher（spd-say）
+// 播报在独立线程中进行，新的播报会打断还没说完的上一句
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Voice {
+    pub name: String,
+    pub language: String,
+}
+
+enum Message {
+    Say(String, SpeechSettings, String),
+    Stop,
+}
+
+#[derive(Debug, Default)]
+pub struct SpeechState {
+    sender: Option<mpsc::Sender<Message>>,
+}
+
+fn chinese_number(n: usize) -> String {
+    const DIGITS: [&str; 10] = ["零", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+    match n {
+        0..=9 => DIGITS[n].to_string(),
+        10 => "十".to_string(),
+        11..=19 => format!("十{}", DIGITS[n % 10]),
+        20..=99 if n % 10 == 0 => format!("{}十", DIGITS[n / 10]),
+        20..=99 => format!("{}十{}", DIGITS[n / 10], DIGITS[n % 10]),
+        _ => n.to_string(),
+    }
+}
+
+fn grams(value: f64) -> String {
+    format!("{}", (value * 10.0).round() / 10.0)
+}
+
+// 某个步骤的播报文字；注水步骤按第几段注水计数（不含焖蒸）
+fn stage_text(recipe: &Recipe, index: usize, language: Language) -> Option<String> {
+    let stage = recipe.stages.get(index)?;
+    let pour = recipe.stages[..=index].iter().filter(|s| s.kind == StageKind::Pour).count();
+    let seconds = (stage.end - stage.start).round();
+    let water = grams(stage.cumulative_water);
+    let text = match (language, stage.kind) {
+        (Language::Zh, StageKind::Bloom) => format!("开始焖蒸，注入至 {} 克", water),
+        (Language::Zh, StageKind::Pour) => format!("开始第{}段注水，注入至 {} 克", chinese_number(pour), water),
+        (Language::Zh, StageKind::Wait) => format!("等待 {} 秒", seconds),
+        (Language::Zh, StageKind::Stir) => "搅拌".to_string(),
+        (Language::Zh, StageKind::Drawdown) => "等待下渗".to_string(),
+        (Language::En, StageKind::Bloom) => format!("Bloom, pour to {} grams", water),
+        (Language::En, StageKind::Pour) => format!("Pour {}, up to {} grams", pour, water),
+        (Language::En, StageKind::Wait) => format!("Wait {} seconds", seconds),
+        (Language::En, StageKind::Stir) => "Stir".to_string(),
+        (Language::En, StageKind::Drawdown) => "Let it draw down".to_string(),
+    };
+    Some(text)
+}
+
+// 播报语言：设置了 language 时使用设置，否则跟随界面语言
+fn speech_locale(app: &tauri::AppHandle, settings: &SpeechSettings) -> (Locale, String) {
+    match settings.language.as_deref() {
+        Some(tag) => (Locale::parse(tag), tag.to_string()),
+        None => {
+            let locale = crate::current_locale(app);
+            let tag = if locale.language == Language::Zh { "zh-CN" } else { "en-US" };
+            (locale, tag.to_string())
+        }
+    }
+}
+
+// 语言标签只比较主语言和地区，zh_CN 与 zh-CN 视为相同
+#[cfg(any(target_os = "macos", target_os = "windows", test))]
+fn same_language(a: &str, b: &str) -> bool {
+    let normalize = |tag: &str| tag.replace('_', "-").to_ascii_lowercase();
+    let (a, b) = (normalize(a), normalize(b));
+    a.starts_with(&b) || b.starts_with(&a)
+}
+
+// `say -v '?'` 的输出：名称和语言之间至少两个空格，# 后为示例句子
+#[cfg(any(target_os = "macos", test))]
+fn parse_say_voices(output: &str) -> Vec<Voice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next()?.trim_end();
+            let split = line.rfind(char::is_whitespace)?;
+            let (name, language) = (line[..split].trim(), line[split..].trim());
+            (!name.is_empty() && !language.is_empty()).then(|| Voice {
+                name: name.to_string(),
+                language: language.to_string(),
+            })
+        })
+        .collect()
+}
+
+// `spd-say -L` 的输出：第一行为表头，之后每行为 名称 语言 变体，列之间至少两个空格
+#[cfg(any(target_os = "linux", test))]
+fn parse_spd_voices(output: &str) -> Vec<Voice> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.trim().split("  ").map(str::trim).filter(|c| !c.is_empty()).collect();
+            match columns.as_slice() {
+                [name, language, ..] => Some(Voice {
+                    name: name.to_string(),
+                    language: language.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn command_output(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("无法运行 {}：{}", program, e))?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "macos")]
+fn voices() -> Result<Vec<Voice>, String> {
+    command_output("say", &["-v", "?"]).map(|output| parse_say_voices(&output))
+}
+
+#[cfg(target_os = "linux")]
+fn voices() -> Result<Vec<Voice>, String> {
+    command_output("spd-say", &["-L"]).map(|output| parse_spd_voices(&output))
+}
+
+#[cfg(target_os = "windows")]
+fn voices() -> Result<Vec<Voice>, String> {
+    use windows::Media::SpeechSynthesis::SpeechSynthesizer;
+    let list = || -> windows::core::Result<Vec<Voice>> {
+        let all = SpeechSynthesizer::AllVoices()?;
+        let mut voices = Vec::new();
+        for i in 0..all.Size()? {
+            let voice = all.GetAt(i)?;
+            voices.push(Voice {
+                name: voice.DisplayName()?.to_string(),
+                language: voice.Language()?.to_string(),
+            });
+        }
+        Ok(voices)
+    };
+    list().map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn voices() -> Result<Vec<Voice>, String> {
+    Err("当前平台不支持语音播报".to_string())
+}
+
+// 正在进行的播报，stop 打断
+#[cfg(not(target_os = "windows"))]
+struct Speaking(std::process::Child);
+
+#[cfg(not(target_os = "windows"))]
+impl Speaking {
+    fn stop(mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+        // spd-say 只是把文字交给 speech-dispatcher，结束进程不会停止朗读
+        #[cfg(target_os = "linux")]
+        let _ = std::process::Command::new("spd-say").arg("--cancel").status();
+    }
+}
+
+#[cfg(target_os = "windows")]
+struct Speaking(windows::Media::Playback::MediaPlayer);
+
+#[cfg(target_os = "windows")]
+impl Speaking {
+    fn stop(self) {
+        let _ = self.0.Pause();
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn start_speaking(text: &str, settings: &SpeechSettings, language: &str) -> Result<Speaking, String> {
+    // say 没有语言参数，没有指定语音时选第一个该语言的系统语音
+    let voice = match settings.voice.clone() {
+        Some(voice) => Some(voice),
+        None => voices()?.into_iter().find(|v| same_language(&v.language, language)).map(|v| v.name),
+    };
+    let mut command = std::process::Command::new("say");
+    if let Some(voice) = voice {
+        command.args(["-v", &voice]);
+    }
+    // say 的默认语速约为每分钟 175 词
+    let rate = (175.0 * settings.rate).round().to_string();
+    command.args(["-r", &rate, "--", text]);
+    command.spawn().map(Speaking).map_err(|e| format!("无法运行 say：{}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn start_speaking(text: &str, settings: &SpeechSettings, language: &str) -> Result<Speaking, String> {
+    let mut command = std::process::Command::new("spd-say");
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+    command.args(["-l", primary]);
+    if let Some(voice) = settings.voice.as_deref() {
+        command.args(["-y", voice]);
+    }
+    // spd-say 的语速为 -100–100，0 为正常
+    let rate = (((settings.rate - 1.0) * 100.0).round() as i64).clamp(-100, 100).to_string();
+    command.args(["-r", &rate, "--", text]);
+    command.spawn().map(Speaking).map_err(|e| format!("无法运行 spd-say（需要安装 speech-dispatcher）：{}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn start_speaking(text: &str, settings: &SpeechSettings, language: &str) -> Result<Speaking, String> {
+    use windows::core::HSTRING;
+    use windows::Media::Core::MediaSource;
+    use windows::Media::Playback::MediaPlayer;
+    use windows::Media::SpeechSynthesis::SpeechSynthesizer;
+
+    let speak = || -> windows::core::Result<MediaPlayer> {
+        let synthesizer = SpeechSynthesizer::new()?;
+        let all = SpeechSynthesizer::AllVoices()?;
+        for i in 0..all.Size()? {
+            let voice = all.GetAt(i)?;
+            let matches = match settings.voice.as_deref() {
+                Some(name) => voice.DisplayName()?.to_string() == name,
+                None => same_language(&voice.Language()?.to_string(), language),
+            };
+            if matches {
+                synthesizer.SetVoice(&voice)?;
+                break;
+            }
+        }
+        synthesizer.Options()?.SetSpeakingRate(settings.rate)?;
+        let stream = synthesizer.SynthesizeTextToStreamAsync(&HSTRING::from(text))?.get()?;
+        let player = MediaPlayer::new()?;
+        player.SetSource(&MediaSource::CreateFromStream(&stream, &stream.ContentType()?)?)?;
+        player.Play()?;
+        Ok(player)
+    };
+    speak().map(Speaking).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn start_speaking(_text: &str, _settings: &SpeechSettings, _language: &str) -> Result<Speaking, String> {
+    Err("当前平台不支持语音播报".to_string())
+}
+
+fn speak_loop(receiver: mpsc::Receiver<Message>) {
+    let mut current: Option<Speaking> = None;
+    for message in receiver {
+        if let Some(speaking) = current.take() {
+            speaking.stop();
+        }
+        if let Message::Say(text, settings, language) = message {
+            match start_speaking(&text, &settings, &language) {
+                Ok(speaking) => current = Some(speaking),
+                Err(e) => log::warn!("语音播报失败：{}", e),
+            }
+        }
+    }
+}
+
+impl SpeechState {
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        match std::thread::Builder::new().name("speech".into()).spawn(move || speak_loop(receiver)) {
+            Ok(_) => Self { sender: Some(sender) },
+            Err(e) => {
+                log::warn!("启动语音播报线程失败：{}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+fn send(app: &tauri::AppHandle, message: Message) {
+    let sender = app
+        .try_state::<Arc<Mutex<SpeechState>>>()
+        .and_then(|state| state.lock().ok().and_then(|state| state.sender.clone()));
+    if let Some(sender) = sender {
+        let _ = sender.send(message);
+    }
+}
+
+fn say(app: &tauri::AppHandle, text: impl FnOnce(Language) -> Option<String>) {
+    let settings = crate::settings::get(app).speech;
+    if !settings.enabled || settings.muted {
+        return;
+    }
+    let (locale, language) = speech_locale(app, &settings);
+    if let Some(text) = text(locale.language) {
+        send(app, Message::Say(text, settings, language));
+    }
+}
+
+// 计时进入新步骤时调用
+pub fn announce_stage(app: &tauri::AppHandle, recipe: &Recipe, index: usize) {
+    say(app, |language| stage_text(recipe, index, language));
+}
+
+pub fn announce_finished(app: &tauri::AppHandle) {
+    say(app, |language| {
+        Some(match language {
+            Language::Zh => "冲煮完成".to_string(),
+            Language::En => "Brew complete".to_string(),
+        })
+    });
+}
+
+#[tauri::command]
+pub fn list_speech_voices() -> Result<Vec<Voice>, String> {
+    voices()
+}
+
+#[tauri::command]
+pub fn set_speech_settings(app: tauri::AppHandle, speech: SpeechSettings) -> Result<(), String> {
+    if !(0.5..=2.0).contains(&speech.rate) {
+        return Err("语速应在 0.5–2 之间".to_string());
+    }
+    let stop = !speech.enabled || speech.muted;
+    crate::settings::update(&app, |s| s.speech = speech)?;
+    if stop {
+        send(&app, Message::Stop);
+    }
+    Ok(())
+}
+
+// 静音开关（迷你计时器、快捷操作使用），静音时立即停止正在进行的播报
+#[tauri::command]
+pub fn set_speech_muted(app: tauri::AppHandle, muted: bool) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.speech.muted = muted)?;
+    if muted {
+        send(&app, Message::Stop);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builds_step_announcements() {
+        let method = json!({ "params": { "water": "225g", "stages": [
+            { "pourType": "circle", "label": "焖蒸", "water": "30", "duration": 30 },
+            { "pourType": "circle", "label": "第一段", "water": "60", "duration": 20 },
+            { "pourType": "circle", "label": "第二段", "water": "60", "duration": 20 },
+            { "pourType": "wait", "label": "等待", "duration": 45 },
+        ] } });
+        let recipe = Recipe::from_method(&method).unwrap();
+        assert_eq!(stage_text(&recipe, 0, Language::Zh).unwrap(), "开始焖蒸，注入至 30 克");
+        assert_eq!(stage_text(&recipe, 2, Language::Zh).unwrap(), "开始第二段注水，注入至 150 克");
+        assert_eq!(stage_text(&recipe, 2, Language::En).unwrap(), "Pour 2, up to 150 grams");
+        assert_eq!(stage_text(&recipe, 3, Language::Zh).unwrap(), "等待 45 秒");
+        assert!(stage_text(&recipe, 4, Language::Zh).is_none());
+        assert_eq!(chinese_number(12), "十二");
+        assert_eq!(chinese_number(30), "三十");
+
+        let say = "Alex                en_US    # Most people recognize me by my voice.\nTing-Ting           zh_CN    # 你好，我叫婷婷。\nGood News           en_US    # Congratulations!\n";
+        let voices = parse_say_voices(say);
+        assert_eq!(voices.len(), 3);
+        assert_eq!(voices[2], Voice { name: "Good News".to_string(), language: "en_US".to_string() });
+        assert!(same_language(&voices[1].language, "zh-CN"));
+        assert!(same_language("zh-CN", "zh"));
+        assert!(!same_language("en_US", "zh-CN"));
+
+        let spd = "NAME                 LANGUAGE   VARIANT\nChinese (Mandarin)   zh         none\nenglish              en         none\n";
+        assert_eq!(parse_spd_voices(spd)[0], Voice { name: "Chinese (Mandarin)".to_string(), language: "zh".to_string() });
+    }
+}
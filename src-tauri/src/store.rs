@@ -0,0 +1,501 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+use crate::journal;
+use crate::json_file;
+use crate::sync::Records;
+use crate::CoffeeBean;
+
+mod migrations;
+
+// 本地数据库（SQLite）：咖啡豆写入后由 Rust 端保存，托盘和通知不依赖前端也能工作
+// 表结构由 migrations 管理
+const DATABASE_FILE: &str = "brew-guide.db";
+const BACKUP_DIR: &str = "backups";
+// 界面和托盘的数据仍以前端（IndexedDB）为准，托盘缓存只由前端推送；导入、同步、撤销、恢复等只写数据库的修改
+// 与上次交给前端的内容比较后放进待取队列，前端取走写入自己的存储，再照常推回托盘
+const OUTBOX_FILE: &str = "store-outbox.json";
+
+// 待取队列的读写在这把锁内完成，避免发布和取走同时进行时丢失修改
+static OUTBOX_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct Store {
+    pub(crate) conn: Connection,
+}
+
+impl Store {
+    pub fn open(app: &tauri::AppHandle) -> Result<Self, String> {
+        let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let mut conn = Connection::open(dir.join(DATABASE_FILE)).map_err(|e| e.to_string())?;
+        migrations::migrate(&mut conn, Some(&dir))?;
+        conn.pragma_update(None, "foreign_keys", true).map_err(|e| e.to_string())?;
+        if let Err(e) = crate::search::rebuild_if_needed(&conn) {
+            log::warn!("重建搜索索引失败：{}", e);
+        }
+        if let Err(e) = crate::trash::purge_expired(&conn) {
+            log::warn!("清理回收站失败：{}", e);
+        }
+        Ok(Self { conn })
+    }
+
+    pub(crate) fn beans(&self) -> rusqlite::Result<Vec<Value>> {
+        all_beans(&self.conn)
+    }
+}
+
+pub(crate) fn database_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(DATABASE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+// 用备份中的数据库替换当前数据库：先关闭连接，复制文件后重新打开（旧版本的备份会在打开时迁移）
+pub(crate) fn replace_database(app: &tauri::AppHandle, source: &Path) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<Store>>>()
+        .ok_or("本地数据库未初始化")?;
+    let mut store = state.lock().map_err(|e| e.to_string())?;
+    store.conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    let path = database_path(app)?;
+    std::fs::copy(source, &path).map_err(|e| e.to_string())?;
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let mut sidecar = path.clone().into_os_string();
+        sidecar.push(suffix);
+        let _ = std::fs::remove_file(sidecar);
+    }
+    *store = Store::open(app)?;
+    Ok(())
+}
+
+// 全部咖啡豆，最近修改的在前
+pub(crate) fn all_beans(conn: &Connection) -> rusqlite::Result<Vec<Value>> {
+    let mut stmt = conn.prepare("SELECT data FROM beans ORDER BY updated_at DESC")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut beans = Vec::new();
+    for data in rows {
+        match serde_json::from_str(&data?) {
+            Ok(bean) => beans.push(bean),
+            Err(e) => log::warn!("跳过无法解析的咖啡豆记录：{}", e),
+        }
+    }
+    Ok(beans)
+}
+
+// 数据库中保存的记录种类
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordKind {
+    Bean,
+    Note,
+}
+
+impl RecordKind {
+    pub(crate) fn key(self) -> &'static str {
+        match self {
+            RecordKind::Bean => "bean",
+            RecordKind::Note => "note",
+        }
+    }
+
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "bean" => Some(RecordKind::Bean),
+            "note" => Some(RecordKind::Note),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn table(self) -> &'static str {
+        match self {
+            RecordKind::Bean => "beans",
+            RecordKind::Note => "notes",
+        }
+    }
+}
+
+// 用 VACUUM INTO 把整个数据库备份到 backups/brew-guide-{label}-{时间}.db，返回备份路径
+pub(crate) fn backup(conn: &Connection, data_dir: &Path, label: &str) -> Result<PathBuf, String> {
+    let dir = data_dir.join(BACKUP_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "brew-guide-{}-{}.db",
+        label,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    conn.execute("VACUUM INTO ?1", [path.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+// 在保存点中执行：可以嵌套在其他事务中，出错时回滚这一部分
+pub(crate) fn atomically<T>(conn: &Connection, f: impl FnOnce() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    conn.execute_batch("SAVEPOINT atomically")?;
+    match f() {
+        Ok(value) => {
+            conn.execute_batch("RELEASE atomically")?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(rollback) = conn.execute_batch("ROLLBACK TO atomically; RELEASE atomically") {
+                log::warn!("回滚失败：{}", rollback);
+            }
+            Err(e)
+        }
+    }
+}
+
+// 记录当前的完整数据，不存在时为 None
+pub(crate) fn record_data(conn: &Connection, kind: RecordKind, id: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(&format!("SELECT data FROM {} WHERE id = ?1", kind.table()), [id], |row| row.get(0))
+        .optional()
+}
+
+// 写入一条记录（从回收站恢复、撤销时使用，数据是之前保存过的）
+pub(crate) fn write_record(conn: &Connection, kind: RecordKind, value: &Value) -> rusqlite::Result<()> {
+    match kind {
+        RecordKind::Bean => write_bean(conn, value),
+        RecordKind::Note => crate::notes::restore_note(conn, value),
+    }
+}
+
+pub(crate) fn with_store<T>(app: &tauri::AppHandle, f: impl FnOnce(&Store) -> rusqlite::Result<T>) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<Store>>>()
+        .ok_or("本地数据库未初始化")?;
+    let store = state.lock().map_err(|e| e.to_string())?;
+    f(&store).map_err(|e| e.to_string())
+}
+
+pub(crate) fn text_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key)?.as_str().map(str::trim).filter(|s| !s.is_empty())
+}
+
+// 数字字段：前端有时以字符串保存
+pub(crate) fn number_field(value: &Value, key: &str) -> Option<f64> {
+    match value.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+// 与前端 useTraySync 的转换一致：拼配豆的产地、处理法用 " / " 连接
+pub(crate) fn join_components(value: &Value, pick: impl Fn(&Value) -> Option<&str>) -> Option<String> {
+    let mut values: Vec<&str> = Vec::new();
+    for component in value.get("blendComponents").and_then(Value::as_array).into_iter().flatten() {
+        if let Some(v) = pick(component).map(str::trim).filter(|v| !v.is_empty()) {
+            if !values.contains(&v) {
+                values.push(v);
+            }
+        }
+    }
+    (!values.is_empty()).then(|| values.join(" / "))
+}
+
+// 把前端的完整咖啡豆数据转换为托盘使用的结构；生豆不显示在托盘中
+fn tray_bean(value: &Value) -> Option<CoffeeBean> {
+    if text_field(value, "beanState") == Some("green") {
+        return None;
+    }
+    let amount = |key: &str| match value.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    let flag = |key: &str| value.get(key).and_then(Value::as_bool);
+    Some(CoffeeBean {
+        id: text_field(value, "id")?.to_string(),
+        name: text_field(value, "name").unwrap_or_default().to_string(),
+        remaining: amount("remaining"),
+        capacity: amount("capacity"),
+        roast_date: text_field(value, "roastDate").map(str::to_string),
+        start_day: number_field(value, "startDay").map(|d| d as i32),
+        end_day: number_field(value, "endDay").map(|d| d as i32),
+        is_frozen: flag("isFrozen"),
+        is_in_transit: flag("isInTransit"),
+        expected_arrival: text_field(value, "expectedArrival").map(str::to_string),
+        pinned: flag("pinned"),
+        roaster: text_field(value, "roaster").map(str::to_string),
+        origin: join_components(value, |c| text_field(c, "origin").or_else(|| text_field(c, "country"))),
+        process: join_components(value, |c| text_field(c, "process")),
+    })
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Outbox {
+    published: BTreeMap<String, String>,       // 记录 -> 交给前端时的哈希
+    pending: BTreeMap<String, Option<Value>>, // 记录 -> 最新数据，None 表示已删除
+}
+
+// 数据库中的一条修改，data 为 None 时前端删除该记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreChange {
+    pub kind: RecordKind,
+    pub id: String,
+    pub data: Option<Value>,
+}
+
+// 与上次交给前端的哈希比较，把新增、修改、删除的记录放进待取队列，返回变化的数量
+// 从没交给前端的记录不会出现在删除里，前端自己保存的数据不受影响
+fn collect_changes(outbox: &mut Outbox, records: &Records) -> usize {
+    let mut changed = 0;
+    for (key, value) in records {
+        let hash = crate::sync::hash(value);
+        if outbox.published.get(key) != Some(&hash) {
+            outbox.published.insert(key.clone(), hash);
+            outbox.pending.insert(key.clone(), Some(value.clone()));
+            changed += 1;
+        }
+    }
+    let removed: Vec<String> = outbox.published.keys().filter(|key| !records.contains_key(*key)).cloned().collect();
+    for key in removed {
+        outbox.published.remove(&key);
+        outbox.pending.insert(key, None);
+        changed += 1;
+    }
+    changed
+}
+
+fn outbox_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(OUTBOX_FILE))
+        .map_err(|e| e.to_string())
+}
+
+// 第一次启动（还没有待取队列）时把数据库现有的记录当作前端已有，之后只交出新的修改，
+// 避免数据库里较旧的数据覆盖前端
+pub(crate) fn seed_outbox(app: &tauri::AppHandle) -> Result<(), String> {
+    let path = outbox_path(app)?;
+    if path.exists() {
+        return Ok(());
+    }
+    let records = with_store(app, |store| crate::sync::local_records(&store.conn))?;
+    let outbox = Outbox {
+        published: crate::sync::hashes(&records),
+        pending: BTreeMap::new(),
+    };
+    let _guard = OUTBOX_LOCK.lock().map_err(|e| e.to_string())?;
+    json_file::save(&path, &outbox).map_err(|e| e.to_string())
+}
+
+// 数据库修改后调用：把变化交给前端（通过 store-changes-pending 事件通知，前端调用 take_store_changes 取走）
+pub(crate) fn publish_changes(app: &tauri::AppHandle) -> Result<(), String> {
+    let records = with_store(app, |store| crate::sync::local_records(&store.conn))?;
+    let path = outbox_path(app)?;
+    let changed = {
+        let _guard = OUTBOX_LOCK.lock().map_err(|e| e.to_string())?;
+        let mut outbox: Outbox = json_file::load(&path).map_err(|e| e.to_string())?;
+        let changed = collect_changes(&mut outbox, &records);
+        if changed > 0 {
+            json_file::save(&path, &outbox).map_err(|e| e.to_string())?;
+        }
+        changed
+    };
+    if changed > 0 {
+        let _ = app.emit("store-changes-pending", changed);
+    }
+    Ok(())
+}
+
+// 前端取走待处理的修改（取走后从队列移除）
+#[tauri::command]
+pub fn take_store_changes(app: tauri::AppHandle) -> Result<Vec<StoreChange>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let path = outbox_path(&app)?;
+    let _guard = OUTBOX_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut outbox: Outbox = json_file::load(&path).map_err(|e| e.to_string())?;
+    if outbox.pending.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pending = std::mem::take(&mut outbox.pending);
+    json_file::save(&path, &outbox).map_err(|e| e.to_string())?;
+    Ok(pending
+        .into_iter()
+        .filter_map(|(key, data)| {
+            let (kind, id) = crate::sync::parse_key(&key)?;
+            Some(StoreChange {
+                kind,
+                id: id.to_string(),
+                data,
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BeanFilter {
+    pub query: Option<String>,   // 名称、烘焙商包含的文字（忽略大小写）
+    pub state: Option<String>,   // 赏味期状态（optimal / resting / decline / frozen / inTransit / unknown）
+    pub in_stock: Option<bool>,  // 只要有剩余 / 只要已喝完
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl BeanFilter {
+    fn matches(&self, value: &Value, today: chrono::NaiveDate) -> bool {
+        if let Some(query) = self.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let query = query.to_lowercase();
+            let hit = ["name", "roaster"]
+                .iter()
+                .filter_map(|key| text_field(value, key))
+                .any(|text| text.to_lowercase().contains(&query));
+            if !hit {
+                return false;
+            }
+        }
+        let bean = tray_bean(value);
+        if let Some(in_stock) = self.in_stock {
+            let remaining = number_field(value, "remaining").unwrap_or(0.0);
+            if (remaining > 0.0) != in_stock {
+                return false;
+            }
+        }
+        if let Some(state) = self.state.as_deref() {
+            let Some(bean) = bean else {
+                return false;
+            };
+            let freshness = crate::calculate_freshness(&bean, today).freshness_state;
+            if crate::widget::state_key(&freshness) != state {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[tauri::command]
+pub fn list_beans(app: tauri::AppHandle) -> Result<Vec<Value>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, Store::beans)
+}
+
+// 按条件筛选咖啡豆，结果按最近修改排序
+#[tauri::command]
+pub fn query_beans(app: tauri::AppHandle, filter: BeanFilter) -> Result<Vec<Value>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let today = crate::today(&app);
+    let beans = with_store(&app, Store::beans)?;
+    Ok(beans
+        .into_iter()
+        .filter(|bean| filter.matches(bean, today))
+        .skip(filter.offset.unwrap_or(0))
+        .take(filter.limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+fn validate_bean(bean: &Value) -> Result<(), String> {
+    text_field(bean, "id").ok_or("咖啡豆缺少 id")?;
+    text_field(bean, "name").ok_or("咖啡豆缺少名称")?;
+    Ok(())
+}
+
+// 写入咖啡豆并更新搜索索引、附件引用；调用前需已通过 validate_bean
+pub(crate) fn write_bean(conn: &Connection, bean: &Value) -> rusqlite::Result<()> {
+    let id = text_field(bean, "id").unwrap_or_default();
+    let name = text_field(bean, "name").unwrap_or_default();
+    let roaster = text_field(bean, "roaster");
+    let data = serde_json::to_string(bean).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        "INSERT INTO beans (id, name, roaster, data, updated_at) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(id) DO UPDATE SET name = ?2, roaster = ?3, data = ?4, updated_at = ?5",
+        params![id, name, roaster, data, now],
+    )?;
+    crate::search::index_bean(conn, id, bean)?;
+    crate::attachments::sync_refs(conn, RecordKind::Bean, id, bean)
+}
+
+// 新增或整体替换一款咖啡豆（需要非空的 id），保存后交给前端
+#[tauri::command]
+pub fn upsert_bean(app: tauri::AppHandle, bean: Value) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    validate_bean(&bean)?;
+    let id = text_field(&bean, "id").unwrap_or_default();
+    with_store(&app, |store| {
+        atomically(&store.conn, || {
+            journal::track(&store.conn, RecordKind::Bean, id, || write_bean(&store.conn, &bean))
+        })
+    })?;
+    publish_changes(&app)?;
+    Ok(bean)
+}
+
+// 删除咖啡豆（移到回收站），返回是否存在
+#[tauri::command]
+pub fn delete_bean(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let existed = with_store(&app, |store| {
+        atomically(&store.conn, || {
+            journal::track(&store.conn, RecordKind::Bean, &id, || {
+                crate::trash::move_to_trash(&store.conn, RecordKind::Bean, &id)
+            })
+        })
+    })?;
+    if existed {
+        publish_changes(&app)?;
+    }
+    Ok(existed)
+}
+
+// 已执行全部迁移的内存数据库
+#[cfg(test)]
+pub(crate) fn test_connection() -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    migrations::migrate(&mut conn, None).unwrap();
+    conn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_frontend_beans_for_the_tray() {
+        let bean = tray_bean(&json!({
+            "id": "b1",
+            "name": "耶加雪菲",
+            "remaining": 120,
+            "startDay": "7",
+            "blendComponents": [{ "country": "埃塞俄比亚" }, { "origin": "埃塞俄比亚" }],
+        }))
+        .unwrap();
+        assert_eq!(bean.remaining.as_deref(), Some("120"));
+        assert_eq!(bean.start_day, Some(7));
+        assert_eq!(bean.origin.as_deref(), Some("埃塞俄比亚"));
+
+        assert!(tray_bean(&json!({ "id": "b2", "name": "生豆", "beanState": "green" })).is_none());
+        assert!(tray_bean(&json!({ "name": "没有 ID" })).is_none());
+    }
+
+    #[test]
+    fn queues_only_changed_records_for_the_frontend() {
+        let mut outbox = Outbox::default();
+        let mut records = Records::new();
+        records.insert("bean:b1".to_string(), json!({ "id": "b1", "name": "耶加雪菲" }));
+        records.insert("note:n1".to_string(), json!({ "id": "n1", "timestamp": 1 }));
+        assert_eq!(collect_changes(&mut outbox, &records), 2);
+        outbox.pending.clear();
+
+        // 没有变化时不重复交给前端
+        assert_eq!(collect_changes(&mut outbox, &records), 0);
+
+        records.insert("bean:b1".to_string(), json!({ "id": "b1", "name": "耶加雪菲 日晒" }));
+        records.remove("note:n1");
+        assert_eq!(collect_changes(&mut outbox, &records), 2);
+        assert_eq!(outbox.pending["bean:b1"].as_ref().unwrap()["name"], "耶加雪菲 日晒");
+        assert_eq!(outbox.pending["note:n1"], None);
+        assert!(!outbox.published.contains_key("note:n1"));
+    }
+}
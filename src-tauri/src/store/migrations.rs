@@ -0,0 +1,67 @@
+use rusqlite::Connection;
+use std::path::Path;
+
+// 数据库结构迁移：migrations/ 下按编号排列的 SQL 文件，只能追加、不能修改已发布的文件
+// 当前版本记录在 PRAGMA user_version 中（等于已执行的迁移数量）
+// 需要迁移时先用 VACUUM INTO 备份整个数据库，每个迁移在单独的事务中执行
+const MIGRATIONS: &[(&str, &str)] = &[
+    ("0001_create_beans", include_str!("../../migrations/0001_create_beans.sql")),
+    ("0002_create_notes", include_str!("../../migrations/0002_create_notes.sql")),
+    ("0003_create_search_index", include_str!("../../migrations/0003_create_search_index.sql")),
+    ("0004_create_trash", include_str!("../../migrations/0004_create_trash.sql")),
+    ("0005_create_journal", include_str!("../../migrations/0005_create_journal.sql")),
+    ("0006_create_attachments", include_str!("../../migrations/0006_create_attachments.sql")),
+    ("0007_create_sync_conflicts", include_str!("../../migrations/0007_create_sync_conflicts.sql")),
+    ("0008_create_roast_batches", include_str!("../../migrations/0008_create_roast_batches.sql")),
+];
+
+fn user_version(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))
+        .map(|version| version.max(0) as usize)
+}
+
+// 执行尚未执行的迁移；data_dir 为空时（如内存数据库）不备份
+pub fn migrate(conn: &mut Connection, data_dir: Option<&Path>) -> Result<(), String> {
+    let version = user_version(conn).map_err(|e| e.to_string())?;
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "数据库版本（{}）比应用支持的版本（{}）新，请更新应用",
+            version,
+            MIGRATIONS.len()
+        ));
+    }
+    if version == MIGRATIONS.len() {
+        return Ok(());
+    }
+
+    // 全新的数据库没有需要保护的数据
+    if let Some(dir) = data_dir.filter(|_| version > 0) {
+        super::backup(conn, dir, &format!("v{}", version)).map_err(|e| format!("迁移前备份数据库失败：{}", e))?;
+    }
+    for (index, (name, sql)) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute_batch(sql)
+            .and_then(|_| tx.pragma_update(None, "user_version", (index + 1) as i64))
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("数据库迁移 {} 失败：{}", name, e))?;
+        log::info!("已执行数据库迁移 {}", name);
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_forward_once_and_rejects_newer_databases() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn, None).unwrap();
+        assert_eq!(user_version(&conn).unwrap(), MIGRATIONS.len());
+        migrate(&mut conn, None).unwrap();
+
+        conn.pragma_update(None, "user_version", (MIGRATIONS.len() + 1) as i64).unwrap();
+        assert!(migrate(&mut conn, None).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::journal;
+use crate::store::{atomically, text_field, RecordKind};
+
+// 同步（同步文件夹、局域网同步）共用的数据格式和三方合并：
+// 以上次同步时每条记录的哈希为基准，只有一边修改的记录采用修改后的版本，两边都修改的记录交给 conflicts 按字段合并
+const SYNC_FILE_VERSION: u32 = 1;
+
+pub type Records = BTreeMap<String, Value>; // bean:{id} / note:{id} -> 记录
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncFile {
+    pub version: u32,
+    pub exported_at: String,
+    pub beans: Vec<Value>,
+    pub notes: Vec<Value>,
+    pub versions: BTreeMap<String, i64>, // 记录 -> 在导出设备上的修改时间（毫秒），旧版本的文件没有
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct MergePlan {
+    pub apply: Vec<(String, Option<Value>)>, // 采用对方的版本，None 表示对方已删除
+    pub conflicts: Vec<(String, Value)>,     // 两边都修改，对方的版本
+}
+
+pub fn record_key(kind: RecordKind, id: &str) -> String {
+    format!("{}:{}", kind.key(), id)
+}
+
+pub fn parse_key(key: &str) -> Option<(RecordKind, &str)> {
+    let (kind, id) = key.split_once(':')?;
+    Some((RecordKind::from_key(kind)?, id))
+}
+
+pub fn hash(value: &Value) -> String {
+    blake3::hash(value.to_string().as_bytes()).to_hex().to_string()
+}
+
+pub fn hashes(records: &Records) -> BTreeMap<String, String> {
+    records.iter().map(|(key, value)| (key.clone(), hash(value))).collect()
+}
+
+pub fn local_records(conn: &Connection) -> rusqlite::Result<Records> {
+    let mut records = Records::new();
+    for kind in [RecordKind::Bean, RecordKind::Note] {
+        let mut stmt = conn.prepare(&format!("SELECT id, data FROM {}", kind.table()))?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        for row in rows {
+            let (id, data) = row?;
+            if let Ok(value) = serde_json::from_str(&data) {
+                records.insert(record_key(kind, &id), value);
+            }
+        }
+    }
+    Ok(records)
+}
+
+// 每条记录在本机的修改时间
+pub fn local_versions(conn: &Connection) -> rusqlite::Result<BTreeMap<String, i64>> {
+    let mut versions = BTreeMap::new();
+    for kind in [RecordKind::Bean, RecordKind::Note] {
+        let mut stmt = conn.prepare(&format!("SELECT id, updated_at FROM {}", kind.table()))?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (id, updated_at) = row?;
+            versions.insert(record_key(kind, &id), updated_at);
+        }
+    }
+    Ok(versions)
+}
+
+impl SyncFile {
+    pub fn records(&self) -> Records {
+        let tagged = |kind: RecordKind, values: &[Value]| -> Vec<(String, Value)> {
+            values
+                .iter()
+                .filter_map(|value| Some((record_key(kind, text_field(value, "id")?), value.clone())))
+                .collect()
+        };
+        tagged(RecordKind::Bean, &self.beans)
+            .into_iter()
+            .chain(tagged(RecordKind::Note, &self.notes))
+            .collect()
+    }
+
+    pub fn from_records<'a>(records: impl IntoIterator<Item = (&'a String, &'a Value)>) -> Self {
+        let mut file = SyncFile {
+            version: SYNC_FILE_VERSION,
+            exported_at: chrono::Local::now().to_rfc3339(),
+            ..Default::default()
+        };
+        for (key, value) in records {
+            match parse_key(key) {
+                Some((RecordKind::Bean, _)) => file.beans.push(value.clone()),
+                Some((RecordKind::Note, _)) => file.notes.push(value.clone()),
+                None => {}
+            }
+        }
+        file
+    }
+}
+
+// 三方合并：以上次同步时的哈希为基准比较本地和同步文件中的记录
+pub fn plan(base: &BTreeMap<String, String>, local: &Records, remote: &Records) -> MergePlan {
+    let mut plan = MergePlan::default();
+    let keys: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    for key in keys {
+        let (local_value, remote_value) = (local.get(key), remote.get(key));
+        let (local_hash, remote_hash) = (local_value.map(hash), remote_value.map(hash));
+        if local_hash == remote_hash {
+            continue;
+        }
+        let base_hash = base.get(key);
+        let local_changed = local_hash.as_ref() != base_hash;
+        let remote_changed = remote_hash.as_ref() != base_hash;
+        match (local_changed, remote_changed, remote_value) {
+            (false, true, _) => plan.apply.push((key.clone(), remote_value.cloned())),
+            (true, true, Some(remote_value)) => plan.conflicts.push((key.clone(), remote_value.clone())),
+            // 只有本地修改，或对方删除了本地修改过的记录：保留本地版本，写回同步文件
+            _ => {}
+        }
+    }
+    plan
+}
+
+// 字段级三方合并：只有一边修改的字段采用修改后的值，两边改成不同值的字段作为冲突（合并结果中保留本地的值）
+// 没有基准版本时，两边不同的字段都是冲突
+pub fn merge_fields(base: Option<&Value>, local: &Value, remote: &Value) -> (Value, Vec<String>) {
+    let (Some(local_fields), Some(remote_fields)) = (local.as_object(), remote.as_object()) else {
+        return (local.clone(), Vec::new());
+    };
+    let base_fields = base.and_then(Value::as_object);
+    let mut merged = local_fields.clone();
+    let mut conflicts = Vec::new();
+    let names: BTreeSet<&String> = local_fields.keys().chain(remote_fields.keys()).collect();
+    for name in names {
+        let (local_value, remote_value) = (local_fields.get(name), remote_fields.get(name));
+        if local_value == remote_value {
+            continue;
+        }
+        let base_value = base_fields.map(|fields| fields.get(name));
+        if base_value == Some(local_value) {
+            match remote_value {
+                Some(value) => merged.insert(name.clone(), value.clone()),
+                None => merged.remove(name),
+            };
+        } else if base_value != Some(remote_value) {
+            conflicts.push(name.clone());
+        }
+    }
+    (Value::Object(merged), conflicts)
+}
+
+pub fn apply(conn: &Connection, changes: &[(String, Option<Value>)]) -> rusqlite::Result<()> {
+    atomically(conn, || {
+        for (key, value) in changes {
+            if let Some((kind, id)) = parse_key(key) {
+                journal::track(conn, kind, id, || journal::apply(conn, kind, id, value.as_ref()))?;
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_one_sided_changes_and_reports_conflicts() {
+        let bean = |name: &str| json!({ "id": "b1", "name": name });
+        let records = |items: &[(&str, Value)]| -> Records {
+            items.iter().map(|(key, value)| (key.to_string(), value.clone())).collect()
+        };
+        let base = hashes(&records(&[("bean:b1", bean("耶加雪菲")), ("bean:b2", json!({ "id": "b2" }))]));
+
+        // 对方修改了 b1、删除了 b2，本地没动：采用对方的版本
+        let local = records(&[("bean:b1", bean("耶加雪菲")), ("bean:b2", json!({ "id": "b2" }))]);
+        let remote = records(&[("bean:b1", bean("耶加雪菲 G1"))]);
+        let merged = plan(&base, &local, &remote);
+        assert_eq!(
+            merged.apply,
+            vec![("bean:b1".to_string(), Some(bean("耶加雪菲 G1"))), ("bean:b2".to_string(), None)]
+        );
+
+        // 两边都修改了 b1：保留本地版本，对方的版本作为冲突
+        let local = records(&[("bean:b1", bean("本地")), ("bean:b2", json!({ "id": "b2" }))]);
+        let remote = records(&[("bean:b1", bean("对方")), ("bean:b2", json!({ "id": "b2" }))]);
+        let merged = plan(&base, &local, &remote);
+        assert!(merged.apply.is_empty());
+        assert_eq!(merged.conflicts, vec![("bean:b1".to_string(), bean("对方"))]);
+
+        // 按字段合并：名称只有对方改了，产地两边改得不一样
+        let base = json!({ "id": "b1", "name": "耶加雪菲", "origin": "埃塞俄比亚" });
+        let local = json!({ "id": "b1", "name": "耶加雪菲", "origin": "Ethiopia" });
+        let remote = json!({ "id": "b1", "name": "耶加雪菲 G1", "origin": "埃塞" });
+        let (merged, fields) = merge_fields(Some(&base), &local, &remote);
+        assert_eq!(merged, json!({ "id": "b1", "name": "耶加雪菲 G1", "origin": "Ethiopia" }));
+        assert_eq!(fields, vec!["origin".to_string()]);
+        assert_eq!(merge_fields(None, &local, &remote).1, vec!["name".to_string(), "origin".to_string()]);
+    }
+}
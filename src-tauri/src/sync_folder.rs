@@ -0,0 +1,224 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::json_file;
+use crate::store::with_store;
+use crate::sync::{hashes, plan, SyncFile};
+
+// 同步文件夹：把全部咖啡豆和笔记写入用户选择的文件夹（iCloud Drive、OneDrive、Dropbox 等）中的 brew-guide-sync.json，
+// 监听这个文件，其它设备修改后合并回本地数据库。合并以上次同步时每条记录的哈希为基准：
+// 只有一边修改的记录直接采用修改后的版本；两边都修改的按字段合并，修改了同一字段的记为冲突（见 conflicts）
+const SYNC_FILE: &str = "brew-guide-sync.json";
+const SYNC_BASE_FILE: &str = "sync-folder-base.json";
+
+// 没有文件变化时也定期检查，把本地修改写入同步文件
+const EXPORT_INTERVAL: Duration = Duration::from_secs(30);
+// 收到文件变化后等待一会儿，合并网盘客户端连续写入产生的多个事件
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+// 上次同步后每条记录的哈希，换文件夹后重新开始
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SyncBase {
+    folder: Option<String>,
+    hashes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub folder: Option<String>,
+    pub last_synced_at: Option<String>,
+    pub last_error: Option<String>,
+    pub applied: usize,   // 上次同步时采用的其它设备的修改
+    pub conflicts: usize, // 上次同步时新发现的冲突
+}
+
+#[derive(Default)]
+pub struct SyncFolderState {
+    watcher: Option<RecommendedWatcher>,
+    status: SyncStatus,
+}
+
+fn base_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SYNC_BASE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn sync_once(app: &tauri::AppHandle, folder: &str, status: &mut SyncStatus) -> Result<(), String> {
+    let path = Path::new(folder).join(SYNC_FILE);
+    let base_path = base_path(app)?;
+    let mut base: SyncBase = json_file::load(&base_path).map_err(|e| e.to_string())?;
+    if base.folder.as_deref() != Some(folder) {
+        base = SyncBase {
+            folder: Some(folder.to_string()),
+            hashes: BTreeMap::new(),
+        };
+    }
+    let remote = if path.exists() {
+        let bytes = std::fs::read(&path).map_err(|e| e.to_string())?;
+        let file: SyncFile = serde_json::from_slice(&bytes).map_err(|e| format!("无法读取同步文件：{}", e))?;
+        Some(file)
+    } else {
+        None
+    };
+    let remote_records = remote.as_ref().map(SyncFile::records);
+
+    let mut local = with_store(app, |store| crate::sync::local_records(&store.conn))?;
+    let plan = remote_records
+        .as_ref()
+        .map(|records| plan(&base.hashes, &local, records))
+        .unwrap_or_default();
+    let mut conflicts = 0;
+    if !plan.apply.is_empty() || !plan.conflicts.is_empty() {
+        let versions = remote.map(|file| file.versions).unwrap_or_default();
+        local = with_store(app, |store| {
+            crate::sync::apply(&store.conn, &plan.apply)?;
+            conflicts = crate::conflicts::detect(&store.conn, "sync-folder", &base.hashes, &plan.conflicts, &versions)?;
+            crate::sync::local_records(&store.conn)
+        })?;
+    }
+    if remote_records.as_ref() != Some(&local) {
+        let mut file = SyncFile::from_records(&local);
+        file.versions = with_store(app, |store| crate::sync::local_versions(&store.conn))?;
+        json_file::save(&path, &file).map_err(|e| e.to_string())?;
+    }
+    base.hashes = hashes(&local);
+    json_file::save(&base_path, &base).map_err(|e| e.to_string())?;
+
+    status.applied = plan.apply.len();
+    status.conflicts = conflicts;
+    status.last_synced_at = Some(chrono::Local::now().to_rfc3339());
+    if !plan.apply.is_empty() || !plan.conflicts.is_empty() {
+        crate::store::publish_changes(app)?;
+    }
+    Ok(())
+}
+
+// 同步一次并记录结果，状态变化通过 sync-folder-status 事件发给前端
+fn run_sync(app: &tauri::AppHandle) -> Result<SyncStatus, String> {
+    let state = app
+        .try_state::<Arc<Mutex<SyncFolderState>>>()
+        .ok_or("同步文件夹未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    let Some(folder) = state.status.folder.clone() else {
+        return Err("还没有设置同步文件夹".to_string());
+    };
+    let result = sync_once(app, &folder, &mut state.status);
+    state.status.last_error = result.as_ref().err().cloned();
+    let status = state.status.clone();
+    drop(state);
+    let _ = app.emit("sync-folder-status", &status);
+    result.map(|()| status)
+}
+
+// 监听同步文件，后台线程在文件变化后、以及每隔一段时间同步一次；停止监听后线程随之退出
+fn watch(app: &tauri::AppHandle, folder: &str) -> Result<RecommendedWatcher, String> {
+    let (sender, receiver) = mpsc::channel::<()>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let ours = event.is_ok_and(|event| {
+            event.paths.iter().any(|path| path.file_name().is_some_and(|name| name == SYNC_FILE))
+        });
+        if ours {
+            let _ = sender.send(());
+        }
+    })
+    .map_err(|e| e.to_string())?;
+    watcher
+        .watch(Path::new(folder), RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+
+    let app = app.clone();
+    thread::spawn(move || loop {
+        match receiver.recv_timeout(EXPORT_INTERVAL) {
+            Ok(()) => {
+                thread::sleep(DEBOUNCE);
+                while receiver.try_recv().is_ok() {}
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        if let Err(e) = run_sync(&app) {
+            log::warn!("同步文件夹失败：{}", e);
+        }
+    });
+    Ok(watcher)
+}
+
+fn start(app: &tauri::AppHandle, folder: Option<String>) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<SyncFolderState>>>()
+        .ok_or("同步文件夹未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.watcher = None;
+    state.status = SyncStatus {
+        folder: folder.clone(),
+        ..Default::default()
+    };
+    if let Some(folder) = folder {
+        state.watcher = Some(watch(app, &folder)?);
+    }
+    Ok(())
+}
+
+// 启动时按设置开始监听，在后台线程中先同步一次，不阻塞启动
+pub fn start_from_settings(app: &tauri::AppHandle) {
+    let folder = crate::settings::get(app).sync_folder;
+    if folder.is_none() {
+        return;
+    }
+    if let Err(e) = start(app, folder) {
+        log::warn!("启动同步文件夹失败：{}", e);
+        return;
+    }
+    let app = app.clone();
+    thread::spawn(move || {
+        if let Err(e) = run_sync(&app) {
+            log::warn!("同步文件夹失败：{}", e);
+        }
+    });
+}
+
+// 设置同步文件夹并立即同步一次，None 表示停止同步
+#[tauri::command]
+pub fn set_sync_folder(app: tauri::AppHandle, folder: Option<String>) -> Result<SyncStatus, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let folder = folder.map(|f| f.trim().to_string()).filter(|f| !f.is_empty());
+    if let Some(folder) = &folder {
+        if !Path::new(folder).is_dir() {
+            return Err(format!("找不到文件夹：{}", folder));
+        }
+    }
+    crate::settings::update(&app, |settings| settings.sync_folder = folder.clone())?;
+    start(&app, folder.clone())?;
+    if folder.is_none() {
+        return get_sync_status(app);
+    }
+    let status = run_sync(&app)?;
+    crate::telemetry::record(&app, "sync_folder.enable");
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn sync_folder_now(app: tauri::AppHandle) -> Result<SyncStatus, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    run_sync(&app)
+}
+
+#[tauri::command]
+pub fn get_sync_status(app: tauri::AppHandle) -> Result<SyncStatus, String> {
+    let state = app
+        .try_state::<Arc<Mutex<SyncFolderState>>>()
+        .ok_or("同步文件夹未初始化")?;
+    let status = state.lock().map_err(|e| e.to_string())?.status.clone();
+    Ok(status)
+}
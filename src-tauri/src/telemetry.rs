@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::json_file;
+
+// 匿名使用统计：只统计功能使用次数（如 "tray.open_bean"），不包含咖啡豆名称等任何内容，也没有设备 ID
+// 默认关闭；关闭时不计数（预览只展示上传格式），开启后才计数并落盘排队，按天打包上传，失败时保留下次重试
+const TELEMETRY_QUEUE_FILE: &str = "telemetry-queue.json";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const MAX_QUEUED_DAYS: usize = 30;
+const MAX_FEATURE_NAME_LEN: usize = 64;
+
+// 上传地址在构建时配置，未配置时只在本地计数，不上传
+const TELEMETRY_ENDPOINT: Option<&str> = option_env!("BREW_GUIDE_TELEMETRY_ENDPOINT");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryBatch {
+    pub day: String,
+    pub app_version: String,
+    pub os: String,
+    pub counts: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TelemetryQueue {
+    batches: Vec<TelemetryBatch>,
+}
+
+impl TelemetryBatch {
+    fn new(day: &str) -> Self {
+        Self {
+            day: day.to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            counts: BTreeMap::new(),
+        }
+    }
+}
+
+impl TelemetryQueue {
+    fn increment(&mut self, day: &str, feature: &str) {
+        if !matches!(self.batches.last(), Some(batch) if batch.day == day) {
+            self.batches.push(TelemetryBatch::new(day));
+            // 长时间离线时丢弃最早的数据
+            if self.batches.len() > MAX_QUEUED_DAYS {
+                self.batches.remove(0);
+            }
+        }
+        if let Some(batch) = self.batches.last_mut() {
+            *batch.counts.entry(feature.to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TelemetryState {
+    queue: TelemetryQueue,
+    flushing: bool,
+}
+
+impl TelemetryState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let queue = if crate::settings::get(app).telemetry_enabled {
+            queue_path(app)
+                .ok()
+                .and_then(|path| json_file::load(&path).ok())
+                .unwrap_or_default()
+        } else {
+            TelemetryQueue::default()
+        };
+        Self { queue, flushing: false }
+    }
+}
+
+// 将要上传的内容（与实际上传的请求体完全一致）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryPreview {
+    pub enabled: bool,
+    pub upload_configured: bool,
+    pub batches: Vec<TelemetryBatch>,
+}
+
+#[derive(Serialize)]
+struct TelemetryPayload<'a> {
+    batches: &'a [TelemetryBatch],
+}
+
+fn queue_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(TELEMETRY_QUEUE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn with_state<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut TelemetryState) -> T) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<TelemetryState>>>()
+        .ok_or("统计模块未初始化")?;
+    let mut lock = state.lock().map_err(|e| e.to_string())?;
+    Ok(f(&mut lock))
+}
+
+fn save_queue(app: &tauri::AppHandle, queue: &TelemetryQueue) -> Result<(), String> {
+    json_file::save(&queue_path(app)?, queue).map_err(|e| e.to_string())
+}
+
+// 功能名只允许小写字母、数字和 . _ -，避免误把内容当作功能名上传
+fn is_valid_feature(feature: &str) -> bool {
+    !feature.is_empty()
+        && feature.len() <= MAX_FEATURE_NAME_LEN
+        && feature
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'))
+}
+
+// 预览内容：开启时为排队中的数据；关闭时没有任何计数，只用一个空的当天批次说明上传格式
+fn preview_batches(enabled: bool, queue: &TelemetryQueue, day: &str) -> Vec<TelemetryBatch> {
+    if enabled {
+        queue.batches.clone()
+    } else {
+        vec![TelemetryBatch::new(day)]
+    }
+}
+
+// 记录一次功能使用，未开启统计时什么都不做
+pub fn record(app: &tauri::AppHandle, feature: &str) {
+    if !is_valid_feature(feature) {
+        log::warn!("忽略无效的统计项：{}", feature);
+        return;
+    }
+    if !crate::settings::get(app).telemetry_enabled {
+        return;
+    }
+    let day = crate::today(app).to_string();
+    let result = with_state(app, |s| {
+        s.queue.increment(&day, feature);
+        save_queue(app, &s.queue)
+    })
+    .and_then(|saved| saved);
+    if let Err(e) = result {
+        log::warn!("记录使用统计失败：{}", e);
+    }
+}
+
+// 上传已经结束的日期（当天的数据还在累计），成功后从队列移除
+async fn flush(app: &tauri::AppHandle) -> Result<(), String> {
+    let Some(endpoint) = TELEMETRY_ENDPOINT else {
+        return Ok(());
+    };
+    if !crate::settings::get(app).telemetry_enabled {
+        return Ok(());
+    }
+    let today = crate::today(app).to_string();
+    let pending = with_state(app, |s| {
+        if s.flushing {
+            return Vec::new();
+        }
+        let pending: Vec<TelemetryBatch> = s.queue.batches.iter().filter(|b| b.day < today).cloned().collect();
+        s.flushing = !pending.is_empty();
+        pending
+    })?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let result = reqwest::Client::builder()
+        .user_agent(concat!("BrewGuide/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())?
+        .post(endpoint)
+        .json(&TelemetryPayload { batches: &pending })
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string());
+
+    // 上传期间关闭了统计时队列已清空，不再写回
+    let enabled = crate::settings::get(app).telemetry_enabled;
+    with_state(app, |s| {
+        s.flushing = false;
+        if result.is_ok() && enabled {
+            s.queue.batches.retain(|b| !pending.iter().any(|p| p.day == b.day));
+            save_queue(app, &s.queue)
+        } else {
+            Ok(())
+        }
+    })??;
+    result.map(|_| ())
+}
+
+// 定期上传，离线或失败时下次再试
+pub fn spawn_flush_loop(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            if let Err(e) = flush(&app).await {
+                log::info!("上传使用统计失败，稍后重试：{}", e);
+            }
+        }
+    });
+}
+
+// 前端上报功能使用
+#[tauri::command]
+pub fn track_feature(app: tauri::AppHandle, feature: String) {
+    record(&app, &feature);
+}
+
+// 开启前也可以预览上传格式
+#[tauri::command]
+pub fn get_telemetry_preview(app: tauri::AppHandle) -> Result<TelemetryPreview, String> {
+    let enabled = crate::settings::get(&app).telemetry_enabled;
+    let day = crate::today(&app).to_string();
+    let batches = with_state(&app, |s| preview_batches(enabled, &s.queue, &day))?;
+    Ok(TelemetryPreview {
+        enabled,
+        upload_configured: TELEMETRY_ENDPOINT.is_some(),
+        batches,
+    })
+}
+
+// 关闭时清空内存中的计数并删除本地队列
+#[tauri::command]
+pub fn set_telemetry_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.telemetry_enabled = enabled)?;
+    if enabled {
+        return Ok(());
+    }
+    with_state(&app, |s| s.queue = TelemetryQueue::default())?;
+    match std::fs::remove_file(queue_path(&app)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_valid_features_with_consent() {
+        assert!(is_valid_feature("tray.open_bean"));
+        assert!(is_valid_feature("import.beanconqueror-v2"));
+        assert!(!is_valid_feature(""));
+        assert!(!is_valid_feature("Tray.Open"));
+        assert!(!is_valid_feature("search:埃塞俄比亚"));
+        assert!(!is_valid_feature(&"a".repeat(MAX_FEATURE_NAME_LEN + 1)));
+
+        let mut queue = TelemetryQueue::default();
+        queue.increment("2026-10-01", "tray.open_bean");
+        queue.increment("2026-10-01", "tray.open_bean");
+        queue.increment("2026-10-02", "share.text");
+        assert_eq!(queue.batches.len(), 2);
+        assert_eq!(queue.batches[0].counts["tray.open_bean"], 2);
+
+        // 未开启时预览不包含任何计数
+        let preview = preview_batches(false, &queue, "2026-10-02");
+        assert_eq!(preview.len(), 1);
+        assert!(preview[0].counts.is_empty());
+        assert_eq!(preview_batches(true, &queue, "2026-10-02").len(), 2);
+
+        // 长时间离线只保留最近的天数
+        let start = chrono::NaiveDate::from_ymd_opt(2026, 11, 1).unwrap();
+        for offset in 0..MAX_QUEUED_DAYS + 5 {
+            let day = start + chrono::Duration::days(offset as i64);
+            queue.increment(&day.to_string(), "timer.quick_log");
+        }
+        assert_eq!(queue.batches.len(), MAX_QUEUED_DAYS);
+        assert_eq!(queue.batches[0].day, "2026-11-06");
+    }
+}
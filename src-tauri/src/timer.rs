@@ -0,0 +1,590 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::json_file;
+use crate::recipe::{Recipe, RecipeStage, StageKind};
+
+// 提示音和节拍器按这个间隔检查，间隔越短节拍越准
+const TICK_INTERVAL: Duration = Duration::from_millis(50);
+
+// 后端计时每秒把进度保存到 brew-timer.json，应用崩溃或中途退出后下次启动可以从中断处继续
+// 正常结束或停止时删除；超过 RECOVERY_MAX_AGE 的记录不再提供恢复
+const RECOVERY_FILE: &str = "brew-timer.json";
+const RECOVERY_MAX_AGE: i64 = 30 * 60 * 1000;
+
+// 冲煮计时器状态：前端自己计时时只在开始/停止时同步到这里；
+// 通过 start_brew_recipe 开始时由后端按方案步骤计时，并在切换阶段时发出 brew-stage 事件
+// 托盘据此在计时中禁用「开始冲煮计时」，并在下次开始时沿用上次的冲煮方案
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerState {
+    pub running: bool,
+    pub last_method: Option<String>,
+    #[serde(skip)]
+    pub run: Option<BrewRun>,
+    // 最近一次后端计时的方案，快捷键开始计时时不需要前端再传一次
+    #[serde(skip)]
+    pub last_recipe: Option<Recipe>,
+    // 最近一次结束（完成或停止）的后端计时，用于快捷键快速记录
+    #[serde(skip)]
+    pub last_brew: Option<FinishedBrew>,
+    #[serde(skip)]
+    next_run_id: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct FinishedBrew {
+    pub recipe: Recipe,
+    pub elapsed: f64,
+    pub finished_at: i64,
+}
+
+// 后端计时中的冲煮，id 用来让旧的计时任务在重新开始后退出
+// 暂停时记下暂停的时刻，继续时把开始时间顺延暂停的时长
+#[derive(Debug, Clone)]
+pub struct BrewRun {
+    pub id: u64,
+    pub recipe: Recipe,
+    pub started: Instant,
+    pub paused_at: Option<Instant>,
+}
+
+// 阶段切换时发给前端和硬件集成（蓝牙秤等）的进度，水量为累计目标
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewProgress {
+    pub index: usize,
+    pub kind: StageKind,
+    pub label: String,
+    pub detail: String,
+    pub elapsed: f64,
+    pub stage_remaining: f64,
+    pub target_water: f64,
+    pub stage_water: f64,
+    pub total_time: f64,
+    pub next: Option<RecipeStage>,
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BrewFinishedEvent {
+    method: Option<String>,
+    elapsed: f64,
+    water: f64,
+}
+
+// 可恢复的冲煮计时：方案、已计时的秒数、当时所在的步骤和保存时间（毫秒时间戳）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverableTimer {
+    pub recipe: Recipe,
+    pub elapsed: f64,
+    pub stage_index: usize,
+    pub saved_at: i64,
+}
+
+// 托盘发给前端的开始计时事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StartBrewTimerEvent {
+    method: Option<String>,
+}
+
+pub fn get(app: &tauri::AppHandle) -> TimerState {
+    app.try_state::<Arc<Mutex<TimerState>>>()
+        .and_then(|state| state.lock().ok().map(|s| s.clone()))
+        .unwrap_or_default()
+}
+
+impl BrewRun {
+    fn elapsed(&self) -> f64 {
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        now.saturating_duration_since(self.started).as_secs_f64()
+    }
+
+    pub fn progress(&self) -> Option<BrewProgress> {
+        let elapsed = self.elapsed();
+        let stage = self.recipe.stage_at(elapsed)?;
+        Some(BrewProgress {
+            index: stage.index,
+            kind: stage.kind,
+            label: stage.label.clone(),
+            detail: stage.detail.clone(),
+            elapsed,
+            stage_remaining: stage.end - elapsed,
+            target_water: stage.cumulative_water,
+            stage_water: stage.water,
+            total_time: self.recipe.total_time,
+            next: self.recipe.stages.get(stage.index + 1).cloned(),
+            paused: self.paused_at.is_some(),
+        })
+    }
+}
+
+// 当前步骤和剩余时间，例如“焖蒸 0:23”；剩余时间向上取整，避免在最后一秒显示 0:00
+pub fn countdown_text(progress: &BrewProgress) -> String {
+    let seconds = progress.stage_remaining.max(0.0).ceil() as i64;
+    let label = if progress.label.is_empty() { "冲煮" } else { progress.label.as_str() };
+    format!("{} {}:{:02}", label, seconds / 60, seconds % 60)
+}
+
+// 计时中在托盘提示文字（以及 macOS 菜单栏标题）显示倒计时；停止后 background::refresh 重建托盘时恢复
+pub fn show_countdown(app: &tauri::AppHandle, progress: &BrewProgress) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        if let Some(tray) = app.tray_by_id("main-tray") {
+            let locale = crate::current_locale(app);
+            let text = countdown_text(progress);
+            let tooltip = format!("{} · {} {}g", text, locale.tr("目标", "target"), progress.target_water);
+            tray.set_tooltip(Some(tooltip)).map_err(|e| e.to_string())?;
+            #[cfg(target_os = "macos")]
+            tray.set_title(Some(text)).map_err(|e| e.to_string())?;
+        }
+    }
+    #[cfg(not(desktop))]
+    let _ = (app, progress);
+    Ok(())
+}
+
+pub fn progress(app: &tauri::AppHandle) -> Option<BrewProgress> {
+    get(app).run.and_then(|run| run.progress())
+}
+
+fn current_run(app: &tauri::AppHandle, id: u64) -> Option<BrewRun> {
+    get(app).run.filter(|run| run.id == id)
+}
+
+fn recovery_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(RECOVERY_FILE))
+        .map_err(|e| e.to_string())
+}
+
+// 持有计时器锁写入，避免计时停止并删除记录后又被计时任务写回
+fn save_recovery(app: &tauri::AppHandle, id: u64) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<TimerState>>>()
+        .ok_or("计时器未初始化")?;
+    let timer = state.lock().map_err(|e| e.to_string())?;
+    let Some(run) = timer.run.as_ref().filter(|run| run.id == id) else {
+        return Ok(());
+    };
+    let Some(progress) = run.progress() else {
+        return Ok(());
+    };
+    let saved = RecoverableTimer {
+        recipe: run.recipe.clone(),
+        elapsed: progress.elapsed,
+        stage_index: progress.index,
+        saved_at: chrono::Utc::now().timestamp_millis(),
+    };
+    json_file::save(&recovery_path(app)?, &Some(saved)).map_err(|e| e.to_string())
+}
+
+fn clear_recovery(app: &tauri::AppHandle) -> Result<(), String> {
+    match std::fs::remove_file(recovery_path(app)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// 太久以前或已经走完的计时不再恢复
+fn recoverable(saved: Option<RecoverableTimer>, now: i64) -> Option<RecoverableTimer> {
+    saved.filter(|saved| {
+        now - saved.saved_at <= RECOVERY_MAX_AGE && saved.elapsed >= 0.0 && saved.elapsed < saved.recipe.total_time
+    })
+}
+
+fn load_recovery(app: &tauri::AppHandle) -> Result<Option<RecoverableTimer>, String> {
+    let saved: Option<RecoverableTimer> = json_file::load(&recovery_path(app)?).map_err(|e| e.to_string())?;
+    Ok(recoverable(saved, chrono::Utc::now().timestamp_millis()))
+}
+
+// 计时任务：阶段变化时发出 brew-stage，全部步骤结束后发出 brew-finished 并停止计时
+fn spawn_ticker(app: tauri::AppHandle, id: u64) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_stage = None;
+        let mut last_countdown = String::new();
+        let mut last_elapsed = -1.0;
+        let mut last_saved = None;
+        while let Some(run) = current_run(&app, id) {
+            let elapsed = run.elapsed();
+            let audio = crate::settings::get(&app).audio;
+            for cue in crate::audio::cues_between(&run.recipe, &audio, last_elapsed, elapsed) {
+                crate::audio::play(&app, cue, &audio);
+            }
+            last_elapsed = elapsed;
+            match run.progress() {
+                Some(progress) => {
+                    let second = progress.elapsed.floor() as i64;
+                    if last_saved != Some(second) {
+                        if let Err(e) = save_recovery(&app, id) {
+                            log::warn!("保存冲煮计时进度失败：{}", e);
+                        }
+                        last_saved = Some(second);
+                    }
+                    let countdown = countdown_text(&progress);
+                    if countdown != last_countdown {
+                        if let Err(e) = show_countdown(&app, &progress) {
+                            log::warn!("更新托盘倒计时失败：{}", e);
+                        }
+                        last_countdown = countdown;
+                    }
+                    if last_stage != Some(progress.index) {
+                        last_stage = Some(progress.index);
+                        crate::speech::announce_stage(&app, &run.recipe, progress.index);
+                        let _ = app.emit("brew-stage", progress);
+                    }
+                }
+                None => {
+                    let event = BrewFinishedEvent {
+                        method: run.recipe.name.clone(),
+                        elapsed: run.elapsed(),
+                        water: run.recipe.stages.last().map_or(0.0, |stage| stage.cumulative_water),
+                    };
+                    if let Err(e) = set_brew_timer_state(app.clone(), false, None) {
+                        log::warn!("停止冲煮计时失败：{}", e);
+                    }
+                    crate::speech::announce_finished(&app);
+                    let _ = app.emit("brew-finished", event);
+                    break;
+                }
+            }
+            tokio::time::sleep(TICK_INTERVAL).await;
+        }
+    });
+}
+
+// 托盘「开始冲煮计时」：显示窗口并通知前端开始计时
+pub fn start_from_tray(app: &tauri::AppHandle) {
+    let state = get(app);
+    if state.running {
+        return;
+    }
+    crate::show_main_window(app);
+    let _ = app.emit("start-brew-timer", StartBrewTimerEvent { method: state.last_method });
+}
+
+#[tauri::command]
+pub fn get_brew_timer_state(app: tauri::AppHandle) -> TimerState {
+    get(&app)
+}
+
+// 前端计时开始/停止时调用，method 为当前冲煮方案名称
+#[tauri::command]
+pub fn set_brew_timer_state(app: tauri::AppHandle, running: bool, method: Option<String>) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<TimerState>>>()
+        .ok_or("计时器未初始化")?;
+    let changed = {
+        let mut timer = state.lock().map_err(|e| e.to_string())?;
+        let changed = timer.running != running;
+        timer.running = running;
+        if !running {
+            if let Some(run) = timer.run.take() {
+                timer.last_brew = Some(FinishedBrew {
+                    elapsed: run.elapsed().min(run.recipe.total_time),
+                    recipe: run.recipe,
+                    finished_at: chrono::Utc::now().timestamp_millis(),
+                });
+                if let Err(e) = clear_recovery(&app) {
+                    log::warn!("删除冲煮计时进度失败：{}", e);
+                }
+            }
+        }
+        if method.is_some() {
+            timer.last_method = method;
+        }
+        changed
+    };
+    if changed {
+        crate::background::refresh(&app)?;
+        if let Err(e) = crate::keep_awake::sync(&app) {
+            log::warn!("保持唤醒失败：{}", e);
+        }
+    }
+    Ok(())
+}
+
+// elapsed 为已经计时的秒数，恢复中断的计时时从这里继续
+fn start(app: &tauri::AppHandle, recipe: Recipe, elapsed: f64) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<TimerState>>>()
+        .ok_or("计时器未初始化")?;
+    let (id, changed) = {
+        let mut timer = state.lock().map_err(|e| e.to_string())?;
+        timer.next_run_id += 1;
+        let id = timer.next_run_id;
+        let changed = !timer.running;
+        timer.running = true;
+        if recipe.name.is_some() {
+            timer.last_method = recipe.name.clone();
+        }
+        timer.last_recipe = Some(recipe.clone());
+        timer.run = Some(BrewRun {
+            id,
+            recipe,
+            started: Instant::now()
+                .checked_sub(Duration::from_secs_f64(elapsed.max(0.0)))
+                .unwrap_or_else(Instant::now),
+            paused_at: None,
+        });
+        (id, changed)
+    };
+    if changed {
+        crate::background::refresh(app)?;
+        if let Err(e) = crate::keep_awake::sync(app) {
+            log::warn!("保持唤醒失败：{}", e);
+        }
+    }
+    spawn_ticker(app.clone(), id);
+    Ok(())
+}
+
+// 快捷键「开始 / 停止冲煮计时」：计时中则停止（前端计时通过 stop-brew-timer 事件通知前端）；
+// 否则用上次的方案在后台开始计时，没有后端计时过的方案时和托盘一样交给前端开始
+pub fn toggle(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = get(app);
+    if state.running {
+        if state.run.is_none() {
+            let _ = app.emit("stop-brew-timer", ());
+        }
+        return set_brew_timer_state(app.clone(), false, None);
+    }
+    match state.last_recipe {
+        Some(recipe) => start(app, recipe, 0.0),
+        None => {
+            start_from_tray(app);
+            Ok(())
+        }
+    }
+}
+
+// 用上次后端计时的方案重新开始（手表等不能选方案的入口使用）；计时中时不做处理
+pub fn start_last_recipe(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = get(app);
+    if state.running {
+        return Ok(());
+    }
+    let recipe = state.last_recipe.ok_or("还没有用过冲煮方案，请先在手机上开始一次计时")?;
+    start(app, recipe, 0.0)
+}
+
+// 快捷键「下一步」：后端计时时把开始时间提前到下一步开始，计时任务随即发出 brew-stage；
+// 已是最后一步时直接结束。前端计时通过 brew-next-step 事件交给前端处理
+pub fn next_step(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<TimerState>>>()
+        .ok_or("计时器未初始化")?;
+    let mut timer = state.lock().map_err(|e| e.to_string())?;
+    let Some(run) = timer.run.as_mut() else {
+        if timer.running {
+            let _ = app.emit("brew-next-step", ());
+        }
+        return Ok(());
+    };
+    let target = match run.progress() {
+        Some(progress) => run.recipe.stages.get(progress.index + 1).map_or(run.recipe.total_time, |stage| stage.start),
+        None => return Ok(()),
+    };
+    // 暂停中切到下一步时保持暂停，从下一步开始处继续
+    let now = run.paused_at.unwrap_or_else(Instant::now);
+    if let Some(started) = now.checked_sub(Duration::from_secs_f64(target)) {
+        run.started = started;
+    }
+    Ok(())
+}
+
+// 暂停或继续后端计时；没有后端计时时返回错误（前端计时由前端自己暂停）
+pub fn set_paused(app: &tauri::AppHandle, paused: bool) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<TimerState>>>()
+        .ok_or("计时器未初始化")?;
+    let changed = {
+        let mut timer = state.lock().map_err(|e| e.to_string())?;
+        let run = timer.run.as_mut().ok_or("没有正在进行的冲煮计时")?;
+        match (run.paused_at, paused) {
+            (None, true) => {
+                run.paused_at = Some(Instant::now());
+                true
+            }
+            (Some(paused_at), false) => {
+                run.started += paused_at.elapsed();
+                run.paused_at = None;
+                true
+            }
+            _ => false,
+        }
+    };
+    if changed {
+        if let Some(progress) = progress(app) {
+            let _ = app.emit("brew-stage", progress);
+        }
+    }
+    Ok(())
+}
+
+// 快捷键「快速记录」：把最近一次结束的后端计时记录为冲煮笔记（不打开主窗口），
+// 没有可记录的冲煮时打开主窗口新建记录
+pub fn quick_log(app: &tauri::AppHandle) -> Result<(), String> {
+    let brew = app
+        .try_state::<Arc<Mutex<TimerState>>>()
+        .ok_or("计时器未初始化")?
+        .lock()
+        .map_err(|e| e.to_string())?
+        .last_brew
+        .take();
+    let Some(brew) = brew else {
+        return crate::navigation::navigate_to(app, crate::navigation::NavigationTarget::NewBrewLog { bean_id: None })
+            .map_err(|e| e.to_string());
+    };
+    let note = crate::notes::create_brew_note(app.clone(), brew_note(&brew))?;
+    let locale = crate::current_locale(app);
+    let seconds = brew.elapsed.round() as i64;
+    let body = format!(
+        "{} · {}:{:02}",
+        brew.recipe.name.as_deref().unwrap_or(locale.tr("冲煮", "Brew")),
+        seconds / 60,
+        seconds % 60
+    );
+    crate::notifications::show(app, locale.tr("已记录冲煮", "Brew logged"), &body, None);
+    crate::telemetry::record(app, "timer.quick_log");
+    let _ = app.emit("brew-note-logged", note);
+    Ok(())
+}
+
+// 冲煮笔记的字段与前端 BrewingNote 对应，评分和风味留给之后在前端补充
+fn brew_note(brew: &FinishedBrew) -> Value {
+    let recipe = &brew.recipe;
+    let grams = |value: Option<f64>| value.map(|g| format!("{}g", g));
+    let ratio = match (recipe.coffee, recipe.water) {
+        (Some(coffee), Some(water)) if coffee > 0.0 => Some(format!("1:{}", (water / coffee * 10.0).round() / 10.0)),
+        _ => None,
+    };
+    json!({
+        "timestamp": brew.finished_at,
+        "method": recipe.name,
+        "params": {
+            "coffee": grams(recipe.coffee),
+            "water": grams(recipe.water),
+            "ratio": ratio,
+        },
+        "totalTime": brew.elapsed.round(),
+        "rating": 0,
+        "taste": {},
+        "notes": "",
+    })
+}
+
+// 按冲煮方案开始后端计时，method 为方案的完整数据；方案校验失败时不开始
+#[tauri::command]
+pub fn start_brew_recipe(app: tauri::AppHandle, method: Value) -> Result<Recipe, String> {
+    let recipe = Recipe::from_method(&method)?;
+    start(&app, recipe.clone(), 0.0)?;
+    Ok(recipe)
+}
+
+#[tauri::command]
+pub fn stop_brew_recipe(app: tauri::AppHandle) -> Result<(), String> {
+    set_brew_timer_state(app, false, None)
+}
+
+#[tauri::command]
+pub fn pause_brew_recipe(app: tauri::AppHandle) -> Result<(), String> {
+    set_paused(&app, true)
+}
+
+#[tauri::command]
+pub fn resume_brew_recipe(app: tauri::AppHandle) -> Result<(), String> {
+    set_paused(&app, false)
+}
+
+// 当前阶段的进度，没有后端计时时返回 None
+#[tauri::command]
+pub fn get_brew_progress(app: tauri::AppHandle) -> Option<BrewProgress> {
+    progress(&app)
+}
+
+// 上次应用崩溃或中途退出时未完成的后端计时，前端启动后据此提示是否继续；计时中返回 None
+#[tauri::command]
+pub fn get_recoverable_timer(app: tauri::AppHandle) -> Result<Option<RecoverableTimer>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if get(&app).running {
+        return Ok(None);
+    }
+    load_recovery(&app)
+}
+
+// 从中断时的进度继续计时
+#[tauri::command]
+pub fn resume_recoverable_timer(app: tauri::AppHandle) -> Result<RecoverableTimer, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if get(&app).running {
+        return Err("正在计时".to_string());
+    }
+    let saved = load_recovery(&app)?.ok_or("没有可恢复的冲煮计时")?;
+    start(&app, saved.recipe.clone(), saved.elapsed)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn discard_recoverable_timer(app: tauri::AppHandle) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    clear_recovery(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_recent_unfinished_brews() {
+        let method = json!({ "params": { "water": "225g", "stages": [
+            { "pourType": "circle", "label": "焖蒸", "water": "30", "duration": 30 },
+            { "pourType": "circle", "label": "注水", "water": "195", "duration": 60 },
+        ] } });
+        let saved = RecoverableTimer {
+            recipe: Recipe::from_method(&method).unwrap(),
+            elapsed: 42.0,
+            stage_index: 1,
+            saved_at: 1_000_000,
+        };
+        // 保存后再读回来，方案数据不丢失
+        let json = serde_json::to_value(&saved).unwrap();
+        let restored: RecoverableTimer = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.recipe, saved.recipe);
+
+        assert!(recoverable(Some(restored.clone()), 1_000_000 + 60_000).is_some());
+        assert!(recoverable(Some(restored.clone()), 1_000_000 + RECOVERY_MAX_AGE + 1).is_none());
+        let finished = RecoverableTimer { elapsed: 90.0, ..restored };
+        assert!(recoverable(Some(finished), 1_000_000).is_none());
+        assert!(recoverable(None, 1_000_000).is_none());
+    }
+
+    #[test]
+    fn paused_runs_stop_the_clock() {
+        let method = json!({ "params": { "water": "225g", "stages": [
+            { "pourType": "circle", "label": "焖蒸", "water": "30", "duration": 30 },
+            { "pourType": "circle", "label": "注水", "water": "195", "duration": 60 },
+        ] } });
+        let now = Instant::now();
+        let run = BrewRun {
+            id: 1,
+            recipe: Recipe::from_method(&method).unwrap(),
+            started: now - Duration::from_secs(40),
+            paused_at: Some(now - Duration::from_secs(10)),
+        };
+        let progress = run.progress().unwrap();
+        assert!(progress.paused);
+        assert_eq!(progress.index, 1);
+        assert!((progress.elapsed - 30.0).abs() < 0.5);
+        assert_eq!(countdown_text(&progress), "注水 1:00");
+    }
+}
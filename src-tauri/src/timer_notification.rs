@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+use crate::timer::{self, BrewProgress};
+
+// Android 前台服务的常驻通知：后端计时中显示当前步骤、剩余时间和暂停/继续、停止按钮
+// 原生服务每秒调用 get_timer_notification 刷新通知，返回 None 时结束服务；
+// 用户点按钮后调用 handle_timer_notification_action，交回后端计时处理
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimerNotificationAction {
+    Pause,
+    Resume,
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerNotificationButton {
+    pub action: TimerNotificationAction,
+    pub title: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerNotification {
+    pub title: String,
+    pub text: String,
+    pub paused: bool,
+    // 进度条：已计时 / 总时长（秒）
+    pub elapsed: f64,
+    pub total_time: f64,
+    pub actions: Vec<TimerNotificationButton>,
+}
+
+fn build(progress: &BrewProgress, method: Option<&str>, locale: Locale) -> TimerNotification {
+    let method = method.unwrap_or(locale.tr("冲煮", "Brew"));
+    let title = if progress.paused {
+        format!("{} · {}", method, locale.tr("已暂停", "Paused"))
+    } else {
+        method.to_string()
+    };
+    let text = format!(
+        "{} · {} {}g",
+        timer::countdown_text(progress),
+        locale.tr("目标", "target"),
+        progress.target_water
+    );
+    let toggle = if progress.paused {
+        TimerNotificationButton {
+            action: TimerNotificationAction::Resume,
+            title: locale.tr("继续", "Resume"),
+        }
+    } else {
+        TimerNotificationButton {
+            action: TimerNotificationAction::Pause,
+            title: locale.tr("暂停", "Pause"),
+        }
+    };
+    TimerNotification {
+        title,
+        text,
+        paused: progress.paused,
+        elapsed: progress.elapsed,
+        total_time: progress.total_time,
+        actions: vec![
+            toggle,
+            TimerNotificationButton {
+                action: TimerNotificationAction::Stop,
+                title: locale.tr("停止", "Stop"),
+            },
+        ],
+    }
+}
+
+// 只有后端计时有通知；前端计时或没有计时时返回 None；应用锁定时返回错误，通知中不显示计时内容
+#[tauri::command]
+pub fn get_timer_notification(app: tauri::AppHandle) -> Result<Option<TimerNotification>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let state = timer::get(&app);
+    let Some(progress) = state.run.as_ref().and_then(|run| run.progress()) else {
+        return Ok(None);
+    };
+    let method = state.run.as_ref().and_then(|run| run.recipe.name.as_deref());
+    Ok(Some(build(&progress, method, crate::current_locale(&app))))
+}
+
+#[tauri::command]
+pub fn handle_timer_notification_action(app: tauri::AppHandle, action: TimerNotificationAction) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    crate::telemetry::record(&app, "timer.notification_action");
+    match action {
+        TimerNotificationAction::Pause => timer::set_paused(&app, true),
+        TimerNotificationAction::Resume => timer::set_paused(&app, false),
+        TimerNotificationAction::Stop => timer::stop_brew_recipe(app),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipe::StageKind;
+
+    fn progress(paused: bool) -> BrewProgress {
+        BrewProgress {
+            index: 0,
+            kind: StageKind::Pour,
+            label: "焖蒸".to_string(),
+            detail: String::new(),
+            elapsed: 7.2,
+            stage_remaining: 22.8,
+            target_water: 30.0,
+            stage_water: 30.0,
+            total_time: 150.0,
+            next: None,
+            paused,
+        }
+    }
+
+    #[test]
+    fn shows_stage_countdown_and_toggle_action() {
+        let locale = Locale::default();
+        let running = build(&progress(false), Some("V60 一刀流"), locale);
+        assert_eq!(running.title, "V60 一刀流");
+        assert_eq!(running.text, "焖蒸 0:23 · 目标 30g");
+        let actions: Vec<_> = running.actions.iter().map(|button| button.action).collect();
+        assert_eq!(actions, vec![TimerNotificationAction::Pause, TimerNotificationAction::Stop]);
+
+        let paused = build(&progress(true), None, locale);
+        assert_eq!(paused.title, "冲煮 · 已暂停");
+        assert_eq!(paused.actions[0].action, TimerNotificationAction::Resume);
+    }
+}
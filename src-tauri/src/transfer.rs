@@ -0,0 +1,402 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+use crate::backups;
+use crate::channel::{hex, read_frame, unhex, write_frame, Channel, HANDSHAKE_FRAME_BYTES};
+
+// 设备迁移：在一台设备上打开临时的传输服务，另一台设备（通常是手机）扫描二维码后拉取全部数据，或把自己的数据推送过来
+// 二维码内容为 brewguide://transfer?host=..&port=..&key=..，一次性密钥只出现在二维码中，
+// 连接双方用它和各自的随机数派生会话密钥；服务一直等到有设备证明持有密钥并完成一次传输，超时或停止后关闭
+const PAYLOAD_PREFIX: &str = "brewguide://transfer";
+const EXPIRES_AFTER: Duration = Duration::from_secs(10 * 60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+// 连接后需要在这段时间内发来能解密的请求，否则断开，继续等待下一个连接
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOffer {
+    pub payload: String, // 二维码内容
+    pub address: String,
+    pub expires_at: String,
+}
+
+// transfer-progress 事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransferProgress {
+    direction: &'static str, // send / receive
+    transferred: u64,
+    total: u64,
+}
+
+// transfer-finished 事件，传输服务一侧完成或失败后发出
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransferFinished {
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "action")]
+enum Request {
+    Pull,
+    Push { size: u64 },
+}
+
+#[derive(Default)]
+pub struct TransferState {
+    generation: u64, // 每次启动或停止后加一，旧的传输服务随之关闭
+}
+
+struct Payload {
+    address: SocketAddr,
+    key: [u8; 32],
+}
+
+fn format_payload(address: SocketAddr, key: &[u8; 32]) -> String {
+    format!(
+        "{}?host={}&port={}&key={}",
+        PAYLOAD_PREFIX,
+        address.ip(),
+        address.port(),
+        hex(key)
+    )
+}
+
+fn parse_payload(payload: &str) -> Option<Payload> {
+    let query = payload.trim().strip_prefix(PAYLOAD_PREFIX)?.strip_prefix('?')?;
+    let mut host = None;
+    let mut port = None;
+    let mut key = None;
+    for pair in query.split('&') {
+        match pair.split_once('=')? {
+            ("host", value) => host = value.parse::<IpAddr>().ok(),
+            ("port", value) => port = value.parse::<u16>().ok(),
+            ("key", value) => key = unhex(value)?.try_into().ok(),
+            _ => {}
+        }
+    }
+    Some(Payload {
+        address: SocketAddr::new(host?, port?),
+        key: key?,
+    })
+}
+
+// 本机在局域网中的地址：UDP 套接字 connect 时不会发送数据，只用来让系统选出出口地址；
+// 没有默认路由（只连着不通外网的局域网）时 connect 会失败，改为从网卡地址中挑选
+fn local_ip() -> Result<IpAddr, String> {
+    let routed = UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect("8.8.8.8:80").and_then(|()| socket.local_addr()))
+        .map(|addr| addr.ip())
+        .ok()
+        .filter(|ip| !ip.is_unspecified());
+    if let Some(ip) = routed {
+        return Ok(ip);
+    }
+    let interfaces = if_addrs::get_if_addrs().map_err(|e| e.to_string())?;
+    preferred_address(
+        interfaces
+            .iter()
+            .filter(|interface| interface.is_oper_up() && !interface.is_p2p() && !interface.is_link_local())
+            .map(|interface| interface.ip()),
+    )
+    .ok_or_else(|| "没有连接到局域网".to_string())
+}
+
+// 优先选择私有 IPv4 地址，其次是其它 IPv4、IPv6 地址；不使用回环地址
+fn preferred_address(addresses: impl IntoIterator<Item = IpAddr>) -> Option<IpAddr> {
+    addresses
+        .into_iter()
+        .filter(|ip| !ip.is_loopback() && !ip.is_unspecified())
+        .min_by_key(|ip| match ip {
+            IpAddr::V4(v4) if v4.is_private() => 0,
+            IpAddr::V4(_) => 1,
+            IpAddr::V6(_) => 2,
+        })
+}
+
+// 双方交换随机数，用一次性密钥派生本次连接的会话密钥；密钥不对时第一条加密消息就会校验失败
+fn open(mut stream: TcpStream, key: &[u8; 32], is_client: bool, timeout: Duration) -> Result<Channel, String> {
+    stream.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    let mut mine = [0u8; 16];
+    OsRng.fill_bytes(&mut mine);
+    write_frame(&mut stream, &mine)?;
+    let theirs = read_frame(&mut stream, HANDSHAKE_FRAME_BYTES)?;
+    let (client, server) = if is_client { (&mine[..], &theirs[..]) } else { (&theirs[..], &mine[..]) };
+    let session: [u8; 32] = Sha256::new()
+        .chain_update(b"brew-guide-transfer-1")
+        .chain_update(key)
+        .chain_update(client)
+        .chain_update(server)
+        .finalize()
+        .into();
+    Channel::new(stream, &session, is_client)
+}
+
+fn emit_progress(app: &tauri::AppHandle, direction: &'static str, transferred: u64, total: u64) {
+    let progress = TransferProgress {
+        direction,
+        transferred,
+        total,
+    };
+    let _ = app.emit("transfer-progress", &progress);
+}
+
+fn send_file(app: &tauri::AppHandle, channel: &mut Channel, path: &Path) -> Result<(), String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let total = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut transferred = 0;
+    emit_progress(app, "send", 0, total);
+    while transferred < total {
+        let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("备份文件意外结束".to_string());
+        }
+        channel.send_bytes(&buffer[..read])?;
+        transferred += read as u64;
+        emit_progress(app, "send", transferred, total);
+    }
+    Ok(())
+}
+
+fn receive_file(app: &tauri::AppHandle, channel: &mut Channel, path: &Path, total: u64) -> Result<(), String> {
+    let mut file = File::create(path).map_err(|e| e.to_string())?;
+    let mut transferred = 0;
+    emit_progress(app, "receive", 0, total);
+    while transferred < total {
+        let chunk = channel.receive_bytes()?;
+        file.write_all(&chunk).map_err(|e| e.to_string())?;
+        transferred += chunk.len() as u64;
+        emit_progress(app, "receive", transferred, total);
+    }
+    file.sync_all().map_err(|e| e.to_string())
+}
+
+fn package(app: &tauri::AppHandle, name: &str) -> Result<std::path::PathBuf, String> {
+    let path = backups::work_path(app, name)?;
+    backups::package(app, &path, "transfer")?;
+    Ok(path)
+}
+
+// 收到的数据按恢复备份处理（会先备份当前数据）
+fn receive_and_restore(app: &tauri::AppHandle, channel: &mut Channel, total: u64) -> Result<(), String> {
+    let path = backups::work_path(app, "transfer-receive.zip")?;
+    let result = receive_file(app, channel, &path, total).and_then(|()| backups::restore_archive(app, &path));
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+// 读取第一条请求：能解密说明对方持有二维码里的密钥；失败的连接不算一次传输
+fn authenticate(stream: TcpStream, key: &[u8; 32]) -> Result<(Channel, Request), String> {
+    stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+    let mut channel = open(stream, key, false, HANDSHAKE_TIMEOUT)?;
+    let request = channel.receive::<Request>()?;
+    channel.authenticated();
+    channel.stream.set_read_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+    channel.stream.set_write_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+    Ok((channel, request))
+}
+
+fn serve(app: &tauri::AppHandle, mut channel: Channel, request: Request) -> Result<(), String> {
+    match request {
+        Request::Pull => {
+            let path = package(app, "transfer-send.zip")?;
+            let size = std::fs::metadata(&path).map(|m| m.len()).map_err(|e| e.to_string());
+            let result = size
+                .and_then(|size| channel.send(&size))
+                .and_then(|()| send_file(app, &mut channel, &path));
+            let _ = std::fs::remove_file(&path);
+            result
+        }
+        Request::Push { size } => {
+            let result = receive_and_restore(app, &mut channel, size);
+            channel.send(&result.as_ref().err())?;
+            result
+        }
+    }
+}
+
+fn generation(app: &tauri::AppHandle) -> Result<u64, String> {
+    let state = app
+        .try_state::<Arc<Mutex<TransferState>>>()
+        .ok_or("设备迁移未初始化")?;
+    let generation = state.lock().map_err(|e| e.to_string())?.generation;
+    Ok(generation)
+}
+
+fn next_generation(app: &tauri::AppHandle) -> Result<u64, String> {
+    let state = app
+        .try_state::<Arc<Mutex<TransferState>>>()
+        .ok_or("设备迁移未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    state.generation += 1;
+    Ok(state.generation)
+}
+
+// 等待持有密钥的设备连接并完成一次传输，超时或被新的传输服务取代后退出；
+// 密钥不对或没有及时发来请求的连接直接断开，继续等待
+fn spawn_server(app: tauri::AppHandle, listener: TcpListener, key: [u8; 32], generation: u64) {
+    thread::spawn(move || {
+        let deadline = Instant::now() + EXPIRES_AFTER;
+        while Instant::now() < deadline && self::generation(&app) == Ok(generation) {
+            match listener.accept() {
+                Ok((stream, address)) => {
+                    let (channel, request) = match authenticate(stream, &key) {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            log::warn!("设备迁移拒绝了 {} 的连接：{}", address, e);
+                            continue;
+                        }
+                    };
+                    let result = serve(&app, channel, request);
+                    if let Err(e) = &result {
+                        log::warn!("设备迁移（{}）失败：{}", address, e);
+                    }
+                    let finished = TransferFinished { error: result.err() };
+                    let _ = app.emit("transfer-finished", &finished);
+                    return;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+                Err(e) => {
+                    log::warn!("设备迁移监听失败：{}", e);
+                    return;
+                }
+            }
+        }
+    });
+}
+
+// 打开临时传输服务，返回二维码内容；再次调用会关闭之前的服务
+#[tauri::command]
+pub fn start_transfer_server(app: tauri::AppHandle) -> Result<TransferOffer, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let ip = local_ip()?;
+    let listener = TcpListener::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let address = SocketAddr::new(ip, listener.local_addr().map_err(|e| e.to_string())?.port());
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+
+    let generation = next_generation(&app)?;
+    spawn_server(app.clone(), listener, key, generation);
+    crate::telemetry::record(&app, "transfer.start");
+    Ok(TransferOffer {
+        payload: format_payload(address, &key),
+        address: address.to_string(),
+        expires_at: (chrono::Local::now() + EXPIRES_AFTER).to_rfc3339(),
+    })
+}
+
+#[tauri::command]
+pub fn stop_transfer_server(app: tauri::AppHandle) -> Result<(), String> {
+    next_generation(&app).map(|_| ())
+}
+
+// 扫码的一方：action 为 pull 时用对方的数据替换本机数据，为 push 时用本机数据替换对方的数据
+#[tauri::command]
+pub async fn transfer_with_device(app: tauri::AppHandle, payload: String, action: String) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let payload = parse_payload(&payload).ok_or("无法识别的二维码")?;
+    let push = match action.as_str() {
+        "pull" => false,
+        "push" => true,
+        _ => return Err(format!("未知的操作：{}", action)),
+    };
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let stream = TcpStream::connect_timeout(&payload.address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+        let mut channel = open(stream, &payload.key, true, IO_TIMEOUT)?;
+        if push {
+            let path = package(&handle, "transfer-send.zip")?;
+            let result = std::fs::metadata(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|m| channel.send(&Request::Push { size: m.len() }))
+                .and_then(|()| send_file(&handle, &mut channel, &path));
+            let _ = std::fs::remove_file(&path);
+            result?;
+            // 对方恢复数据可能需要一些时间
+            channel
+                .stream
+                .set_read_timeout(Some(IO_TIMEOUT * 5))
+                .map_err(|e| e.to_string())?;
+            match channel.receive::<Option<String>>()? {
+                Some(error) => Err(format!("对方导入数据失败：{}", error)),
+                None => Ok(()),
+            }
+        } else {
+            channel.send(&Request::Pull)?;
+            let size: u64 = channel.receive()?;
+            channel.authenticated();
+            receive_and_restore(&handle, &mut channel, size)
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    crate::telemetry::record(&app, if push { "transfer.push" } else { "transfer.pull" });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_its_own_payload() {
+        let address: SocketAddr = "192.168.1.20:51234".parse().unwrap();
+        let key = [7u8; 32];
+        let payload = format_payload(address, &key);
+        assert!(payload.starts_with("brewguide://transfer?host=192.168.1.20&port=51234&key=0707"));
+        let parsed = parse_payload(&payload).unwrap();
+        assert_eq!(parsed.address, address);
+        assert_eq!(parsed.key, key);
+
+        assert!(parse_payload("brewguide://transfer?host=192.168.1.20&port=51234").is_none());
+        assert!(parse_payload("https://example.com/?host=1.2.3.4&port=1&key=00").is_none());
+    }
+
+    #[test]
+    fn only_peers_with_the_key_are_accepted() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let key = [7u8; 32];
+        let client = thread::spawn(move || {
+            for key in [[9u8; 32], key] {
+                let stream = TcpStream::connect(address).unwrap();
+                let mut channel = open(stream, &key, true, IO_TIMEOUT).unwrap();
+                channel.send(&Request::Pull).unwrap();
+            }
+        });
+        let (stream, _) = listener.accept().unwrap();
+        assert!(authenticate(stream, &key).is_err());
+        let (stream, _) = listener.accept().unwrap();
+        let (_, request) = authenticate(stream, &key).unwrap();
+        assert!(matches!(request, Request::Pull));
+        client.join().unwrap();
+    }
+
+    #[test]
+    fn prefers_private_ipv4_addresses() {
+        let addresses: Vec<IpAddr> = ["127.0.0.1", "fd00::12", "203.0.113.7", "192.168.1.20"]
+            .iter()
+            .map(|ip| ip.parse().unwrap())
+            .collect();
+        assert_eq!(preferred_address(addresses.clone()), Some("192.168.1.20".parse().unwrap()));
+        assert_eq!(preferred_address(addresses[..3].to_vec()), Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(preferred_address(["127.0.0.1".parse().unwrap()]), None);
+    }
+}
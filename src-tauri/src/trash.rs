@@ -0,0 +1,167 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::journal;
+use crate::store::{atomically, record_data, text_field, with_store, write_record, RecordKind};
+
+// 回收站：删除的咖啡豆、冲煮笔记连同完整数据移到 trash 表，可以恢复
+// 超过 30 天的记录在打开数据库时自动清除
+const RETENTION_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub kind: RecordKind,
+    pub id: String,
+    pub name: String, // 咖啡豆名称；笔记为对应的咖啡豆名称
+    pub deleted_at: i64, // 毫秒
+    pub data: Value,
+}
+
+// 把记录移到回收站并移出搜索索引，返回记录是否存在
+pub fn move_to_trash(conn: &Connection, kind: RecordKind, id: &str) -> rusqlite::Result<bool> {
+    atomically(conn, || {
+        let Some(data) = record_data(conn, kind, id)? else {
+            return Ok(false);
+        };
+        conn.execute(
+            "INSERT OR REPLACE INTO trash (kind, id, data, deleted_at) VALUES (?1, ?2, ?3, ?4)",
+            params![kind.key(), id, data, chrono::Utc::now().timestamp_millis()],
+        )?;
+        conn.execute(&format!("DELETE FROM {} WHERE id = ?1", kind.table()), [id])?;
+        match kind {
+            RecordKind::Bean => crate::search::remove_bean(conn, id)?,
+            RecordKind::Note => crate::search::remove_note(conn, id)?,
+        }
+        Ok(true)
+    })
+}
+
+// 清除回收站中的记录，同时释放它们的附件引用（已恢复、重新创建的记录除外）
+fn purge_before(conn: &Connection, cutoff: i64) -> rusqlite::Result<usize> {
+    atomically(conn, || {
+        conn.execute(
+            "DELETE FROM attachment_refs WHERE (kind, entity_id) IN (
+                 SELECT kind, id FROM trash WHERE deleted_at < ?1
+                 AND NOT (kind = 'bean' AND id IN (SELECT id FROM beans))
+                 AND NOT (kind = 'note' AND id IN (SELECT id FROM notes)))",
+            [cutoff],
+        )?;
+        conn.execute("DELETE FROM trash WHERE deleted_at < ?1", [cutoff])
+    })
+}
+
+fn cutoff(days: u32) -> i64 {
+    chrono::Utc::now().timestamp_millis() - i64::from(days) * 24 * 60 * 60 * 1000
+}
+
+// 清除超过保留期的记录
+pub fn purge_expired(conn: &Connection) -> rusqlite::Result<()> {
+    let purged = purge_before(conn, cutoff(RETENTION_DAYS))?;
+    if purged > 0 {
+        log::info!("已从回收站清除 {} 条过期记录", purged);
+    }
+    Ok(())
+}
+
+fn list(conn: &Connection) -> rusqlite::Result<Vec<TrashItem>> {
+    let mut stmt = conn.prepare("SELECT kind, id, data, deleted_at FROM trash ORDER BY deleted_at DESC")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })?;
+    let mut items = Vec::new();
+    for row in rows {
+        let (kind, id, data, deleted_at) = row?;
+        let (Some(kind), Ok(data)) = (RecordKind::from_key(&kind), serde_json::from_str::<Value>(&data)) else {
+            log::warn!("跳过无法解析的回收站记录：{}", id);
+            continue;
+        };
+        let name = match kind {
+            RecordKind::Bean => text_field(&data, "name"),
+            RecordKind::Note => data.get("coffeeBeanInfo").and_then(|info| text_field(info, "name")),
+        };
+        items.push(TrashItem {
+            kind,
+            id,
+            name: name.unwrap_or_default().to_string(),
+            deleted_at,
+            data,
+        });
+    }
+    Ok(items)
+}
+
+// 恢复记录；已有同 id 的记录（删除后又新建了）时不覆盖
+fn restore(conn: &Connection, kind: RecordKind, id: &str) -> rusqlite::Result<Result<Value, String>> {
+    let data: Option<String> = conn
+        .query_row("SELECT data FROM trash WHERE kind = ?1 AND id = ?2", [kind.key(), id], |row| row.get(0))
+        .optional()?;
+    let Some(data) = data else {
+        return Ok(Err(format!("回收站中找不到：{}", id)));
+    };
+    if record_data(conn, kind, id)?.is_some() {
+        return Ok(Err(format!("已存在相同 id 的记录：{}", id)));
+    }
+    let value: Value = serde_json::from_str(&data).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+    atomically(conn, || {
+        write_record(conn, kind, &value)?;
+        conn.execute("DELETE FROM trash WHERE kind = ?1 AND id = ?2", [kind.key(), id])
+    })?;
+    Ok(Ok(value))
+}
+
+// 回收站中的记录，最近删除的在前
+#[tauri::command]
+pub fn list_trash(app: tauri::AppHandle) -> Result<Vec<TrashItem>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, |store| list(&store.conn))
+}
+
+// 恢复回收站中的记录，返回恢复后的数据
+#[tauri::command]
+pub fn restore_from_trash(app: tauri::AppHandle, kind: RecordKind, id: String) -> Result<Value, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let value = with_store(&app, |store| {
+        journal::track(&store.conn, kind, &id, || restore(&store.conn, kind, &id))
+    })??;
+    crate::store::publish_changes(&app)?;
+    Ok(value)
+}
+
+// 清除删除超过指定天数的记录（0 表示清空回收站），返回清除的条数
+#[tauri::command]
+pub fn purge_trash_older_than(app: tauri::AppHandle, days: u32) -> Result<usize, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_store(&app, |store| purge_before(&store.conn, cutoff(days) + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deleted_beans_can_be_restored_once() {
+        let conn = crate::store::test_connection();
+        crate::store::write_bean(&conn, &json!({ "id": "b1", "name": "耶加雪菲" })).unwrap();
+
+        assert!(move_to_trash(&conn, RecordKind::Bean, "b1").unwrap());
+        assert!(!move_to_trash(&conn, RecordKind::Bean, "b1").unwrap());
+        let items = list(&conn).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "耶加雪菲");
+
+        assert!(restore(&conn, RecordKind::Bean, "b1").unwrap().is_ok());
+        assert!(restore(&conn, RecordKind::Bean, "b1").unwrap().is_err());
+        assert!(list(&conn).unwrap().is_empty());
+
+        move_to_trash(&conn, RecordKind::Bean, "b1").unwrap();
+        assert_eq!(purge_before(&conn, cutoff(0) + 1).unwrap(), 1);
+    }
+}
@@ -0,0 +1,380 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::image::Image;
+use tauri::Manager;
+
+use crate::FreshnessState;
+
+// 托盘图标：在基础图标右上角叠加数字角标（赏味期内的咖啡豆数量）
+// 有咖啡豆进入衰退期或即将离开赏味期时切换为提醒样式，在左下角画一个圆点
+// macOS 使用模板图标，系统只看透明度，所以角标画成实心圆、数字镂空；其它平台画红底白字
+// Windows 的图标是白色的，任务栏为浅色主题时改画成深色
+// 用户可以设置自定义图标（PNG），复制一份到应用数据目录，启动时重新加载；自定义图标按原色显示，不作为模板
+
+#[cfg(target_os = "windows")]
+const BASE_ICON: &[u8] = include_bytes!("../icons/tray-icon-win.png");
+#[cfg(not(target_os = "windows"))]
+const BASE_ICON: &[u8] = include_bytes!("../icons/tray-iconTemplate@2x.png");
+
+const TEMPLATE_ICON: bool = cfg!(target_os = "macos");
+const CUSTOM_ICON_FILE: &str = "tray-icon-custom.png";
+const CUSTOM_ICON_SIZE: u32 = 64;
+const MAX_CUSTOM_ICON_BYTES: u64 = 5 * 1024 * 1024;
+const BADGE_COLOR: [u8; 4] = [0xE5, 0x48, 0x4D, 0xFF];
+const BADGE_TEXT_COLOR: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const TEMPLATE_COLOR: [u8; 4] = [0x00, 0x00, 0x00, 0xFF];
+const ATTENTION_COLOR: [u8; 4] = [0xFF, 0x9F, 0x0A, 0xFF];
+const LIGHT_TASKBAR_COLOR: [u8; 3] = [0x1F, 0x1F, 0x1F];
+
+// 3x5 点阵字体：数字 0-9 和 "+"，每行 3 位
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const DIGIT_GLYPHS: [[u8; GLYPH_HEIGHT]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const PLUS_GLYPH: [u8; GLYPH_HEIGHT] = [0b000, 0b010, 0b111, 0b010, 0b000];
+
+// 菜单项前的状态圆点
+const DOT_SIZE: u32 = 16;
+const DOT_DIAMETER: f32 = 10.0;
+
+fn state_color(state: &FreshnessState) -> [u8; 4] {
+    match state {
+        FreshnessState::Optimal => [0x34, 0xC7, 0x59, 0xFF],   // 绿
+        FreshnessState::Resting => [0xFF, 0xCC, 0x00, 0xFF],   // 黄
+        FreshnessState::Decline => [0xFF, 0x3B, 0x30, 0xFF],   // 红
+        FreshnessState::Frozen => [0x0A, 0x84, 0xFF, 0xFF],    // 蓝
+        FreshnessState::InTransit | FreshnessState::Unknown => [0x8E, 0x8E, 0x93, 0xFF], // 灰
+    }
+}
+
+// 生成带颜色圆点的小图标，边缘做简单的抗锯齿
+pub fn state_dot(state: &FreshnessState) -> Image<'static> {
+    let [r, g, b, _] = state_color(state);
+    let center = DOT_SIZE as f32 / 2.0;
+    let radius = DOT_DIAMETER / 2.0;
+    let mut rgba = Vec::with_capacity((DOT_SIZE * DOT_SIZE * 4) as usize);
+    for y in 0..DOT_SIZE {
+        for x in 0..DOT_SIZE {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let coverage = (radius + 0.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+            rgba.extend_from_slice(&[r, g, b, (coverage * 255.0) as u8]);
+        }
+    }
+    Image::new_owned(rgba, DOT_SIZE, DOT_SIZE)
+}
+
+// 图标参数：角标数字和是否显示提醒样式
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrayIconParams {
+    pub badge: Option<usize>,
+    pub attention: bool,
+}
+
+// 最近一次设置的图标参数和任务栏主题，没有变化时不重新绘制
+#[derive(Default)]
+pub struct TrayIconState {
+    params: Option<TrayIconParams>,
+    light_taskbar: bool,
+    custom: Option<Image<'static>>,
+}
+
+impl TrayIconState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let custom = custom_icon_path(app).ok().filter(|path| path.exists()).and_then(|path| {
+            load_custom_icon(&path)
+                .map_err(|e| log::warn!("加载自定义托盘图标失败：{}", e))
+                .ok()
+        });
+        Self {
+            custom,
+            ..Self::default()
+        }
+    }
+}
+
+fn custom_icon_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(CUSTOM_ICON_FILE))
+        .map_err(|e| e.to_string())
+}
+
+// 读取并校验图标文件，缩放到托盘使用的尺寸
+fn load_custom_icon(path: &Path) -> Result<Image<'static>, String> {
+    let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+    if size > MAX_CUSTOM_ICON_BYTES {
+        return Err("图标文件不能超过 5 MB".to_string());
+    }
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let image = Image::from_bytes(&bytes).map_err(|_| "无法识别的图片，请使用 PNG 格式".to_string())?;
+    if image.width() < 16 || image.height() < 16 {
+        return Err("图标至少需要 16×16 像素".to_string());
+    }
+    Ok(fit_square(&image, CUSTOM_ICON_SIZE))
+}
+
+// 等比缩放到 size×size 的正方形内并居中，每个目标像素取覆盖区域的平均值
+fn fit_square(image: &Image<'_>, size: u32) -> Image<'static> {
+    let (src_w, src_h) = (image.width() as usize, image.height() as usize);
+    let src = image.rgba();
+    let scale = (size as f32 / src_w.max(src_h) as f32).min(1.0);
+    let dst_w = ((src_w as f32 * scale).round() as usize).max(1);
+    let dst_h = ((src_h as f32 * scale).round() as usize).max(1);
+    let (offset_x, offset_y) = ((size as usize - dst_w) / 2, (size as usize - dst_h) / 2);
+
+    let mut rgba = vec![0u8; (size * size * 4) as usize];
+    for y in 0..dst_h {
+        let (y0, y1) = (y * src_h / dst_h, ((y + 1) * src_h / dst_h).max(y * src_h / dst_h + 1));
+        for x in 0..dst_w {
+            let (x0, x1) = (x * src_w / dst_w, ((x + 1) * src_w / dst_w).max(x * src_w / dst_w + 1));
+            let mut sum = [0u32; 4];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let offset = (sy * src_w + sx) * 4;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += src[offset + channel] as u32;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u32;
+            let color = sum.map(|total| (total / count) as u8);
+            set_pixel(&mut rgba, size as usize, x + offset_x, y + offset_y, color);
+        }
+    }
+    Image::new_owned(rgba, size, size)
+}
+
+// 读取任务栏是否为浅色主题（注册表 SystemUsesLightTheme，读取失败按深色处理）
+#[cfg(target_os = "windows")]
+fn light_taskbar() -> bool {
+    use windows::core::w;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let mut value: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+            w!("SystemUsesLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut u32 as *mut _),
+            Some(&mut size),
+        )
+    };
+    result == ERROR_SUCCESS && value == 1
+}
+
+#[cfg(not(target_os = "windows"))]
+fn light_taskbar() -> bool {
+    false
+}
+
+// 保留透明度，把图标主体换成指定颜色
+fn recolor(rgba: &mut [u8], [r, g, b]: [u8; 3]) {
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel[..3].copy_from_slice(&[r, g, b]);
+    }
+}
+
+// 角标文字：超过 9 显示 "9+"
+fn badge_glyphs(count: usize) -> Vec<[u8; GLYPH_HEIGHT]> {
+    if count > 9 {
+        vec![DIGIT_GLYPHS[9], PLUS_GLYPH]
+    } else {
+        vec![DIGIT_GLYPHS[count]]
+    }
+}
+
+fn set_pixel(rgba: &mut [u8], width: usize, x: usize, y: usize, color: [u8; 4]) {
+    let offset = (y * width + x) * 4;
+    if let Some(pixel) = rgba.get_mut(offset..offset + 4) {
+        pixel.copy_from_slice(&color);
+    }
+}
+
+// 在图标右上角画角标，直径约为图标宽度的 60%
+fn draw_badge(rgba: &mut [u8], width: usize, height: usize, count: usize, template: bool) {
+    let diameter = (width.min(height) * 3 / 5).max(GLYPH_HEIGHT + 2);
+    let radius = diameter as f32 / 2.0;
+    let left = width - diameter;
+    let (center_x, center_y) = (left as f32 + radius, radius);
+
+    let (fill, text) = if template {
+        (TEMPLATE_COLOR, [0, 0, 0, 0])
+    } else {
+        (BADGE_COLOR, BADGE_TEXT_COLOR)
+    };
+
+    for y in 0..diameter.min(height) {
+        for x in left..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            if dx * dx + dy * dy <= radius * radius {
+                set_pixel(rgba, width, x, y, fill);
+            }
+        }
+    }
+
+    let glyphs = badge_glyphs(count);
+    let text_cells = glyphs.len() * (GLYPH_WIDTH + 1) - 1;
+    let scale = ((diameter * 3 / 5) / GLYPH_HEIGHT)
+        .min(diameter * 4 / 5 / text_cells)
+        .max(1);
+    let text_left = (center_x - (text_cells * scale) as f32 / 2.0).round() as usize;
+    let text_top = (center_y - (GLYPH_HEIGHT * scale) as f32 / 2.0).round() as usize;
+
+    for (index, glyph) in glyphs.iter().enumerate() {
+        let glyph_left = text_left + index * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        let x = glyph_left + col * scale + sx;
+                        let y = text_top + row * scale + sy;
+                        if x < width && y < height {
+                            set_pixel(rgba, width, x, y, text);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 提醒样式：左下角的圆点，直径约为图标宽度的 40%，外圈留出透明描边与图标主体分开
+fn draw_attention(rgba: &mut [u8], width: usize, height: usize, template: bool) {
+    let diameter = (width.min(height) * 2 / 5).max(4);
+    let radius = diameter as f32 / 2.0;
+    let (center_x, center_y) = (radius, height as f32 - radius);
+    let fill = if template { TEMPLATE_COLOR } else { ATTENTION_COLOR };
+    let gap = (radius / 4.0).max(1.0);
+
+    for y in height.saturating_sub(diameter + gap as usize)..height {
+        for x in 0..(diameter + gap as usize).min(width) {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance <= radius - gap {
+                set_pixel(rgba, width, x, y, fill);
+            } else if distance <= radius {
+                set_pixel(rgba, width, x, y, [0, 0, 0, 0]);
+            }
+        }
+    }
+}
+
+fn render(params: TrayIconParams, light_taskbar: bool, custom: Option<Image<'static>>) -> tauri::Result<Image<'static>> {
+    let builtin = custom.is_none();
+    let template = TEMPLATE_ICON && builtin;
+    let base = match custom {
+        Some(image) => image,
+        None => Image::from_bytes(BASE_ICON)?,
+    };
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    if light_taskbar && builtin {
+        recolor(&mut rgba, LIGHT_TASKBAR_COLOR);
+    }
+    if params.attention {
+        draw_attention(&mut rgba, width as usize, height as usize, template);
+    }
+    if let Some(count) = params.badge.filter(|count| *count > 0) {
+        draw_badge(&mut rgba, width as usize, height as usize, count, template);
+    }
+    Ok(Image::new_owned(rgba, width, height))
+}
+
+// 更新托盘图标；badge 为 None 或 0 且不需要提醒时显示原始图标
+pub fn update(app: &tauri::AppHandle, params: TrayIconParams) -> tauri::Result<()> {
+    let Some(tray) = app.tray_by_id("main-tray") else {
+        return Ok(());
+    };
+    let light = light_taskbar();
+    let state = app.try_state::<Arc<Mutex<TrayIconState>>>();
+    let (unchanged, custom) = state
+        .as_ref()
+        .and_then(|state| {
+            state
+                .lock()
+                .ok()
+                .map(|s| (s.params == Some(params) && s.light_taskbar == light, s.custom.clone()))
+        })
+        .unwrap_or((false, None));
+    if unchanged {
+        return Ok(());
+    }
+
+    let template = TEMPLATE_ICON && custom.is_none();
+    tray.set_icon(Some(render(params, light, custom)?))?;
+    tray.set_icon_as_template(template)?;
+    if let Some(state) = state {
+        if let Ok(mut s) = state.lock() {
+            s.params = Some(params);
+            s.light_taskbar = light;
+        }
+    }
+    Ok(())
+}
+
+// 按上次的参数重新绘制（系统主题变化、更换图标后）
+pub fn redraw(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let params = app
+        .try_state::<Arc<Mutex<TrayIconState>>>()
+        .and_then(|state| {
+            state.lock().ok().and_then(|mut s| {
+                let params = s.params;
+                // 清除记录，强制重新绘制
+                s.params = None;
+                params
+            })
+        })
+        .unwrap_or_default();
+    update(app, params)
+}
+
+fn set_custom(app: &tauri::AppHandle, custom: Option<Image<'static>>) -> Result<(), String> {
+    let state = app
+        .try_state::<Arc<Mutex<TrayIconState>>>()
+        .ok_or("托盘图标未初始化")?;
+    state.lock().map_err(|e| e.to_string())?.custom = custom;
+    redraw(app).map_err(|e| e.to_string())
+}
+
+// 设置自定义托盘图标：校验后复制到应用数据目录
+#[tauri::command]
+pub fn set_tray_icon(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let image = load_custom_icon(Path::new(&path))?;
+    let target = custom_icon_path(&app)?;
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    std::fs::copy(&path, &target).map_err(|e| e.to_string())?;
+    set_custom(&app, Some(image))
+}
+
+// 恢复默认托盘图标
+#[tauri::command]
+pub fn reset_tray_icon(app: tauri::AppHandle) -> Result<(), String> {
+    let target = custom_icon_path(&app)?;
+    if target.exists() {
+        std::fs::remove_file(target).map_err(|e| e.to_string())?;
+    }
+    set_custom(&app, None)
+}
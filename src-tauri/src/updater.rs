@@ -0,0 +1,401 @@
+use base64::Engine;
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+// 应用更新：从 GitHub Releases 检查新版本，按渠道（正式版 / 测试版）筛选，后台下载安装包
+const RELEASES_API: &str = "https://api.github.com/repos/chuthree/brew-guide/releases?per_page=20";
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+// 下载进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+// 安装包签名公钥（tauri signer generate 生成的 base64 公钥），发布构建时通过环境变量注入
+// 安装包必须有同名的 .sig 签名文件并通过校验才会被打开；没有公钥或签名时只打开发布页
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("BREW_GUIDE_UPDATER_PUBKEY");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub notes: Option<String>,
+    pub release_url: String,
+    pub prerelease: bool,
+    pub downloaded_path: Option<String>,
+}
+
+// 更新进度，通过 update-progress 事件发送给设置页
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "camelCase")]
+enum UpdateProgress {
+    Checking,
+    UpToDate,
+    Available { version: String },
+    #[serde(rename_all = "camelCase")]
+    Downloading { downloaded: u64, total: Option<u64> },
+    Downloaded { path: String },
+    Error { message: String },
+}
+
+#[derive(Default)]
+pub struct UpdaterState {
+    available: Option<UpdateInfo>,
+    checking: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    body: Option<String>,
+    draft: bool,
+    prerelease: bool,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+}
+
+fn emit_progress(app: &tauri::AppHandle, progress: UpdateProgress) {
+    let _ = app.emit("update-progress", progress);
+}
+
+fn with_state<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut UpdaterState) -> T) -> Option<T> {
+    let state = app.try_state::<Arc<Mutex<UpdaterState>>>()?;
+    let mut s = state.lock().ok()?;
+    Some(f(&mut s))
+}
+
+// 托盘菜单用：当前是否有可用的新版本
+pub fn available_update(app: &tauri::AppHandle) -> Option<UpdateInfo> {
+    with_state(app, |s| s.available.clone()).flatten()
+}
+
+// 当前平台对应的安装包
+fn is_platform_asset(name: &str) -> bool {
+    let name = name.to_lowercase();
+    if cfg!(target_os = "macos") {
+        name.ends_with(".dmg")
+    } else if cfg!(target_os = "windows") {
+        name.ends_with("-setup.exe") || name.ends_with(".msi")
+    } else {
+        name.ends_with(".appimage")
+    }
+}
+
+// 安装包对应的签名文件，例如 BrewGuide_1.7.0_x64-setup.exe.sig
+fn signature_asset<'a>(assets: &'a [GithubAsset], asset: &GithubAsset) -> Option<&'a GithubAsset> {
+    let name = format!("{}.sig", asset.name);
+    assets.iter().find(|a| a.name == name)
+}
+
+// tauri signer 的公钥和签名文件都是 minisign 文本再做一次 base64
+fn decode_minisign(text: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(text.trim())
+        .map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn parse_signature(public_key: &str, signature: &str) -> Result<(PublicKey, Signature), String> {
+    let public_key = PublicKey::decode(&decode_minisign(public_key)?).map_err(|e| format!("无效的更新公钥：{}", e))?;
+    let signature = Signature::decode(&decode_minisign(signature)?).map_err(|e| format!("无效的安装包签名：{}", e))?;
+    Ok((public_key, signature))
+}
+
+// 校验已经下载好的安装包
+fn verify_file(path: &Path, public_key: &PublicKey, signature: &Signature) -> Result<(), String> {
+    let mut verifier = public_key.verify_stream(signature).map_err(|e| e.to_string())?;
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        verifier.update(&buffer[..read]);
+    }
+    verifier.finalize().map_err(|_| "安装包签名校验失败".to_string())
+}
+
+fn parse_version(tag: &str) -> Option<semver::Version> {
+    semver::Version::parse(tag.trim_start_matches('v')).ok()
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(concat!("BrewGuide/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// 查找所选渠道中比当前版本更新的最新发布
+async fn find_newer_release(channel: UpdateChannel) -> Result<Option<GithubRelease>, String> {
+    let current = parse_version(env!("CARGO_PKG_VERSION")).ok_or("无法解析当前版本号")?;
+    let releases: Vec<GithubRelease> = http_client()?
+        .get(RELEASES_API)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|r| !r.draft && (channel == UpdateChannel::Beta || !r.prerelease))
+        .filter_map(|r| parse_version(&r.tag_name).map(|v| (v, r)))
+        .filter(|(v, _)| *v > current)
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r))
+}
+
+async fn download_signature(asset: &GithubAsset) -> Result<String, String> {
+    http_client()?
+        .get(&asset.browser_download_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 边下载边写入文件并计算签名摘要，校验通过后才改成正式文件名
+async fn download_asset(
+    app: &tauri::AppHandle,
+    asset: &GithubAsset,
+    public_key: &PublicKey,
+    signature: &Signature,
+) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("updates");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let target = dir.join(&asset.name);
+
+    // 已经下载过同一个安装包，重新校验一次
+    if std::fs::metadata(&target).map(|m| m.len() == asset.size).unwrap_or(false) {
+        match verify_file(&target, public_key, signature) {
+            Ok(()) => return Ok(target),
+            Err(e) => log::warn!("已下载的安装包校验失败，重新下载：{}", e),
+        }
+    }
+
+    let mut partial = target.clone().into_os_string();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    let result = async {
+        let mut response = http_client()?
+            .get(&asset.browser_download_url)
+            .timeout(Duration::from_secs(30 * 60))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?;
+        let total = response.content_length();
+        let mut verifier = public_key.verify_stream(signature).map_err(|e| e.to_string())?;
+        let mut file = std::fs::File::create(&partial).map_err(|e| e.to_string())?;
+        let mut downloaded = 0u64;
+        let mut last_progress: Option<Instant> = None;
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            file.write_all(&chunk).map_err(|e| e.to_string())?;
+            verifier.update(&chunk);
+            downloaded += chunk.len() as u64;
+            if last_progress.map_or(true, |at| at.elapsed() >= PROGRESS_INTERVAL) {
+                last_progress = Some(Instant::now());
+                emit_progress(app, UpdateProgress::Downloading { downloaded, total });
+            }
+        }
+        file.sync_all().map_err(|e| e.to_string())?;
+        emit_progress(app, UpdateProgress::Downloading { downloaded, total });
+        verifier.finalize().map_err(|_| "安装包签名校验失败".to_string())
+    }
+    .await;
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, &target).map_err(|e| e.to_string())?;
+    Ok(target)
+}
+
+async fn run_check(app: &tauri::AppHandle) -> Result<Option<UpdateInfo>, String> {
+    emit_progress(app, UpdateProgress::Checking);
+    let Some(release) = find_newer_release(crate::settings::get(app).update_channel).await? else {
+        emit_progress(app, UpdateProgress::UpToDate);
+        return Ok(None);
+    };
+
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    emit_progress(app, UpdateProgress::Available { version: version.clone() });
+
+    let mut info = UpdateInfo {
+        version,
+        notes: release.body.clone(),
+        release_url: release.html_url.clone(),
+        prerelease: release.prerelease,
+        downloaded_path: None,
+    };
+    // 只下载有签名的安装包；无法校验时不下载，托盘改为打开发布页
+    let asset = release.assets.iter().find(|a| is_platform_asset(&a.name));
+    let signed = asset.and_then(|asset| Some((asset, signature_asset(&release.assets, asset)?)));
+    match (signed, UPDATE_PUBLIC_KEY) {
+        (Some((asset, signature_asset)), Some(public_key)) => {
+            let (public_key, signature) = parse_signature(public_key, &download_signature(signature_asset).await?)?;
+            let path = download_asset(app, asset, &public_key, &signature).await?;
+            let path = path.to_string_lossy().into_owned();
+            emit_progress(app, UpdateProgress::Downloaded { path: path.clone() });
+            info.downloaded_path = Some(path);
+        }
+        (Some(_), None) => log::info!("未配置更新签名公钥，不自动下载安装包"),
+        (None, _) if asset.is_some() => log::warn!("安装包缺少签名文件，不自动下载"),
+        (None, _) => {}
+    }
+
+    with_state(app, |s| s.available = Some(info.clone()));
+    // 刷新托盘，显示"有新版本"
+    let _ = crate::background::refresh(app);
+    Ok(Some(info))
+}
+
+// 检查更新（有新版本时在后台下载安装包），进度通过 update-progress 事件通知
+#[tauri::command]
+pub async fn check_for_updates(app: tauri::AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let already_checking = with_state(&app, |s| std::mem::replace(&mut s.checking, true)).unwrap_or(false);
+    if already_checking {
+        return Err("正在检查更新".to_string());
+    }
+
+    let result = run_check(&app).await;
+    with_state(&app, |s| s.checking = false);
+    if let Err(ref message) = result {
+        crate::diagnostics::record_error(&app, "updater", message.clone());
+        emit_progress(&app, UpdateProgress::Error { message: message.clone() });
+    }
+    result
+}
+
+#[tauri::command]
+pub fn get_update_channel(app: tauri::AppHandle) -> UpdateChannel {
+    crate::settings::get(&app).update_channel
+}
+
+#[tauri::command]
+pub fn set_update_channel(app: tauri::AppHandle, channel: UpdateChannel) -> Result<(), String> {
+    crate::settings::update(&app, |s| s.update_channel = channel)?;
+    // 切换渠道后之前找到的版本可能不再适用
+    with_state(&app, |s| s.available = None);
+    Ok(())
+}
+
+// 打开已下载并通过签名校验的安装包；还没下载好或无法校验时打开发布页
+pub fn open_update(app: &tauri::AppHandle) {
+    let Some(info) = available_update(app) else {
+        return;
+    };
+    let result = match info.downloaded_path {
+        Some(path) => app.opener().open_path(path, None::<&str>),
+        None => app.opener().open_url(info.release_url, None::<&str>),
+    };
+    if let Err(e) = result {
+        log::warn!("打开更新失败：{}", e);
+    }
+}
+
+// 启动后稍等片刻检查一次，之后每天检查一次
+pub fn spawn_update_checker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            if let Err(e) = check_for_updates(app.clone()).await {
+                log::info!("自动检查更新失败：{}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> GithubAsset {
+        GithubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{}", name),
+            size: 0,
+        }
+    }
+
+    fn encode(text: &str) -> String {
+        base64::engine::general_purpose::STANDARD.encode(text)
+    }
+
+    #[test]
+    fn selects_signed_platform_assets() {
+        assert_eq!(parse_version("v1.7.0-beta.2"), semver::Version::parse("1.7.0-beta.2").ok());
+        assert_eq!(parse_version("nightly"), None);
+
+        let installer = if cfg!(target_os = "macos") {
+            "BrewGuide_1.7.0_aarch64.dmg"
+        } else if cfg!(target_os = "windows") {
+            "BrewGuide_1.7.0_x64-setup.exe"
+        } else {
+            "BrewGuide_1.7.0_amd64.AppImage"
+        };
+        assert!(is_platform_asset(installer));
+        assert!(!is_platform_asset(&format!("{}.sig", installer)));
+        assert!(!is_platform_asset("latest.json"));
+
+        let assets = vec![asset(installer), asset(&format!("{}.sig", installer)), asset("other.sig")];
+        assert_eq!(signature_asset(&assets, &assets[0]).map(|a| a.name.as_str()), Some(assets[1].name.as_str()));
+        assert!(signature_asset(&assets[..1], &assets[0]).is_none());
+    }
+
+    #[test]
+    fn verifies_installer_signatures() {
+        // minisign 的测试向量：内容为 "test" 的预哈希签名
+        let public_key = encode("untrusted comment: minisign public key E7620F1842B4E81F\nRWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3");
+        let signature = encode(
+            "untrusted comment: signature from minisign secret key\n\
+             RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=\n\
+             trusted comment: timestamp:1556193335\tfile:test\n\
+             y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==",
+        );
+        let (public_key, signature) = parse_signature(&public_key, &signature).unwrap();
+
+        let path = std::env::temp_dir().join(format!("brew-guide-update-{}", std::process::id()));
+        std::fs::write(&path, b"test").unwrap();
+        assert!(verify_file(&path, &public_key, &signature).is_ok());
+        std::fs::write(&path, b"tampered").unwrap();
+        assert!(verify_file(&path, &public_key, &signature).is_err());
+        let _ = std::fs::remove_file(&path);
+
+        assert!(parse_signature("not base64!", "").is_err());
+    }
+}
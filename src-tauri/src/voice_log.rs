@@ -0,0 +1,107 @@
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::background::BeanCache;
+
+// Siri 语音记录：“嘿 Siri，用 Brew Guide 记录一杯 18 克的耶加雪菲”
+// iOS 的 App Intent 从语句中取出粉量和咖啡豆名称后调用 log_brew_by_voice，
+// 这里按名称模糊匹配咖啡豆，新建冲煮笔记、扣减用量，并用通知确认（Siri 同时读出返回的文字）
+// 笔记经本地数据库的修改队列交给前端（store-changes-pending），与其他笔记一起显示
+const MAX_DOSE_GRAMS: f64 = 100.0;
+
+// 冲煮笔记的字段与前端 BrewingNote 对应，评分和风味留给之后在前端补充
+fn voice_note(bean: Option<&crate::CoffeeBean>, dose: Option<f64>, timestamp: i64) -> Value {
+    let mut note = json!({
+        "timestamp": timestamp,
+        "params": { "coffee": dose.map(|g| format!("{}g", g)) },
+        "coffeeBeanInfo": {
+            "name": bean.map(|b| b.name.clone()).unwrap_or_default(),
+            "roastLevel": "",
+        },
+        "rating": 0,
+        "taste": {},
+        "notes": "",
+    });
+    if let Some(bean) = bean {
+        note["beanId"] = json!(bean.id);
+        if let Some(roaster) = &bean.roaster {
+            note["coffeeBeanInfo"]["roaster"] = json!(roaster);
+        }
+        if let Some(roast_date) = &bean.roast_date {
+            note["coffeeBeanInfo"]["roastDate"] = json!(roast_date);
+        }
+    }
+    note
+}
+
+fn confirmation(bean: Option<&crate::CoffeeBean>, dose: Option<f64>, locale: crate::i18n::Locale) -> String {
+    let name = bean.map_or(locale.tr("一杯咖啡", "a brew"), |bean| bean.name.as_str());
+    match dose {
+        Some(grams) => format!("{} · {}", name, locale.format_weight(grams)),
+        None => name.to_string(),
+    }
+}
+
+// bean 为语句中的咖啡豆名称，dose 为粉量（克）；都可以省略。返回给 Siri 朗读的确认文字
+#[tauri::command]
+pub fn log_brew_by_voice(app: tauri::AppHandle, bean: Option<String>, dose: Option<f64>) -> Result<String, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    if dose.is_some_and(|grams| !(grams > 0.0 && grams <= MAX_DOSE_GRAMS)) {
+        return Err(format!("粉量需要在 0 到 {} 克之间", MAX_DOSE_GRAMS));
+    }
+    let beans = app
+        .try_state::<Arc<Mutex<BeanCache>>>()
+        .and_then(|state| state.lock().ok().map(|cache| cache.beans.clone()))
+        .unwrap_or_default();
+    let query = bean.as_deref().map(str::trim).filter(|name| !name.is_empty());
+    let matched = match query {
+        Some(name) => Some(crate::bean_search::best_match(name, &beans).ok_or_else(|| format!("找不到咖啡豆：{}", name))?),
+        None => None,
+    };
+
+    let note = voice_note(matched, dose, chrono::Utc::now().timestamp_millis());
+    crate::notes::create_brew_note(app.clone(), note)?;
+    if let (Some(bean), Some(grams)) = (matched, dose) {
+        crate::consume_bean(&app, &bean.id, Some(grams));
+    }
+
+    let locale = crate::current_locale(&app);
+    let body = confirmation(matched, dose, locale);
+    // 用户刚刚对 Siri 说完，确认通知立即发送，不等免打扰结束
+    crate::notifications::show_now(&app, locale.tr("已记录冲煮", "Brew logged"), &body, None);
+    crate::telemetry::record(&app, "voice.log_brew");
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bean(id: &str, name: &str, remaining: &str) -> crate::CoffeeBean {
+        serde_json::from_value(json!({ "id": id, "name": name, "remaining": remaining, "roaster": "某某烘焙" })).unwrap()
+    }
+
+    #[test]
+    fn matches_spoken_bean_and_builds_note() {
+        let beans = vec![
+            bean("b1", "埃塞俄比亚 耶加雪菲 水洗", "0"),
+            bean("b2", "耶加雪菲 日晒", "120"),
+            bean("b3", "哥伦比亚 蕙兰", "200"),
+        ];
+        // 已经喝完的咖啡豆排在还有剩余的后面
+        let matched = crate::bean_search::best_match("耶加雪菲", &beans).unwrap();
+        assert_eq!(matched.id, "b2");
+        assert!(crate::bean_search::best_match("肯尼亚", &beans).is_none());
+
+        let note = voice_note(Some(matched), Some(18.0), 1_790_000_000_000);
+        assert_eq!(note["beanId"], "b2");
+        assert_eq!(note["params"]["coffee"], "18g");
+        assert_eq!(note["coffeeBeanInfo"]["name"], "耶加雪菲 日晒");
+        assert_eq!(note["coffeeBeanInfo"]["roaster"], "某某烘焙");
+
+        let locale = crate::i18n::Locale::default();
+        assert_eq!(confirmation(None, None, locale), "一杯咖啡");
+        assert!(confirmation(Some(matched), Some(18.0), locale).starts_with("耶加雪菲 日晒 · 18"));
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+use crate::background::BeanCache;
+use crate::timer::{self, BrewProgress};
+
+// Apple Watch 数据桥：iOS 端的 WatchConnectivity 会话把手表的请求转成这里的命令
+// 手表显示当前咖啡豆的剩余克数和后端计时的进度，并能开始、切到下一步、暂停、停止计时
+// 会话状态（当前咖啡豆）由后端保存，手机和手表看到的一致；计时进度变化时原生端轮询 get_watch_snapshot
+#[derive(Debug, Default)]
+pub struct WatchSessionState {
+    bean_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchTimerCommand {
+    Start,
+    Next,
+    Pause,
+    Resume,
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchTimer {
+    pub label: String,
+    pub stage: usize,
+    pub stage_count: usize,
+    pub stage_remaining: f64,
+    pub target_water: f64,
+    pub elapsed: f64,
+    pub total_time: f64,
+    pub paused: bool,
+}
+
+// 隐私模式下不发送咖啡豆名称
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchBean {
+    pub id: String,
+    pub name: Option<String>,
+    pub remaining_grams: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSnapshot {
+    pub timer: Option<WatchTimer>,
+    pub bean: Option<WatchBean>,
+}
+
+fn build(progress: Option<BrewProgress>, stage_count: usize, bean: Option<&crate::CoffeeBean>, private: bool) -> WatchSnapshot {
+    WatchSnapshot {
+        timer: progress.map(|progress| WatchTimer {
+            label: progress.label,
+            stage: progress.index,
+            stage_count,
+            stage_remaining: progress.stage_remaining.max(0.0),
+            target_water: progress.target_water,
+            elapsed: progress.elapsed,
+            total_time: progress.total_time,
+            paused: progress.paused,
+        }),
+        bean: bean.map(|bean| WatchBean {
+            id: bean.id.clone(),
+            name: (!private).then(|| bean.name.clone()),
+            remaining_grams: bean.remaining_grams(),
+        }),
+    }
+}
+
+fn with_state<T>(app: &tauri::AppHandle, f: impl FnOnce(&mut WatchSessionState) -> T) -> Result<T, String> {
+    let state = app
+        .try_state::<Arc<Mutex<WatchSessionState>>>()
+        .ok_or("手表会话未初始化")?;
+    let mut lock = state.lock().map_err(|e| e.to_string())?;
+    Ok(f(&mut lock))
+}
+
+fn snapshot(app: &tauri::AppHandle) -> Result<WatchSnapshot, String> {
+    let bean_id = with_state(app, |s| s.bean_id.clone())?;
+    let bean = bean_id.and_then(|id| {
+        app.try_state::<Arc<Mutex<BeanCache>>>()
+            .and_then(|state| state.lock().ok()?.beans.iter().find(|bean| bean.id == id).cloned())
+    });
+    let run = timer::get(app).run;
+    let stage_count = run.as_ref().map_or(0, |run| run.recipe.stages.len());
+    let private = crate::settings::get(app).tray.privacy_mode;
+    Ok(build(run.and_then(|run| run.progress()), stage_count, bean.as_ref(), private))
+}
+
+// 手表打开或计时中定时调用
+#[tauri::command]
+pub fn get_watch_snapshot(app: tauri::AppHandle) -> Result<WatchSnapshot, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    snapshot(&app)
+}
+
+// 手机上选择当前冲煮的咖啡豆后调用，手表随之显示它的剩余克数
+#[tauri::command]
+pub fn set_watch_bean(app: tauri::AppHandle, bean_id: Option<String>) -> Result<WatchSnapshot, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    with_state(&app, |s| s.bean_id = bean_id)?;
+    let snapshot = snapshot(&app)?;
+    let _ = app.emit("watch-snapshot", &snapshot);
+    Ok(snapshot)
+}
+
+// 手表上的计时按钮，执行后返回最新状态
+#[tauri::command]
+pub fn watch_timer_command(app: tauri::AppHandle, command: WatchTimerCommand) -> Result<WatchSnapshot, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    crate::telemetry::record(&app, "watch.timer");
+    match command {
+        WatchTimerCommand::Start => timer::start_last_recipe(&app)?,
+        WatchTimerCommand::Next => timer::next_step(&app)?,
+        WatchTimerCommand::Pause => timer::set_paused(&app, true)?,
+        WatchTimerCommand::Resume => timer::set_paused(&app, false)?,
+        WatchTimerCommand::Stop => timer::stop_brew_recipe(app.clone())?,
+    }
+    let snapshot = snapshot(&app)?;
+    let _ = app.emit("watch-snapshot", &snapshot);
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipe::StageKind;
+
+    #[test]
+    fn hides_bean_name_in_privacy_mode() {
+        let bean: crate::CoffeeBean =
+            serde_json::from_value(serde_json::json!({ "id": "b1", "name": "耶加雪菲", "remaining": "132.5" })).unwrap();
+        let progress = BrewProgress {
+            index: 1,
+            kind: StageKind::Pour,
+            label: "注水".to_string(),
+            detail: String::new(),
+            elapsed: 41.0,
+            stage_remaining: -0.2,
+            target_water: 225.0,
+            stage_water: 195.0,
+            total_time: 90.0,
+            next: None,
+            paused: false,
+        };
+        let shown = build(Some(progress), 2, Some(&bean), false);
+        let bean_info = shown.bean.unwrap();
+        assert_eq!(bean_info.name.as_deref(), Some("耶加雪菲"));
+        assert_eq!(bean_info.remaining_grams, Some(132.5));
+        let timer = shown.timer.unwrap();
+        assert_eq!((timer.stage, timer.stage_count, timer.stage_remaining), (1, 2, 0.0));
+
+        let private = build(None, 0, Some(&bean), true);
+        assert!(private.timer.is_none());
+        assert_eq!(private.bean.unwrap().name, None);
+    }
+}
@@ -0,0 +1,281 @@
+use reqwest::{Method, RequestBuilder, StatusCode};
+use std::time::Duration;
+
+use crate::backups::{self, BackupInfo};
+use crate::xml::elements;
+
+// WebDAV 备份：把备份上传到 WebDAV 服务器的 brew-guide/ 目录（坚果云、Nextcloud 等网盘），可以从中恢复
+// 地址和用户名保存在设置中，密码保存在系统钥匙串中；开启自动上传后每天上传一份，远程备份按本地备份的规则清理
+const REMOTE_DIR: &str = "brew-guide";
+const CREDENTIAL_ACCOUNT: &str = "webdav";
+
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(5 * 60);
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getcontentlength/></d:prop></d:propfind>"#;
+
+struct WebDav {
+    client: reqwest::Client,
+    base: String, // 以 / 结尾的备份目录地址
+    username: String,
+    password: String,
+}
+
+// PROPFIND 响应中的备份文件
+fn parse_listing(xml: &str) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = elements(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = elements(response, "href").into_iter().next()?.trim();
+            let id = href.rsplit('/').next()?.strip_suffix(".zip")?;
+            let size = elements(response, "getcontentlength")
+                .into_iter()
+                .next()
+                .and_then(|size| size.trim().parse().ok())
+                .unwrap_or(0);
+            backups::describe(id, size)
+        })
+        .collect();
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    backups
+}
+
+impl WebDav {
+    fn connect(app: &tauri::AppHandle) -> Result<Self, String> {
+        let settings = crate::settings::get(app).webdav;
+        let url = settings.url.ok_or("还没有设置 WebDAV 地址")?;
+        let username = settings.username.unwrap_or_default();
+        let password = crate::credentials::load(CREDENTIAL_ACCOUNT)?.unwrap_or_default();
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("BrewGuide/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30 * 60))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            client,
+            base: format!("{}/{}/", url.trim_end_matches('/'), REMOTE_DIR),
+            username,
+            password,
+        })
+    }
+
+    fn request(&self, method: Method, name: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.base, name))
+            .basic_auth(&self.username, Some(&self.password))
+    }
+
+    // 创建备份目录，已存在时服务器返回 405
+    async fn ensure_dir(&self) -> Result<(), String> {
+        let method = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+        let response = self.request(method, "").send().await.map_err(|e| e.to_string())?;
+        match response.status() {
+            status if status.is_success() || status == StatusCode::METHOD_NOT_ALLOWED => Ok(()),
+            status => Err(format!("创建 WebDAV 目录失败：{}", status)),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<BackupInfo>, String> {
+        let method = Method::from_bytes(b"PROPFIND").map_err(|e| e.to_string())?;
+        let response = self
+            .request(method, "")
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(PROPFIND_BODY)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let xml = response
+            .error_for_status()
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(parse_listing(&xml))
+    }
+
+    async fn upload(&self, id: &str, bytes: Vec<u8>) -> Result<(), String> {
+        self.request(Method::PUT, &format!("{}.zip", id))
+            .body(bytes)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    async fn download(&self, id: &str) -> Result<Vec<u8>, String> {
+        let bytes = self
+            .request(Method::GET, &format!("{}.zip", id))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .bytes()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(bytes.to_vec())
+    }
+
+    // 按本地备份的保留规则删除旧的远程备份
+    async fn prune(&self) -> Result<(), String> {
+        let ids: Vec<String> = self.list().await?.into_iter().map(|info| info.id).collect();
+        for id in backups::expired(&ids) {
+            let result = self
+                .request(Method::DELETE, &format!("{}.zip", id))
+                .send()
+                .await
+                .and_then(|r| r.error_for_status());
+            if let Err(e) = result {
+                log::warn!("删除 WebDAV 备份 {} 失败：{}", id, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn upload_snapshot(app: &tauri::AppHandle) -> Result<BackupInfo, String> {
+    let dav = WebDav::connect(app)?;
+    let id = backups::new_id("webdav");
+    let handle = app.clone();
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        let path = backups::work_path(&handle, "webdav-upload.zip")?;
+        let bytes = backups::package(&handle, &path, "webdav")
+            .and_then(|()| std::fs::read(&path).map_err(|e| e.to_string()));
+        let _ = std::fs::remove_file(&path);
+        bytes
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let size = bytes.len() as u64;
+    dav.ensure_dir().await?;
+    dav.upload(&id, bytes).await?;
+    if let Err(e) = dav.prune().await {
+        log::warn!("清理 WebDAV 备份失败：{}", e);
+    }
+    backups::describe(&id, size).ok_or_else(|| format!("无效的备份：{}", id))
+}
+
+// 开启自动上传且今天还没有上传过时上传一份备份
+async fn auto_upload(app: &tauri::AppHandle) -> Result<(), String> {
+    let settings = crate::settings::get(app).webdav;
+    if !settings.auto_upload || settings.url.is_none() {
+        return Ok(());
+    }
+    let dav = WebDav::connect(app)?;
+    if dav.list().await?.iter().any(|info| backups::created_today(&info.id)) {
+        return Ok(());
+    }
+    let info = upload_snapshot(app).await?;
+    log::info!("已自动上传 WebDAV 备份 {}", info.id);
+    Ok(())
+}
+
+// 桌面端：启动后稍等片刻检查一次，之后每小时检查一次是否需要自动上传
+pub fn spawn_upload_loop(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            if let Err(e) = auto_upload(&app).await {
+                log::info!("自动上传 WebDAV 备份失败，稍后重试：{}", e);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// 保存 WebDAV 设置；password 为 None 时保留钥匙串中原来的密码，为空字符串时删除
+#[tauri::command]
+pub fn configure_webdav(
+    app: tauri::AppHandle,
+    url: String,
+    username: String,
+    password: Option<String>,
+    auto_upload: bool,
+) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let url = url.trim().to_string();
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("无效的 WebDAV 地址：{}", url));
+    }
+    match password.as_deref() {
+        Some("") => crate::credentials::delete(CREDENTIAL_ACCOUNT)?,
+        Some(password) => crate::credentials::save(CREDENTIAL_ACCOUNT, password)?,
+        None => {}
+    }
+    crate::settings::update(&app, |settings| {
+        settings.webdav.url = Some(url);
+        settings.webdav.username = Some(username.trim().to_string()).filter(|u| !u.is_empty());
+        settings.webdav.auto_upload = auto_upload;
+    })?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn backup_to_webdav(app: tauri::AppHandle) -> Result<BackupInfo, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let info = upload_snapshot(&app).await?;
+    crate::telemetry::record(&app, "backup.webdav_upload");
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn list_webdav_backups(app: tauri::AppHandle) -> Result<Vec<BackupInfo>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    WebDav::connect(&app)?.list().await
+}
+
+// 从 WebDAV 恢复指定的备份，不指定时恢复最新的一份
+#[tauri::command]
+pub async fn restore_from_webdav(app: tauri::AppHandle, id: Option<String>) -> Result<(), String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    let dav = WebDav::connect(&app)?;
+    let id = match id {
+        Some(id) => id,
+        None => dav.list().await?.into_iter().next().ok_or("WebDAV 上没有备份")?.id,
+    };
+    if backups::describe(&id, 0).is_none() {
+        return Err(format!("无效的备份：{}", id));
+    }
+    let bytes = dav.download(&id).await?;
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = backups::work_path(&handle, "webdav-restore.zip")?;
+        let result = std::fs::write(&path, bytes)
+            .map_err(|e| e.to_string())
+            .and_then(|()| backups::restore_archive(&handle, &path));
+        let _ = std::fs::remove_file(&path);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    crate::telemetry::record(&app, "backup.webdav_restore");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_propfind_listing() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:">
+  <D:response><D:href>/dav/brew-guide/</D:href><D:propstat><D:prop/></D:propstat></D:response>
+  <D:response>
+    <D:href>/dav/brew-guide/snapshot-20261015-090000-webdav.zip</D:href>
+    <D:propstat><D:prop><D:getcontentlength>2048</D:getcontentlength></D:prop></D:propstat>
+  </D:response>
+  <D:response><D:href>/dav/brew-guide/notes.txt</D:href></D:response>
+  <D:response><D:href>/dav/brew-guide/snapshot-20261016-090000-webdav.zip</D:href></D:response>
+</D:multistatus>"#;
+        let backups = parse_listing(xml);
+        let ids: Vec<&str> = backups.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["snapshot-20261016-090000-webdav", "snapshot-20261015-090000-webdav"]);
+        assert_eq!(backups[1].size, 2048);
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::background::BeanCache;
+use crate::i18n::Locale;
+use crate::json_file;
+use crate::notifications::NotificationTarget;
+use crate::{CoffeeBean, FreshnessState};
+
+// 每周一生成上周（周一至周日）的库存周报：冲煮消耗、喝完的咖啡豆、进入衰退期的咖啡豆
+// Markdown 保存到应用数据目录的 reports/ 下，同时发送通知，点击后在周报窗口中查看
+const REPORT_STATE_FILE: &str = "weekly-report.json";
+const REPORTS_DIR: &str = "reports";
+const LABEL: &str = "weekly-report";
+
+// 喝完记录保留的天数，超过两周的记录不会再出现在任何周报里
+const FINISHED_RETENTION_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FinishedBean {
+    name: String,
+    date: String, // YYYY-MM-DD
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyReport {
+    pub week_start: String,
+    pub week_end: String,
+    pub brew_count: usize,
+    pub grams: f64,
+    pub finished: Vec<String>,
+    pub declined: Vec<String>,
+    pub bean_count: usize,
+    pub remaining_grams: f64,
+    pub path: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WeeklyReportState {
+    last_week: Option<String>, // 最近一次生成的周报对应的周一
+    finished: Vec<FinishedBean>,
+    latest: Option<WeeklyReport>,
+}
+
+impl WeeklyReportState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        state_path(app)
+            .ok()
+            .and_then(|path| {
+                json_file::load(&path)
+                    .map_err(|e| log::warn!("读取周报状态失败：{}", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(REPORT_STATE_FILE))
+        .map_err(|e| e.to_string())
+}
+
+fn save(app: &tauri::AppHandle, state: &WeeklyReportState) -> Result<(), String> {
+    json_file::save(&state_path(app)?, state).map_err(|e| e.to_string())
+}
+
+// 本周的周一
+fn week_start(today: NaiveDate) -> NaiveDate {
+    today - chrono::Days::new(today.weekday().num_days_from_monday() as u64)
+}
+
+// 剩余量从有到无时记为喝完
+pub fn record_finished(app: &tauri::AppHandle, previous: &[CoffeeBean], current: &[CoffeeBean]) {
+    let finished: Vec<&CoffeeBean> = current
+        .iter()
+        .filter(|bean| !bean.id.is_empty())
+        .filter(|bean| bean.remaining_grams().is_some_and(|g| g <= 0.0))
+        .filter(|bean| {
+            previous
+                .iter()
+                .find(|b| b.id == bean.id)
+                .and_then(|b| b.remaining_grams())
+                .is_some_and(|g| g > 0.0)
+        })
+        .collect();
+    if finished.is_empty() {
+        return;
+    }
+    let today = crate::today(app);
+    let Some(state) = app.try_state::<Arc<Mutex<WeeklyReportState>>>() else {
+        return;
+    };
+    let Ok(mut state) = state.lock() else {
+        return;
+    };
+    let oldest = (today - chrono::Days::new(FINISHED_RETENTION_DAYS as u64)).format("%Y-%m-%d").to_string();
+    state.finished.retain(|bean| bean.date >= oldest);
+    let day = today.format("%Y-%m-%d").to_string();
+    state.finished.extend(finished.into_iter().map(|bean| FinishedBean {
+        name: bean.name.clone(),
+        date: day.clone(),
+    }));
+    if let Err(e) = save(app, &state) {
+        log::warn!("保存周报状态失败：{}", e);
+    }
+}
+
+fn build_report(
+    app: &tauri::AppHandle,
+    start: NaiveDate,
+    end: NaiveDate,
+    finished: &[FinishedBean],
+    beans: &[CoffeeBean],
+) -> WeeklyReport {
+    let brews = crate::brews::stats_between(app, start, end);
+    let (from, to) = (start.format("%Y-%m-%d").to_string(), end.format("%Y-%m-%d").to_string());
+    let active: Vec<&CoffeeBean> = beans
+        .iter()
+        .filter(|bean| bean.remaining_grams().unwrap_or(0.0) > 0.0)
+        .collect();
+    // 周一之前还没进入衰退期、周日时已进入的
+    let before = start - chrono::Days::new(1);
+    let declined = active
+        .iter()
+        .filter(|bean| crate::calculate_freshness(bean, end).freshness_state == FreshnessState::Decline)
+        .filter(|bean| crate::calculate_freshness(bean, before).freshness_state != FreshnessState::Decline)
+        .map(|bean| bean.name.clone())
+        .collect();
+    WeeklyReport {
+        week_start: from.clone(),
+        week_end: to.clone(),
+        brew_count: brews.count,
+        grams: brews.grams,
+        finished: finished
+            .iter()
+            .filter(|bean| bean.date >= from && bean.date <= to)
+            .map(|bean| bean.name.clone())
+            .collect(),
+        declined,
+        bean_count: active.len(),
+        remaining_grams: active.iter().filter_map(|bean| bean.remaining_grams()).sum(),
+        path: String::new(),
+    }
+}
+
+fn to_markdown(report: &WeeklyReport, locale: Locale) -> String {
+    let none = locale.tr("无", "None");
+    let list = |names: &[String]| {
+        if names.is_empty() {
+            format!("- {}\n", none)
+        } else {
+            names.iter().map(|name| format!("- {}\n", name)).collect()
+        }
+    };
+    format!(
+        "# {} {} – {}\n\n## {}\n\n- {}\n- {}\n\n## {}\n\n{}\n## {}\n\n{}\n## {}\n\n- {} · {}\n",
+        locale.tr("咖啡豆周报", "Weekly Coffee Report"),
+        report.week_start,
+        report.week_end,
+        locale.tr("消耗", "Consumption"),
+        locale.format_cup_count(report.brew_count),
+        locale.format_weight(report.grams),
+        locale.tr("喝完的咖啡豆", "Finished beans"),
+        list(&report.finished),
+        locale.tr("进入衰退期", "Past peak"),
+        list(&report.declined),
+        locale.tr("当前库存", "Inventory"),
+        locale.format_bean_count(report.bean_count),
+        locale.format_weight(report.remaining_grams),
+    )
+}
+
+// 跨过周一后生成上周的周报；第一次运行时只记下本周，下周一再生成完整的周报
+pub fn check(app: &tauri::AppHandle) -> Result<(), String> {
+    let this_week = week_start(crate::today(app));
+    let this_week_key = this_week.format("%Y-%m-%d").to_string();
+    let beans = match app.try_state::<Arc<Mutex<BeanCache>>>() {
+        Some(state) => state.lock().map_err(|e| e.to_string())?.beans.clone(),
+        None => return Ok(()),
+    };
+    // 启动后前端还没推送数据时先不生成
+    if beans.is_empty() {
+        return Ok(());
+    }
+    let state = app
+        .try_state::<Arc<Mutex<WeeklyReportState>>>()
+        .ok_or("周报模块未初始化")?;
+    let mut state = state.lock().map_err(|e| e.to_string())?;
+    match state.last_week.as_deref() {
+        Some(last) if last >= this_week_key.as_str() => return Ok(()),
+        Some(_) => {}
+        None => {
+            state.last_week = Some(this_week_key);
+            return save(app, &state);
+        }
+    }
+
+    let start = this_week - chrono::Days::new(7);
+    let end = this_week - chrono::Days::new(1);
+    let locale = crate::current_locale(app);
+    let mut report = build_report(app, start, end, &state.finished, &beans);
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join(REPORTS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("weekly-{}.md", report.week_start));
+    std::fs::write(&path, to_markdown(&report, locale)).map_err(|e| e.to_string())?;
+    report.path = path.to_string_lossy().into_owned();
+
+    let body = locale.format_weekly_report(report.brew_count, report.grams);
+    state.last_week = Some(this_week_key);
+    state.latest = Some(report);
+    save(app, &state)?;
+    drop(state);
+
+    crate::notifications::show(app, "Brew Guide", &body, Some(NotificationTarget::WeeklyReport));
+    Ok(())
+}
+
+pub fn open(app: &tauri::AppHandle) -> tauri::Result<()> {
+    #[cfg(desktop)]
+    {
+        let title = crate::current_locale(app).tr("咖啡豆周报", "Weekly Report");
+        crate::open_tray_window(app, LABEL, title, 380.0, 520.0)
+    }
+    #[cfg(not(desktop))]
+    {
+        crate::show_main_window(app);
+        Ok(())
+    }
+}
+
+// 周报窗口读取最近一次的周报
+#[tauri::command]
+pub fn get_weekly_report(app: tauri::AppHandle) -> Result<Option<WeeklyReport>, String> {
+    crate::app_lock::ensure_unlocked(&app)?;
+    Ok(app
+        .try_state::<Arc<Mutex<WeeklyReportState>>>()
+        .and_then(|state| state.lock().ok()?.latest.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn week_starts_on_monday() {
+        let friday = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        assert_eq!(week_start(friday), monday);
+        assert_eq!(week_start(monday), monday);
+    }
+}
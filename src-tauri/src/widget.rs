@@ -0,0 +1,193 @@
+use serde::Serialize;
+use std::io;
+use std::path::Path;
+
+use crate::i18n::Locale;
+use crate::json_file;
+use crate::{BeanFreshnessInfo, FreshnessState};
+
+// 小组件快照文件名，iOS WidgetKit / Android Glance 小组件从共享目录读取
+pub const SNAPSHOT_FILE_NAME: &str = "widget-snapshot.json";
+
+// 小组件最多展示的咖啡豆数量
+const TOP_BEANS_LIMIT: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetSnapshot {
+    pub generated_at: String,
+    pub counts: WidgetCounts,
+    pub top_beans: Vec<WidgetBean>,
+    pub next_transition: Option<WidgetTransition>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetCounts {
+    pub optimal: usize,
+    pub resting: usize,
+    pub decline: usize,
+    pub frozen: usize,
+    pub in_transit: usize,
+    pub unknown: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetBean {
+    pub id: String,
+    pub name: String,
+    pub state: &'static str,
+    pub days_left: Option<i32>,
+    pub remaining_grams: Option<f64>,
+}
+
+// 下一次状态变化（如某款豆子 3 天后进入赏味期）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetTransition {
+    pub bean_id: String,
+    pub bean_name: String,
+    pub to_state: &'static str,
+    pub date: String,
+    pub days_until: i32,
+}
+
+pub fn state_key(state: &FreshnessState) -> &'static str {
+    match state {
+        FreshnessState::Resting => "resting",
+        FreshnessState::Optimal => "optimal",
+        FreshnessState::Decline => "decline",
+        FreshnessState::Frozen => "frozen",
+        FreshnessState::InTransit => "inTransit",
+        FreshnessState::Unknown => "unknown",
+    }
+}
+
+// privacy 为隐私模式下的语言：名称按在 beans 中的顺序换成编号（与托盘一致），不包含剩余克数
+pub fn build_snapshot(beans: &[BeanFreshnessInfo], today: chrono::NaiveDate, privacy: Option<Locale>) -> WidgetSnapshot {
+    let name = |index: usize| match privacy {
+        Some(locale) => locale.format_private_bean_name(index + 1),
+        None => beans[index].bean.name.clone(),
+    };
+
+    let mut counts = WidgetCounts::default();
+    for info in beans {
+        match info.freshness_state {
+            FreshnessState::Optimal => counts.optimal += 1,
+            FreshnessState::Resting => counts.resting += 1,
+            FreshnessState::Decline => counts.decline += 1,
+            FreshnessState::Frozen => counts.frozen += 1,
+            FreshnessState::InTransit => counts.in_transit += 1,
+            FreshnessState::Unknown => counts.unknown += 1,
+        }
+    }
+
+    // 优先展示快过赏味期的，其次是快养好的
+    let mut optimal: Vec<(usize, &BeanFreshnessInfo)> = beans
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.freshness_state == FreshnessState::Optimal)
+        .collect();
+    optimal.sort_by_key(|(_, b)| b.end_day - b.days_since_roast);
+    let mut resting: Vec<(usize, &BeanFreshnessInfo)> = beans
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.freshness_state == FreshnessState::Resting)
+        .collect();
+    resting.sort_by_key(|(_, b)| b.start_day - b.days_since_roast);
+
+    let top_beans = optimal
+        .iter()
+        .chain(resting.iter())
+        .take(TOP_BEANS_LIMIT)
+        .map(|&(index, info)| WidgetBean {
+            id: info.bean.id.clone(),
+            name: name(index),
+            state: state_key(&info.freshness_state),
+            days_left: match info.freshness_state {
+                FreshnessState::Optimal => Some(info.end_day - info.days_since_roast),
+                FreshnessState::Resting => Some(info.start_day - info.days_since_roast),
+                _ => None,
+            },
+            remaining_grams: info.bean.remaining_grams().filter(|_| privacy.is_none()),
+        })
+        .collect();
+
+    // 养豆期 -> 赏味期：还差 start_day - days 天；赏味期 -> 衰退期：end_day 之后的第一天
+    let next_transition = beans
+        .iter()
+        .enumerate()
+        .filter_map(|(index, info)| match info.freshness_state {
+            FreshnessState::Resting => Some((index, info, "optimal", info.start_day - info.days_since_roast)),
+            FreshnessState::Optimal => Some((index, info, "decline", info.end_day - info.days_since_roast + 1)),
+            _ => None,
+        })
+        .min_by_key(|(_, _, _, days)| *days)
+        .map(|(index, info, to_state, days_until)| WidgetTransition {
+            bean_id: info.bean.id.clone(),
+            bean_name: name(index),
+            to_state,
+            date: (today + chrono::Duration::days(days_until as i64)).to_string(),
+            days_until,
+        });
+
+    WidgetSnapshot {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        counts,
+        top_beans,
+        next_transition,
+    }
+}
+
+pub fn write_snapshot(dir: &Path, snapshot: &WidgetSnapshot) -> io::Result<()> {
+    json_file::save(&dir.join(SNAPSHOT_FILE_NAME), snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CoffeeBean;
+
+    #[test]
+    fn hides_names_and_grams_in_privacy_mode() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let bean = |id: &str, name: &str, roast_date: &str| CoffeeBean {
+            id: id.to_string(),
+            name: name.to_string(),
+            remaining: Some("120".to_string()),
+            capacity: None,
+            roast_date: Some(roast_date.to_string()),
+            start_day: Some(7),
+            end_day: Some(30),
+            is_frozen: None,
+            is_in_transit: None,
+            expected_arrival: None,
+            pinned: None,
+            roaster: Some("Some Roaster".to_string()),
+            origin: None,
+            process: None,
+        };
+        let infos: Vec<BeanFreshnessInfo> = [bean("b1", "耶加雪菲", "2026-10-12"), bean("b2", "瑰夏", "2026-10-01")]
+            .iter()
+            .map(|bean| crate::calculate_freshness(bean, today))
+            .collect();
+
+        let snapshot = build_snapshot(&infos, today, None);
+        assert_eq!(snapshot.counts.optimal, 1);
+        assert_eq!(snapshot.counts.resting, 1);
+        assert_eq!(snapshot.top_beans[0].name, "瑰夏");
+        assert_eq!(snapshot.top_beans[0].remaining_grams, Some(120.0));
+
+        let private = build_snapshot(&infos, today, Some(Locale::default()));
+        assert_eq!(private.counts.optimal, 1);
+        // 编号按传入顺序，与托盘一致
+        assert_eq!(private.top_beans[0].name, "咖啡豆 2");
+        assert_eq!(private.top_beans[1].name, "咖啡豆 1");
+        assert!(private.top_beans.iter().all(|bean| bean.remaining_grams.is_none()));
+        let transition = private.next_transition.as_ref().unwrap();
+        assert_eq!((transition.bean_id.as_str(), transition.bean_name.as_str()), ("b1", "咖啡豆 1"));
+        let json = serde_json::to_string(&private).unwrap();
+        assert!(!json.contains("耶加雪菲") && !json.contains("瑰夏"));
+    }
+}
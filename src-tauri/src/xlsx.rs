@@ -0,0 +1,148 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+// 最简单的 xlsx 写入：只有一个工作表，单元格为文字（内联字符串）或数字，逐行写入压缩包，不在内存中保存整张表
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+const SHEET_START: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#;
+const SHEET_END: &str = "</sheetData></worksheet>";
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+pub struct XlsxWriter {
+    zip: ZipWriter<File>,
+    rows: u32,
+}
+
+// 列名：0 -> A，25 -> Z，26 -> AA
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+// 转义 XML 特殊字符，去掉 XML 中不允许的控制字符
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl XlsxWriter {
+    pub fn create(path: &Path, sheet_name: &str) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+        let sheet_name: String = sheet_name
+            .chars()
+            .filter(|c| !matches!(c, '\\' | '/' | '?' | '*' | '[' | ']' | ':'))
+            .take(MAX_SHEET_NAME_CHARS)
+            .collect();
+        let workbook = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            escape(&sheet_name)
+        );
+        for (name, content) in [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", ROOT_RELS),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS),
+            ("xl/worksheets/sheet1.xml", SHEET_START),
+        ] {
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            zip.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        Ok(Self { zip, rows: 0 })
+    }
+
+    pub fn write_row(&mut self, cells: &[Cell]) -> Result<(), String> {
+        self.rows += 1;
+        let mut xml = format!(r#"<row r="{}">"#, self.rows);
+        for (index, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(index), self.rows);
+            match cell {
+                Cell::Text(text) => xml.push_str(&format!(
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    reference,
+                    escape(text)
+                )),
+                Cell::Number(n) if n.is_finite() => xml.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, reference, n)),
+                _ => {}
+            }
+        }
+        xml.push_str("</row>");
+        self.zip.write_all(xml.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    pub fn finish(mut self) -> Result<(), String> {
+        self.zip.write_all(SHEET_END.as_bytes()).map_err(|e| e.to_string())?;
+        self.zip.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn writes_inline_strings_and_numbers() {
+        assert_eq!(
+            [0, 25, 26, 701, 702].map(column_name),
+            ["A", "Z", "AA", "ZZ", "AAA"].map(str::to_string)
+        );
+
+        let path = std::env::temp_dir().join(format!("brew-guide-xlsx-{}.xlsx", std::process::id()));
+        let mut writer = XlsxWriter::create(&path, "咖啡豆").unwrap();
+        writer.write_row(&[Cell::Text("名称".into()), Cell::Text("克数".into())]).unwrap();
+        writer
+            .write_row(&[Cell::Text("A&B <耶加雪菲>".into()), Cell::Number(15.5), Cell::Empty])
+            .unwrap();
+        writer.finish().unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(sheet.contains(r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">A&amp;B &lt;耶加雪菲&gt;</t></is></c>"#));
+        assert!(sheet.contains(r#"<c r="B2"><v>15.5</v></c>"#));
+        assert!(sheet.ends_with("</sheetData></worksheet>"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,27 @@
+// 按本地名称取 XML 元素的内容（忽略 d:、D: 等命名空间前缀）
+// WebDAV、S3 的响应结构简单且不嵌套同名元素，不需要完整的 XML 解析
+pub fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('>') else {
+            break;
+        };
+        let tag = &after[..close];
+        rest = &after[close + 1..];
+        if tag.starts_with(['/', '?', '!']) || tag.ends_with('/') {
+            continue;
+        }
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        if tag_name.rsplit(':').next() != Some(name) {
+            continue;
+        }
+        let end_tag = format!("</{}>", tag_name);
+        if let Some(end) = rest.find(&end_tag) {
+            found.push(&rest[..end]);
+            rest = &rest[end + end_tag.len()..];
+        }
+    }
+    found
+}
@@ -0,0 +1,110 @@
+'use client';
+
+import { useEffect, useState, type KeyboardEvent } from 'react';
+
+// 托盘「搜索咖啡豆…」窗口：模糊匹配由 Rust 端在缓存的咖啡豆中完成
+interface BeanSearchResult {
+  id: string;
+  name: string;
+  roaster: string | null;
+  state: string;
+}
+
+const STATE_LABELS: Record<string, string> = {
+  optimal: '赏味期',
+  resting: '养豆期',
+  decline: '衰退期',
+  frozen: '冷冻中',
+  inTransit: '在途中',
+};
+
+const openBean = async (beanId: string) => {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke('open_searched_bean', { beanId });
+  } catch (error) {
+    console.debug('Open searched bean failed:', error);
+  }
+};
+
+export default function BeanSearchPage() {
+  const [query, setQuery] = useState('');
+  const [results, setResults] = useState<BeanSearchResult[]>([]);
+  const [selected, setSelected] = useState(0);
+
+  useEffect(() => {
+    let cancelled = false;
+    void (async () => {
+      try {
+        const { invoke } = await import('@tauri-apps/api/core');
+        const next = await invoke<BeanSearchResult[]>('search_beans', {
+          query,
+        });
+        if (cancelled) return;
+        setResults(next);
+        setSelected(0);
+      } catch (error) {
+        console.debug('Bean search failed:', error);
+      }
+    })();
+    return () => {
+      cancelled = true;
+    };
+  }, [query]);
+
+  // 上下键选择，回车打开
+  const handleKeyDown = (event: KeyboardEvent<HTMLInputElement>) => {
+    if (event.key === 'ArrowDown') {
+      event.preventDefault();
+      setSelected(i => Math.min(i + 1, results.length - 1));
+    } else if (event.key === 'ArrowUp') {
+      event.preventDefault();
+      setSelected(i => Math.max(i - 1, 0));
+    } else if (event.key === 'Enter' && results[selected]) {
+      void openBean(results[selected].id);
+    }
+  };
+
+  return (
+    <div className="flex h-screen w-screen flex-col bg-neutral-50 text-neutral-800 dark:bg-neutral-900 dark:text-neutral-100">
+      <input
+        autoFocus
+        value={query}
+        onChange={e => setQuery(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder="名称、烘焙商或产地"
+        className="m-3 rounded-lg bg-neutral-100 px-3 py-2 text-sm outline-none dark:bg-neutral-800"
+      />
+      <div className="flex-1 overflow-y-auto px-2 pb-2">
+        {results.length === 0 && (
+          <p className="py-6 text-center text-sm text-neutral-500">
+            没有匹配的咖啡豆
+          </p>
+        )}
+        {results.map((bean, index) => (
+          <button
+            key={bean.id}
+            type="button"
+            onClick={() => void openBean(bean.id)}
+            onMouseEnter={() => setSelected(index)}
+            className={`flex w-full items-center justify-between rounded-lg px-2 py-2 text-left ${
+              index === selected ? 'bg-neutral-100 dark:bg-neutral-800' : ''
+            }`}
+          >
+            <span className="flex min-w-0 flex-col">
+              <span className="truncate text-sm">{bean.name}</span>
+              {bean.roaster && (
+                <span className="truncate text-xs text-neutral-500">
+                  {bean.roaster}
+                </span>
+              )}
+            </span>
+            <span className="ml-2 shrink-0 text-xs text-neutral-500">
+              {STATE_LABELS[bean.state] ?? ''}
+            </span>
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,113 @@
+'use client';
+
+import { useCallback, useEffect, useState } from 'react';
+
+// 迷你计时器窗口：无边框置顶小窗，数据结构与 Rust 端 BrewProgress 对应
+interface BrewProgress {
+  index: number;
+  kind: 'bloom' | 'pour' | 'wait' | 'stir' | 'drawdown';
+  label: string;
+  detail: string;
+  elapsed: number;
+  stageRemaining: number;
+  targetWater: number;
+  stageWater: number;
+  totalTime: number;
+  next: { label: string; cumulativeWater: number } | null;
+  paused: boolean;
+}
+
+const REFRESH_INTERVAL = 500;
+
+const formatClock = (seconds: number) => {
+  const total = Math.max(0, Math.ceil(seconds));
+  return `${Math.floor(total / 60)}:${String(total % 60).padStart(2, '0')}`;
+};
+
+const invokeCommand = async (command: string) => {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke(command);
+  } catch (error) {
+    console.debug(`${command} failed:`, error);
+  }
+};
+
+export default function MiniTimerPage() {
+  const [progress, setProgress] = useState<BrewProgress | null>(null);
+
+  const loadProgress = useCallback(async () => {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      setProgress(await invoke<BrewProgress | null>('get_brew_progress'));
+    } catch (error) {
+      console.debug('Mini timer progress failed:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadProgress();
+    const timer = window.setInterval(
+      () => void loadProgress(),
+      REFRESH_INTERVAL
+    );
+    return () => window.clearInterval(timer);
+  }, [loadProgress]);
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="flex h-screen w-screen select-none items-center gap-3 overflow-hidden rounded-xl bg-neutral-50/95 px-4 text-neutral-800 dark:bg-neutral-900/95 dark:text-neutral-100"
+    >
+      <div data-tauri-drag-region className="min-w-0 flex-1">
+        {progress ? (
+          <>
+            <p
+              data-tauri-drag-region
+              className="truncate text-xs text-neutral-500"
+            >
+              {progress.label || '冲煮'} · {progress.targetWater}g
+              {progress.paused && ' · 已暂停'}
+            </p>
+            <p
+              data-tauri-drag-region
+              className="text-2xl font-medium tabular-nums"
+            >
+              {formatClock(progress.stageRemaining)}
+            </p>
+            {progress.next && (
+              <p
+                data-tauri-drag-region
+                className="truncate text-xs text-neutral-400"
+              >
+                下一步：{progress.next.label}
+              </p>
+            )}
+          </>
+        ) : (
+          <p data-tauri-drag-region className="text-sm text-neutral-500">
+            未在计时
+          </p>
+        )}
+      </div>
+      <div className="flex shrink-0 flex-col gap-1">
+        {progress && (
+          <button
+            type="button"
+            onClick={() => void invokeCommand('stop_brew_recipe')}
+            className="rounded-full bg-neutral-100 px-2 py-0.5 text-xs dark:bg-neutral-800"
+          >
+            停止
+          </button>
+        )}
+        <button
+          type="button"
+          onClick={() => void invokeCommand('hide_mini_timer')}
+          className="rounded-full px-2 py-0.5 text-xs text-neutral-500"
+        >
+          关闭
+        </button>
+      </div>
+    </div>
+  );
+}
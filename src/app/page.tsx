@@ -82,6 +82,7 @@ import { updateParameterInfo } from '@/lib/brewing/parameters';
 import BrewingNoteFormModal from '@/components/notes/Form/BrewingNoteFormModal';
 import CoffeeBeans from '@/components/coffee-bean/List';
 import { useCoffeeBeanStore } from '@/lib/stores/coffeeBeanStore';
+import type { OpenNewBrewLogDetail } from '@/lib/hooks/useTraySync';
 import { useBrewingNoteStore } from '@/lib/stores/brewingNoteStore';
 import {
   loadCustomEquipments,
@@ -110,7 +111,10 @@ import {
   useIsDesktopLayout,
   useIsLargeScreen,
 } from '@/lib/navigation/pageTransition';
-import { COFFEE_BEAN_NAVIGATION_EVENTS } from '@/lib/navigation/coffeeBeanNavigation';
+import {
+  COFFEE_BEAN_NAVIGATION_EVENTS,
+  type SyncCoffeeBeanInventoryContextDetail,
+} from '@/lib/navigation/coffeeBeanNavigation';
 import BeanDetailModal from '@/components/coffee-bean/Detail/BeanDetailModal';
 import NoteDetailModal from '@/components/notes/Detail/NoteDetailModal';
 import type { ConvertToGreenPreview } from '@/components/coffee-bean/ConvertToGreenDrawer';
@@ -1542,6 +1546,55 @@ const PourOverRecipes = ({ initialHasBeans }: { initialHasBeans: boolean }) => {
     [currentBeanView, setActiveMainTab, startContentDetailTransition]
   );
 
+  // 菜单栏「开始冲煮计时」：切换到冲煮页，由计时器组件开始计时
+  // 计时器组件在切换标签页之后才挂载，所以先记下请求，挂载后再开始；离开冲煮页时取消
+  const [pendingTimerStart, setPendingTimerStart] = useState(false);
+  useEffect(() => {
+    const handleStartFromTray = () => {
+      saveMainTabPreference('冲煮');
+      setActiveMainTab('冲煮');
+      setPendingTimerStart(true);
+    };
+
+    window.addEventListener('brewing:startFromTray', handleStartFromTray);
+    return () => {
+      window.removeEventListener('brewing:startFromTray', handleStartFromTray);
+    };
+  }, [setActiveMainTab]);
+
+  useEffect(() => {
+    if (activeMainTab !== '冲煮') setPendingTimerStart(false);
+  }, [activeMainTab]);
+
+  // 从菜单栏等外部入口打开咖啡豆库存列表
+  useEffect(() => {
+    const handleOpenInventory = (
+      event: CustomEvent<SyncCoffeeBeanInventoryContextDetail>
+    ) => {
+      saveMainTabPreference('咖啡豆');
+      setActiveMainTab('咖啡豆');
+      setCurrentBeanView(VIEW_OPTIONS.INVENTORY);
+      saveStringState('coffee-beans', 'viewMode', VIEW_OPTIONS.INVENTORY);
+
+      window.dispatchEvent(
+        new CustomEvent(COFFEE_BEAN_NAVIGATION_EVENTS.SYNC_INVENTORY_CONTEXT, {
+          detail: event.detail,
+        })
+      );
+    };
+
+    window.addEventListener(
+      COFFEE_BEAN_NAVIGATION_EVENTS.OPEN_INVENTORY,
+      handleOpenInventory as EventListener
+    );
+    return () => {
+      window.removeEventListener(
+        COFFEE_BEAN_NAVIGATION_EVENTS.OPEN_INVENTORY,
+        handleOpenInventory as EventListener
+      );
+    };
+  }, [setActiveMainTab]);
+
   const handleOpenBeanDetailFromNote = useCallback(
     (bean: CoffeeBean) => {
       const navigateToBeanDetail = () => openBeanDetailInInventory(bean);
@@ -2669,6 +2722,28 @@ const PourOverRecipes = ({ initialHasBeans }: { initialHasBeans: boolean }) => {
     setShowBeanForm(true);
   };
 
+  // 从快捷操作等外部入口添加咖啡豆：切换到库存页并打开添加表单
+  useEffect(() => {
+    const handleOpenAddForm = () => {
+      saveMainTabPreference('咖啡豆');
+      setActiveMainTab('咖啡豆');
+      setCurrentBeanView(VIEW_OPTIONS.INVENTORY);
+      saveStringState('coffee-beans', 'viewMode', VIEW_OPTIONS.INVENTORY);
+      handleBeanForm(null, 'roasted');
+    };
+
+    window.addEventListener(
+      COFFEE_BEAN_NAVIGATION_EVENTS.OPEN_ADD_FORM,
+      handleOpenAddForm
+    );
+    return () => {
+      window.removeEventListener(
+        COFFEE_BEAN_NAVIGATION_EVENTS.OPEN_ADD_FORM,
+        handleOpenAddForm
+      );
+    };
+  });
+
   // 完全重写checkCoffeeBeans函数，简化逻辑
   const checkCoffeeBeans = useCallback(async () => {
     try {
@@ -3362,6 +3437,34 @@ const PourOverRecipes = ({ initialHasBeans }: { initialHasBeans: boolean }) => {
     [setActiveMainTab]
   );
 
+  // 从快捷操作、NFC 标签等外部入口新建冲煮笔记，带咖啡豆 ID 时预填该咖啡豆
+  useEffect(() => {
+    const handleOpenNewBrewLog = (event: CustomEvent<OpenNewBrewLogDetail>) => {
+      const beanId = event.detail?.beanId;
+      const bean = beanId
+        ? useCoffeeBeanStore.getState().beans.find(b => b.id === beanId)
+        : undefined;
+      if (bean) {
+        handleCreateNoteFromBean(bean);
+        return;
+      }
+      saveMainTabPreference('笔记');
+      setActiveMainTab('笔记');
+      handleAddNote();
+    };
+
+    window.addEventListener(
+      'brewingNotes:openNewForm',
+      handleOpenNewBrewLog as EventListener
+    );
+    return () => {
+      window.removeEventListener(
+        'brewingNotes:openNewForm',
+        handleOpenNewBrewLog as EventListener
+      );
+    };
+  });
+
   const buildPersistedBrewingNote = useCallback(
     (note: BrewingNoteData, overrides?: Partial<BrewingNote>): BrewingNote => {
       const relation = normalizeBrewingNoteSelection({
@@ -4225,6 +4328,8 @@ const PourOverRecipes = ({ initialHasBeans }: { initialHasBeans: boolean }) => {
                     selectedEquipment={selectedEquipment}
                     isCoffeeBrewed={isCoffeeBrewed}
                     layoutSettings={settings.layoutSettings}
+                    startRequested={pendingTimerStart}
+                    onStartRequestHandled={() => setPendingTimerStart(false)}
                   />
                 </div>
               )}
@@ -0,0 +1,71 @@
+'use client';
+
+import { useState, type FormEvent } from 'react';
+
+// 托盘「快速添加咖啡豆…」窗口：校验和解析由 Rust 端完成，保存由主窗口完成
+const INPUT_CLASS =
+  'w-full rounded-lg bg-neutral-100 px-3 py-2 text-sm outline-none dark:bg-neutral-800';
+
+export default function QuickAddPage() {
+  const [name, setName] = useState('');
+  const [roastDate, setRoastDate] = useState('');
+  const [weight, setWeight] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+
+  const handleSubmit = async (event: FormEvent) => {
+    event.preventDefault();
+    setSubmitting(true);
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      await invoke('submit_quick_add_bean', { name, roastDate, weight });
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <form
+      onSubmit={handleSubmit}
+      className="flex h-screen w-screen flex-col gap-3 bg-neutral-50 p-4 text-neutral-800 dark:bg-neutral-900 dark:text-neutral-100"
+    >
+      <label className="flex flex-col gap-1 text-xs text-neutral-500">
+        名称
+        <input
+          autoFocus
+          value={name}
+          onChange={e => setName(e.target.value)}
+          className={INPUT_CLASS}
+        />
+      </label>
+      <label className="flex flex-col gap-1 text-xs text-neutral-500">
+        烘焙日期
+        <input
+          value={roastDate}
+          onChange={e => setRoastDate(e.target.value)}
+          placeholder="2024-11-05"
+          className={INPUT_CLASS}
+        />
+      </label>
+      <label className="flex flex-col gap-1 text-xs text-neutral-500">
+        重量
+        <input
+          value={weight}
+          onChange={e => setWeight(e.target.value)}
+          placeholder="200g"
+          className={INPUT_CLASS}
+        />
+      </label>
+      {error && <p className="text-xs text-red-500">{error}</p>}
+      <button
+        type="submit"
+        disabled={submitting}
+        className="mt-auto rounded-full bg-neutral-800 py-2 text-sm text-neutral-50 disabled:opacity-50 dark:bg-neutral-100 dark:text-neutral-900"
+      >
+        添加
+      </button>
+    </form>
+  );
+}
@@ -0,0 +1,148 @@
+'use client';
+
+import { useCallback, useEffect, useState } from 'react';
+
+// 托盘快速面板：点击托盘图标时在图标旁弹出，数据结构与 Rust 端 WidgetSnapshot 对应
+type BeanState =
+  | 'optimal'
+  | 'resting'
+  | 'decline'
+  | 'frozen'
+  | 'inTransit'
+  | 'unknown';
+
+interface QuickPanelBean {
+  id: string;
+  name: string;
+  state: BeanState;
+  daysLeft: number | null;
+  remainingGrams: number | null;
+}
+
+interface QuickPanelSnapshot {
+  counts: Record<BeanState, number>;
+  topBeans: QuickPanelBean[];
+  nextTransition: {
+    beanName: string;
+    toState: BeanState;
+    daysUntil: number;
+  } | null;
+}
+
+const STATE_LABELS: Record<BeanState, string> = {
+  optimal: '赏味期',
+  resting: '养豆期',
+  decline: '衰退期',
+  frozen: '冷冻中',
+  inTransit: '在途中',
+  unknown: '未知',
+};
+
+const COUNT_STATES: BeanState[] = ['optimal', 'resting', 'decline', 'frozen'];
+
+const openApp = async (beanId: string | null) => {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke('open_from_quick_panel', { beanId });
+  } catch (error) {
+    console.debug('Open from quick panel failed:', error);
+  }
+};
+
+export default function QuickPanelPage() {
+  const [snapshot, setSnapshot] = useState<QuickPanelSnapshot | null>(null);
+
+  const loadSnapshot = useCallback(async () => {
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+      setSnapshot(await invoke<QuickPanelSnapshot>('get_quick_panel_snapshot'));
+    } catch (error) {
+      // 应用锁定时不再显示之前的库存
+      setSnapshot(null);
+      console.debug('Quick panel snapshot failed:', error);
+    }
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let disposed = false;
+
+    const setup = async () => {
+      await loadSnapshot();
+      try {
+        const { listen } = await import('@tauri-apps/api/event');
+        const stop = await listen<QuickPanelSnapshot>(
+          'quick-panel-snapshot',
+          event => setSnapshot(event.payload)
+        );
+        if (disposed) stop();
+        else unlisten = stop;
+      } catch (error) {
+        console.debug('Quick panel listener failed:', error);
+      }
+    };
+
+    void setup();
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [loadSnapshot]);
+
+  const next = snapshot?.nextTransition;
+
+  return (
+    <div className="flex h-screen w-screen flex-col overflow-hidden bg-neutral-50 text-neutral-800 dark:bg-neutral-900 dark:text-neutral-100">
+      <div className="grid grid-cols-4 gap-2 px-4 pt-4">
+        {COUNT_STATES.map(state => (
+          <div key={state} className="flex flex-col items-center">
+            <span className="text-lg font-medium">
+              {snapshot?.counts[state] ?? 0}
+            </span>
+            <span className="text-xs text-neutral-500">
+              {STATE_LABELS[state]}
+            </span>
+          </div>
+        ))}
+      </div>
+
+      <div className="mt-4 flex-1 overflow-y-auto px-2">
+        {snapshot && snapshot.topBeans.length === 0 && (
+          <p className="px-2 py-6 text-center text-sm text-neutral-500">
+            暂无咖啡豆
+          </p>
+        )}
+        {snapshot?.topBeans.map(bean => (
+          <button
+            key={bean.id}
+            type="button"
+            onClick={() => void openApp(bean.id)}
+            className="flex w-full items-center justify-between rounded-lg px-2 py-2 text-left hover:bg-neutral-100 dark:hover:bg-neutral-800"
+          >
+            <span className="truncate text-sm">{bean.name}</span>
+            <span className="ml-2 shrink-0 text-xs text-neutral-500">
+              {STATE_LABELS[bean.state]}
+              {bean.daysLeft != null && ` · ${bean.daysLeft}天`}
+              {bean.remainingGrams != null && ` · ${bean.remainingGrams}g`}
+            </span>
+          </button>
+        ))}
+      </div>
+
+      {next && (
+        <p className="px-4 pb-2 text-xs text-neutral-500">
+          {`${next.beanName} ${next.daysUntil} 天后进入${STATE_LABELS[next.toState]}`}
+        </p>
+      )}
+
+      <button
+        type="button"
+        onClick={() => void openApp(null)}
+        className="m-3 rounded-full bg-neutral-100 py-2 text-sm dark:bg-neutral-800"
+      >
+        打开 Brew Guide
+      </button>
+    </div>
+  );
+}
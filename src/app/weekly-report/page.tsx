@@ -0,0 +1,83 @@
+'use client';
+
+import { useEffect, useState } from 'react';
+
+// 周报窗口：点击周报通知后打开，内容由 Rust 端生成（同时保存为 Markdown 文件）
+interface WeeklyReport {
+  weekStart: string;
+  weekEnd: string;
+  brewCount: number;
+  grams: number;
+  finished: string[];
+  declined: string[];
+  beanCount: number;
+  remainingGrams: number;
+  path: string;
+}
+
+const Section = ({ title, items }: { title: string; items: string[] }) => (
+  <section className="flex flex-col gap-1">
+    <h2 className="text-xs text-neutral-500">{title}</h2>
+    {items.length === 0 ? (
+      <p className="text-sm text-neutral-400">无</p>
+    ) : (
+      items.map(item => (
+        <p key={item} className="truncate text-sm">
+          {item}
+        </p>
+      ))
+    )}
+  </section>
+);
+
+export default function WeeklyReportPage() {
+  const [report, setReport] = useState<WeeklyReport | null>(null);
+  const [loaded, setLoaded] = useState(false);
+
+  useEffect(() => {
+    void (async () => {
+      try {
+        const { invoke } = await import('@tauri-apps/api/core');
+        setReport(await invoke<WeeklyReport | null>('get_weekly_report'));
+      } catch (error) {
+        console.debug('Weekly report failed:', error);
+      } finally {
+        setLoaded(true);
+      }
+    })();
+  }, []);
+
+  if (!report) {
+    return (
+      <div className="flex h-screen w-screen items-center justify-center bg-neutral-50 text-sm text-neutral-500 dark:bg-neutral-900">
+        {loaded ? '还没有周报，每周一生成上周的周报' : ''}
+      </div>
+    );
+  }
+
+  return (
+    <div className="flex h-screen w-screen flex-col gap-4 overflow-y-auto bg-neutral-50 p-4 text-neutral-800 dark:bg-neutral-900 dark:text-neutral-100">
+      <h1 className="text-sm font-medium">
+        {report.weekStart} – {report.weekEnd}
+      </h1>
+      <div className="grid grid-cols-2 gap-2">
+        <div className="rounded-lg bg-neutral-100 p-3 dark:bg-neutral-800">
+          <p className="text-xs text-neutral-500">冲煮</p>
+          <p className="text-lg">{report.brewCount} 杯</p>
+        </div>
+        <div className="rounded-lg bg-neutral-100 p-3 dark:bg-neutral-800">
+          <p className="text-xs text-neutral-500">用豆</p>
+          <p className="text-lg">{Math.round(report.grams)} g</p>
+        </div>
+      </div>
+      <Section title="喝完的咖啡豆" items={report.finished} />
+      <Section title="进入衰退期" items={report.declined} />
+      <section className="flex flex-col gap-1">
+        <h2 className="text-xs text-neutral-500">当前库存</h2>
+        <p className="text-sm">
+          {report.beanCount} 款 · {Math.round(report.remainingGrams)} g
+        </p>
+      </section>
+    </div>
+  );
+}
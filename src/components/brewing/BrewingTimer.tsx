@@ -36,6 +36,7 @@ import type {
   TimerCallbacks,
 } from '@/components/brewing/Timer';
 import { useSettingsStore } from '@/lib/stores/settingsStore';
+import { syncBrewTimerToTray } from '@/lib/hooks/useTraySync';
 
 // 保留布局设置接口的导出，但使用从Timer模块导入的定义
 export type { LayoutSettings } from '@/components/brewing/Timer';
@@ -72,6 +73,8 @@ interface BrewingTimerProps {
   selectedEquipment: string | null;
   isCoffeeBrewed?: boolean;
   layoutSettings?: LayoutSettings; // 添加布局设置选项
+  startRequested?: boolean; // 菜单栏等外部入口请求开始计时
+  onStartRequestHandled?: () => void;
 }
 
 const BrewingTimer: React.FC<BrewingTimerProps> = ({
@@ -86,6 +89,8 @@ const BrewingTimer: React.FC<BrewingTimerProps> = ({
   selectedEquipment,
   isCoffeeBrewed,
   layoutSettings = {}, // 使用空对象作为默认值
+  startRequested = false,
+  onStartRequestHandled,
 }) => {
   const [currentTime, setCurrentTime] = useState(0);
   const [isRunning, setIsRunning] = useState(false);
@@ -1004,6 +1009,18 @@ const BrewingTimer: React.FC<BrewingTimerProps> = ({
     };
   }, [clearTimerAndStates]);
 
+  // 同步计时状态到菜单栏（桌面端）
+  useEffect(() => {
+    void syncBrewTimerToTray(isRunning, currentBrewingMethod?.name ?? null);
+  }, [isRunning, currentBrewingMethod]);
+
+  // 菜单栏「开始冲煮计时」：请求可能在本组件挂载前发出，由页面保留到这里处理
+  useEffect(() => {
+    if (!startRequested) return;
+    onStartRequestHandled?.();
+    startTimer();
+  }, [startRequested, onStartRequestHandled, startTimer]);
+
   // 简化跳过处理函数
   const handleSkip = useCallback(() => {
     if (!currentBrewingMethod || !expandedStagesRef.current.length) return;
@@ -1237,6 +1237,7 @@ const CoffeeBeans: React.FC<CoffeeBeansProps> = ({
     ({
       beanState,
       clearSearch = false,
+      flavorPeriod,
     }: SyncCoffeeBeanInventoryContextDetail = {}) => {
       const nextBeanState: BeanState =
         beanState === 'green' && enableGreenBeanInventory ? 'green' : 'roasted';
@@ -1250,10 +1251,18 @@ const CoffeeBeans: React.FC<CoffeeBeansProps> = ({
         setSearchQuery('');
         setIsSearchAllScope(false);
       }
+
+      // 赏味期筛选只适用于熟豆
+      if (flavorPeriod && nextBeanState === 'roasted') {
+        handleFilterModeChange('flavorPeriod');
+        handleFlavorPeriodClick(flavorPeriod);
+      }
     },
     [
       enableGreenBeanInventory,
       handleBeanStateChange,
+      handleFilterModeChange,
+      handleFlavorPeriodClick,
       selectedBeanState,
       setIsSearching,
       setSearchQuery,
@@ -3,13 +3,37 @@
  * 将咖啡豆数据同步到 macOS 菜单栏显示
  */
 import { useEffect, useRef, useCallback } from 'react';
-import { useCoffeeBeanStore } from '@/lib/stores/coffeeBeanStore';
+import {
+  updateBeanRemaining,
+  useCoffeeBeanStore,
+} from '@/lib/stores/coffeeBeanStore';
+import {
+  COFFEE_BEAN_NAVIGATION_EVENTS,
+  type SyncCoffeeBeanInventoryContextDetail,
+} from '@/lib/navigation/coffeeBeanNavigation';
+import { FlavorPeriodStatus } from '@/lib/utils/beanVarietyUtils';
+import type { BlendComponent, CoffeeBean } from '@/types/app';
+import type { BrewingNote } from '@/lib/core/config';
+import { useBrewingNoteStore } from '@/lib/stores/brewingNoteStore';
 
 // 检查是否在 Tauri 环境中
 const isTauri = () => {
   return typeof window !== 'undefined' && '__TAURI__' in window;
 };
 
+// 快速面板、迷你计时器等辅助窗口也加载了全局 Provider，只在主窗口中同步和处理托盘事件，
+// 否则每个托盘事件（扣减用量、快速添加等）会被处理多次
+const isMainWindow = async () => {
+  if (!isTauri()) return false;
+  try {
+    const { getCurrentWindow } = await import('@tauri-apps/api/window');
+    return getCurrentWindow().label === 'main';
+  } catch (error) {
+    console.debug('Failed to read window label:', error);
+    return false;
+  }
+};
+
 // 简化的咖啡豆数据结构（用于传递给 Tauri）
 interface TrayBeanData {
   id: string;
@@ -21,6 +45,154 @@ interface TrayBeanData {
   endDay: number | null;
   isFrozen: boolean | null;
   isInTransit: boolean | null;
+  expectedArrival: string | null;
+  pinned: boolean | null;
+  roaster: string | null;
+  origin: string | null;
+  process: string | null;
+}
+
+// 菜单栏「最近冲煮」条目
+interface TrayRecentBrew {
+  id: string;
+  method: string | null;
+  beanName: string | null;
+  rating: number | null;
+  timestamp: number;
+}
+
+// 菜单栏「今日冲煮」统计用的用粉量，按日期统计由 Rust 端完成
+interface TrayBrewDose {
+  timestamp: number;
+  grams: number | null;
+}
+
+// 菜单栏最多显示的最近冲煮数量（与 Rust 端一致）
+const MAX_RECENT_BREWS = 5;
+// 推送最近八天的用粉量，覆盖各时区的「今天」和周报统计的上一周
+const DOSE_WINDOW_MS = 8 * 24 * 60 * 60 * 1000;
+
+// 菜单栏返回的咖啡豆 ID 检查结果
+interface TrayBeanIdReport {
+  duplicateIds: string[];
+  missingIdCount: number;
+}
+
+// 菜单栏快捷扣减用量事件，amount 为空表示自定义用量
+interface TrayConsumeEvent {
+  beanId: string;
+  amount: number | null;
+}
+
+// 菜单栏分区（与 Rust 端 TraySection 对应）
+type TraySection =
+  | 'frozen'
+  | 'optimal'
+  | 'resting'
+  | 'decline'
+  | 'inTransit'
+  | 'lowStock';
+
+// 应用内导航目标（与 Rust 端 NavigationTarget 对应）
+// 咖啡豆详情和笔记详情另有 navigate-to-bean / navigate-to-note 事件
+type TrayNavigationTarget =
+  | { kind: 'beanList'; section: TraySection | null }
+  | { kind: 'startTimer' }
+  | { kind: 'addBean' }
+  | { kind: 'newBrewLog'; beanId: string | null };
+
+// 新建冲煮笔记事件，beanId 不为空时预填该咖啡豆
+export interface OpenNewBrewLogDetail {
+  beanId: string | null;
+}
+
+// 「即将喝完」和分组模式没有对应的赏味期筛选，只打开库存列表
+const SECTION_FLAVOR_PERIODS: Partial<
+  Record<TraySection, FlavorPeriodStatus>
+> = {
+  frozen: FlavorPeriodStatus.FROZEN,
+  optimal: FlavorPeriodStatus.OPTIMAL,
+  resting: FlavorPeriodStatus.AGING,
+  decline: FlavorPeriodStatus.DECLINE,
+  inTransit: FlavorPeriodStatus.IN_TRANSIT,
+};
+
+// 拼配成分中不重复的字段值，如 "埃塞俄比亚 / 哥伦比亚"
+const joinComponents = (
+  bean: CoffeeBean,
+  pick: (component: BlendComponent) => string | undefined
+): string | null => {
+  const values = (bean.blendComponents ?? [])
+    .map(component => pick(component)?.trim())
+    .filter((value): value is string => !!value);
+  return values.length > 0 ? Array.from(new Set(values)).join(' / ') : null;
+};
+
+// 菜单栏「快速添加咖啡豆」事件，字段已由 Rust 端校验
+interface TrayQuickAddEvent {
+  name: string;
+  roastDate: string | null;
+  weight: number | null;
+}
+
+// 菜单栏「已收到」事件，date 为收到当天（YYYY-MM-DD）
+interface TrayBeanArrivedEvent {
+  beanId: string;
+  date: string;
+}
+
+// 菜单栏「开始冲煮计时」事件，method 为上次使用的冲煮方案
+interface TrayStartTimerEvent {
+  method: string | null;
+}
+
+// 本地数据库的修改（导入、同步、撤销、Siri 记录等），data 为空表示已删除
+type StoreChange =
+  | { kind: 'bean'; id: string; data: CoffeeBean | null }
+  | { kind: 'note'; id: string; data: BrewingNote | null };
+
+// 应用锁状态（与 Rust 端 AppLockStatus 对应，这里只用到 locked）
+interface AppLockStatus {
+  locked: boolean;
+}
+
+// 取走本地数据库的修改并写入前端存储，写入后咖啡豆会照常推送到菜单栏
+async function applyStoreChanges() {
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    const changes = await invoke<StoreChange[]>('take_store_changes');
+    const beanStore = useCoffeeBeanStore.getState();
+    const noteStore = useBrewingNoteStore.getState();
+    for (const change of changes) {
+      if (change.kind === 'bean') {
+        await (change.data
+          ? beanStore.upsertBean(change.data)
+          : beanStore.removeBean(change.id));
+      } else {
+        await (change.data
+          ? noteStore.upsertNote(change.data)
+          : noteStore.removeNote(change.id));
+      }
+    }
+  } catch (error) {
+    // 应用锁定时会失败，解锁后再取
+    console.debug('Failed to apply store changes:', error);
+  }
+}
+
+// 同步冲煮计时状态，菜单栏在计时中禁用「开始冲煮计时」
+export async function syncBrewTimerToTray(
+  running: boolean,
+  method: string | null
+) {
+  if (!isTauri()) return;
+
+  try {
+    const { invoke } = await import('@tauri-apps/api/core');
+    await invoke('set_brew_timer_state', { running, method });
+  } catch (error) {
+    console.debug('Brew timer sync failed:', error);
+  }
 }
 
 // 独立的同步函数，可以在任何地方调用
@@ -29,7 +201,12 @@ export async function syncBeansToTray(beans: TrayBeanData[]) {
 
   try {
     const { invoke } = await import('@tauri-apps/api/core');
-    await invoke('update_tray_menu', { beans });
+    const report = await invoke<TrayBeanIdReport>('update_tray_menu', {
+      beans,
+    });
+    if (report.duplicateIds.length > 0 || report.missingIdCount > 0) {
+      console.warn('菜单栏咖啡豆 ID 异常:', report);
+    }
     console.log('✅ 菜单栏同步成功，咖啡豆数量:', beans.length);
   } catch (error) {
     console.debug('Tray sync failed:', error);
@@ -39,31 +216,163 @@ export async function syncBeansToTray(beans: TrayBeanData[]) {
 /**
  * 同步咖啡豆数据到 Tauri 菜单栏
  * @param onNavigateToBean 当用户点击菜单栏中的咖啡豆时调用的回调函数
+ * @param onNavigateToNote 当用户点击菜单栏「最近冲煮」中的笔记时调用的回调函数
  */
-export function useTraySync(onNavigateToBean?: (beanId: string) => void) {
+export function useTraySync(
+  onNavigateToBean?: (beanId: string) => void,
+  onNavigateToNote?: (noteId: string) => void
+) {
   const beans = useCoffeeBeanStore(state => state.beans);
+  const notes = useBrewingNoteStore(state => state.notes);
   const lastSyncRef = useRef<string>('');
+  const lastBrewSyncRef = useRef<string>('');
   const callbackRef = useRef(onNavigateToBean);
+  const noteCallbackRef = useRef(onNavigateToNote);
 
   // 保持回调引用最新
   useEffect(() => {
     callbackRef.current = onNavigateToBean;
-  }, [onNavigateToBean]);
+    noteCallbackRef.current = onNavigateToNote;
+  }, [onNavigateToBean, onNavigateToNote]);
 
   // 监听 Tauri 事件
   useEffect(() => {
     if (!isTauri()) return;
 
-    let unlisten: (() => void) | undefined;
+    const unlisteners: (() => void)[] = [];
 
     const setupListener = async () => {
+      if (!(await isMainWindow())) return;
       try {
         const { listen } = await import('@tauri-apps/api/event');
-        unlisten = await listen<string>('navigate-to-bean', event => {
-          const beanId = event.payload;
-          console.log('📍 收到导航事件，咖啡豆 ID:', beanId);
-          callbackRef.current?.(beanId);
-        });
+        unlisteners.push(
+          await listen<string>('navigate-to-bean', event => {
+            const beanId = event.payload;
+            console.log('📍 收到导航事件，咖啡豆 ID:', beanId);
+            callbackRef.current?.(beanId);
+          })
+        );
+        unlisteners.push(
+          await listen<string>('navigate-to-note', event => {
+            noteCallbackRef.current?.(event.payload);
+          })
+        );
+        unlisteners.push(
+          await listen<TrayConsumeEvent>('tray-consume-bean', event => {
+            const { beanId, amount } = event.payload;
+            if (amount == null) {
+              // 自定义用量：打开咖啡豆详情让用户自行调整
+              callbackRef.current?.(beanId);
+              return;
+            }
+            void updateBeanRemaining(beanId, amount);
+          })
+        );
+        unlisteners.push(
+          await listen<TrayNavigationTarget>('navigate-to', event => {
+            const target = event.payload;
+            switch (target.kind) {
+              case 'beanList': {
+                const detail: SyncCoffeeBeanInventoryContextDetail = {
+                  beanState: 'roasted',
+                  clearSearch: true,
+                  flavorPeriod: target.section
+                    ? SECTION_FLAVOR_PERIODS[target.section]
+                    : undefined,
+                };
+                window.dispatchEvent(
+                  new CustomEvent(
+                    COFFEE_BEAN_NAVIGATION_EVENTS.OPEN_INVENTORY,
+                    { detail }
+                  )
+                );
+                break;
+              }
+              case 'startTimer':
+                window.dispatchEvent(
+                  new CustomEvent('brewing:startFromTray', {
+                    detail: { method: null },
+                  })
+                );
+                break;
+              case 'addBean':
+                window.dispatchEvent(
+                  new CustomEvent(COFFEE_BEAN_NAVIGATION_EVENTS.OPEN_ADD_FORM)
+                );
+                break;
+              case 'newBrewLog': {
+                const detail: OpenNewBrewLogDetail = {
+                  beanId: target.beanId ?? null,
+                };
+                window.dispatchEvent(
+                  new CustomEvent('brewingNotes:openNewForm', { detail })
+                );
+                break;
+              }
+            }
+          })
+        );
+        unlisteners.push(
+          await listen<TrayStartTimerEvent>('start-brew-timer', event => {
+            window.dispatchEvent(
+              new CustomEvent('brewing:startFromTray', {
+                detail: event.payload,
+              })
+            );
+          })
+        );
+        unlisteners.push(
+          await listen<TrayQuickAddEvent>('quick-add-bean', async event => {
+            const { name, roastDate, weight } = event.payload;
+            const amount = weight != null ? String(weight) : undefined;
+            await useCoffeeBeanStore.getState().addBean({
+              name,
+              roastDate: roastDate ?? undefined,
+              capacity: amount,
+              remaining: amount,
+              beanState: 'roasted',
+            });
+          })
+        );
+        unlisteners.push(
+          await listen<TrayBeanArrivedEvent>('bean-arrived', event => {
+            const { beanId, date } = event.payload;
+            const store = useCoffeeBeanStore.getState();
+            const bean = store.beans.find(b => b.id === beanId);
+            if (!bean) return;
+            // 没有烘焙日期时以收到日期开始计算赏味期
+            void store.updateBean(beanId, {
+              isInTransit: false,
+              roastDate: bean.roastDate || date,
+            });
+          })
+        );
+        unlisteners.push(
+          await listen<string>('finish-bean', event => {
+            void useCoffeeBeanStore
+              .getState()
+              .updateBean(event.payload, { remaining: '0' });
+          })
+        );
+        unlisteners.push(
+          await listen<string>('tray-unpin-bean', event => {
+            void useCoffeeBeanStore
+              .getState()
+              .updateBean(event.payload, { pinned: false });
+          })
+        );
+        unlisteners.push(
+          await listen<number>('store-changes-pending', () => {
+            void applyStoreChanges();
+          })
+        );
+        unlisteners.push(
+          await listen<AppLockStatus>('app-lock-changed', event => {
+            if (!event.payload.locked) void applyStoreChanges();
+          })
+        );
+        // 应用未打开时的修改（如后台同步）在启动后取走
+        void applyStoreChanges();
       } catch (error) {
         console.debug('Failed to setup Tauri event listener:', error);
       }
@@ -72,7 +381,7 @@ export function useTraySync(onNavigateToBean?: (beanId: string) => void) {
     setupListener();
 
     return () => {
-      unlisten?.();
+      unlisteners.forEach(unlisten => unlisten());
     };
   }, []);
 
@@ -93,19 +402,65 @@ export function useTraySync(onNavigateToBean?: (beanId: string) => void) {
         endDay: bean.endDay != null ? Number(bean.endDay) : null,
         isFrozen: bean.isFrozen ?? null,
         isInTransit: bean.isInTransit ?? null,
+        expectedArrival: bean.expectedArrival ?? null,
+        pinned: bean.pinned ?? null,
+        roaster: bean.roaster ?? null,
+        origin: joinComponents(bean, c => c.origin || c.country),
+        process: joinComponents(bean, c => c.process),
       }));
 
     // 简单的去重检查，避免重复同步
-    const syncKey = JSON.stringify(
-      trayBeans.map(
-        b =>
-          `${b.id}-${b.remaining}-${b.roastDate}-${b.isFrozen}-${b.isInTransit}`
-      )
-    );
+    const syncKey = JSON.stringify(trayBeans);
     if (syncKey === lastSyncRef.current) return;
     lastSyncRef.current = syncKey;
 
     // 执行同步
-    syncBeansToTray(trayBeans);
+    void (async () => {
+      if (await isMainWindow()) await syncBeansToTray(trayBeans);
+    })();
   }, [beans]);
+
+  // 同步最近冲煮和今日冲煮统计（不包含快捷扣除、容量调整等变动记录）
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    const brews = notes
+      .filter(note => !note.source)
+      .sort((a, b) => b.timestamp - a.timestamp);
+    const recentBrews: TrayRecentBrew[] = brews
+      .slice(0, MAX_RECENT_BREWS)
+      .map(note => ({
+        id: note.id,
+        method: note.method ?? null,
+        beanName: note.coffeeBeanInfo?.name ?? null,
+        rating: note.rating ?? null,
+        timestamp: note.timestamp,
+      }));
+
+    const since = Date.now() - DOSE_WINDOW_MS;
+    const doses: TrayBrewDose[] = brews
+      .filter(note => note.timestamp >= since)
+      .map(note => {
+        const grams = parseFloat(note.params?.coffee ?? '');
+        return {
+          timestamp: note.timestamp,
+          grams: Number.isFinite(grams) ? grams : null,
+        };
+      });
+
+    const syncKey = JSON.stringify({ recentBrews, doses });
+    if (syncKey === lastBrewSyncRef.current) return;
+    lastBrewSyncRef.current = syncKey;
+
+    void (async () => {
+      if (!(await isMainWindow())) return;
+      try {
+        const { invoke } = await import('@tauri-apps/api/core');
+        await invoke('update_tray_recent_brews', { brews: recentBrews });
+        await invoke('update_tray_daily_stats', { doses });
+      } catch (error) {
+        console.debug('Recent brews sync failed:', error);
+      }
+    })();
+  }, [notes]);
 }
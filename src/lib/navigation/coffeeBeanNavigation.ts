@@ -1,5 +1,11 @@
+import type { FlavorPeriodStatus } from '@/lib/utils/beanVarietyUtils';
+
 export const COFFEE_BEAN_NAVIGATION_EVENTS = {
   SYNC_INVENTORY_CONTEXT: 'coffeeBeans:syncInventoryContext',
+  // 切换到咖啡豆库存页并同步筛选（如菜单栏的「查看全部」）
+  OPEN_INVENTORY: 'coffeeBeans:openInventory',
+  // 切换到咖啡豆库存页并打开添加表单（如桌面快捷操作「添加咖啡豆」）
+  OPEN_ADD_FORM: 'coffeeBeans:openAddForm',
 } as const;
 
 export type CoffeeBeanInventoryState = 'green' | 'roasted';
@@ -7,4 +13,5 @@ export type CoffeeBeanInventoryState = 'green' | 'roasted';
 export interface SyncCoffeeBeanInventoryContextDetail {
   beanState?: CoffeeBeanInventoryState;
   clearSearch?: boolean;
+  flavorPeriod?: FlavorPeriodStatus; // 按赏味期状态筛选
 }
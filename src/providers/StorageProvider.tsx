@@ -3,6 +3,12 @@
 import { useEffect, useRef, useCallback } from 'react';
 import { useTraySync } from '@/lib/hooks/useTraySync';
 import { useCoffeeBeanStore } from '@/lib/stores/coffeeBeanStore';
+import { useBrewingNoteStore } from '@/lib/stores/brewingNoteStore';
+import {
+  buildCoffeeBeanLookup,
+  getBeanUnitPrice,
+  resolveNoteBean,
+} from '@/lib/notes/noteDisplay';
 import { useSyncStatusStore } from '@/lib/stores/syncStatusStore';
 import { getRealtimeSyncService } from '@/lib/supabase/realtime';
 import { useSettingsStore } from '@/lib/stores/settingsStore';
@@ -46,8 +52,33 @@ export default function StorageInit() {
     [beans]
   );
 
+  // 处理从菜单栏「最近冲煮」打开笔记详情
+  const handleNavigateToNote = useCallback(
+    (noteId: string) => {
+      const note = useBrewingNoteStore
+        .getState()
+        .notes.find(n => n.id === noteId);
+      if (!note) {
+        console.warn('未找到笔记:', noteId);
+        return;
+      }
+      const beanInfo = resolveNoteBean(note, buildCoffeeBeanLookup(beans));
+      window.dispatchEvent(
+        new CustomEvent('noteDetailOpened', {
+          detail: {
+            note,
+            equipmentName: note.equipment ?? '',
+            beanUnitPrice: getBeanUnitPrice(beanInfo),
+            beanInfo,
+          },
+        })
+      );
+    },
+    [beans]
+  );
+
   // 同步咖啡豆数据到 Tauri 菜单栏（桌面端）
-  useTraySync(handleNavigateToBean);
+  useTraySync(handleNavigateToBean, handleNavigateToNote);
   useCalendarSync(dataLayerReady);
 
   // 初始化托盘图标可见性（根据设置）
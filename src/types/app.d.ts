@@ -59,6 +59,8 @@ export interface CoffeeBean {
   endDay?: number; // 结束使用天数
   isFrozen?: boolean; // 是否冷冻状态
   isInTransit?: boolean; // 是否在途状态
+  expectedArrival?: string; // 预计到货日期（YYYY-MM-DD）
+  pinned?: boolean; // 是否置顶到菜单栏
 
   // 分类标签
   beanType?: 'espresso' | 'filter' | 'omni'; // 豆子类型：意式/手冲/全能